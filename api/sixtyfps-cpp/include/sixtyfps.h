@@ -78,6 +78,7 @@ namespace private_api {
 using ItemTreeNode = cbindgen_private::ItemTreeNode<uint8_t>;
 using cbindgen_private::KeyboardModifiers;
 using cbindgen_private::KeyEvent;
+using cbindgen_private::MenuEntry;
 
 class ComponentWindow
 {
@@ -132,6 +133,49 @@ public:
         cbindgen_private::sixtyfps_component_window_show_popup(&inner, &popup, p);
     }
 
+    template<typename Component, typename Parent>
+    void show_dialog(const Parent *parent_component,
+                     cbindgen_private::DialogPresentation presentation) const
+    {
+        auto dialog = Component::create(parent_component).into_dyn();
+        cbindgen_private::sixtyfps_component_window_show_dialog(&inner, &dialog, presentation);
+    }
+
+    void close_dialog() const { cbindgen_private::sixtyfps_component_window_close_dialog(&inner); }
+
+    bool has_native_menus() const
+    {
+        return cbindgen_private::sixtyfps_component_window_has_native_menus(&inner);
+    }
+
+    template<typename F>
+    void show_native_menu(const SharedVector<MenuEntry> &entries, cbindgen_private::Point p,
+                          F activated) const
+    {
+        cbindgen_private::sixtyfps_component_window_show_native_menu(
+                &inner, &entries, p,
+                [](void *data, int index) { (*reinterpret_cast<F *>(data))(index); },
+                new F(std::move(activated)), [](void *data) { delete reinterpret_cast<F *>(data); });
+    }
+
+    template<typename E, typename F>
+    void set_native_menu_bar(E entries, F activated) const
+    {
+        struct Callbacks
+        {
+            E entries;
+            F activated;
+        };
+        cbindgen_private::sixtyfps_component_window_set_native_menu_bar(
+                &inner,
+                [](void *data, SharedVector<MenuEntry> *result) {
+                    *result = reinterpret_cast<Callbacks *>(data)->entries();
+                },
+                [](void *data, int index) { reinterpret_cast<Callbacks *>(data)->activated(index); },
+                new Callbacks { std::move(entries), std::move(activated) },
+                [](void *data) { delete reinterpret_cast<Callbacks *>(data); });
+    }
+
 private:
     cbindgen_private::ComponentWindowOpaque inner;
 };
@@ -377,20 +421,42 @@ public:
     /// Internal function called by the view to register itself
     void attach_peer(private_api::ModelPeer p) { peers.push_back(std::move(p)); }
 
+    /// \private
+    /// Register a dependency of the binding currently evaluated on the number of rows
+    void track_row_count_changes() const { model_row_count_dirty_property.get(); }
+
+    /// \private
+    /// Register a dependency of the binding currently evaluated on the data of the row
+    void track_row_data_changes(int row) const
+    {
+        auto it = std::lower_bound(tracked_rows.begin(), tracked_rows.end(), row);
+        if (it == tracked_rows.end() || *it != row) {
+            tracked_rows.insert(it, row);
+        }
+        model_row_data_dirty_property.get();
+    }
+
 protected:
     /// Notify the views that a specific row was changed
     void row_changed(int row)
     {
+        if (std::binary_search(tracked_rows.begin(), tracked_rows.end(), row)) {
+            mark_row_data_dirty();
+        }
         for_each_peers([=](auto peer) { peer->row_changed(row); });
     }
     /// Notify the views that rows were added
     void row_added(int index, int count)
     {
+        model_row_count_dirty_property.mark_dirty();
+        mark_row_data_dirty();
         for_each_peers([=](auto peer) { peer->row_added(index, count); });
     }
     /// Notify the views that rows were removed
     void row_removed(int index, int count)
     {
+        model_row_count_dirty_property.mark_dirty();
+        mark_row_data_dirty();
         for_each_peers([=](auto peer) { peer->row_removed(index, count); });
     }
 
@@ -408,7 +474,16 @@ private:
                                    }),
                     peers.end());
     }
+    void mark_row_data_dirty()
+    {
+        // The bindings register their rows again when they are evaluated
+        tracked_rows.clear();
+        model_row_data_dirty_property.mark_dirty();
+    }
     std::vector<private_api::ModelPeer> peers;
+    private_api::Property<bool> model_row_count_dirty_property;
+    private_api::Property<bool> model_row_data_dirty_property;
+    mutable std::vector<int> tracked_rows;
 };
 
 namespace private_api {
@@ -445,6 +520,33 @@ struct IntModel : Model<int>
     int row_count() const override { return data; }
     int row_data(int value) const override { return value; }
 };
+
+/// Returns the number of rows of the model, and tracks the changes in the number of rows.
+/// This is used for `model.length` in the .60 language
+template<typename M>
+int model_length(const std::shared_ptr<M> &model)
+{
+    if (!model) {
+        return 0;
+    }
+    model->track_row_count_changes();
+    return model->row_count();
+}
+
+/// Returns the data of the row, or a default constructed value if the row is out of range,
+/// and tracks the changes of this row. This is used for `model[index]` in the .60 language
+template<typename M>
+auto access_array_index(const std::shared_ptr<M> &model, int index)
+{
+    if (!model) {
+        return decltype(model->row_data(0)) {};
+    }
+    model->track_row_data_changes(index);
+    if (index < 0 || index >= model->row_count()) {
+        return decltype(model->row_data(0)) {};
+    }
+    return model->row_data(index);
+}
 } // namespace private_api
 
 /// A Model backed by a SharedVector
@@ -639,6 +741,15 @@ cbindgen_private::Flickable::~Flickable()
     sixtyfps_flickable_data_free(&data);
 }
 
+cbindgen_private::Shortcut::Shortcut()
+{
+    sixtyfps_shortcut_data_init(&data);
+}
+cbindgen_private::Shortcut::~Shortcut()
+{
+    sixtyfps_shortcut_data_free(&data);
+}
+
 cbindgen_private::NativeStyleMetrics::NativeStyleMetrics()
 {
     sixtyfps_init_native_style_metrics(this);
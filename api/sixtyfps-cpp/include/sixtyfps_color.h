@@ -121,6 +121,15 @@ public:
         return Color::from_argb_float(1.0, red, green, blue);
     }
 
+    /// Construct a color from the \a hue (in degrees), \a saturation, \a value (brightness),
+    /// and \a alpha, in the HSV color space. The saturation, value, and alpha are in the range 0..1.
+    inline static Color from_hsva(float hue, float saturation, float value, float alpha = 1.0);
+
+    /// Construct a color from the \a hue (in degrees), \a saturation, \a lightness, and
+    /// \a alpha, in the HSL color space. The saturation, lightness, and alpha are in the
+    /// range 0..1.
+    inline static Color from_hsla(float hue, float saturation, float lightness, float alpha = 1.0);
+
     /// Converts this color to an RgbaColor struct for easy destructuring.
     inline RgbaColor<uint8_t> to_argb_uint() const;
 
@@ -153,6 +162,23 @@ public:
     /// So for example `darker(0.3)` will decrease the brightness by 30%.
     inline Color darker(float factor) const;
 
+    /// Returns a new version of this color with the alpha channel set to \a alpha, in the range
+    /// 0..1.
+    inline Color with_alpha(float alpha) const;
+
+    /// Returns a new version of this color that is more transparent: the alpha channel is
+    /// multiplied by (1 - \a amount). So for example `transparentize(0.5)` halves the opacity.
+    inline Color transparentize(float amount) const;
+
+    /// Returns the mix of this color with \a other, by interpolating each channel. \a factor is
+    /// the proportion of \a other in the range 0..1, so `mix(other, 0)` returns this color and
+    /// `mix(other, 1)` returns \a other.
+    inline Color mix(const Color &other, float factor) const;
+
+    /// Returns the relative luminance of this color, as defined by WCAG 2.0, in the range 0..1.
+    /// The alpha channel is ignored.
+    inline float luminance() const;
+
     /// Returns true if \a lhs has the same values for the individual color channels as \a rhs;
     /// false otherwise.
     friend bool operator==(const Color &lhs, const Color &rhs)
@@ -200,6 +226,48 @@ inline Color Color::darker(float factor) const
     return result;
 }
 
+inline Color Color::from_hsva(float hue, float saturation, float value, float alpha)
+{
+    Color result;
+    cbindgen_private::types::sixtyfps_color_from_hsva(hue, saturation, value, alpha,
+                                                      &result.inner);
+    return result;
+}
+
+inline Color Color::from_hsla(float hue, float saturation, float lightness, float alpha)
+{
+    Color result;
+    cbindgen_private::types::sixtyfps_color_from_hsla(hue, saturation, lightness, alpha,
+                                                      &result.inner);
+    return result;
+}
+
+inline Color Color::with_alpha(float alpha) const
+{
+    Color result;
+    cbindgen_private::types::sixtyfps_color_with_alpha(&inner, alpha, &result.inner);
+    return result;
+}
+
+inline Color Color::transparentize(float amount) const
+{
+    Color result;
+    cbindgen_private::types::sixtyfps_color_transparentize(&inner, amount, &result.inner);
+    return result;
+}
+
+inline Color Color::mix(const Color &other, float factor) const
+{
+    Color result;
+    cbindgen_private::types::sixtyfps_color_mix(&inner, &other.inner, factor, &result.inner);
+    return result;
+}
+
+inline float Color::luminance() const
+{
+    return cbindgen_private::types::sixtyfps_color_luminance(&inner);
+}
+
 /// Constructs a new RgbaColor<uint8_t> from the color \a color.
 template<>
 inline RgbaColor<uint8_t>::RgbaColor(const Color &color)
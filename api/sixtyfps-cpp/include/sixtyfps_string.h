@@ -198,6 +198,89 @@ inline cbindgen_private::Slice<uint8_t> string_to_slice(std::string_view str)
         const_cast<unsigned char *>(reinterpret_cast<const unsigned char *>(str.data())), str.size()
     };
 }
+
+inline SharedString format_fixed(double n, int digits)
+{
+    SharedString out;
+    cbindgen_private::sixtyfps_string_format_fixed(&out, n, digits);
+    return out;
+}
+
+inline SharedString format_grouped(double n, int digits)
+{
+    SharedString out;
+    cbindgen_private::sixtyfps_string_format_grouped(&out, n, digits);
+    return out;
+}
+
+inline SharedString format_padded(double n, int width)
+{
+    SharedString out;
+    cbindgen_private::sixtyfps_string_format_padded(&out, n, width);
+    return out;
+}
+
+inline SharedString format_percent(double n, int digits)
+{
+    SharedString out;
+    cbindgen_private::sixtyfps_string_format_percent(&out, n, digits);
+    return out;
+}
+
+inline SharedString format_byte_size(double n)
+{
+    SharedString out;
+    cbindgen_private::sixtyfps_string_format_byte_size(&out, n);
+    return out;
+}
+
+inline SharedString string_to_upper(const SharedString &s)
+{
+    SharedString out;
+    cbindgen_private::sixtyfps_string_to_uppercase(&out, &s);
+    return out;
+}
+
+inline SharedString string_to_lower(const SharedString &s)
+{
+    SharedString out;
+    cbindgen_private::sixtyfps_string_to_lowercase(&out, &s);
+    return out;
+}
+
+inline SharedString string_trim(const SharedString &s)
+{
+    SharedString out;
+    cbindgen_private::sixtyfps_string_trim(&out, &s);
+    return out;
+}
+
+inline bool string_contains(std::string_view s, std::string_view other)
+{
+    return s.find(other) != std::string_view::npos;
+}
+
+inline bool string_starts_with(std::string_view s, std::string_view other)
+{
+    return s.substr(0, other.size()) == other;
+}
+
+inline bool string_ends_with(std::string_view s, std::string_view other)
+{
+    return s.size() >= other.size() && s.substr(s.size() - other.size()) == other;
+}
+
+inline int string_length(const SharedString &s)
+{
+    return cbindgen_private::sixtyfps_string_length(&s);
+}
+
+inline SharedString string_substring(const SharedString &s, int start, int length)
+{
+    SharedString out;
+    cbindgen_private::sixtyfps_string_substring(&out, &s, start, length);
+    return out;
+}
 }
 
 }
@@ -93,6 +93,14 @@ fn attach_peer(&self, peer: sixtyfps_corelib::model::ModelPeer) {
         self.notify.attach(peer)
     }
 
+    fn track_row_count_changes(&self) {
+        self.notify.track_row_count_changes()
+    }
+
+    fn track_row_data_changes(&self, row: usize) {
+        self.notify.track_row_data_changes(row)
+    }
+
     fn set_row_data(&self, row: usize, data: Self::Data) {
         crate::run_with_global_context(&|cx, persistent_context| {
             let row = JsNumber::new(cx, row as f64).as_value(cx);
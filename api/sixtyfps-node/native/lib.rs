@@ -221,7 +221,14 @@ fn to_eval_value<'cx>(
                     .collect::<Result<_, _>>()?,
             ))
         }
-        Type::Enumeration(_) => todo!(),
+        Type::Enumeration(enumeration) => {
+            let s = val.to_string(cx)?.value();
+            let value = s.replace('-', "_");
+            match enumeration.values.iter().find(|v| v.replace('-', "_") == value) {
+                Some(v) => Ok(Value::EnumerationValue(enumeration.name.clone(), v.clone())),
+                None => cx.throw_error(format!("{} is not a value of {}", s, enumeration.name)),
+            }
+        }
         Type::Invalid
         | Type::Void
         | Type::InferredProperty
@@ -279,6 +286,7 @@ fn to_js_value<'cx>(
             &format!("#{:02x}{:02x}{:02x}{:02x}", c.red(), c.green(), c.blue(), c.alpha()),
         )
         .as_value(cx),
+        Value::EnumerationValue(_, value) => JsString::new(cx, value.as_str()).as_value(cx),
         _ => todo!("converting {:?} to js has not been implemented", val),
     })
 }
@@ -0,0 +1,586 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+/*!
+The `sixtyfps` Python extension module, which wraps the interpreter.
+
+See the README.md for the Python API.
+*/
+
+use pyo3::exceptions::{PyAttributeError, PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyString, PyTuple};
+use sixtyfps_compilerlib::langtype::Type;
+use sixtyfps_corelib::{ImageInner, SharedVector};
+use sixtyfps_interpreter::Value;
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+mod models;
+
+thread_local! {
+    /// The first exception raised by Python code called from the interpreter (callback handlers,
+    /// models). It is raised again when the control returns to Python.
+    static PENDING_ERROR: RefCell<Option<PyErr>> = const { RefCell::new(None) };
+}
+
+/// Remember an exception raised by Python code called from the interpreter
+fn report_error(err: PyErr) {
+    PENDING_ERROR.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        if pending.is_none() {
+            *pending = Some(err)
+        }
+    })
+}
+
+/// Raise the exception reported during a call into the interpreter, if any
+fn take_pending_error() -> PyResult<()> {
+    match PENDING_ERROR.with(|pending| pending.borrow_mut().take()) {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Compile .60 files or sources to a [`ComponentDefinition`]
+#[pyclass(unsendable, module = "sixtyfps")]
+struct ComponentCompiler {
+    compiler: sixtyfps_interpreter::ComponentCompiler,
+}
+
+#[pymethods]
+impl ComponentCompiler {
+    #[new]
+    fn new() -> Self {
+        Self { compiler: Default::default() }
+    }
+
+    /// The paths where the imported .60 files are looked up
+    #[getter]
+    fn include_paths(&self) -> Vec<PathBuf> {
+        self.compiler.include_paths().clone()
+    }
+
+    #[setter]
+    fn set_include_paths(&mut self, include_paths: Vec<PathBuf>) {
+        self.compiler.set_include_paths(include_paths)
+    }
+
+    /// The style of the widgets ('native', 'ugly', or the path of a custom style)
+    #[getter]
+    fn style(&self) -> Option<String> {
+        self.compiler.style().cloned()
+    }
+
+    #[setter]
+    fn set_style(&mut self, style: String) {
+        self.compiler.set_style(style)
+    }
+
+    /// The errors and warnings of the last compilation
+    #[getter]
+    fn diagnostics(&self) -> Vec<String> {
+        self.compiler.diagnostics().iter().map(|d| d.to_string()).collect()
+    }
+
+    /// Compile the .60 file at `path`. Return None if there is an error: see `diagnostics`.
+    fn build_from_path(&mut self, path: PathBuf) -> Option<ComponentDefinition> {
+        spin_on::spin_on(self.compiler.build_from_path(path))
+            .map(|definition| ComponentDefinition { definition })
+    }
+
+    /// Compile the source code of a .60 file, and use `path` for the diagnostics and to
+    /// resolve the relative imports. Return None if there is an error: see `diagnostics`.
+    #[pyo3(signature = (source, path = PathBuf::new()))]
+    fn build_from_source(&mut self, source: String, path: PathBuf) -> Option<ComponentDefinition> {
+        spin_on::spin_on(self.compiler.build_from_source(source, path))
+            .map(|definition| ComponentDefinition { definition })
+    }
+}
+
+/// A compiled component, which can be instantiated with `create()`, or by calling it with
+/// the initial values of the properties and the callback handlers as keyword arguments
+#[pyclass(unsendable, module = "sixtyfps")]
+#[derive(Clone)]
+struct ComponentDefinition {
+    definition: sixtyfps_interpreter::ComponentDefinition,
+}
+
+#[pymethods]
+impl ComponentDefinition {
+    #[getter]
+    fn name(&self) -> String {
+        self.definition.name().into()
+    }
+
+    /// The names of the public properties
+    #[getter]
+    fn properties(&self) -> Vec<String> {
+        self.definition
+            .properties_and_callbacks()
+            .filter(|(_, ty)| ty.is_property_type())
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// The names of the callbacks
+    #[getter]
+    fn callbacks(&self) -> Vec<String> {
+        self.definition
+            .properties_and_callbacks()
+            .filter(|(_, ty)| matches!(ty, Type::Callback { .. }))
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    fn create(&self) -> ComponentInstance {
+        ComponentInstance { instance: self.definition.create() }
+    }
+
+    #[pyo3(signature = (**kwargs))]
+    fn __call__(&self, kwargs: Option<&PyDict>) -> PyResult<ComponentInstance> {
+        let instance = self.create();
+        for (name, value) in kwargs.into_iter().flatten() {
+            instance.__setattr__(name.extract()?, value)?;
+        }
+        Ok(instance)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<sixtyfps.ComponentDefinition {}>", self.definition.name())
+    }
+}
+
+/// An instance of a component. The public properties and callbacks are attributes of the
+/// instance.
+#[pyclass(unsendable, module = "sixtyfps")]
+struct ComponentInstance {
+    instance: sixtyfps_interpreter::ComponentInstance,
+}
+
+impl ComponentInstance {
+    fn property_type(&self, name: &str) -> Option<Type> {
+        let name = name.replace('-', "_");
+        self.instance.definition().properties_and_callbacks().find_map(|(n, ty)| {
+            if n == name {
+                Some(ty)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn callback_types(&self, name: &str) -> PyResult<(Vec<Type>, Option<Box<Type>>)> {
+        match self.property_type(name) {
+            Some(Type::Callback { args, return_type }) => Ok((args, return_type)),
+            _ => Err(PyAttributeError::new_err(format!("{} is not a callback", name))),
+        }
+    }
+}
+
+#[pymethods]
+impl ComponentInstance {
+    #[getter]
+    fn definition(&self) -> ComponentDefinition {
+        ComponentDefinition { definition: self.instance.definition() }
+    }
+
+    fn get_property(&self, py: Python, name: &str) -> PyResult<PyObject> {
+        let ty = match self.property_type(name) {
+            Some(ty) if ty.is_property_type() => ty,
+            _ => return Err(PyAttributeError::new_err(format!("no property {}", name))),
+        };
+        let value = self
+            .instance
+            .get_property(&name.replace('-', "_"))
+            .map_err(|err| PyAttributeError::new_err(format!("{}: {}", name, err)))?;
+        take_pending_error()?;
+        Ok(to_py(py, value, Some(&ty)))
+    }
+
+    fn set_property(&self, name: &str, value: &PyAny) -> PyResult<()> {
+        let ty = match self.property_type(name) {
+            Some(ty) if ty.is_property_type() => ty,
+            _ => return Err(PyAttributeError::new_err(format!("no property {}", name))),
+        };
+        let value = to_value(value, &ty)?;
+        self.instance
+            .set_property(&name.replace('-', "_"), value)
+            .map_err(|err| PyValueError::new_err(format!("{}: {}", name, err)))?;
+        take_pending_error()
+    }
+
+    /// Call the callback `name` with the arguments, and return its return value
+    #[pyo3(signature = (name, *args))]
+    fn invoke(&self, py: Python, name: &str, args: &PyTuple) -> PyResult<PyObject> {
+        let (arg_types, return_type) = self.callback_types(name)?;
+        if args.len() != arg_types.len() {
+            return Err(PyTypeError::new_err(format!(
+                "{} expects {} arguments, but {} were provided",
+                name,
+                arg_types.len(),
+                args.len()
+            )));
+        }
+        let args = args
+            .iter()
+            .zip(arg_types.iter())
+            .map(|(arg, ty)| to_value(arg, ty))
+            .collect::<PyResult<Vec<_>>>()?;
+        let result = self
+            .instance
+            .invoke_callback(&name.replace('-', "_"), &args)
+            .map_err(|err| PyAttributeError::new_err(format!("{}: {}", name, err)))?;
+        take_pending_error()?;
+        Ok(to_py(py, result, return_type.as_deref()))
+    }
+
+    /// Set the handler of the callback `name` to a Python callable
+    fn set_callback(&self, name: &str, handler: PyObject) -> PyResult<()> {
+        let (arg_types, return_type) = self.callback_types(name)?;
+        self.instance
+            .set_callback(
+                &name.replace('-', "_"),
+                make_callback_handler(handler, arg_types, return_type),
+            )
+            .map_err(|err| PyAttributeError::new_err(format!("{}: {}", name, err)))
+    }
+
+    fn __getattr__(&self, py: Python, name: &str) -> PyResult<PyObject> {
+        match self.property_type(name) {
+            Some(Type::Callback { .. }) => {
+                Ok(Callback { instance: self.instance.clone_strong(), name: name.into() }
+                    .into_py(py))
+            }
+            Some(_) => self.get_property(py, name),
+            None => Err(PyAttributeError::new_err(format!(
+                "'{}' has no property or callback '{}'",
+                self.instance.definition().name(),
+                name
+            ))),
+        }
+    }
+
+    /// Set a property, or the handler of a callback
+    fn __setattr__(&self, name: &str, value: &PyAny) -> PyResult<()> {
+        match self.property_type(name) {
+            Some(Type::Callback { .. }) => self.set_callback(name, value.into()),
+            _ => self.set_property(name, value),
+        }
+    }
+
+    fn __dir__(slf: &PyCell<Self>) -> PyResult<Vec<String>> {
+        let mut names: Vec<String> = slf.get_type().dir().extract()?;
+        names.extend(
+            slf.borrow().instance.definition().properties_and_callbacks().map(|(name, _)| name),
+        );
+        Ok(names)
+    }
+
+    fn show(&self) -> PyResult<()> {
+        self.instance.show();
+        take_pending_error()
+    }
+
+    fn hide(&self) -> PyResult<()> {
+        self.instance.hide();
+        take_pending_error()
+    }
+
+    /// Show the window, run the event loop, and hide the window when the event loop returns
+    fn run(&self) -> PyResult<()> {
+        self.instance.run();
+        take_pending_error()
+    }
+
+    fn send_mouse_click(&self, x: f32, y: f32) -> PyResult<()> {
+        sixtyfps_interpreter::testing::send_mouse_click(&self.instance, x, y);
+        take_pending_error()
+    }
+
+    fn send_keyboard_string_sequence(&self, sequence: &str) -> PyResult<()> {
+        sixtyfps_interpreter::testing::send_keyboard_string_sequence(
+            &self.instance,
+            sequence.into(),
+        );
+        take_pending_error()
+    }
+}
+
+/// A callback of a component instance: calling it invokes the callback
+#[pyclass(unsendable, module = "sixtyfps")]
+struct Callback {
+    instance: sixtyfps_interpreter::ComponentInstance,
+    name: String,
+}
+
+#[pymethods]
+impl Callback {
+    #[pyo3(signature = (*args))]
+    fn __call__(&self, py: Python, args: &PyTuple) -> PyResult<PyObject> {
+        ComponentInstance { instance: self.instance.clone_strong() }.invoke(py, &self.name, args)
+    }
+
+    /// Set the handler of the callback to a Python callable
+    fn set_handler(&self, handler: PyObject) -> PyResult<()> {
+        ComponentInstance { instance: self.instance.clone_strong() }
+            .set_callback(&self.name, handler)
+    }
+}
+
+type CallbackHandler = Box<dyn Fn(&[Value]) -> Value>;
+
+fn make_callback_handler(
+    handler: PyObject,
+    arg_types: Vec<Type>,
+    return_type: Option<Box<Type>>,
+) -> CallbackHandler {
+    Box::new(move |args| {
+        Python::with_gil(|py| {
+            let args = PyTuple::new(
+                py,
+                args.iter().zip(arg_types.iter()).map(|(arg, ty)| to_py(py, arg.clone(), Some(ty))),
+            );
+            let result = handler.call1(py, args).and_then(|result| match &return_type {
+                Some(ty) => to_value(result.as_ref(py), ty),
+                None => Ok(Value::Void),
+            });
+            result.unwrap_or_else(|err| {
+                report_error(err);
+                Value::Void
+            })
+        })
+    })
+}
+
+/// Convert a Python object to a value of the given type
+fn to_value(obj: &PyAny, ty: &Type) -> PyResult<Value> {
+    let type_error = |expected: &str| {
+        let actual = obj.get_type().name().unwrap_or("<unknown>");
+        PyTypeError::new_err(format!("expected {}, got {}", expected, actual))
+    };
+    Ok(match ty {
+        Type::Float32
+        | Type::Int32
+        | Type::Duration
+        | Type::Angle
+        | Type::PhysicalLength
+        | Type::LogicalLength
+        | Type::Percent
+        | Type::UnitProduct(_) => Value::Number(obj.extract().map_err(|_| type_error("a number"))?),
+        Type::String => {
+            Value::String(obj.extract::<&str>().map_err(|_| type_error("a string"))?.into())
+        }
+        Type::Bool => Value::Bool(obj.extract().map_err(|_| type_error("a bool"))?),
+        Type::Color | Type::Brush => {
+            let s = obj.extract::<&str>().map_err(|_| type_error("a color string"))?;
+            let c = s
+                .parse::<css_color_parser2::Color>()
+                .map_err(|err| PyValueError::new_err(err.to_string()))?;
+            Value::Brush(sixtyfps_corelib::Brush::SolidColor(
+                sixtyfps_corelib::Color::from_argb_u8((c.a * 255.) as u8, c.r, c.g, c.b),
+            ))
+        }
+        Type::Image => {
+            let path = obj.extract::<PathBuf>().map_err(|_| type_error("a path"))?;
+            Value::Image(sixtyfps_corelib::graphics::Image::load_from_path(&path).map_err(
+                |_| PyValueError::new_err(format!("cannot load image {}", path.display())),
+            )?)
+        }
+        Type::Array(element_type) => {
+            if let Ok(model) = obj.extract::<PyRef<models::Model>>() {
+                Value::Model(models::PyModel::new(obj.into(), &model, (**element_type).clone()))
+            } else if obj.is_instance_of::<PyString>()? {
+                return Err(type_error("a sequence or a sixtyfps.Model"));
+            } else {
+                Value::Array(
+                    obj.iter()
+                        .map_err(|_| type_error("a sequence or a sixtyfps.Model"))?
+                        .map(|item| to_value(item?, element_type))
+                        .collect::<PyResult<SharedVector<_>>>()?,
+                )
+            }
+        }
+        Type::Struct { fields, .. } => {
+            let dict = obj.downcast::<PyDict>().map_err(|_| type_error("a dict"))?;
+            for key in dict.keys() {
+                let key = key.extract::<&str>()?.replace('-', "_");
+                if !fields.contains_key(&key) {
+                    return Err(PyValueError::new_err(format!("unknown field {}", key)));
+                }
+            }
+            Value::Struct(
+                fields
+                    .iter()
+                    .map(|(name, ty)| {
+                        let item = dict
+                            .get_item(name)
+                            .or_else(|| dict.get_item(name.replace('_', "-")))
+                            .ok_or_else(|| {
+                                PyValueError::new_err(format!("missing field {}", name))
+                            })?;
+                        Ok((name.clone(), to_value(item, ty)?))
+                    })
+                    .collect::<PyResult<_>>()?,
+            )
+        }
+        Type::Enumeration(enumeration) => {
+            let s = obj.extract::<&str>().map_err(|_| type_error("a string"))?;
+            let value = s.replace('-', "_");
+            match enumeration.values.iter().find(|v| v.replace('-', "_") == value) {
+                Some(v) => Value::EnumerationValue(enumeration.name.clone(), v.clone()),
+                None => {
+                    return Err(PyValueError::new_err(format!(
+                        "{} is not a value of {}",
+                        s, enumeration.name
+                    )))
+                }
+            }
+        }
+        _ => return Err(PyTypeError::new_err("cannot convert to a SixtyFPS property value")),
+    })
+}
+
+/// Convert a value to a Python object. The type is used to convert the integers to `int`.
+fn to_py(py: Python, value: Value, ty: Option<&Type>) -> PyObject {
+    match value {
+        Value::Void => py.None(),
+        Value::Number(n) => match ty {
+            Some(Type::Int32) => (n as i64).into_py(py),
+            _ => n.into_py(py),
+        },
+        Value::String(s) => s.as_str().into_py(py),
+        Value::Bool(b) => b.into_py(py),
+        Value::Image(image) => match <&ImageInner>::from(&image) {
+            ImageInner::AbsoluteFilePath(path) => path.as_str().into_py(py),
+            _ => py.None(),
+        },
+        Value::Array(array) => {
+            let element_type = match ty {
+                Some(Type::Array(element_type)) => Some(&**element_type),
+                _ => None,
+            };
+            PyList::new(py, array.iter().map(|v| to_py(py, v.clone(), element_type))).into()
+        }
+        Value::Model(model) => {
+            let element_type = match ty {
+                Some(Type::Array(element_type)) => Some(&**element_type),
+                _ => None,
+            };
+            PyList::new(
+                py,
+                (0..model.row_count()).map(|row| to_py(py, model.row_data(row), element_type)),
+            )
+            .into()
+        }
+        Value::Struct(s) => {
+            let fields = match ty {
+                Some(Type::Struct { fields, .. }) => Some(fields),
+                _ => None,
+            };
+            let dict = PyDict::new(py);
+            for (name, value) in s.iter() {
+                let value = to_py(py, value.clone(), fields.and_then(|f| f.get(name)));
+                dict.set_item(name, value).unwrap();
+            }
+            dict.into()
+        }
+        Value::Brush(brush) => {
+            let c = brush.color();
+            format!("#{:02x}{:02x}{:02x}{:02x}", c.red(), c.green(), c.blue(), c.alpha())
+                .into_py(py)
+        }
+        Value::EnumerationValue(_, value) => value.into_py(py),
+        _ => py.None(),
+    }
+}
+
+/// Load the .60 file at `path`, and return a module with the exported component, like
+/// importing the .60 file with node.
+///
+/// The include paths are given by the `SIXTYFPS_INCLUDE_PATH` environment variable.
+#[pyfunction]
+fn load_file(py: Python, path: PathBuf) -> PyResult<PyObject> {
+    let include_paths = match std::env::var_os("SIXTYFPS_INCLUDE_PATH") {
+        Some(paths) => {
+            std::env::split_paths(&paths).filter(|path| !path.as_os_str().is_empty()).collect()
+        }
+        None => vec![],
+    };
+    let mut compiler = sixtyfps_interpreter::ComponentCompiler::default();
+    compiler.set_include_paths(include_paths);
+    let definition = spin_on::spin_on(compiler.build_from_path(&path));
+    sixtyfps_interpreter::print_diagnostics(compiler.diagnostics());
+    let definition = definition.ok_or_else(|| {
+        PyRuntimeError::new_err(format!("Compilation error in {}", path.display()))
+    })?;
+    let module = pyo3::types::PyModule::new(
+        py,
+        &path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default(),
+    )?;
+    let name = definition.name().to_string();
+    module.add(&name, ComponentDefinition { definition })?;
+    Ok(module.into())
+}
+
+/// Run the event loop until the last window is closed
+#[pyfunction]
+fn run_event_loop() -> PyResult<()> {
+    sixtyfps_interpreter::run_event_loop();
+    take_pending_error()
+}
+
+/// Call `handler` once after `duration` milliseconds
+#[pyfunction]
+fn single_shot_timer(duration: u64, handler: PyObject) {
+    sixtyfps_corelib::timers::Timer::single_shot(
+        std::time::Duration::from_millis(duration),
+        move || {
+            Python::with_gil(|py| {
+                if let Err(err) = handler.call0(py) {
+                    report_error(err)
+                }
+            })
+        },
+    );
+}
+
+/// Let some time elapse for testing purposes
+#[pyfunction]
+fn mock_elapsed_time(ms: u64) {
+    sixtyfps_corelib::tests::sixtyfps_mock_elapsed_time(ms);
+}
+
+#[cfg(feature = "testing")]
+#[pyfunction]
+fn init_testing_backend() {
+    sixtyfps_rendering_backend_testing::init();
+}
+
+#[pymodule]
+#[pyo3(name = "sixtyfps")]
+fn sixtyfps_python_native(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<ComponentCompiler>()?;
+    m.add_class::<ComponentDefinition>()?;
+    m.add_class::<ComponentInstance>()?;
+    m.add_class::<Callback>()?;
+    m.add_class::<models::Model>()?;
+    m.add_class::<models::ModelPeer>()?;
+    m.add_class::<models::ListModel>()?;
+    m.add_function(wrap_pyfunction!(load_file, m)?)?;
+    m.add_function(wrap_pyfunction!(run_event_loop, m)?)?;
+    m.add_function(wrap_pyfunction!(single_shot_timer, m)?)?;
+
+    let private_api = PyModule::new(py, "private_api")?;
+    private_api.add_function(wrap_pyfunction!(mock_elapsed_time, private_api)?)?;
+    #[cfg(feature = "testing")]
+    private_api.add_function(wrap_pyfunction!(init_testing_backend, private_api)?)?;
+    m.add_submodule(private_api)?;
+    Ok(())
+}
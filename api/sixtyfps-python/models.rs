@@ -0,0 +1,208 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+//! Models implemented in Python
+
+use pyo3::exceptions::{PyIndexError, PyNotImplementedError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use sixtyfps_compilerlib::langtype::Type;
+use sixtyfps_corelib::model::ModelNotify;
+use sixtyfps_interpreter::Value;
+use std::rc::Rc;
+
+/// The base class of the models implemented in Python.
+///
+/// Subclasses must implement `row_count()` and `row_data(row)`, and `set_row_data(row, data)`
+/// if the data can be modified from the .60 code. They must notify the changes with `notify`.
+#[pyclass(unsendable, subclass, module = "sixtyfps")]
+pub struct Model {
+    notify: Rc<ModelNotify>,
+}
+
+#[pymethods]
+impl Model {
+    #[new]
+    #[pyo3(signature = (*_args, **_kwargs))]
+    fn new(_args: &PyTuple, _kwargs: Option<&PyDict>) -> Self {
+        Self { notify: Default::default() }
+    }
+
+    fn row_count(&self) -> PyResult<usize> {
+        Err(PyNotImplementedError::new_err("Model.row_count must be implemented"))
+    }
+
+    fn row_data(&self, _row: usize) -> PyResult<PyObject> {
+        Err(PyNotImplementedError::new_err("Model.row_data must be implemented"))
+    }
+
+    fn set_row_data(&self, _row: usize, _data: PyObject) {}
+
+    /// The peer used to notify the views of the changes in the model
+    #[getter]
+    fn notify(&self) -> ModelPeer {
+        ModelPeer { notify: self.notify.clone() }
+    }
+}
+
+/// Notify the views using a model of the changes in the model
+#[pyclass(unsendable, module = "sixtyfps")]
+pub struct ModelPeer {
+    notify: Rc<ModelNotify>,
+}
+
+#[pymethods]
+impl ModelPeer {
+    /// Notify that the data of the row has changed
+    fn row_data_changed(&self, row: usize) {
+        self.notify.row_changed(row)
+    }
+
+    /// Notify that `count` rows were added at the index `row`
+    fn row_added(&self, row: usize, count: usize) {
+        self.notify.row_added(row, count)
+    }
+
+    /// Notify that `count` rows were removed from the index `row`
+    fn row_removed(&self, row: usize, count: usize) {
+        self.notify.row_removed(row, count)
+    }
+}
+
+/// A model backed by a Python list. It can be modified with `append` and `remove`.
+#[pyclass(unsendable, extends = Model, module = "sixtyfps")]
+pub struct ListModel {
+    items: Vec<PyObject>,
+}
+
+#[pymethods]
+impl ListModel {
+    #[new]
+    #[pyo3(signature = (items = None))]
+    fn new(items: Option<&PyAny>) -> PyResult<(Self, Model)> {
+        let items = match items {
+            Some(items) => items.iter()?.map(|item| Ok(item?.into())).collect::<PyResult<_>>()?,
+            None => vec![],
+        };
+        Ok((Self { items }, Model { notify: Default::default() }))
+    }
+
+    fn row_count(&self) -> usize {
+        self.items.len()
+    }
+
+    fn row_data(&self, py: Python, row: usize) -> PyResult<PyObject> {
+        self.items
+            .get(row)
+            .map(|item| item.clone_ref(py))
+            .ok_or_else(|| PyIndexError::new_err("row out of range"))
+    }
+
+    fn set_row_data(mut slf: PyRefMut<Self>, row: usize, data: PyObject) -> PyResult<()> {
+        let item =
+            slf.items.get_mut(row).ok_or_else(|| PyIndexError::new_err("row out of range"))?;
+        *item = data;
+        let notify = slf.as_ref().notify.clone();
+        drop(slf);
+        notify.row_changed(row);
+        Ok(())
+    }
+
+    /// Add a row at the end of the model
+    fn append(mut slf: PyRefMut<Self>, value: PyObject) {
+        slf.items.push(value);
+        let row = slf.items.len() - 1;
+        let notify = slf.as_ref().notify.clone();
+        drop(slf);
+        notify.row_added(row, 1);
+    }
+
+    /// Remove `count` rows from the index `row`
+    #[pyo3(signature = (row, count = 1))]
+    fn remove(mut slf: PyRefMut<Self>, row: usize, count: usize) -> PyResult<()> {
+        if row + count > slf.items.len() {
+            return Err(PyIndexError::new_err("row out of range"));
+        }
+        slf.items.drain(row..row + count);
+        let notify = slf.as_ref().notify.clone();
+        drop(slf);
+        notify.row_removed(row, count);
+        Ok(())
+    }
+
+    fn __len__(&self) -> usize {
+        self.items.len()
+    }
+
+    fn __getitem__(&self, py: Python, row: usize) -> PyResult<PyObject> {
+        self.row_data(py, row)
+    }
+}
+
+/// The Model used by the interpreter for a Python model
+pub struct PyModel {
+    object: PyObject,
+    notify: Rc<ModelNotify>,
+    data_type: Type,
+}
+
+impl PyModel {
+    pub fn new(object: PyObject, model: &Model, data_type: Type) -> Rc<Self> {
+        Rc::new(Self { object, notify: model.notify.clone(), data_type })
+    }
+}
+
+impl sixtyfps_corelib::model::Model for PyModel {
+    type Data = Value;
+
+    fn row_count(&self) -> usize {
+        Python::with_gil(|py| {
+            self.object
+                .call_method0(py, "row_count")
+                .and_then(|count| count.extract(py))
+                .unwrap_or_else(|err| {
+                    crate::report_error(err);
+                    0
+                })
+        })
+    }
+
+    fn row_data(&self, row: usize) -> Self::Data {
+        Python::with_gil(|py| {
+            self.object
+                .call_method1(py, "row_data", (row,))
+                .and_then(|data| crate::to_value(data.as_ref(py), &self.data_type))
+                .unwrap_or_else(|err| {
+                    crate::report_error(err);
+                    Value::Void
+                })
+        })
+    }
+
+    fn set_row_data(&self, row: usize, data: Self::Data) {
+        Python::with_gil(|py| {
+            let data = crate::to_py(py, data, Some(&self.data_type));
+            if let Err(err) = self.object.call_method1(py, "set_row_data", (row, data)) {
+                crate::report_error(err)
+            }
+        })
+    }
+
+    fn attach_peer(&self, peer: sixtyfps_corelib::model::ModelPeer) {
+        self.notify.attach(peer)
+    }
+
+    fn track_row_count_changes(&self) {
+        self.notify.track_row_count_changes()
+    }
+
+    fn track_row_data_changes(&self, row: usize) {
+        self.notify.track_row_data_changes(row)
+    }
+}
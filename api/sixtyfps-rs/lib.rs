@@ -242,7 +242,13 @@ pub mod re_exports {
         set_state_binding, Property, PropertyTracker, StateInfo,
     };
     pub use sixtyfps_corelib::slice::Slice;
-    pub use sixtyfps_corelib::window::ComponentWindow;
+    pub use sixtyfps_corelib::string::{
+        format_byte_size, format_fixed, format_grouped, format_padded, format_percent,
+        string_length, string_substring,
+    };
+    pub use sixtyfps_corelib::window::{
+        standard_button_rank, ComponentWindow, DialogPresentation, MenuEntry, StandardButtonKind,
+    };
     pub use sixtyfps_corelib::Color;
     pub use sixtyfps_corelib::ComponentVTable_static;
     pub use sixtyfps_corelib::SharedString;
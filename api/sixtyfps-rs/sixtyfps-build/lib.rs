@@ -85,7 +85,21 @@ pub fn with_include_paths(self, include_paths: Vec<std::path::PathBuf>) -> Self
         Self { config }
     }
 
+    /// Create a new configuration that sets the library paths used for looking up
+    /// `@library/file.60` imports to the specified map of library names to directories.
+    pub fn with_library_paths(
+        self,
+        library_paths: std::collections::HashMap<String, std::path::PathBuf>,
+    ) -> Self {
+        let mut config = self.config;
+        config.library_paths = library_paths;
+        Self { config }
+    }
+
     /// Create a new configuration that selects the style to be used for widgets.
+    ///
+    /// The style is either the name of a builtin style, or the path of a directory containing
+    /// a `sixtyfps_widgets.60` file that exports the same widgets as the builtin styles.
     pub fn with_style(self, style: String) -> Self {
         let mut config = self.config;
         config.style = Some(style);
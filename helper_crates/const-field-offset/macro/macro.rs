@@ -225,6 +225,7 @@ impl #drop_trait_ident for #struct_name {}
             },
             Some(quote! {
                     /// Make sure that Unpin is not implemented
+                    #[allow(dead_code)]
                     pub struct __MustNotImplUnpin<'__dummy_lifetime> (
                         #(#types, )*
                         ::core::marker::PhantomData<&'__dummy_lifetime ()>
@@ -61,6 +61,12 @@ pub fn lower_macro(
             expr
         }
         BuiltinMacroFunction::Rgb => rgb_macro(n, sub_expr.collect(), diag),
+        BuiltinMacroFunction::Hsv => {
+            hsv_hsl_macro(n, BuiltinFunction::Hsv, sub_expr.collect(), diag)
+        }
+        BuiltinMacroFunction::Hsl => {
+            hsv_hsl_macro(n, BuiltinFunction::Hsl, sub_expr.collect(), diag)
+        }
     }
 }
 
@@ -138,6 +144,129 @@ fn rgb_macro(
     }
 }
 
+/// Lower `hsv(h, s, v, [a])` and `hsl(h, s, l, [a])` to a call to the builtin function `f`.
+/// When all the arguments are number literals, the color is computed at compile time.
+fn hsv_hsl_macro(
+    node: Option<NodeOrToken>,
+    f: BuiltinFunction,
+    args: Vec<(Expression, Option<NodeOrToken>)>,
+    diag: &mut BuildDiagnostics,
+) -> Expression {
+    if args.len() < 3 || args.len() > 4 {
+        diag.push_error("Needs 3 or 4 argument".into(), &node);
+        return Expression::Invalid;
+    }
+    let mut arguments: Vec<_> =
+        args.into_iter().map(|(expr, n)| expr.maybe_convert_to(Type::Float32, &n, diag)).collect();
+    if arguments.len() < 4 {
+        arguments.push(Expression::NumberLiteral(1., Unit::None))
+    }
+    let constants: Vec<_> =
+        arguments.iter().map(|e| constant_number(e).map(|x| x as f32)).collect();
+    if let [Some(h), Some(s), Some(v), Some(a)] = constants[..] {
+        let argb = match f {
+            BuiltinFunction::Hsv => hsva_to_argb(h, s, v, a),
+            _ => hsla_to_argb(h, s, v, a),
+        };
+        return Expression::Cast {
+            from: Box::new(Expression::NumberLiteral(argb as _, Unit::None)),
+            to: Type::Color,
+        };
+    }
+    Expression::FunctionCall {
+        function: Box::new(Expression::BuiltinFunctionReference(
+            f,
+            node.as_ref().map(|t| t.to_source_location()),
+        )),
+        arguments,
+        source_location: Some(node.to_source_location()),
+    }
+}
+
+/// Returns the value of `expr` if it is a number literal, possibly negated or converted
+fn constant_number(expr: &Expression) -> Option<f64> {
+    match expr {
+        Expression::NumberLiteral(value, Unit::None | Unit::Percent) => Some(*value),
+        Expression::Cast { from, to: Type::Float32 } => constant_number(from),
+        Expression::UnaryOp { sub, op: '-' } => constant_number(sub).map(|x| -x),
+        Expression::BinaryExpression { lhs, rhs, op: '*' } => {
+            Some(constant_number(lhs)? * constant_number(rhs)?)
+        }
+        _ => None,
+    }
+}
+
+/// Same as `Color::from_hsva` in the run-time, returning the color encoded as `0xAARRGGBB`
+pub fn hsva_to_argb(hue: f32, saturation: f32, value: f32, alpha: f32) -> u32 {
+    // Must give the same result as the run-time, so this uses the same f32 arithmetic
+    let h = hue.rem_euclid(360.);
+    let s = saturation.clamp(0., 1.);
+    let v = value.clamp(0., 1.);
+    let alpha = alpha.clamp(0., 1.);
+    let chroma = s * v;
+    let x = chroma * (1. - ((h / 60.) % 2. - 1.).abs());
+    let (red, green, blue) = match (h / 60.0) as usize {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        5 => (chroma, 0., x),
+        _ => (0., 0., 0.),
+    };
+    let m = v - chroma;
+    let channel = |c: f32| ((c * 255.) as u8) as u32;
+    channel(alpha) << 24 | channel(red + m) << 16 | channel(green + m) << 8 | channel(blue + m)
+}
+
+/// Same as `Color::from_hsla` in the run-time, returning the color encoded as `0xAARRGGBB`
+pub fn hsla_to_argb(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> u32 {
+    let saturation = saturation.clamp(0., 1.);
+    let lightness = lightness.clamp(0., 1.);
+    let value = lightness + saturation * lightness.min(1. - lightness);
+    let saturation = if value == 0. { 0. } else { 2. * (1. - lightness / value) };
+    hsva_to_argb(hue, saturation, value, alpha)
+}
+
+#[test]
+fn test_hsv_hsl_to_argb() {
+    assert_eq!(hsva_to_argb(0., 1., 1., 1.), 0xffff0000);
+    assert_eq!(hsva_to_argb(120., 1., 0.5, 0.5), 0x7f007f00);
+    assert_eq!(hsva_to_argb(-120., 1., 1., 1.), 0xff0000ff);
+    assert_eq!(hsla_to_argb(0., 1., 0.5, 1.), 0xffff0000);
+    assert_eq!(hsla_to_argb(240., 1., 0.25, 1.), 0xff00007f);
+    assert_eq!(hsla_to_argb(0., 0., 1., 1.), 0xffffffff);
+}
+
+#[test]
+fn test_hsv_hsl_same_as_runtime() {
+    use sixtyfps_corelib::Color;
+    for hue in (-360..=720).step_by(15) {
+        let hue = hue as f32;
+        for a in 0..=10 {
+            for b in 0..=10 {
+                let (a, b) = (a as f32 / 10., b as f32 / 10.);
+                assert_eq!(
+                    hsva_to_argb(hue, a, b, 0.5),
+                    Color::from_hsva(hue, a, b, 0.5).as_argb_encoded(),
+                    "hsv({}, {}, {})",
+                    hue,
+                    a,
+                    b
+                );
+                assert_eq!(
+                    hsla_to_argb(hue, a, b, 0.5),
+                    Color::from_hsla(hue, a, b, 0.5).as_argb_encoded(),
+                    "hsl({}, {}, {})",
+                    hue,
+                    a,
+                    b
+                );
+            }
+        }
+    }
+}
+
 fn debug_macro(
     node: Option<NodeOrToken>,
     args: Vec<(Expression, Option<NodeOrToken>)>,
@@ -13,12 +13,14 @@
 
 /// Span represent an error location within a file.
 ///
-/// Currently, it is just an offset in byte within the file.
+/// Currently, it is just an offset in byte within the file, and the length in byte of the
+/// code it spans. The length may be 0 if the span only represent a position.
 ///
 /// When the `proc_macro_span` feature is enabled, it may also hold a proc_macro span.
 #[derive(Debug, Clone)]
 pub struct Span {
     pub offset: usize,
+    pub length: usize,
     #[cfg(feature = "proc_macro_span")]
     pub span: Option<proc_macro::Span>,
 }
@@ -31,12 +33,17 @@ pub fn is_valid(&self) -> bool {
     pub fn new(offset: usize) -> Self {
         Self { offset, ..Default::default() }
     }
+
+    pub fn new_with_length(offset: usize, length: usize) -> Self {
+        Self { length, ..Self::new(offset) }
+    }
 }
 
 impl Default for Span {
     fn default() -> Self {
         Span {
             offset: usize::MAX,
+            length: 0,
             #[cfg(feature = "proc_macro_span")]
             span: Default::default(),
         }
@@ -91,6 +98,11 @@ pub fn path(&self) -> &Path {
         &self.path
     }
 
+    /// The source code of the file, if it was loaded
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
     /// Create a SourceFile that has just a path, but no contents
     pub fn from_path_only(path: PathBuf) -> Rc<Self> {
         Rc::new(Self { path, ..Default::default() })
@@ -109,6 +121,15 @@ fn line_offsets(&self) -> &[usize] {
                 .unwrap_or_default()
         })
     }
+
+    /// Returns a tuple with the line and column number (both starting at 1) of the given
+    /// byte offset. The column is counted in bytes.
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let line_offsets = self.line_offsets();
+        let line = line_offsets.partition_point(|x| *x < offset);
+        let line_start = if line == 0 { 0 } else { line_offsets[line - 1] + 1 };
+        (line + 1, offset.saturating_sub(line_start) + 1)
+    }
 }
 
 pub type SourceFile = Rc<SourceFileInner>;
@@ -121,6 +142,9 @@ pub fn load_from_path(path: &Path) -> Result<String, Diagnostic> {
             span: Default::default(),
         },
         level: DiagnosticLevel::Error,
+        code: DiagnosticCode::CannotLoadFile,
+        notes: vec![],
+        suggestions: vec![],
     })
 }
 
@@ -130,6 +154,21 @@ pub struct SourceLocation {
     pub span: Span,
 }
 
+impl SourceLocation {
+    /// Returns the line and column (both starting at 1) of the start and the end of this
+    /// location, or None if the location is not within a source file.
+    pub fn line_column_range(&self) -> Option<((usize, usize), (usize, usize))> {
+        let sf = self.source_file.as_ref()?;
+        if !self.span.is_valid() || sf.source.is_none() {
+            return None;
+        }
+        Some((
+            sf.line_column(self.span.offset),
+            sf.line_column(self.span.offset + self.span.length),
+        ))
+    }
+}
+
 impl Spanned for SourceLocation {
     fn span(&self) -> Span {
         self.span.clone()
@@ -173,18 +212,250 @@ fn from(l: DiagnosticLevel) -> Self {
     }
 }
 
+/// A code identifying the kind of a diagnostic.
+///
+/// The string representation of the code, returned by [`Self::as_str()`], is stable, so tools
+/// can rely on it even if the message changes. Codes of errors start with `E`, codes of
+/// warnings start with `W`, and codes of lints, which can be either, start with `L`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum DiagnosticCode {
+    /// An error that does not have a more specific code
+    GenericError,
+    /// A warning that does not have a more specific code
+    GenericWarning,
+    SyntaxError,
+    CannotLoadFile,
+    ImportNotFound,
+    RecursiveImport,
+    UnknownLibrary,
+    NoSuchExport,
+    InvalidStyle,
+    UnknownType,
+    UnknownProperty,
+    UnknownIdentifier,
+    DuplicatedElementId,
+    DuplicatedBinding,
+    TypeMismatch,
+    WrongArgumentCount,
+    BindingLoop,
+    DeprecatedProperty,
+    Lint(Lint),
+}
+
+impl DiagnosticCode {
+    /// Returns the stable string representation of this code
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::GenericError => "E0000",
+            Self::GenericWarning => "W0000",
+            Self::SyntaxError => "E0001",
+            Self::CannotLoadFile => "E0101",
+            Self::ImportNotFound => "E0102",
+            Self::RecursiveImport => "E0103",
+            Self::UnknownLibrary => "E0104",
+            Self::NoSuchExport => "E0105",
+            Self::InvalidStyle => "E0106",
+            Self::UnknownType => "E0201",
+            Self::UnknownProperty => "E0202",
+            Self::UnknownIdentifier => "E0203",
+            Self::DuplicatedElementId => "E0204",
+            Self::DuplicatedBinding => "E0205",
+            Self::TypeMismatch => "E0301",
+            Self::WrongArgumentCount => "E0302",
+            Self::BindingLoop => "E0303",
+            Self::DeprecatedProperty => "W0001",
+            Self::Lint(lint) => lint.code(),
+        }
+    }
+
+    /// The code of the diagnostics reported without a more specific code
+    fn generic(level: DiagnosticLevel) -> Self {
+        match level {
+            DiagnosticLevel::Error => Self::GenericError,
+            DiagnosticLevel::Warning => Self::GenericWarning,
+        }
+    }
+}
+
+impl std::fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The optional checks reporting code that is valid but probably not what was intended.
+///
+/// They are enabled with [`crate::CompilerConfiguration::lints`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum Lint {
+    /// A property is declared but never read or set
+    UnusedProperty,
+    /// A callback is declared but never connected
+    UnusedCallback,
+    /// An element has an id which is never used
+    UnusedId,
+    /// The `when` condition of a state is a constant
+    ConstantStateCondition,
+    /// A binding is never used because it is overridden by a state which is always active
+    OverriddenBinding,
+    /// An element has a constant zero size, or is never visible
+    InvisibleElement,
+}
+
+impl Lint {
+    pub const ALL: &'static [Lint] = &[
+        Lint::UnusedProperty,
+        Lint::UnusedCallback,
+        Lint::UnusedId,
+        Lint::ConstantStateCondition,
+        Lint::OverriddenBinding,
+        Lint::InvisibleElement,
+    ];
+
+    /// The name used to enable this lint from the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedProperty => "unused-property",
+            Lint::UnusedCallback => "unused-callback",
+            Lint::UnusedId => "unused-id",
+            Lint::ConstantStateCondition => "constant-state-condition",
+            Lint::OverriddenBinding => "overridden-binding",
+            Lint::InvisibleElement => "invisible-element",
+        }
+    }
+
+    /// Parse a lint given on the command line of the tools, with `-W` or `-D`: the name of a
+    /// lint, or 'all' for all the lints
+    pub fn parse_list(s: &str) -> Result<Vec<Lint>, String> {
+        if s == "all" {
+            Ok(Lint::ALL.to_vec())
+        } else {
+            s.parse().map(|lint| vec![lint])
+        }
+    }
+
+    fn code(self) -> &'static str {
+        match self {
+            Lint::UnusedProperty => "L0001",
+            Lint::UnusedCallback => "L0002",
+            Lint::UnusedId => "L0003",
+            Lint::ConstantStateCondition => "L0004",
+            Lint::OverriddenBinding => "L0005",
+            Lint::InvisibleElement => "L0006",
+        }
+    }
+}
+
+impl std::str::FromStr for Lint {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lint::ALL
+            .iter()
+            .find(|l| l.name() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown lint {}", s))
+    }
+}
+
+/// How a [`Lint`] is reported
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum LintLevel {
+    /// The lint is not reported
+    Allow,
+    /// The lint is reported as a warning
+    Warn,
+    /// The lint is reported as an error
+    Deny,
+}
+
+impl Default for LintLevel {
+    fn default() -> Self {
+        Self::Allow
+    }
+}
+
+/// The level of the lints reported as warnings and of the lints reported as errors, as given
+/// on the command line of the tools. A lint in both lists is reported as an error.
+pub fn lint_levels(
+    warn_lints: impl IntoIterator<Item = Lint>,
+    deny_lints: impl IntoIterator<Item = Lint>,
+) -> std::collections::HashMap<Lint, LintLevel> {
+    warn_lints
+        .into_iter()
+        .map(|lint| (lint, LintLevel::Warn))
+        .chain(deny_lints.into_iter().map(|lint| (lint, LintLevel::Deny)))
+        .collect()
+}
+
+/// A note giving more context to a diagnostic, attached to another position in the code
+#[derive(Debug, Clone)]
+pub struct DiagnosticNote {
+    pub message: String,
+    pub span: SourceLocation,
+}
+
+/// A suggested fix for a diagnostic: the code covered by `span` should be replaced by
+/// `replacement`
+#[derive(Debug, Clone)]
+pub struct DiagnosticSuggestion {
+    pub message: String,
+    pub span: SourceLocation,
+    pub replacement: String,
+}
+
 /// This structure represent a diagnostic emitted while compiling .60 code.
 ///
 /// It is basically a message, a level (warning or error), attached to a
-/// position in the code
+/// position in the code. It may also have notes pointing to related code, and
+/// suggestions of replacement.
 #[derive(Debug, Clone)]
 pub struct Diagnostic {
     message: String,
     span: SourceLocation,
     level: DiagnosticLevel,
+    code: DiagnosticCode,
+    notes: Vec<DiagnosticNote>,
+    suggestions: Vec<DiagnosticSuggestion>,
 }
 
 impl Diagnostic {
+    pub fn new(
+        level: DiagnosticLevel,
+        code: DiagnosticCode,
+        message: String,
+        source: &dyn Spanned,
+    ) -> Self {
+        Self {
+            message,
+            span: source.to_source_location(),
+            level,
+            code,
+            notes: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    /// Add a note pointing to `source`
+    pub fn with_note(mut self, message: String, source: &dyn Spanned) -> Self {
+        self.notes.push(DiagnosticNote { message, span: source.to_source_location() });
+        self
+    }
+
+    /// Add a suggestion to replace the code of `source` by `replacement`
+    pub fn with_suggestion(
+        mut self,
+        message: String,
+        source: &dyn Spanned,
+        replacement: String,
+    ) -> Self {
+        self.suggestions.push(DiagnosticSuggestion {
+            message,
+            span: source.to_source_location(),
+            replacement,
+        });
+        self
+    }
+
     /// Return the level for this diagnostic
     pub fn level(&self) -> DiagnosticLevel {
         self.level
@@ -195,6 +466,26 @@ pub fn message(&self) -> &str {
         &self.message
     }
 
+    /// Return the code identifying the kind of this diagnostic
+    pub fn code(&self) -> DiagnosticCode {
+        self.code
+    }
+
+    /// Return the location in the code of this diagnostic
+    pub fn source_location(&self) -> &SourceLocation {
+        &self.span
+    }
+
+    /// Return the notes giving more context to this diagnostic
+    pub fn notes(&self) -> &[DiagnosticNote] {
+        &self.notes
+    }
+
+    /// Return the suggested fixes for this diagnostic
+    pub fn suggestions(&self) -> &[DiagnosticSuggestion] {
+        &self.suggestions
+    }
+
     /// Returns a tuple with the line (starting at 1) and column number (starting at 0)
     pub fn line_column(&self) -> (usize, usize) {
         let offset = self.span.span.offset;
@@ -256,13 +547,34 @@ pub fn push_diagnostic_with_span(
         message: String,
         span: SourceLocation,
         level: DiagnosticLevel,
+    ) {
+        self.push_diagnostic_with_span_and_code(
+            message,
+            span,
+            level,
+            DiagnosticCode::generic(level),
+        )
+    }
+    pub fn push_diagnostic_with_span_and_code(
+        &mut self,
+        message: String,
+        span: SourceLocation,
+        level: DiagnosticLevel,
+        code: DiagnosticCode,
     ) {
         debug_assert!(
             !message.as_str().ends_with('.'),
             "Error message should not end with a period: ({:?})",
             message
         );
-        self.inner.push(Diagnostic { message, span, level }.into());
+        self.inner.push(Diagnostic {
+            message,
+            span,
+            level,
+            code,
+            notes: vec![],
+            suggestions: vec![],
+        });
     }
     pub fn push_error_with_span(&mut self, message: String, span: SourceLocation) {
         self.push_diagnostic_with_span(message, span, DiagnosticLevel::Error)
@@ -270,6 +582,19 @@ pub fn push_error_with_span(&mut self, message: String, span: SourceLocation) {
     pub fn push_error(&mut self, message: String, source: &dyn Spanned) {
         self.push_error_with_span(message, source.to_source_location());
     }
+    pub fn push_error_with_code(
+        &mut self,
+        code: DiagnosticCode,
+        message: String,
+        source: &dyn Spanned,
+    ) {
+        self.push_diagnostic_with_span_and_code(
+            message,
+            source.to_source_location(),
+            DiagnosticLevel::Error,
+            code,
+        );
+    }
     pub fn push_compiler_error(&mut self, error: Diagnostic) {
         self.inner.push(error.into());
     }
@@ -280,13 +605,21 @@ pub fn push_property_deprecation_warning(
         new_property: &str,
         source: &dyn Spanned,
     ) {
-        self.push_diagnostic_with_span(
-            format!(
-                "The property '{}' has been deprecated. Please use '{}' instead",
-                old_property, new_property
+        self.push_compiler_error(
+            Diagnostic::new(
+                DiagnosticLevel::Warning,
+                DiagnosticCode::DeprecatedProperty,
+                format!(
+                    "The property '{}' has been deprecated. Please use '{}' instead",
+                    old_property, new_property
+                ),
+                source,
+            )
+            .with_suggestion(
+                format!("Use '{}'", new_property),
+                source,
+                new_property.replace('_', "-"),
             ),
-            source.to_source_location(),
-            crate::diagnostics::DiagnosticLevel::Warning,
         )
     }
 
@@ -344,7 +677,22 @@ fn call_diagnostics<'a, Output>(
                         style: codemap_diagnostic::SpanStyle::Primary,
                         label: None,
                     };
-                    vec![s]
+                    // Only the notes within the same file can be shown next to the code
+                    let notes = d.notes.iter().filter(|n| {
+                        n.span.span.is_valid()
+                            && n.span.span.offset != d.span.span.offset
+                            && n.span.source_file.as_ref().map_or(false, |f| f.path == sf.path)
+                    });
+                    std::iter::once(s)
+                        .chain(notes.map(|n| {
+                            codemap_diagnostic::SpanLabel {
+                                span: file_span
+                                    .subspan(n.span.span.offset as u64, n.span.span.offset as u64),
+                                style: codemap_diagnostic::SpanStyle::Secondary,
+                                label: Some(n.message.clone()),
+                            }
+                        }))
+                        .collect()
                 } else {
                     vec![]
                 };
@@ -475,3 +823,55 @@ pub fn print_warnings_and_exit_on_error(self) {
         }
     }
 }
+
+#[test]
+fn test_diagnostic_code_and_suggestion() {
+    let source = "X := Rectangle {\n    Image { maximum-width: 3px; }\n}\n".to_owned();
+    let mut diag = BuildDiagnostics::default();
+    let node = crate::parser::parse(source, Some(Path::new("test.60")), &mut diag);
+    assert!(!diag.has_error());
+    let compiler_config =
+        crate::CompilerConfiguration::new(crate::generator::OutputFormat::Interpreter);
+    let (_, diag) = spin_on::spin_on(crate::compile_syntax_node(node, diag, compiler_config));
+    let warning = diag
+        .iter()
+        .find(|d| d.code() == DiagnosticCode::DeprecatedProperty)
+        .expect("deprecation warning");
+    assert_eq!(warning.level(), DiagnosticLevel::Warning);
+    assert_eq!(warning.code().as_str(), "W0001");
+    assert_eq!(warning.source_location().line_column_range(), Some(((2, 13), (2, 26))));
+    let suggestion = &warning.suggestions()[0];
+    assert_eq!(suggestion.replacement, "max-width");
+    assert_eq!(suggestion.span.line_column_range(), Some(((2, 13), (2, 26))));
+}
+
+#[test]
+fn test_generic_warning_code() {
+    let source = "X := Rectangle {}\n".to_owned();
+    let mut diag = BuildDiagnostics::default();
+    let node = crate::parser::parse(source, Some(Path::new("test.60")), &mut diag);
+    let mut compiler_config =
+        crate::CompilerConfiguration::new(crate::generator::OutputFormat::Interpreter);
+    compiler_config.style = Some("does_not_exist".into());
+    let (_, diag) = spin_on::spin_on(crate::compile_syntax_node(node, diag, compiler_config));
+    assert!(!diag.has_error());
+    // A warning without a more specific code has the generic one
+    let warning = diag.iter().next().expect("unknown style warning");
+    assert_eq!(warning.level(), DiagnosticLevel::Warning);
+    assert_eq!(warning.code(), DiagnosticCode::GenericWarning);
+    assert_eq!(warning.code().as_str(), "W0000");
+}
+
+#[test]
+fn test_lint_levels_from_command_line() {
+    assert_eq!(Lint::parse_list("all").unwrap(), Lint::ALL);
+    assert_eq!(Lint::parse_list("unused-id").unwrap(), vec![Lint::UnusedId]);
+    assert!(Lint::parse_list("unused").is_err());
+    let levels = lint_levels(
+        vec![Lint::UnusedId, Lint::UnusedProperty],
+        Lint::parse_list("unused-property").unwrap(),
+    );
+    assert_eq!(levels.len(), 2);
+    assert_eq!(levels[&Lint::UnusedId], LintLevel::Warn);
+    assert_eq!(levels[&Lint::UnusedProperty], LintLevel::Deny);
+}
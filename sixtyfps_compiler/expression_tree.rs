@@ -7,7 +7,7 @@
     This file is also available under commercial licensing terms.
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
-use crate::diagnostics::{BuildDiagnostics, SourceLocation, Spanned};
+use crate::diagnostics::{BuildDiagnostics, DiagnosticCode, SourceLocation, Spanned};
 use crate::langtype::{BuiltinElement, EnumerationValue, Type};
 use crate::layout::Orientation;
 use crate::object_tree::*;
@@ -38,14 +38,69 @@ pub enum BuiltinFunction {
     ATan,
     SetFocusItem,
     ShowPopupWindow,
+    /// the `dialog.show()`
+    ShowDialog,
+    /// the `dialog.close()`
+    CloseDialog,
+    /// The position of a standard button in the button row of a dialog, according to the
+    /// platform convention. Buttons with a higher rank are placed further right.
+    StandardButtonRank,
+    /// Whether the platform shows the context menus with native menus
+    HasNativeMenus,
+    /// Show the context menu of the given `ContextMenu` element with a native menu. The second
+    /// argument is an array literal of the `MenuEntry` of the menu, and the third a reference to
+    /// the callback called with the index of the activated entry
+    ShowNativeMenu,
+    /// Show a native menu bar. The first argument is an array literal of the `MenuEntry` of the
+    /// menus, evaluated each time the menus are shown, and the second a reference to the callback
+    /// called with the index of the activated entry
+    SetNativeMenuBar,
     /// the "42".to_float()
     StringToFloat,
     /// the "42".is_float()
     StringIsFloat,
+    /// the `value.to_fixed(digits)`
+    NumberToFixed,
+    /// the `value.to_grouped(digits)`
+    NumberToGrouped,
+    /// the `value.to_padded(width)`
+    NumberToPadded,
+    /// the `value.to_percent(digits)`
+    NumberToPercent,
+    /// the `value.to_byte_size()`
+    NumberToByteSize,
+    /// the `text.to_upper()`
+    StringToUpper,
+    /// the `text.to_lower()`
+    StringToLower,
+    /// the `text.trim()`
+    StringTrim,
+    /// the `text.contains(other)`
+    StringContains,
+    /// the `text.starts_with(other)`
+    StringStartsWith,
+    /// the `text.ends_with(other)`
+    StringEndsWith,
+    /// the `text.length`
+    StringLength,
+    /// the `text.substring(start, length)`
+    StringSubstring,
     ColorBrighter,
     ColorDarker,
+    /// the `color.with_alpha(alpha)`
+    ColorWithAlpha,
+    /// the `color.transparentize(amount)`
+    ColorTransparentize,
+    /// the `color.mix(other, factor)`
+    ColorMix,
+    /// the `color.luminance`
+    ColorLuminance,
     ImageSize,
+    /// the `model.length`
+    ArrayLength,
     Rgb,
+    Hsv,
+    Hsl,
     ImplicitLayoutInfo(Orientation),
     RegisterCustomFontByPath,
     RegisterCustomFontByMemory,
@@ -58,6 +113,8 @@ pub enum BuiltinMacroFunction {
     Max,
     CubicBezier,
     Rgb,
+    Hsv,
+    Hsl,
     Debug,
 }
 
@@ -91,16 +148,68 @@ pub fn ty(&self) -> Type {
                 return_type: Box::new(Type::Void),
                 args: vec![Type::ElementReference],
             },
-            BuiltinFunction::ShowPopupWindow => Type::Function {
+            BuiltinFunction::ShowPopupWindow
+            | BuiltinFunction::ShowDialog
+            | BuiltinFunction::CloseDialog => Type::Function {
                 return_type: Box::new(Type::Void),
                 args: vec![Type::ElementReference],
             },
+            BuiltinFunction::StandardButtonRank => Type::Function {
+                return_type: Box::new(Type::Int32),
+                args: vec![crate::typeregister::standard_button_kind_type()],
+            },
+            BuiltinFunction::HasNativeMenus => {
+                Type::Function { return_type: Box::new(Type::Bool), args: vec![] }
+            }
+            BuiltinFunction::ShowNativeMenu => Type::Function {
+                return_type: Box::new(Type::Void),
+                args: vec![
+                    Type::ElementReference,
+                    Type::Model,
+                    Type::Callback { return_type: None, args: vec![Type::Int32] },
+                ],
+            },
+            BuiltinFunction::SetNativeMenuBar => Type::Function {
+                return_type: Box::new(Type::Void),
+                args: vec![
+                    Type::Model,
+                    Type::Callback { return_type: None, args: vec![Type::Int32] },
+                ],
+            },
             BuiltinFunction::StringToFloat => {
                 Type::Function { return_type: Box::new(Type::Float32), args: vec![Type::String] }
             }
             BuiltinFunction::StringIsFloat => {
                 Type::Function { return_type: Box::new(Type::Bool), args: vec![Type::String] }
             }
+            BuiltinFunction::NumberToFixed
+            | BuiltinFunction::NumberToGrouped
+            | BuiltinFunction::NumberToPadded
+            | BuiltinFunction::NumberToPercent => Type::Function {
+                return_type: Box::new(Type::String),
+                args: vec![Type::Float32, Type::Int32],
+            },
+            BuiltinFunction::NumberToByteSize => {
+                Type::Function { return_type: Box::new(Type::String), args: vec![Type::Float32] }
+            }
+            BuiltinFunction::StringToUpper
+            | BuiltinFunction::StringToLower
+            | BuiltinFunction::StringTrim => {
+                Type::Function { return_type: Box::new(Type::String), args: vec![Type::String] }
+            }
+            BuiltinFunction::StringContains
+            | BuiltinFunction::StringStartsWith
+            | BuiltinFunction::StringEndsWith => Type::Function {
+                return_type: Box::new(Type::Bool),
+                args: vec![Type::String, Type::String],
+            },
+            BuiltinFunction::StringLength => {
+                Type::Function { return_type: Box::new(Type::Int32), args: vec![Type::String] }
+            }
+            BuiltinFunction::StringSubstring => Type::Function {
+                return_type: Box::new(Type::String),
+                args: vec![Type::String, Type::Int32, Type::Int32],
+            },
             BuiltinFunction::ImplicitLayoutInfo(_) => Type::Function {
                 return_type: Box::new(crate::layout::layout_info_type()),
                 args: vec![Type::ElementReference],
@@ -113,6 +222,19 @@ pub fn ty(&self) -> Type {
                 return_type: Box::new(Type::Color),
                 args: vec![Type::Color, Type::Float32],
             },
+            BuiltinFunction::ColorWithAlpha | BuiltinFunction::ColorTransparentize => {
+                Type::Function {
+                    return_type: Box::new(Type::Color),
+                    args: vec![Type::Color, Type::Float32],
+                }
+            }
+            BuiltinFunction::ColorMix => Type::Function {
+                return_type: Box::new(Type::Color),
+                args: vec![Type::Color, Type::Color, Type::Float32],
+            },
+            BuiltinFunction::ColorLuminance => {
+                Type::Function { return_type: Box::new(Type::Float32), args: vec![Type::Color] }
+            }
             BuiltinFunction::ImageSize => Type::Function {
                 return_type: Box::new(Type::Struct {
                     fields: std::array::IntoIter::new([
@@ -125,10 +247,17 @@ pub fn ty(&self) -> Type {
                 }),
                 args: vec![Type::Image],
             },
+            BuiltinFunction::ArrayLength => {
+                Type::Function { return_type: Box::new(Type::Int32), args: vec![Type::Model] }
+            }
             BuiltinFunction::Rgb => Type::Function {
                 return_type: Box::new(Type::Color),
                 args: vec![Type::Int32, Type::Int32, Type::Int32, Type::Float32],
             },
+            BuiltinFunction::Hsv | BuiltinFunction::Hsl => Type::Function {
+                return_type: Box::new(Type::Color),
+                args: vec![Type::Float32, Type::Float32, Type::Float32, Type::Float32],
+            },
             BuiltinFunction::RegisterCustomFontByPath => {
                 Type::Function { return_type: Box::new(Type::Void), args: vec![Type::String] }
             }
@@ -158,8 +287,31 @@ fn is_pure(&self) -> bool {
             | BuiltinFunction::ATan => true,
             BuiltinFunction::SetFocusItem => false,
             BuiltinFunction::ShowPopupWindow => false,
+            BuiltinFunction::ShowDialog | BuiltinFunction::CloseDialog => false,
+            // Depends on the desktop environment the program runs in
+            BuiltinFunction::StandardButtonRank => false,
+            BuiltinFunction::HasNativeMenus
+            | BuiltinFunction::ShowNativeMenu
+            | BuiltinFunction::SetNativeMenuBar => false,
             BuiltinFunction::StringToFloat | BuiltinFunction::StringIsFloat => true,
+            BuiltinFunction::NumberToFixed
+            | BuiltinFunction::NumberToGrouped
+            | BuiltinFunction::NumberToPadded
+            | BuiltinFunction::NumberToPercent
+            | BuiltinFunction::NumberToByteSize => true,
+            BuiltinFunction::StringToUpper
+            | BuiltinFunction::StringToLower
+            | BuiltinFunction::StringTrim
+            | BuiltinFunction::StringContains
+            | BuiltinFunction::StringStartsWith
+            | BuiltinFunction::StringEndsWith
+            | BuiltinFunction::StringLength
+            | BuiltinFunction::StringSubstring => true,
             BuiltinFunction::ColorBrighter | BuiltinFunction::ColorDarker => true,
+            BuiltinFunction::ColorWithAlpha
+            | BuiltinFunction::ColorTransparentize
+            | BuiltinFunction::ColorMix
+            | BuiltinFunction::ColorLuminance => true,
             // ImageSize is pure, except when loading images via the network. Then the initial size will be 0/0 and
             // we need to make sure that calls to this function stay within a binding, so that the property
             // notification when updating kicks in. Only the online editor (wasm-interpreter) loads images via the network,
@@ -168,7 +320,9 @@ fn is_pure(&self) -> bool {
             BuiltinFunction::ImageSize => true,
             #[cfg(target_arch = "wasm32")]
             BuiltinFunction::ImageSize => false,
-            BuiltinFunction::Rgb => true,
+            // The length of a model changes when rows are added or removed
+            BuiltinFunction::ArrayLength => false,
+            BuiltinFunction::Rgb | BuiltinFunction::Hsv | BuiltinFunction::Hsl => true,
             BuiltinFunction::ImplicitLayoutInfo(_) => false,
             BuiltinFunction::RegisterCustomFontByPath
             | BuiltinFunction::RegisterCustomFontByMemory => false,
@@ -372,6 +526,14 @@ pub enum Expression {
         name: String,
     },
 
+    /// Access to the element at the given index of an array or model.
+    /// Evaluates to the default value of the element type if the index is out of range.
+    ArrayIndex {
+        /// This expression should have [`Type::Array`] type
+        array: Box<Expression>,
+        index: Box<Expression>,
+    },
+
     /// Cast an expression to the given type
     Cast {
         from: Box<Expression>,
@@ -504,6 +666,10 @@ pub fn ty(&self) -> Type {
                 Type::Component(c) => c.root_element.borrow().lookup_property(name).property_type,
                 _ => Type::Invalid,
             },
+            Expression::ArrayIndex { array, .. } => match array.ty() {
+                Type::Array(ty) => *ty,
+                _ => Type::Invalid,
+            },
             Expression::Cast { to, .. } => to.clone(),
             Expression::CodeBlock(sub) => sub.last().map_or(Type::Void, |e| e.ty()),
             Expression::FunctionCall { function, .. } => match function.ty() {
@@ -615,6 +781,10 @@ pub fn visit(&self, mut visitor: impl FnMut(&Self)) {
             Expression::BuiltinMacroReference { .. } => {}
             Expression::ElementReference(_) => {}
             Expression::StructFieldAccess { base, .. } => visitor(&**base),
+            Expression::ArrayIndex { array, index } => {
+                visitor(array);
+                visitor(index);
+            }
             Expression::RepeaterIndexReference { .. } => {}
             Expression::RepeaterModelReference { .. } => {}
             Expression::Cast { from, .. } => visitor(&**from),
@@ -702,6 +872,10 @@ pub fn visit_mut(&mut self, mut visitor: impl FnMut(&mut Self)) {
             Expression::BuiltinMacroReference { .. } => {}
             Expression::ElementReference(_) => {}
             Expression::StructFieldAccess { base, .. } => visitor(&mut **base),
+            Expression::ArrayIndex { array, index } => {
+                visitor(array);
+                visitor(index);
+            }
             Expression::RepeaterIndexReference { .. } => {}
             Expression::RepeaterModelReference { .. } => {}
             Expression::Cast { from, .. } => visitor(&mut **from),
@@ -792,6 +966,8 @@ pub fn is_constant(&self) -> bool {
             Expression::FunctionParameterReference { .. } => false,
             Expression::BuiltinMacroReference { .. } => true,
             Expression::StructFieldAccess { base, .. } => base.is_constant(),
+            // The data of a model can change
+            Expression::ArrayIndex { .. } => false,
             Expression::Cast { from, .. } => from.is_constant(),
             Expression::CodeBlock(sub) => sub.len() == 1 && sub.first().unwrap().is_constant(),
             Expression::FunctionCall { function, arguments, .. } => {
@@ -981,7 +1157,7 @@ pub fn maybe_convert_to(
                     );
                 }
             }
-            diag.push_error(message, node);
+            diag.push_error_with_code(DiagnosticCode::TypeMismatch, message, node);
             self
         }
     }
@@ -1200,6 +1376,12 @@ pub fn pretty_print(f: &mut dyn std::fmt::Write, expression: &Expression) -> std
             pretty_print(f, base)?;
             write!(f, ".{}", name)
         }
+        Expression::ArrayIndex { array, index } => {
+            pretty_print(f, array)?;
+            write!(f, "[")?;
+            pretty_print(f, index)?;
+            write!(f, "]")
+        }
         Expression::Cast { from, to } => {
             write!(f, "(")?;
             pretty_print(f, from)?;
@@ -97,39 +97,62 @@ pub fn generate(
 ///  1. the item
 ///  2. the first_children_offset,
 ///  3. the parent index
+///  4. the instances of sub-components the item is in, from the outermost to the innermost
+///
+/// An instance of a sub-component is replaced by the root element of that sub-component,
+/// followed by its children.
 #[allow(dead_code)]
-pub fn build_array_helper(component: &Component, mut visit_item: impl FnMut(&ElementRc, u32, u32)) {
-    visit_item(&component.root_element, 1, 0);
-    visit_children(&component.root_element, &mut 0, 1, &mut visit_item);
-
-    fn sub_children_count(e: &ElementRc) -> usize {
-        let mut count = e.borrow().children.len();
-        for i in &e.borrow().children {
-            count += sub_children_count(i);
+pub fn build_array_helper(
+    component: &Component,
+    mut visit_item: impl FnMut(&ElementRc, u32, u32, &[ElementRc]),
+) {
+    visit_item(&component.root_element, 1, 0, &[]);
+    visit_children(&component.root_element, &[], 0, 1, &mut visit_item);
+
+    /// The children of `item` in the item tree, each with the path of the sub-component
+    /// instances that contain it.
+    fn children(item: &ElementRc, path: &[ElementRc]) -> Vec<(ElementRc, Vec<ElementRc>)> {
+        item.borrow()
+            .children
+            .iter()
+            .map(|e| match e.borrow().sub_component() {
+                Some(c) => (
+                    c.root_element.clone(),
+                    path.iter().chain(std::iter::once(e)).cloned().collect(),
+                ),
+                None => (e.clone(), path.to_vec()),
+            })
+            .collect()
+    }
+
+    fn sub_children_count(e: &ElementRc, path: &[ElementRc]) -> usize {
+        let children = children(e, path);
+        let mut count = children.len();
+        for (i, p) in &children {
+            count += sub_children_count(i, p);
         }
         count
     }
 
     fn visit_children(
         item: &ElementRc,
-        index: &mut u32,
+        path: &[ElementRc],
+        item_index: u32,
         children_offset: u32,
-        visit_item: &mut impl FnMut(&ElementRc, u32, u32),
+        visit_item: &mut impl FnMut(&ElementRc, u32, u32, &[ElementRc]),
     ) {
-        let mut offset = children_offset + item.borrow().children.len() as u32;
+        let children = children(item, path);
 
-        for i in &item.borrow().children {
-            visit_item(i, offset, *index);
-            offset += sub_children_count(i) as u32;
+        let mut offset = children_offset + children.len() as u32;
+        for (i, p) in &children {
+            visit_item(i, offset, item_index, p);
+            offset += sub_children_count(i, p) as u32;
         }
 
-        *index += 1;
-
-        let mut offset = children_offset + item.borrow().children.len() as u32;
-
-        for e in &item.borrow().children {
-            visit_children(e, index, offset, visit_item);
-            offset += sub_children_count(e) as u32;
+        let mut offset = children_offset + children.len() as u32;
+        for (index, (e, p)) in children.iter().enumerate() {
+            visit_children(e, p, children_offset + index as u32, offset, visit_item);
+            offset += sub_children_count(e, p) as u32;
         }
     }
 }
@@ -57,6 +57,7 @@ pub enum Declaration {
         Struct(Struct),
         Function(Function),
         Var(Var),
+        Enum(Enum),
     }
 
     #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -121,6 +122,26 @@ pub fn extract_definitions(&mut self) -> impl Iterator<Item = Declaration> + '_
         }
     }
 
+    /// An `enum class`
+    #[derive(Default, Debug)]
+    pub struct Enum {
+        pub name: String,
+        pub values: Vec<String>,
+    }
+
+    impl Display for Enum {
+        fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+            indent(f)?;
+            writeln!(f, "enum class {} {{", self.name)?;
+            for value in &self.values {
+                indent(f)?;
+                writeln!(f, "    {},", value)?;
+            }
+            indent(f)?;
+            writeln!(f, "}};")
+        }
+    }
+
     /// Function or method
     #[derive(Default, Debug)]
     pub struct Function {
@@ -259,6 +280,9 @@ fn cpp_type(&self) -> Option<String> {
             Type::Array(i) => Some(format!("std::shared_ptr<sixtyfps::Model<{}>>", i.cpp_type()?)),
             Type::Image => Some("sixtyfps::Image".to_owned()),
             Type::Builtin(elem) => elem.native_class.cpp_type.clone(),
+            Type::Enumeration(enumeration) if enumeration.node.is_some() => {
+                Some(enumeration.name.clone())
+            }
             Type::Enumeration(enumeration) => {
                 Some(format!("sixtyfps::cbindgen_private::{}", enumeration.name))
             }
@@ -352,15 +376,18 @@ fn handle_property_binding(
 ) {
     let item = elem.borrow();
     let component = item.enclosing_component.upgrade().unwrap();
-    let accessor_prefix = if item.property_declarations.contains_key(prop_name) {
-        String::new()
+    let cpp_prop = if item.property_declarations.contains_key(prop_name) {
+        prop_name.to_owned()
+    } else if item.sub_component().is_some() {
+        access_member(elem, prop_name, &component, "this")
     } else if item.is_flickable_viewport {
         format!(
-            "{id}.viewport.",
-            id = crate::object_tree::find_parent_element(elem).unwrap().borrow().id
+            "{id}.viewport.{prop}",
+            id = crate::object_tree::find_parent_element(elem).unwrap().borrow().id,
+            prop = prop_name
         )
     } else {
-        format!("{id}.", id = item.id)
+        format!("{id}.{prop}", id = item.id, prop = prop_name)
     };
     let prop_type = item.lookup_property(prop_name).property_type;
     if let Type::Callback { args, .. } = &prop_type {
@@ -369,13 +396,12 @@ fn handle_property_binding(
         });
 
         init.push(format!(
-            "{accessor_prefix}{prop}.set_handler(
+            "{cpp_prop}.set_handler(
                     [this]({params}) {{
                         [[maybe_unused]] auto self = this;
                         return {code};
                     }});",
-            accessor_prefix = accessor_prefix,
-            prop = prop_name,
+            cpp_prop = cpp_prop,
             params = params.join(", "),
             code = compile_expression_wrap_return(binding_expression, &component)
         ));
@@ -393,7 +419,6 @@ fn handle_property_binding(
         let component = &item.enclosing_component.upgrade().unwrap();
 
         let init_expr = compile_expression_wrap_return(binding_expression, component);
-        let cpp_prop = format!("{}{}", accessor_prefix, prop_name);
 
         init.push(if is_constant {
             format!("{}.set({});", cpp_prop, init_expr)
@@ -560,18 +585,29 @@ pub fn generate(doc: &Document, diag: &mut BuildDiagnostics) -> Option<impl std:
     file.includes.push("<cmath>".into()); // TODO: ideally only include this if needed (by floor/ceil/round)
     file.includes.push("<sixtyfps.h>".into());
 
-    for ty in doc.root_component.used_types.borrow().structs.iter() {
+    let used_types = doc.used_types();
+    for en in used_types.enums.iter() {
+        file.declarations
+            .push(Declaration::Enum(Enum { name: en.name.clone(), values: en.values.clone() }));
+    }
+    for ty in used_types.structs.iter() {
         if let Type::Struct { fields, name: Some(name), node: Some(_) } = ty {
             generate_struct(&mut file, name, fields, diag);
         }
     }
-    for glob in doc.root_component.used_types.borrow().globals.iter() {
+    for glob in used_types.globals.iter() {
         if !matches!(glob.root_element.borrow().base_type, Type::Builtin(_)) {
             generate_component(&mut file, glob, diag, None);
         }
     }
 
-    generate_component(&mut file, &doc.root_component, diag, None);
+    for sub in used_types.sub_components.iter() {
+        generate_sub_component(&mut file, sub, diag);
+    }
+
+    for root in &doc.exported_roots {
+        generate_component(&mut file, root, diag, None);
+    }
 
     file.definitions.push(Declaration::Var(Var{
         ty: format!(
@@ -975,7 +1011,11 @@ fn generate_component(
         }
 
         create_code.extend(
-            component.setup_code.borrow().iter().map(|code| compile_expression(code, component)),
+            component
+                .setup_code
+                .borrow()
+                .iter()
+                .map(|code| format!("{};", compile_expression(code, component))),
         );
         create_code
             .push(format!("return sixtyfps::ComponentHandle<{0}>{{ self_rc }};", component_id));
@@ -1001,63 +1041,138 @@ fn generate_component(
     let mut tree_array = vec![];
     let mut item_names_and_vt_symbols = vec![];
     let mut repeater_count = 0;
-    super::build_array_helper(component, |item_rc, children_offset, parent_index| {
-        let item = item_rc.borrow();
-        if item.base_type == Type::Void {
-            assert!(component.is_global());
-        } else if let Some(repeated) = &item.repeated {
-            tree_array.push(format!(
-                "sixtyfps::private_api::make_dyn_node({}, {})",
-                repeater_count, parent_index
-            ));
-            let base_component = item.base_type.as_component();
-            let mut friends = Vec::new();
-            generate_component(file, base_component, diag, Some(&mut friends));
-            if let Some(sub_components) = sub_components.as_mut() {
-                sub_components.extend_from_slice(friends.as_slice());
-                sub_components.push(self::component_id(base_component))
-            }
-            component_struct.friends.append(&mut friends);
-            component_struct.friends.push(self::component_id(base_component));
-            handle_repeater(
-                repeated,
-                base_component,
-                component,
-                repeater_count,
-                &mut component_struct,
-                &mut init,
-                &mut children_visitor_cases,
-                &mut repeated_input_branch,
-                &mut repeater_layout_code,
-                diag,
-            );
-            repeater_count += 1;
-        } else {
-            if item.is_flickable_viewport {
-                tree_array.push(format!(
-                    "sixtyfps::private_api::make_item_node(offsetof({}, {}) + offsetof(sixtyfps::cbindgen_private::Flickable, viewport), SIXTYFPS_GET_ITEM_VTABLE(RectangleVTable), {}, {}, {})",
-                    &component_id,
+    let mut sub_component_members = vec![];
+    let mut sub_component_initializers = vec![];
+    super::build_array_helper(
+        component,
+        |item_rc, children_offset, parent_index, sub_component_path| {
+            let item = item_rc.borrow();
+            if !sub_component_path.is_empty() {
+                if let [instance] = sub_component_path {
+                    if let Some(sub) = instance.borrow().sub_component() {
+                        if Rc::ptr_eq(&sub.root_element, item_rc) {
+                            let instance = instance.borrow();
+                            sub_component_members.push((
+                                Access::Private,
+                                Declaration::Var(Var {
+                                    ty: self::component_id(sub),
+                                    name: instance.id.clone(),
+                                    init: None,
+                                }),
+                            ));
+                            let globals = sub
+                                .used_types
+                                .borrow()
+                                .globals
+                                .iter()
+                                .map(|g| global_for_sub_component(component, g))
+                                .collect::<Vec<_>>();
+                            sub_component_initializers.push(format!(
+                                "{}(window{})",
+                                instance.id,
+                                globals.iter().map(|g| format!(", {}", g)).join("")
+                            ));
+                            init.push(format!("{}.init();", instance.id));
+                        }
+                    }
+                }
+                let offset = sub_component_path
+                    .iter()
+                    .map(|instance| {
+                        let instance = instance.borrow();
+                        format!(
+                            "offsetof({}, {})",
+                            self::component_id(&instance.enclosing_component.upgrade().unwrap()),
+                            instance.id
+                        )
+                    })
+                    .join(" + ");
+                let sub = item.enclosing_component.upgrade().unwrap();
+                if item.is_flickable_viewport {
+                    tree_array.push(format!(
+                    "sixtyfps::private_api::make_item_node({} + offsetof({}, {}) + offsetof(sixtyfps::cbindgen_private::Flickable, viewport), SIXTYFPS_GET_ITEM_VTABLE(RectangleVTable), {}, {}, {})",
+                    offset,
+                    self::component_id(&sub),
                     crate::object_tree::find_parent_element(item_rc).unwrap().borrow().id,
                     item.children.len(),
                     children_offset,
                     parent_index,
                 ));
-            } else {
-                tree_array.push(format!(
-                    "sixtyfps::private_api::make_item_node(offsetof({}, {}), {}, {}, {}, {})",
-                    component_id,
+                } else {
+                    tree_array.push(format!(
+                    "sixtyfps::private_api::make_item_node({} + offsetof({}, {}), {}, {}, {}, {})",
+                    offset,
+                    self::component_id(&sub),
                     item.id,
                     item.base_type.as_native().cpp_vtable_getter,
                     item.children.len(),
                     children_offset,
                     parent_index,
                 ));
+                    let member = sub_component_path
+                        .iter()
+                        .map(|instance| instance.borrow().id.clone())
+                        .chain(std::iter::once(item.id.clone()))
+                        .join(".");
+                    item_names_and_vt_symbols
+                        .push((member, item.base_type.as_native().cpp_vtable_getter.clone()));
+                }
+            } else if item.base_type == Type::Void {
+                assert!(component.is_global());
+            } else if let Some(repeated) = &item.repeated {
+                tree_array.push(format!(
+                    "sixtyfps::private_api::make_dyn_node({}, {})",
+                    repeater_count, parent_index
+                ));
+                let base_component = item.base_type.as_component();
+                let mut friends = Vec::new();
+                generate_component(file, base_component, diag, Some(&mut friends));
+                if let Some(sub_components) = sub_components.as_mut() {
+                    sub_components.extend_from_slice(friends.as_slice());
+                    sub_components.push(self::component_id(base_component))
+                }
+                component_struct.friends.append(&mut friends);
+                component_struct.friends.push(self::component_id(base_component));
+                handle_repeater(
+                    repeated,
+                    base_component,
+                    component,
+                    repeater_count,
+                    &mut component_struct,
+                    &mut init,
+                    &mut children_visitor_cases,
+                    &mut repeated_input_branch,
+                    &mut repeater_layout_code,
+                    diag,
+                );
+                repeater_count += 1;
+            } else {
+                if item.is_flickable_viewport {
+                    tree_array.push(format!(
+                    "sixtyfps::private_api::make_item_node(offsetof({}, {}) + offsetof(sixtyfps::cbindgen_private::Flickable, viewport), SIXTYFPS_GET_ITEM_VTABLE(RectangleVTable), {}, {}, {})",
+                    &component_id,
+                    crate::object_tree::find_parent_element(item_rc).unwrap().borrow().id,
+                    item.children.len(),
+                    children_offset,
+                    parent_index,
+                ));
+                } else {
+                    tree_array.push(format!(
+                        "sixtyfps::private_api::make_item_node(offsetof({}, {}), {}, {}, {}, {})",
+                        component_id,
+                        item.id,
+                        item.base_type.as_native().cpp_vtable_getter,
+                        item.children.len(),
+                        children_offset,
+                        parent_index,
+                    ));
+                }
+                handle_item(item_rc, &mut component_struct);
+                item_names_and_vt_symbols
+                    .push((item.id.clone(), item.base_type.as_native().cpp_vtable_getter.clone()));
             }
-            handle_item(item_rc, &mut component_struct);
-            item_names_and_vt_symbols
-                .push((item.id.clone(), item.base_type.as_native().cpp_vtable_getter.clone()));
-        }
-    });
+        },
+    );
 
     super::handle_property_bindings_init(component, |elem, prop, binding| {
         handle_property_binding(
@@ -1082,9 +1197,11 @@ fn generate_component(
             is_constructor_or_destructor: true,
             statements: Some(init),
             constructor_member_initializers: if !component.is_global() && !is_root {
-                vec!["window(parent->window)".into()]
+                std::iter::once("window(parent->window)".into())
+                    .chain(sub_component_initializers)
+                    .collect()
             } else {
-                vec![]
+                sub_component_initializers
             },
             ..Default::default()
         }),
@@ -1248,13 +1365,7 @@ fn generate_component(
     }
 
     for glob in component.used_types.borrow().globals.iter() {
-        let ty = match &glob.root_element.borrow().base_type {
-            Type::Void => self::component_id(glob),
-            Type::Builtin(b) => {
-                format!("sixtyfps::cbindgen_private::{}", b.native_class.class_name)
-            }
-            _ => unreachable!(),
-        };
+        let ty = global_type(glob);
         component_struct.members.push((
             Access::Private,
             Declaration::Var(Var {
@@ -1265,6 +1376,9 @@ fn generate_component(
         ));
     }
 
+    // Must come after the globals and the window, as they are passed to their constructor
+    component_struct.members.extend(sub_component_members);
+
     file.definitions.extend(component_struct.extract_definitions().collect::<Vec<_>>());
     file.declarations.push(Declaration::Struct(component_struct));
 
@@ -1280,6 +1394,160 @@ fn generate_component(
     }
 }
 
+/// Generate the class for a component which is instantiated as a sub-component of other
+/// components. Its items are part of the item tree of the component containing it.
+fn generate_sub_component(file: &mut File, component: &Rc<Component>, diag: &mut BuildDiagnostics) {
+    let component_id = component_id(component);
+    let mut component_struct = Struct { name: component_id.clone(), ..Default::default() };
+
+    for (cpp_name, property_decl) in component.root_element.borrow().property_declarations.iter() {
+        if property_decl.is_alias.is_some() {
+            continue;
+        }
+        let ty = if let Type::Callback { args, return_type } = &property_decl.property_type {
+            let param_types =
+                args.iter().map(|t| get_cpp_type(t, property_decl, diag)).collect::<Vec<_>>();
+            let return_type = return_type
+                .as_ref()
+                .map_or("void".into(), |t| get_cpp_type(t, property_decl, diag));
+            format!("sixtyfps::private_api::Callback<{}({})>", return_type, param_types.join(", "))
+        } else {
+            format!(
+                "sixtyfps::private_api::Property<{}>",
+                get_cpp_type(&property_decl.property_type, property_decl, diag)
+            )
+        };
+        component_struct.members.push((
+            Access::Public,
+            Declaration::Var(Var { ty, name: cpp_name.clone(), init: None }),
+        ));
+    }
+
+    component_struct.members.push((
+        Access::Public,
+        Declaration::Var(Var {
+            ty: "sixtyfps::private_api::ComponentWindow".into(),
+            name: "window".into(),
+            ..Var::default()
+        }),
+    ));
+
+    let mut constructor_args =
+        vec!["const sixtyfps::private_api::ComponentWindow &window".to_owned()];
+    let mut constructor_member_initializers = vec!["window(window)".to_owned()];
+    for glob in component.used_types.borrow().globals.iter() {
+        let name = format!("global_{}", self::component_id(glob));
+        let ty = format!("std::shared_ptr<{}>", global_type(glob));
+        constructor_args.push(format!("{} {}", ty, name));
+        constructor_member_initializers.push(format!("{0}(std::move({0}))", name));
+        component_struct
+            .members
+            .push((Access::Public, Declaration::Var(Var { ty, name, init: None })));
+    }
+
+    let mut init = vec!["[[maybe_unused]] auto self = this;".to_owned()];
+    crate::object_tree::recurse_elem(&component.root_element, &(), &mut |elem, _| {
+        let e = elem.borrow();
+        if let Some(sub) = e.sub_component() {
+            component_struct.members.push((
+                Access::Public,
+                Declaration::Var(Var {
+                    ty: self::component_id(sub),
+                    name: e.id.clone(),
+                    init: None,
+                }),
+            ));
+            let globals = sub
+                .used_types
+                .borrow()
+                .globals
+                .iter()
+                .map(|g| format!(", global_{}", self::component_id(g)))
+                .join("");
+            constructor_member_initializers.push(format!("{}(window{})", e.id, globals));
+            init.push(format!("{}.init();", e.id));
+        } else if !e.is_flickable_viewport {
+            component_struct.members.push((
+                Access::Public,
+                Declaration::Var(Var {
+                    ty: format!(
+                        "sixtyfps::cbindgen_private::{}",
+                        e.base_type.as_native().class_name
+                    ),
+                    name: e.id.clone(),
+                    init: Some("{}".to_owned()),
+                }),
+            ));
+        }
+    });
+
+    super::handle_property_bindings_init(component, |elem, prop, binding| {
+        handle_property_binding(
+            elem,
+            prop,
+            binding,
+            binding.analysis.borrow().as_ref().map_or(false, |a| a.is_const),
+            &mut init,
+        )
+    });
+    init.extend(
+        component
+            .setup_code
+            .borrow()
+            .iter()
+            .map(|code| format!("{};", compile_expression(code, component))),
+    );
+
+    component_struct.members.push((
+        Access::Public,
+        Declaration::Function(Function {
+            name: component_id.clone(),
+            signature: format!("({})", constructor_args.join(", ")),
+            is_constructor_or_destructor: true,
+            statements: Some(vec![]),
+            constructor_member_initializers,
+            ..Default::default()
+        }),
+    ));
+    component_struct.members.push((
+        Access::Public,
+        Declaration::Function(Function {
+            name: "init".into(),
+            signature: "() -> void".into(),
+            statements: Some(init),
+            ..Default::default()
+        }),
+    ));
+
+    file.definitions.extend(component_struct.extract_definitions().collect::<Vec<_>>());
+    file.declarations.push(Declaration::Struct(component_struct));
+}
+
+/// The type of the global, as it is stored in a shared_ptr in the components using it
+fn global_type(glob: &Rc<Component>) -> String {
+    match &glob.root_element.borrow().base_type {
+        Type::Void => self::component_id(glob),
+        Type::Builtin(b) => format!("sixtyfps::cbindgen_private::{}", b.native_class.class_name),
+        _ => unreachable!(),
+    }
+}
+
+/// The expression to get the global from the constructor of the component, in order to pass it
+/// to the constructor of its sub-components. The globals are owned by the root component.
+fn global_for_sub_component(component: &Rc<Component>, glob: &Rc<Component>) -> String {
+    let mut root = String::new();
+    let mut c = component.clone();
+    while let Some(p) = c.parent_element.upgrade() {
+        root += if root.is_empty() { "parent" } else { "->parent" };
+        c = p.borrow().enclosing_component.upgrade().unwrap();
+    }
+    if root.is_empty() {
+        format!("global_{}", component_id(glob))
+    } else {
+        format!("{}->global_{}", root, component_id(glob))
+    }
+}
+
 fn component_id(component: &Rc<Component>) -> String {
     if component.is_global() {
         component.root_element.borrow().id.clone()
@@ -1331,6 +1599,18 @@ fn access_member(
     if Rc::ptr_eq(component, &enclosing_component) {
         if e.property_declarations.contains_key(name) || name == "" || component.is_global() {
             format!("{}->{}", component_cpp, name)
+        } else if let Some(sub) = e.sub_component() {
+            let sub_cpp = format!("(&{}->{})", component_cpp, e.id);
+            let alias = sub
+                .root_element
+                .borrow()
+                .property_declarations
+                .get(name)
+                .and_then(|decl| decl.is_alias.clone());
+            match alias {
+                Some(alias) => access_named_reference(&alias, sub, &sub_cpp),
+                None => access_member(&sub.root_element, name, sub, &sub_cpp),
+            }
         } else if e.is_flickable_viewport {
             format!(
                 "{}->{}.viewport.{}",
@@ -1368,6 +1648,20 @@ fn access_member(
     }
 }
 
+/// Returns the vtable getter and the class name of the item of this element, and the path to its
+/// member in the class of the component. For an instance of a sub-component, this is the item
+/// of the root element of the sub-component.
+fn item_member(elem: &ElementRc) -> (String, String, String) {
+    let e = elem.borrow();
+    if let Some(sub) = e.sub_component() {
+        let (vt, ty, member) = item_member(&sub.root_element);
+        (vt, ty, format!("{}.{}", e.id, member))
+    } else {
+        let native = e.base_type.as_native();
+        (native.cpp_vtable_getter.clone(), native.class_name.clone(), e.id.clone())
+    }
+}
+
 /// Call access_member  for a NamedReference
 fn access_named_reference(
     nr: &NamedReference,
@@ -1422,6 +1716,24 @@ fn compile_expression(
             BuiltinFunction::ShowPopupWindow => {
                 "self->window.show_popup".into()
             }
+            BuiltinFunction::ShowDialog => {
+                "self->window.show_dialog".into()
+            }
+            BuiltinFunction::CloseDialog => {
+                "self->window.close_dialog".into()
+            }
+            BuiltinFunction::StandardButtonRank => {
+                "sixtyfps::cbindgen_private::sixtyfps_standard_button_rank".into()
+            }
+            BuiltinFunction::HasNativeMenus => {
+                "self->window.has_native_menus".into()
+            }
+            BuiltinFunction::ShowNativeMenu => {
+                "self->window.show_native_menu".into()
+            }
+            BuiltinFunction::SetNativeMenuBar => {
+                "self->window.set_native_menu_bar".into()
+            }
 
            /*  std::from_chars is unfortunately not yet implemented in gcc
             BuiltinFunction::StringIsFloat => {
@@ -1440,6 +1752,19 @@ fn compile_expression(
                 "[](const auto &a){ auto e1 = std::end(a); auto e2 = const_cast<char*>(e1); auto r = std::strtod(std::begin(a), &e2); return e1 == e2 ? r : 0; }"
                     .into()
             }
+            BuiltinFunction::NumberToFixed => "sixtyfps::private_api::format_fixed".into(),
+            BuiltinFunction::NumberToGrouped => "sixtyfps::private_api::format_grouped".into(),
+            BuiltinFunction::NumberToPadded => "sixtyfps::private_api::format_padded".into(),
+            BuiltinFunction::NumberToPercent => "sixtyfps::private_api::format_percent".into(),
+            BuiltinFunction::NumberToByteSize => "sixtyfps::private_api::format_byte_size".into(),
+            BuiltinFunction::StringToUpper => "sixtyfps::private_api::string_to_upper".into(),
+            BuiltinFunction::StringToLower => "sixtyfps::private_api::string_to_lower".into(),
+            BuiltinFunction::StringTrim => "sixtyfps::private_api::string_trim".into(),
+            BuiltinFunction::StringContains => "sixtyfps::private_api::string_contains".into(),
+            BuiltinFunction::StringStartsWith => "sixtyfps::private_api::string_starts_with".into(),
+            BuiltinFunction::StringEndsWith => "sixtyfps::private_api::string_ends_with".into(),
+            BuiltinFunction::StringLength => "sixtyfps::private_api::string_length".into(),
+            BuiltinFunction::StringSubstring => "sixtyfps::private_api::string_substring".into(),
             BuiltinFunction::ImplicitLayoutInfo(_) => {
                 unreachable!()
             }
@@ -1449,12 +1774,29 @@ fn compile_expression(
             BuiltinFunction::ColorDarker => {
                 "[](const auto &color, float factor) { return color.darker(factor); }".into()
             }
+            BuiltinFunction::ColorWithAlpha => {
+                "[](const auto &color, float alpha) { return color.with_alpha(alpha); }".into()
+            }
+            BuiltinFunction::ColorTransparentize => {
+                "[](const auto &color, float amount) { return color.transparentize(amount); }".into()
+            }
+            BuiltinFunction::ColorMix => {
+                "[](const auto &color, const auto &other, float factor) { return color.mix(other, factor); }".into()
+            }
+            BuiltinFunction::ColorLuminance => {
+                "[](const auto &color) { return color.luminance(); }".into()
+            }
             BuiltinFunction::ImageSize => {
                 "[](const sixtyfps::Image &img) { return img.size(); }".into()
             }
             BuiltinFunction::Rgb => {
                 "[](int r, int g, int b, float a) {{ return sixtyfps::Color::from_argb_uint8(std::clamp(a * 255., 0., 255.), std::clamp(r, 0, 255), std::clamp(g, 0, 255), std::clamp(b, 0, 255)); }}".into()
             }
+            BuiltinFunction::Hsv => "sixtyfps::Color::from_hsva".into(),
+            BuiltinFunction::Hsl => "sixtyfps::Color::from_hsla".into(),
+            BuiltinFunction::ArrayLength => {
+                "[](const auto &model) { return sixtyfps::private_api::model_length(model); }".into()
+            }
             BuiltinFunction::RegisterCustomFontByPath => {
                 panic!("internal error: RegisterCustomFontByPath can only be evaluated from within a FunctionCall expression")
             }
@@ -1534,6 +1876,13 @@ fn compile_expression(
                 _ => f,
             }
         }
+        Expression::ArrayIndex { array, index } => {
+            format!(
+                "sixtyfps::private_api::access_array_index({}, {})",
+                compile_expression(array, component),
+                compile_expression(index, component)
+            )
+        }
         Expression::CodeBlock(sub) => {
             let len = sub.len();
             let mut x = sub.iter().enumerate().map(|(i, e)| {
@@ -1579,18 +1928,76 @@ fn compile_expression(
                     panic!("internal error: argument to SetFocusItem must be an element")
                 }
             }
+            Expression::BuiltinFunctionReference(BuiltinFunction::ShowDialog, _) => {
+                if arguments.len() != 1 {
+                    panic!("internal error: incorrect argument count to ShowDialog call");
+                }
+                if let Expression::ElementReference(dialog) = &arguments[0] {
+                    let dialog = dialog.upgrade().unwrap();
+                    let dialog_comp = dialog.borrow().enclosing_component.upgrade().unwrap();
+                    let dialog_id = component_id(&dialog_comp);
+                    let parent_component = dialog_comp.parent_element.upgrade().unwrap().borrow().enclosing_component.upgrade().unwrap();
+                    let popup_list = parent_component.popup_windows.borrow();
+                    let popup = popup_list.iter().find(|p| Rc::ptr_eq(&p.component, &dialog_comp)).unwrap();
+                    let presentation = access_named_reference(popup.dialog_presentation.as_ref().unwrap(), component, "self");
+                    format!("self->window.show_dialog<{}>(self, {}.get());", dialog_id, presentation)
+                } else {
+                    panic!("internal error: argument to ShowDialog must be an element")
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::CloseDialog, _) => {
+                "self->window.close_dialog();".into()
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::ShowNativeMenu, _) => {
+                if arguments.len() != 3 {
+                    panic!("internal error: incorrect argument count to ShowNativeMenu call");
+                }
+                if let (Expression::ElementReference(menu), Expression::Array { values, element_ty }) = (&arguments[0], &arguments[1]) {
+                    let menu = menu.upgrade().unwrap();
+                    let menu_comp = menu.borrow().enclosing_component.upgrade().unwrap();
+                    let parent_component = menu_comp.parent_element.upgrade().unwrap().borrow().enclosing_component.upgrade().unwrap();
+                    let popup_list = parent_component.popup_windows.borrow();
+                    let popup = popup_list.iter().find(|p| Rc::ptr_eq(&p.component, &menu_comp)).unwrap();
+                    let x = access_named_reference(&popup.x, component, "self");
+                    let y = access_named_reference(&popup.y, component, "self");
+                    let entries = values.iter().map(|e| compile_expression(e, component)).join(", ");
+                    format!(
+                        "self->window.show_native_menu(sixtyfps::SharedVector<{}>{{ {} }}, {{ {}.get(), {}.get() }}, {})",
+                        element_ty.cpp_type().unwrap(), entries, x, y,
+                        compile_menu_activated_callback(&arguments[2], component)
+                    )
+                } else {
+                    panic!("internal error: arguments to ShowNativeMenu must be an element and an array")
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::SetNativeMenuBar, _) => {
+                if arguments.len() != 2 {
+                    panic!("internal error: incorrect argument count to SetNativeMenuBar call");
+                }
+                if let Expression::Array { values, element_ty } = &arguments[0] {
+                    let entries = values.iter().map(|e| compile_expression(e, component)).join(", ");
+                    let vector_type = format!("sixtyfps::SharedVector<{}>", element_ty.cpp_type().unwrap());
+                    format!(
+                        "self->window.set_native_menu_bar([self_weak = self->self_weak]() -> {vector} {{ \
+                            if (auto self_rc = self_weak.lock()) {{ [[maybe_unused]] auto self = &**self_rc; return {vector}{{ {entries} }}; }} \
+                            return {vector}(); }}, {activated})",
+                        vector = vector_type, entries = entries,
+                        activated = compile_menu_activated_callback(&arguments[1], component)
+                    )
+                } else {
+                    panic!("internal error: the first argument to SetNativeMenuBar must be an array")
+                }
+            }
             Expression::BuiltinFunctionReference(BuiltinFunction::ImplicitLayoutInfo(orientation), _) => {
                 if arguments.len() != 1 {
                     panic!("internal error: incorrect argument count to ImplicitLayoutInfo call");
                 }
                 if let Expression::ElementReference(item) = &arguments[0] {
-                    let item = item.upgrade().unwrap();
-                    let item = item.borrow();
-                    let native_item = item.base_type.as_native();
+                    let (vt, ty, member) = item_member(&item.upgrade().unwrap());
                     format!("{vt}->layouting_info({{{vt}, const_cast<sixtyfps::cbindgen_private::{ty}*>(&self->{id})}}, {o}, &window)",
-                        vt = native_item.cpp_vtable_getter,
-                        ty = native_item.class_name,
-                        id = item.id,
+                        vt = vt,
+                        ty = ty,
+                        id = member,
                         o = to_cpp_orientation(*orientation),
                     )
                 } else {
@@ -1711,6 +2118,9 @@ fn compile_expression(
                 stops_it.join(", "), angle, stops.len()
             )
         }
+        Expression::EnumerationValue(value) if value.enumeration.node.is_some() => {
+            format!("{}::{}", value.enumeration.name, value)
+        }
         Expression::EnumerationValue(value) => {
             format!("sixtyfps::cbindgen_private::{}::{}", value.enumeration.name, value.to_string())
         }
@@ -2020,6 +2430,26 @@ fn layout_geometry_size(
     )
 }
 
+/// Returns a lambda calling the callback of a menu with the index of the activated entry, as
+/// given to the ShowNativeMenu and SetNativeMenuBar functions
+fn compile_menu_activated_callback(callback: &Expression, component: &Rc<Component>) -> String {
+    let call = compile_expression(
+        &Expression::FunctionCall {
+            function: Box::new(callback.clone()),
+            arguments: vec![Expression::ReadLocalVariable {
+                name: "index".into(),
+                ty: Type::Int32,
+            }],
+            source_location: None,
+        },
+        component,
+    );
+    format!(
+        "[self_weak = self->self_weak](int index) {{ if (auto self_rc = self_weak.lock()) {{ [[maybe_unused]] auto self = &**self_rc; {}; }} }}",
+        call
+    )
+}
+
 fn get_layout_info(
     elem: &ElementRc,
     component: &Rc<Component>,
@@ -2031,11 +2461,12 @@ fn get_layout_info(
     {
         format!("{}.get()", access_named_reference(layout_info_prop, component, "self"))
     } else {
+        let (vt, ty, member) = item_member(elem);
         format!(
             "{vt}->layouting_info({{{vt}, const_cast<sixtyfps::cbindgen_private::{ty}*>(&self->{id})}}, {o}, &self->window)",
-            vt = elem.borrow().base_type.as_native().cpp_vtable_getter,
-            ty = elem.borrow().base_type.as_native().class_name,
-            id = elem.borrow().id,
+            vt = vt,
+            ty = ty,
+            id = member,
             o = to_cpp_orientation(orientation),
         )
     };
@@ -20,7 +20,7 @@
 use crate::expression_tree::{
     BuiltinFunction, EasingCurve, Expression, NamedReference, OperatorClass, Path,
 };
-use crate::langtype::Type;
+use crate::langtype::{Enumeration, Type};
 use crate::layout::{Layout, LayoutGeometry, LayoutRect, Orientation};
 use crate::object_tree::{Component, Document, ElementRc};
 use proc_macro2::{Ident, TokenStream};
@@ -61,8 +61,12 @@ fn rust_type(ty: &Type) -> Option<proc_macro2::TokenStream> {
             Some(quote!(sixtyfps::re_exports::ModelHandle<#inner>))
         }
         Type::Enumeration(e) => {
-            let e = format_ident!("r#{}", e.name);
-            Some(quote!(sixtyfps::re_exports::#e))
+            let e_ident = format_ident!("r#{}", e.name);
+            if e.node.is_some() {
+                Some(quote!(#e_ident))
+            } else {
+                Some(quote!(sixtyfps::re_exports::#e_ident))
+            }
         }
         Type::Brush => Some(quote!(sixtyfps::Brush)),
         Type::LayoutCache => Some(quote!(SharedVector<f32>)),
@@ -85,10 +89,8 @@ fn get_rust_type(
 ///
 /// Fill the diagnostic in case of error.
 pub fn generate(doc: &Document, diag: &mut BuildDiagnostics) -> Option<TokenStream> {
-    let (structs_ids, structs): (Vec<_>, Vec<_>) = doc
-        .root_component
-        .used_types
-        .borrow()
+    let used_types = doc.used_types();
+    let (structs_ids, structs): (Vec<_>, Vec<_>) = used_types
         .structs
         .iter()
         .filter_map(|ty| {
@@ -99,19 +101,28 @@ pub fn generate(doc: &Document, diag: &mut BuildDiagnostics) -> Option<TokenStre
             }
         })
         .unzip();
-    let compo = generate_component(&doc.root_component, diag)?;
-    let compo_id = public_component_id(&doc.root_component);
-    let compo_module = format_ident!("sixtyfps_generated_{}", compo_id);
+    let (enums_ids, enums): (Vec<_>, Vec<_>) =
+        used_types.enums.iter().map(|e| (format_ident!("r#{}", e.name), generate_enum(e))).unzip();
+    let sub_components = used_types
+        .sub_components
+        .iter()
+        .filter_map(|c| generate_sub_component(c, diag))
+        .collect::<Vec<_>>();
+    let compos = doc
+        .exported_roots
+        .iter()
+        .map(|c| generate_component(c, diag))
+        .collect::<Option<Vec<_>>>()?;
+    let compo_ids = doc.exported_roots.iter().map(|c| public_component_id(c)).collect::<Vec<_>>();
+    let compo_module =
+        format_ident!("sixtyfps_generated_{}", public_component_id(&doc.root_component));
     let version_check = format_ident!(
         "VersionCheck_{}_{}_{}",
         env!("CARGO_PKG_VERSION_MAJOR"),
         env!("CARGO_PKG_VERSION_MINOR"),
         env!("CARGO_PKG_VERSION_PATCH"),
     );
-    let globals = doc
-        .root_component
-        .used_types
-        .borrow()
+    let globals = used_types
         .globals
         .iter()
         .filter(|glob| !matches!(glob.root_element.borrow().base_type, Type::Builtin(_)))
@@ -121,12 +132,14 @@ pub fn generate(doc: &Document, diag: &mut BuildDiagnostics) -> Option<TokenStre
         #[allow(non_snake_case)]
         mod #compo_module {
             use sixtyfps::re_exports::*;
+            #(#enums)*
             #(#structs)*
             #(#globals)*
-            #compo
+            #(#sub_components)*
+            #(#compos)*
             const _THE_SAME_VERSION_MUST_BE_USED_FOR_THE_COMPILER_AND_THE_RUNTIME : sixtyfps::#version_check = sixtyfps::#version_check;
         }
-        pub use #compo_module::{#compo_id #(,#structs_ids)* };
+        pub use #compo_module::{#(#compo_ids),* #(,#structs_ids)* #(,#enums_ids)* };
         pub use sixtyfps::ComponentHandle;
     })
 }
@@ -155,6 +168,24 @@ pub struct #component_id {
     }
 }
 
+fn generate_enum(en: &Enumeration) -> TokenStream {
+    let enum_name = format_ident!("r#{}", en.name);
+    let values = en.values.iter().map(|value| format_ident!("r#{}", value)).collect::<Vec<_>>();
+    let default_value = &values[en.default_value];
+    quote! {
+        #[allow(non_camel_case_types)]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum #enum_name {
+            #(#values),*
+        }
+        impl Default for #enum_name {
+            fn default() -> Self {
+                Self::#default_value
+            }
+        }
+    }
+}
+
 fn handle_property_binding(
     component: &Rc<Component>,
     item_rc: &ElementRc,
@@ -165,24 +196,33 @@ fn handle_property_binding(
 ) {
     let rust_property = access_member(item_rc, prop_name, component, quote!(_self), false);
     let prop_type = item_rc.borrow().lookup_property(prop_name).property_type;
-    let (downgrade, upgrade) =
-        if item_rc.borrow().enclosing_component.upgrade().unwrap().is_global() {
-            (
-                quote!(let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_rc.clone());),
-                quote!(
-                    let self_rc = self_weak.upgrade().unwrap();
-                    let _self = self_rc.as_ref();
-                ),
-            )
-        } else {
-            (
-                quote!(let self_weak = sixtyfps::re_exports::VRc::downgrade(&self_rc);),
-                quote!(
-                    let self_rc = self_weak.upgrade().unwrap();
-                    let _self = self_rc.as_pin_ref();
-                ),
-            )
-        };
+    let enclosing_component = item_rc.borrow().enclosing_component.upgrade().unwrap();
+    let (downgrade, upgrade) = if enclosing_component.is_global() {
+        (
+            quote!(let self_weak = sixtyfps::re_exports::PinWeak::downgrade(self_rc.clone());),
+            quote!(
+                let self_rc = self_weak.upgrade().unwrap();
+                let _self = self_rc.as_ref();
+            ),
+        )
+    } else if enclosing_component.is_sub_component.get() {
+        // self_rc is the component that contains the sub-component at the given offset
+        (
+            quote!(let self_weak = sixtyfps::re_exports::VRc::downgrade(&self_rc);),
+            quote!(
+                let self_rc = self_weak.upgrade().unwrap();
+                let _self = offset.apply_pin(self_rc.as_pin_ref());
+            ),
+        )
+    } else {
+        (
+            quote!(let self_weak = sixtyfps::re_exports::VRc::downgrade(&self_rc);),
+            quote!(
+                let self_rc = self_weak.upgrade().unwrap();
+                let _self = self_rc.as_pin_ref();
+            ),
+        )
+    };
 
     if matches!(prop_type, Type::Callback { .. }) {
         let tokens_for_expression = compile_expression(binding_expression, component);
@@ -396,182 +436,221 @@ pub fn #setter_ident(&self, value: #rust_property_type) {
     let mut repeated_visit_branch = Vec::new();
     let mut repeated_input_branch = Vec::new();
     let mut init = Vec::new();
-    let mut window_field_init = None;
+    let mut window_init = None;
     let mut window_parent_param = None;
-    super::build_array_helper(component, |item_rc, children_index, parent_index| {
-        let parent_index = parent_index as u32;
-        let item = item_rc.borrow();
-        if item.base_type == Type::Void {
-            assert!(component.is_global());
-        } else if let Some(repeated) = &item.repeated {
-            let base_component = item.base_type.as_component();
-            let repeater_index = repeated_element_names.len();
-            let repeater_id = format_ident!("repeater_{}", item.id);
-            let rep_inner_component_id = self::inner_component_id(&*base_component);
-
-            extra_components.push(generate_component(&*base_component, diag).unwrap_or_else(
-                || {
-                    assert!(diag.has_error());
-                    Default::default()
-                },
-            ));
-
-            let extra_fn = if repeated.is_listview.is_some() {
-                let am = |prop| {
-                    access_member(
-                        &base_component.root_element,
-                        prop,
-                        base_component,
-                        quote!(self),
-                        false,
-                    )
-                };
-                let p_y = am("y");
-                let p_height = am("height");
-                let p_width = am("width");
-                quote! {
-                    fn listview_layout(
-                        self: core::pin::Pin<&Self>,
-                        offset_y: &mut f32,
-                        viewport_width: core::pin::Pin<&sixtyfps::re_exports::Property<f32>>,
-                    ) {
-                        use sixtyfps::re_exports::*;
-                        let vp_w = viewport_width.get();
-                        #p_y.set(*offset_y);
-                        *offset_y += #p_height.get();
-                        let w = #p_width.get();
-                        if vp_w < w {
-                            viewport_width.set(w);
+    let mut sub_component_instances = Vec::new();
+    let mut all_item_fields = Vec::new();
+    super::build_array_helper(
+        component,
+        |item_rc, children_index, parent_index, sub_component_path| {
+            let parent_index = parent_index as u32;
+            let item = item_rc.borrow();
+            if !sub_component_path.is_empty() {
+                if let [instance] = sub_component_path {
+                    if let Some(sub) = instance.borrow().sub_component() {
+                        if Rc::ptr_eq(&sub.root_element, item_rc) {
+                            sub_component_instances
+                                .push((format_ident!("r#{}", instance.borrow().id), sub.clone()));
                         }
                     }
                 }
-            } else {
-                // TODO: we could generate this code only if we know that this component is in a box layout
-                quote! {
-                    fn box_layout_data(self: ::core::pin::Pin<&Self>, o: sixtyfps::re_exports::Orientation)
-                        -> sixtyfps::re_exports::BoxLayoutCellData
-                    {
-                        use sixtyfps::re_exports::*;
-                        BoxLayoutCellData { constraint: self.as_ref().layout_info(o) }
+                let field = sub_component_path
+                    .iter()
+                    .map(|instance| {
+                        let instance = instance.borrow();
+                        access_component_field_offset(
+                            &self::inner_component_id(
+                                &instance.enclosing_component.upgrade().unwrap(),
+                            ),
+                            &format_ident!("r#{}", instance.id),
+                        )
+                    })
+                    .chain(std::iter::once(item_field_offset(item_rc)));
+                let field = quote!(#(#field)+*);
+                let children_count = item.children.len() as u32;
+                item_tree_array.push(quote!(
+                    sixtyfps::re_exports::ItemTreeNode::Item{
+                        item: VOffset::new(#field),
+                        children_count: #children_count,
+                        children_index: #children_index,
+                        parent_index: #parent_index,
                     }
+                ));
+                if !item.is_flickable_viewport {
+                    all_item_fields.push(field);
                 }
-            };
+            } else if item.base_type == Type::Void {
+                assert!(component.is_global());
+            } else if let Some(repeated) = &item.repeated {
+                let base_component = item.base_type.as_component();
+                let repeater_index = repeated_element_names.len();
+                let repeater_id = format_ident!("repeater_{}", item.id);
+                let rep_inner_component_id = self::inner_component_id(&*base_component);
+
+                extra_components.push(generate_component(&*base_component, diag).unwrap_or_else(
+                    || {
+                        assert!(diag.has_error());
+                        Default::default()
+                    },
+                ));
 
-            extra_components.push(if repeated.is_conditional_element {
-                quote! {
-                    impl sixtyfps::re_exports::RepeatedComponent for #rep_inner_component_id {
-                        type Data = ();
-                        fn update(&self, _: usize, _: Self::Data) { }
-                        #extra_fn
+                let extra_fn = if repeated.is_listview.is_some() {
+                    let am = |prop| {
+                        access_member(
+                            &base_component.root_element,
+                            prop,
+                            base_component,
+                            quote!(self),
+                            false,
+                        )
+                    };
+                    let p_y = am("y");
+                    let p_height = am("height");
+                    let p_width = am("width");
+                    quote! {
+                        fn listview_layout(
+                            self: core::pin::Pin<&Self>,
+                            offset_y: &mut f32,
+                            viewport_width: core::pin::Pin<&sixtyfps::re_exports::Property<f32>>,
+                        ) {
+                            use sixtyfps::re_exports::*;
+                            let vp_w = viewport_width.get();
+                            #p_y.set(*offset_y);
+                            *offset_y += #p_height.get();
+                            let w = #p_width.get();
+                            if vp_w < w {
+                                viewport_width.set(w);
+                            }
+                        }
                     }
-                }
-            } else {
-                let data_type = get_rust_type(
-                    &Expression::RepeaterModelReference { element: Rc::downgrade(item_rc) }.ty(),
-                    &item.node.as_ref().map(|x| x.to_source_location()),
-                    diag,
-                );
-
-                quote! {
-                    impl sixtyfps::re_exports::RepeatedComponent for #rep_inner_component_id {
-                        type Data = #data_type;
-                        fn update(&self, index: usize, data: Self::Data) {
-                            self.index.set(index);
-                            self.model_data.set(data);
+                } else {
+                    // TODO: we could generate this code only if we know that this component is in a box layout
+                    quote! {
+                        fn box_layout_data(self: ::core::pin::Pin<&Self>, o: sixtyfps::re_exports::Orientation)
+                            -> sixtyfps::re_exports::BoxLayoutCellData
+                        {
+                            use sixtyfps::re_exports::*;
+                            BoxLayoutCellData { constraint: self.as_ref().layout_info(o) }
                         }
-                        #extra_fn
                     }
-                }
-            });
+                };
 
-            let mut model = compile_expression(&repeated.model, component);
-            if repeated.is_conditional_element {
-                model =
-                    quote!(sixtyfps::re_exports::ModelHandle::new(std::rc::Rc::<bool>::new(#model)))
-            }
+                extra_components.push(if repeated.is_conditional_element {
+                    quote! {
+                        impl sixtyfps::re_exports::RepeatedComponent for #rep_inner_component_id {
+                            type Data = ();
+                            fn update(&self, _: usize, _: Self::Data) { }
+                            #extra_fn
+                        }
+                    }
+                } else {
+                    let data_type = get_rust_type(
+                        &Expression::RepeaterModelReference { element: Rc::downgrade(item_rc) }
+                            .ty(),
+                        &item.node.as_ref().map(|x| x.to_source_location()),
+                        diag,
+                    );
 
-            // FIXME: there could be an optimization if `repeated.model.is_constant()`, we don't need a binding
-            init.push(quote! {
-                _self.#repeater_id.set_model_binding({
-                    let self_weak = sixtyfps::re_exports::VRc::downgrade(&self_rc);
-                    move || {
-                        let self_rc = self_weak.upgrade().unwrap();
-                        let _self = self_rc.as_pin_ref();
-                        (#model) as _
+                    quote! {
+                        impl sixtyfps::re_exports::RepeatedComponent for #rep_inner_component_id {
+                            type Data = #data_type;
+                            fn update(&self, index: usize, data: Self::Data) {
+                                self.index.set(index);
+                                self.model_data.set(data);
+                            }
+                            #extra_fn
+                        }
                     }
                 });
-            });
 
-            if let Some(listview) = &repeated.is_listview {
-                let vp_y = access_named_reference(&listview.viewport_y, component, quote!(_self));
-                let vp_h =
-                    access_named_reference(&listview.viewport_height, component, quote!(_self));
-                let lv_h =
-                    access_named_reference(&listview.listview_height, component, quote!(_self));
-                let vp_w =
-                    access_named_reference(&listview.viewport_width, component, quote!(_self));
-                let lv_w =
-                    access_named_reference(&listview.listview_width, component, quote!(_self));
-
-                let ensure_updated = quote! {
-                    #inner_component_id::FIELD_OFFSETS.#repeater_id.apply_pin(_self).ensure_updated_listview(
-                        || { #rep_inner_component_id::new(_self.self_weak.get().unwrap().clone(), &_self.window).into() },
-                        #vp_w, #vp_h, #vp_y, #lv_w.get(), #lv_h
-                    );
-                };
+                let mut model = compile_expression(&repeated.model, component);
+                if repeated.is_conditional_element {
+                    model = quote!(sixtyfps::re_exports::ModelHandle::new(std::rc::Rc::<bool>::new(#model)))
+                }
 
-                repeated_visit_branch.push(quote!(
-                    #repeater_index => {
-                        #ensure_updated
-                        _self.#repeater_id.visit(order, visitor)
-                    }
-                ));
+                // FIXME: there could be an optimization if `repeated.model.is_constant()`, we don't need a binding
+                init.push(quote! {
+                    _self.#repeater_id.set_model_binding({
+                        let self_weak = sixtyfps::re_exports::VRc::downgrade(&self_rc);
+                        move || {
+                            let self_rc = self_weak.upgrade().unwrap();
+                            let _self = self_rc.as_pin_ref();
+                            (#model) as _
+                        }
+                    });
+                });
 
-                repeated_element_layouts.push(quote!(
-                    #ensure_updated
-                ));
-            } else {
-                let ensure_updated = quote! {
-                    #inner_component_id::FIELD_OFFSETS.#repeater_id.apply_pin(_self).ensure_updated(
-                        || { #rep_inner_component_id::new(_self.self_weak.get().unwrap().clone(), &_self.window).into() }
-                    );
-                };
+                if let Some(listview) = &repeated.is_listview {
+                    let vp_y =
+                        access_named_reference(&listview.viewport_y, component, quote!(_self));
+                    let vp_h =
+                        access_named_reference(&listview.viewport_height, component, quote!(_self));
+                    let lv_h =
+                        access_named_reference(&listview.listview_height, component, quote!(_self));
+                    let vp_w =
+                        access_named_reference(&listview.viewport_width, component, quote!(_self));
+                    let lv_w =
+                        access_named_reference(&listview.listview_width, component, quote!(_self));
+
+                    let ensure_updated = quote! {
+                        #inner_component_id::FIELD_OFFSETS.#repeater_id.apply_pin(_self).ensure_updated_listview(
+                            || { #rep_inner_component_id::new(_self.self_weak.get().unwrap().clone(), &_self.window).into() },
+                            #vp_w, #vp_h, #vp_y, #lv_w.get(), #lv_h
+                        );
+                    };
+
+                    repeated_visit_branch.push(quote!(
+                        #repeater_index => {
+                            #ensure_updated
+                            _self.#repeater_id.visit(order, visitor)
+                        }
+                    ));
 
-                repeated_visit_branch.push(quote!(
-                    #repeater_index => {
+                    repeated_element_layouts.push(quote!(
                         #ensure_updated
-                        _self.#repeater_id.visit(order, visitor)
-                    }
-                ));
+                    ));
+                } else {
+                    let ensure_updated = quote! {
+                        #inner_component_id::FIELD_OFFSETS.#repeater_id.apply_pin(_self).ensure_updated(
+                            || { #rep_inner_component_id::new(_self.self_weak.get().unwrap().clone(), &_self.window).into() }
+                        );
+                    };
+
+                    repeated_visit_branch.push(quote!(
+                        #repeater_index => {
+                            #ensure_updated
+                            _self.#repeater_id.visit(order, visitor)
+                        }
+                    ));
 
-                repeated_element_layouts.push(quote!(
-                    #ensure_updated
-                    _self.#repeater_id.compute_layout();
-                ));
-            }
+                    repeated_element_layouts.push(quote!(
+                        #ensure_updated
+                        _self.#repeater_id.compute_layout();
+                    ));
+                }
 
-            repeated_input_branch.push(quote!(
-                #repeater_index => self.#repeater_id.input_event(rep_index, event, window),
-            ));
+                repeated_input_branch.push(quote!(
+                    #repeater_index => self.#repeater_id.input_event(rep_index, event, window),
+                ));
 
-            item_tree_array.push(quote!(
-                sixtyfps::re_exports::ItemTreeNode::DynamicTree {
-                    index: #repeater_index,
-                    parent_index: #parent_index,
-                }
-            ));
+                item_tree_array.push(quote!(
+                    sixtyfps::re_exports::ItemTreeNode::DynamicTree {
+                        index: #repeater_index,
+                        parent_index: #parent_index,
+                    }
+                ));
 
-            repeated_element_names.push(repeater_id);
-            repeated_element_components.push(rep_inner_component_id);
-        } else if item.is_flickable_viewport {
-            let field_name = format_ident!(
-                "r#{}",
-                crate::object_tree::find_parent_element(item_rc).unwrap().borrow().id
-            );
-            let children_count = item.children.len() as u32;
-            let field = access_component_field_offset(&inner_component_id, &field_name);
+                repeated_element_names.push(repeater_id);
+                repeated_element_components.push(rep_inner_component_id);
+            } else if item.is_flickable_viewport {
+                let field_name = format_ident!(
+                    "r#{}",
+                    crate::object_tree::find_parent_element(item_rc).unwrap().borrow().id
+                );
+                let children_count = item.children.len() as u32;
+                let field = access_component_field_offset(&inner_component_id, &field_name);
 
-            item_tree_array.push(quote!(
+                item_tree_array.push(quote!(
                 sixtyfps::re_exports::ItemTreeNode::Item{
                     item: VOffset::new(#field + sixtyfps::re_exports::Flickable::FIELD_OFFSETS.viewport),
                     children_count: #children_count,
@@ -579,23 +658,25 @@ fn update(&self, index: usize, data: Self::Data) {
                     parent_index: #parent_index
                 }
             ));
-        } else {
-            let field_name = format_ident!("r#{}", item.id);
-            let children_count = item.children.len() as u32;
-            let field = access_component_field_offset(&inner_component_id, &field_name);
-
-            item_tree_array.push(quote!(
-                sixtyfps::re_exports::ItemTreeNode::Item{
-                    item: VOffset::new(#field),
-                    children_count: #children_count,
-                    children_index: #children_index,
-                    parent_index: #parent_index,
-                }
-            ));
-            item_names.push(field_name);
-            item_types.push(format_ident!("r#{}", item.base_type.as_native().class_name));
-        }
-    });
+            } else {
+                let field_name = format_ident!("r#{}", item.id);
+                let children_count = item.children.len() as u32;
+                let field = access_component_field_offset(&inner_component_id, &field_name);
+
+                item_tree_array.push(quote!(
+                    sixtyfps::re_exports::ItemTreeNode::Item{
+                        item: VOffset::new(#field),
+                        children_count: #children_count,
+                        children_index: #children_index,
+                        parent_index: #parent_index,
+                    }
+                ));
+                all_item_fields.push(field);
+                item_names.push(field_name);
+                item_types.push(format_ident!("r#{}", item.base_type.as_native().class_name));
+            }
+        },
+    );
 
     super::handle_property_bindings_init(component, |elem, prop, binding| {
         handle_property_binding(
@@ -639,12 +720,12 @@ fn update(&self, index: usize, data: Self::Data) {
         parent_component_type = Some(self::inner_component_id(
             &parent_element.borrow().enclosing_component.upgrade().unwrap(),
         ));
-        window_field_init = Some(quote!(window: parent_window.clone()));
+        window_init = Some(quote!(let window = parent_window.clone();));
         window_parent_param = Some(quote!(, parent_window: &sixtyfps::re_exports::ComponentWindow))
     } else if !component.is_global() {
         // FIXME: This field is public for testing.
         window_field = Some(quote!(pub window: sixtyfps::re_exports::ComponentWindow));
-        window_field_init = Some(quote!(window: sixtyfps::create_window()));
+        window_init = Some(quote!(let window = sixtyfps::create_window();));
 
         visibility = Some(quote!(pub));
 
@@ -663,6 +744,46 @@ fn component_window(&self) -> &sixtyfps::re_exports::ComponentWindow {
 
     // Trick so we can use `#()` as a `if let Some` in `quote!`
     let parent_component_type = parent_component_type.iter().collect::<Vec<_>>();
+    let window_field_name = window_init.iter().map(|_| format_ident!("window"));
+
+    // The globals passed to the sub-components are taken from the root component
+    let global_for_sub_component = |global: &Rc<Component>| {
+        let global_id = format_ident!("global_{}", public_component_id(global));
+        let mut root = quote!();
+        let mut c = component.clone();
+        while let Some(p) = c.parent_element.upgrade() {
+            root = if root.is_empty() {
+                quote!(parent.upgrade().unwrap())
+            } else {
+                quote!(#root.as_pin_ref().parent.upgrade().unwrap())
+            };
+            c = p.borrow().enclosing_component.upgrade().unwrap();
+        }
+        if root.is_empty() {
+            quote!(#global_id.clone())
+        } else {
+            quote!(#root.as_pin_ref().#global_id.clone())
+        }
+    };
+    let mut sub_component_names = Vec::new();
+    let mut sub_component_types = Vec::new();
+    let mut sub_component_new = Vec::new();
+    let mut sub_component_init = Vec::new();
+    for (name, sub) in &sub_component_instances {
+        let ty = self::inner_component_id(sub);
+        let globals = sub
+            .used_types
+            .borrow()
+            .globals
+            .iter()
+            .map(global_for_sub_component)
+            .collect::<Vec<_>>();
+        sub_component_new.push(quote!(#ty::new(&window #(, #globals)*)));
+        let field = access_component_field_offset(&inner_component_id, name);
+        sub_component_init.push(quote!(#ty::init(self_rc.clone(), #field);));
+        sub_component_names.push(name.clone());
+        sub_component_types.push(ty);
+    }
 
     if diag.has_error() {
         return None;
@@ -671,15 +792,12 @@ fn component_window(&self) -> &sixtyfps::re_exports::ComponentWindow {
     let (drop_impl, pin) = if component.is_global() {
         (None, quote!(#[pin]))
     } else {
-        let item_fields = item_names
-            .iter()
-            .map(|field_name| access_component_field_offset(&format_ident!("Self"), &field_name));
         (
             Some(quote!(impl sixtyfps::re_exports::PinnedDrop for #inner_component_id {
                 fn drop(self: core::pin::Pin<&mut #inner_component_id>) {
                     use sixtyfps::re_exports::*;
                     let items = [
-                        #(VRef::new_pin(#item_fields.apply_pin(self.as_ref())),)*
+                        #(VRef::new_pin((#all_item_fields).apply_pin(self.as_ref())),)*
                     ];
                     self.window.free_graphics_resources(&Slice::from_slice(&items));
                 }
@@ -701,6 +819,7 @@ fn drop(self: core::pin::Pin<&mut #inner_component_id>) {
             .upgrade()
             .and_then(|e| e.borrow().item_index.get().map(|x| *x));
         let parent_item_index = parent_item_index.iter();
+        init.splice(0..0, sub_component_init);
         init.insert(0, quote!(sixtyfps::re_exports::init_component_items(_self, Self::item_tree(), &_self.window);));
         (
             Some(quote! {
@@ -866,6 +985,7 @@ fn from(value: #public_component_id) -> Self {
             #(#item_names : sixtyfps::re_exports::#item_types,)*
             #(#declared_property_vars : sixtyfps::re_exports::Property<#declared_property_types>,)*
             #(#declared_callbacks : sixtyfps::re_exports::Callback<(#(#declared_callbacks_types,)*), #declared_callbacks_ret>,)*
+            #(#sub_component_names : #sub_component_types,)*
             #(#repeated_element_names : sixtyfps::re_exports::Repeater<#repeated_element_components>,)*
             #(#self_weak : sixtyfps::re_exports::OnceCell<sixtyfps::re_exports::VWeak<sixtyfps::re_exports::ComponentVTable, #inner_component_id>>,)*
             #(parent : sixtyfps::re_exports::VWeak<sixtyfps::re_exports::ComponentVTable, #parent_component_type>,)*
@@ -881,15 +1001,18 @@ pub fn new(#(parent: sixtyfps::re_exports::VWeak::<sixtyfps::re_exports::Compone
             {
                 #![allow(unused)]
                 use sixtyfps::re_exports::*;
+                #(let #global_name = #global_type::new();)*
+                #window_init
                 let mut self_ = Self {
                     #(#item_names : ::core::default::Default::default(),)*
                     #(#declared_property_vars : ::core::default::Default::default(),)*
                     #(#declared_callbacks : ::core::default::Default::default(),)*
+                    #(#sub_component_names : #sub_component_new,)*
                     #(#repeated_element_names : ::core::default::Default::default(),)*
                     #(#self_weak : ::core::default::Default::default(),)*
                     #(parent : parent as sixtyfps::re_exports::VWeak::<sixtyfps::re_exports::ComponentVTable, #parent_component_type>,)*
-                    #(#global_name : #global_type::new(),)*
-                    #window_field_init
+                    #(#global_name,)*
+                    #(#window_field_name,)*
                 };
                 #new_code
                 #(#init)*
@@ -909,6 +1032,149 @@ pub fn new(#(parent: sixtyfps::re_exports::VWeak::<sixtyfps::re_exports::Compone
     ))
 }
 
+/// Generate the struct for a component which is instantiated as a sub-component of other
+/// components. Its items are part of the item tree of the component containing it.
+fn generate_sub_component(
+    component: &Rc<Component>,
+    diag: &mut BuildDiagnostics,
+) -> Option<TokenStream> {
+    let inner_component_id = inner_component_id(component);
+
+    let mut declared_property_vars = vec![];
+    let mut declared_property_types = vec![];
+    let mut declared_callbacks = vec![];
+    let mut declared_callbacks_types = vec![];
+    let mut declared_callbacks_ret = vec![];
+    for (prop_name, property_decl) in component.root_element.borrow().property_declarations.iter() {
+        if property_decl.is_alias.is_some() {
+            continue;
+        }
+        let prop_ident = format_ident!("r#{}", prop_name);
+        if let Type::Callback { args, return_type } = &property_decl.property_type {
+            declared_callbacks.push(prop_ident);
+            declared_callbacks_types.push(
+                args.iter()
+                    .map(|a| get_rust_type(a, &property_decl.type_node(), diag))
+                    .collect::<Vec<_>>(),
+            );
+            declared_callbacks_ret.push(
+                return_type
+                    .as_ref()
+                    .map_or(quote!(()), |a| get_rust_type(a, &property_decl.type_node(), diag)),
+            );
+        } else {
+            declared_property_vars.push(prop_ident);
+            declared_property_types.push(get_rust_type(
+                &property_decl.property_type,
+                &property_decl.type_node(),
+                diag,
+            ));
+        }
+    }
+
+    if diag.has_error() {
+        return None;
+    }
+
+    let mut item_names = Vec::new();
+    let mut item_types = Vec::new();
+    let mut sub_component_names = Vec::new();
+    let mut sub_component_types = Vec::new();
+    let mut sub_component_new = Vec::new();
+    let mut init = Vec::new();
+    crate::object_tree::recurse_elem(&component.root_element, &(), &mut |elem, _| {
+        let e = elem.borrow();
+        let field_name = format_ident!("r#{}", e.id);
+        if let Some(sub) = e.sub_component() {
+            let ty = self::inner_component_id(sub);
+            let globals = sub
+                .used_types
+                .borrow()
+                .globals
+                .iter()
+                .map(|g| format_ident!("global_{}", public_component_id(g)))
+                .collect::<Vec<_>>();
+            sub_component_new.push(quote!(#ty::new(window #(, #globals.clone())*)));
+            let field = access_component_field_offset(&inner_component_id, &field_name);
+            init.push(quote!(#ty::init(self_rc.clone(), offset + #field);));
+            sub_component_names.push(field_name);
+            sub_component_types.push(ty);
+        } else if !e.is_flickable_viewport {
+            item_names.push(field_name);
+            item_types.push(format_ident!("r#{}", e.base_type.as_native().class_name));
+        }
+    });
+
+    super::handle_property_bindings_init(component, |elem, prop, binding| {
+        handle_property_binding(
+            component,
+            elem,
+            prop,
+            binding,
+            binding.analysis.borrow().as_ref().map_or(false, |a| a.is_const),
+            &mut init,
+        )
+    });
+
+    for extra_init_code in component.setup_code.borrow().iter() {
+        init.push(compile_expression(extra_init_code, component));
+    }
+
+    let (global_name, global_type): (Vec<_>, Vec<_>) = component
+        .used_types
+        .borrow()
+        .globals
+        .iter()
+        .map(|g| (format_ident!("global_{}", public_component_id(g)), self::inner_component_id(g)))
+        .unzip();
+
+    if diag.has_error() {
+        return None;
+    }
+
+    Some(quote!(
+        #[derive(sixtyfps::re_exports::FieldOffsets)]
+        #[const_field_offset(sixtyfps::re_exports::const_field_offset)]
+        #[repr(C)]
+        #[pin]
+        struct #inner_component_id {
+            #(#item_names : sixtyfps::re_exports::#item_types,)*
+            #(#declared_property_vars : sixtyfps::re_exports::Property<#declared_property_types>,)*
+            #(#declared_callbacks : sixtyfps::re_exports::Callback<(#(#declared_callbacks_types,)*), #declared_callbacks_ret>,)*
+            #(#sub_component_names : #sub_component_types,)*
+            #(#global_name : ::core::pin::Pin<::std::rc::Rc<#global_type>>,)*
+            window: sixtyfps::re_exports::ComponentWindow,
+        }
+
+        impl #inner_component_id {
+            fn new(window: &sixtyfps::re_exports::ComponentWindow #(, #global_name: ::core::pin::Pin<::std::rc::Rc<#global_type>>)*) -> Self {
+                #![allow(unused)]
+                use sixtyfps::re_exports::*;
+                Self {
+                    #(#item_names : ::core::default::Default::default(),)*
+                    #(#declared_property_vars : ::core::default::Default::default(),)*
+                    #(#declared_callbacks : ::core::default::Default::default(),)*
+                    #(#sub_component_names : #sub_component_new,)*
+                    #(#global_name,)*
+                    window: window.clone(),
+                }
+            }
+
+            /// Set the bindings of this sub-component, which is at `offset` in the component `self_rc`.
+            /// The items must have been initialized already.
+            fn init<Root: sixtyfps::re_exports::HasStaticVTable<sixtyfps::re_exports::ComponentVTable> + 'static>(
+                self_rc: sixtyfps::re_exports::VRc<sixtyfps::re_exports::ComponentVTable, Root>,
+                offset: sixtyfps::re_exports::const_field_offset::FieldOffset<Root, Self, sixtyfps::re_exports::const_field_offset::AllowPin>,
+            ) {
+                #![allow(unused)]
+                use sixtyfps::re_exports::*;
+                let _self = offset.apply_pin(self_rc.as_pin_ref());
+                #(#init)*
+            }
+        }
+    ))
+}
+
 /// Return an identifier suitable for this component for internal use
 fn inner_component_id(component: &Component) -> proc_macro2::Ident {
     if component.is_global()
@@ -991,6 +1257,20 @@ fn access_member(
         if e.property_declarations.contains_key(name) || is_special || component.is_global() {
             let field = access_component_field_offset(&inner_component_id, &name_ident);
             quote!(#field.apply_pin(#component_rust))
+        } else if let Some(sub) = e.sub_component() {
+            let elem_ident = format_ident!("r#{}", e.id);
+            let element_field = access_component_field_offset(&inner_component_id, &elem_ident);
+            let sub_rust = quote!(#element_field.apply_pin(#component_rust));
+            let alias = sub
+                .root_element
+                .borrow()
+                .property_declarations
+                .get(name)
+                .and_then(|decl| decl.is_alias.clone());
+            match alias {
+                Some(alias) => access_named_reference(&alias, sub, sub_rust),
+                None => access_member(&sub.root_element, name, sub, sub_rust, is_special),
+            }
         } else if e.is_flickable_viewport {
             let elem_ident = format_ident!(
                 "r#{}",
@@ -1040,6 +1320,28 @@ fn access_member(
     }
 }
 
+/// Returns the field offset of the item of this element within the struct of its component.
+/// For an instance of a sub-component, that is the item of the root element of the sub-component.
+fn item_field_offset(element: &ElementRc) -> TokenStream {
+    let e = element.borrow();
+    let inner_component_id = inner_component_id(&e.enclosing_component.upgrade().unwrap());
+    if let Some(sub) = e.sub_component() {
+        let element_field =
+            access_component_field_offset(&inner_component_id, &format_ident!("r#{}", e.id));
+        let sub_item = item_field_offset(&sub.root_element);
+        quote!(#element_field + #sub_item)
+    } else if e.is_flickable_viewport {
+        let elem_ident = format_ident!(
+            "r#{}",
+            crate::object_tree::find_parent_element(element).unwrap().borrow().id
+        );
+        let element_field = access_component_field_offset(&inner_component_id, &elem_ident);
+        quote!(#element_field + sixtyfps::re_exports::Flickable::FIELD_OFFSETS.viewport)
+    } else {
+        access_component_field_offset(&inner_component_id, &format_ident!("r#{}", e.id))
+    }
+}
+
 /// Call access_member  for a NamedReference
 fn access_named_reference(
     nr: &NamedReference,
@@ -1117,21 +1419,82 @@ fn compile_expression(expr: &Expression, component: &Rc<Component>) -> TokenStre
             BuiltinFunction::ASin => quote!((|a| (a as f64).asin().to_degrees())),
             BuiltinFunction::ACos => quote!((|a| (a as f64).acos().to_degrees())),
             BuiltinFunction::ATan => quote!((|a| (a as f64).atan().to_degrees())),
-            BuiltinFunction::SetFocusItem | BuiltinFunction::ShowPopupWindow | BuiltinFunction::ImplicitLayoutInfo(_) => {
+            BuiltinFunction::SetFocusItem
+            | BuiltinFunction::ShowPopupWindow
+            | BuiltinFunction::ShowDialog
+            | BuiltinFunction::CloseDialog
+            | BuiltinFunction::ShowNativeMenu
+            | BuiltinFunction::SetNativeMenuBar
+            | BuiltinFunction::ImplicitLayoutInfo(_) => {
                 panic!("internal error: should be handled directly in CallFunction")
             }
+            BuiltinFunction::StandardButtonRank => {
+                quote!((|kind| -> i32 { sixtyfps::re_exports::standard_button_rank(kind) }))
+            }
+            BuiltinFunction::HasNativeMenus => quote!(_self.window.has_native_menus),
             BuiltinFunction::StringToFloat => {
                 quote!((|x: SharedString| -> f64 { ::core::str::FromStr::from_str(x.as_str()).unwrap_or_default() } ))
             }
             BuiltinFunction::StringIsFloat => {
                 quote!((|x: SharedString| { <f64 as ::core::str::FromStr>::from_str(x.as_str()).is_ok() } ))
             }
+            BuiltinFunction::NumberToFixed => {
+                quote!((|x: f32, digits: i32| -> SharedString { sixtyfps::re_exports::format_fixed(x as f64, digits) }))
+            }
+            BuiltinFunction::NumberToGrouped => {
+                quote!((|x: f32, digits: i32| -> SharedString { sixtyfps::re_exports::format_grouped(x as f64, digits) }))
+            }
+            BuiltinFunction::NumberToPadded => {
+                quote!((|x: f32, width: i32| -> SharedString { sixtyfps::re_exports::format_padded(x as f64, width) }))
+            }
+            BuiltinFunction::NumberToPercent => {
+                quote!((|x: f32, digits: i32| -> SharedString { sixtyfps::re_exports::format_percent(x as f64, digits) }))
+            }
+            BuiltinFunction::NumberToByteSize => {
+                quote!((|x: f32| -> SharedString { sixtyfps::re_exports::format_byte_size(x as f64) }))
+            }
+            BuiltinFunction::StringToUpper => {
+                quote!((|x: SharedString| -> SharedString { x.to_uppercase().into() }))
+            }
+            BuiltinFunction::StringToLower => {
+                quote!((|x: SharedString| -> SharedString { x.to_lowercase().into() }))
+            }
+            BuiltinFunction::StringTrim => {
+                quote!((|x: SharedString| -> SharedString { x.trim().into() }))
+            }
+            BuiltinFunction::StringContains => {
+                quote!((|x: SharedString, y: SharedString| -> bool { x.contains(y.as_str()) }))
+            }
+            BuiltinFunction::StringStartsWith => {
+                quote!((|x: SharedString, y: SharedString| -> bool { x.starts_with(y.as_str()) }))
+            }
+            BuiltinFunction::StringEndsWith => {
+                quote!((|x: SharedString, y: SharedString| -> bool { x.ends_with(y.as_str()) }))
+            }
+            BuiltinFunction::StringLength => {
+                quote!((|x: SharedString| -> i32 { sixtyfps::re_exports::string_length(&x) }))
+            }
+            BuiltinFunction::StringSubstring => {
+                quote!((|x: SharedString, start: i32, length: i32| -> SharedString { sixtyfps::re_exports::string_substring(&x, start, length) }))
+            }
             BuiltinFunction::ColorBrighter => {
                 quote!((|x: Color, factor| -> Color { x.brighter(factor as f32) }))
             }
             BuiltinFunction::ColorDarker => {
                 quote!((|x: Color, factor| -> Color { x.darker(factor as f32) }))
             }
+            BuiltinFunction::ColorWithAlpha => {
+                quote!((|x: Color, alpha| -> Color { x.with_alpha(alpha as f32) }))
+            }
+            BuiltinFunction::ColorTransparentize => {
+                quote!((|x: Color, amount| -> Color { x.transparentize(amount as f32) }))
+            }
+            BuiltinFunction::ColorMix => {
+                quote!((|x: Color, other: Color, factor| -> Color { x.mix(&other, factor as f32) }))
+            }
+            BuiltinFunction::ColorLuminance => {
+                quote!((|x: Color| -> f32 { x.luminance() }))
+            }
             BuiltinFunction::ImageSize => {
                 quote!((|x: Image| -> Size { x.size() }))
             }
@@ -1145,6 +1508,15 @@ fn compile_expression(expr: &Expression, component: &Rc<Component>) -> TokenStre
                     sixtyfps::re_exports::Color::from_argb_u8(a, r, g, b)
                 }))
             }
+            BuiltinFunction::Hsv => {
+                quote!((|h: f32, s: f32, v: f32, a: f32| { sixtyfps::re_exports::Color::from_hsva(h, s, v, a) }))
+            }
+            BuiltinFunction::Hsl => {
+                quote!((|h: f32, s: f32, l: f32, a: f32| { sixtyfps::re_exports::Color::from_hsla(h, s, l, a) }))
+            }
+            BuiltinFunction::ArrayLength => {
+                quote!((|x: ModelHandle<_>| -> i32 { x.track_row_count_changes(); x.row_count() as i32 }))
+            }
             BuiltinFunction::RegisterCustomFontByPath => {
                 panic!("internal error: BuiltinFunction::RegisterCustomFontByPath can only be compiled as part of a FunctionCall expression")
             }
@@ -1196,6 +1568,16 @@ fn compile_expression(expr: &Expression, component: &Rc<Component>) -> TokenStre
             }
             _ => panic!("Expression::ObjectAccess's base expression is not an Object type"),
         },
+        Expression::ArrayIndex { array, index } => {
+            let array = compile_expression(array, component);
+            let index = compile_expression(index, component);
+            quote!(match &#array {
+                x => {
+                    let index: Option<usize> = ::core::convert::TryFrom::try_from((#index) as i32).ok();
+                    index.and_then(|index| x.row_data_tracked(index)).unwrap_or_default()
+                }
+            })
+        }
         Expression::CodeBlock(sub) => {
             let map = sub.iter().map(|e| compile_expression(e, component));
             quote!({ #(#map);* })
@@ -1245,17 +1627,84 @@ fn compile_expression(expr: &Expression, component: &Rc<Component>) -> TokenStre
                         panic!("internal error: argument to SetFocusItem must be an element")
                     }
                 }
+                Expression::BuiltinFunctionReference(BuiltinFunction::ShowDialog, _) => {
+                    if arguments.len() != 1 {
+                        panic!("internal error: incorrect argument count to ShowDialog call");
+                    }
+                    if let Expression::ElementReference(dialog) = &arguments[0] {
+                        let dialog = dialog.upgrade().unwrap();
+                        let dialog_comp = dialog.borrow().enclosing_component.upgrade().unwrap();
+                        let dialog_id = inner_component_id(&dialog_comp);
+                        let parent_component = dialog_comp.parent_element.upgrade().unwrap().borrow().enclosing_component.upgrade().unwrap();
+                        let popup_list = parent_component.popup_windows.borrow();
+                        let popup = popup_list.iter().find(|p| Rc::ptr_eq(&p.component, &dialog_comp)).unwrap();
+                        let presentation = access_named_reference(popup.dialog_presentation.as_ref().unwrap(), component, quote!(_self));
+                        quote!(
+                            _self.window.show_dialog(
+                                &VRc::into_dyn(#dialog_id::new(_self.self_weak.get().unwrap().clone(), &_self.window).into()),
+                                #presentation.get()
+                            );
+                        )
+                    } else {
+                        panic!("internal error: argument to ShowDialog must be an element")
+                    }
+                }
+                Expression::BuiltinFunctionReference(BuiltinFunction::CloseDialog, _) => {
+                    quote!(_self.window.close_dialog();)
+                }
+                Expression::BuiltinFunctionReference(BuiltinFunction::ShowNativeMenu, _) => {
+                    if arguments.len() != 3 {
+                        panic!("internal error: incorrect argument count to ShowNativeMenu call");
+                    }
+                    if let (Expression::ElementReference(menu), Expression::Array { values, .. }) = (&arguments[0], &arguments[1]) {
+                        let menu = menu.upgrade().unwrap();
+                        let menu_comp = menu.borrow().enclosing_component.upgrade().unwrap();
+                        let parent_component = menu_comp.parent_element.upgrade().unwrap().borrow().enclosing_component.upgrade().unwrap();
+                        let popup_list = parent_component.popup_windows.borrow();
+                        let popup = popup_list.iter().find(|p| Rc::ptr_eq(&p.component, &menu_comp)).unwrap();
+                        let x = access_named_reference(&popup.x, component, quote!(_self));
+                        let y = access_named_reference(&popup.y, component, quote!(_self));
+                        let entries = values.iter().map(|e| compile_expression(e, component));
+                        let activated = compile_menu_activated_callback(&arguments[2], component);
+                        quote!(
+                            _self.window.show_native_menu(&[#(#entries),*], Point::new(#x.get(), #y.get()), #activated)
+                        )
+                    } else {
+                        panic!("internal error: arguments to ShowNativeMenu must be an element and an array")
+                    }
+                }
+                Expression::BuiltinFunctionReference(BuiltinFunction::SetNativeMenuBar, _) => {
+                    if arguments.len() != 2 {
+                        panic!("internal error: incorrect argument count to SetNativeMenuBar call");
+                    }
+                    if let Expression::Array { values, .. } = &arguments[0] {
+                        let entries = values.iter().map(|e| compile_expression(e, component));
+                        let activated = compile_menu_activated_callback(&arguments[1], component);
+                        quote!(
+                            _self.window.set_native_menu_bar({
+                                let self_weak = _self.self_weak.get().unwrap().clone();
+                                move || {
+                                    let self_rc = match self_weak.upgrade() {
+                                        Some(self_rc) => self_rc,
+                                        None => return Vec::new(),
+                                    };
+                                    let _self = self_rc.as_pin_ref();
+                                    vec![#(#entries),*]
+                                }
+                            }, #activated)
+                        )
+                    } else {
+                        panic!("internal error: the first argument to SetNativeMenuBar must be an array")
+                    }
+                }
                 Expression::BuiltinFunctionReference(BuiltinFunction::ImplicitLayoutInfo(orient), _) => {
                     if arguments.len() != 1 {
                         panic!("internal error: incorrect argument count to ImplicitLayoutInfo call");
                     }
                     if let Expression::ElementReference(item) = &arguments[0] {
-                        let item = item.upgrade().unwrap();
-                        let item = item.borrow();
-                        let item_id = format_ident!("r#{}", item.id);
-                        let item_field = access_component_field_offset(&format_ident!("Self"), &item_id);
+                        let item_field = item_field_offset(&item.upgrade().unwrap());
                         quote!(
-                            #item_field.apply_pin(_self).layouting_info(#orient, &_self.window)
+                            (#item_field).apply_pin(_self).layouting_info(#orient, &_self.window)
                         )
                     } else {
                         panic!("internal error: argument to ImplicitLayoutInfo must be an element")
@@ -1457,7 +1906,11 @@ fn compile_expression(expr: &Expression, component: &Rc<Component>) -> TokenStre
         Expression::EnumerationValue(value) => {
             let base_ident = format_ident!("r#{}", value.enumeration.name);
             let value_ident = format_ident!("r#{}", value.to_string());
-            quote!(sixtyfps::re_exports::#base_ident::#value_ident)
+            if value.enumeration.node.is_some() {
+                quote!(#base_ident::#value_ident)
+            } else {
+                quote!(sixtyfps::re_exports::#base_ident::#value_ident)
+            }
         }
         Expression::ReturnStatement(expr) => {
             let return_expr = expr.as_ref().map(|expr| compile_expression(expr, component));
@@ -1826,6 +2279,35 @@ fn layout_info(self: ::core::pin::Pin<&Self>, orientation: sixtyfps::re_exports:
     }
 }
 
+/// Returns a closure calling the callback of a menu with the index of the activated entry, as
+/// given to the ShowNativeMenu and SetNativeMenuBar functions
+fn compile_menu_activated_callback(
+    callback: &Expression,
+    component: &Rc<Component>,
+) -> TokenStream {
+    let call = compile_expression(
+        &Expression::FunctionCall {
+            function: Box::new(callback.clone()),
+            arguments: vec![Expression::ReadLocalVariable {
+                name: "index".into(),
+                ty: Type::Int32,
+            }],
+            source_location: None,
+        },
+        component,
+    );
+    quote!({
+        let self_weak = _self.self_weak.get().unwrap().clone();
+        move |index: usize| {
+            if let Some(self_rc) = self_weak.upgrade() {
+                let _self = self_rc.as_pin_ref();
+                let index = index as i32;
+                #call;
+            }
+        }
+    })
+}
+
 fn get_layout_info(
     elem: &ElementRc,
     component: &Rc<Component>,
@@ -1836,9 +2318,8 @@ fn get_layout_info(
         let li = access_named_reference(layout_info_prop, component, quote!(_self));
         quote! {#li.get()}
     } else {
-        let elem_id = format_ident!("r#{}", elem.borrow().id);
-        let inner_component_id = inner_component_id(component);
-        quote!(#inner_component_id::FIELD_OFFSETS.#elem_id.apply_pin(_self).layouting_info(#orientation, &_self.window))
+        let item_field = item_field_offset(elem);
+        quote!((#item_field).apply_pin(_self).layouting_info(#orientation, &_self.window))
     };
 
     if constraints.has_explicit_restrictions() {
@@ -659,6 +659,8 @@ pub struct Enumeration {
     pub name: String,
     pub values: Vec<String>,
     pub default_value: usize, // index in values
+    /// The declaration of the enum in a .60 file, or None for the builtin enums
+    pub node: Option<syntax_nodes::EnumDeclaration>,
 }
 
 impl PartialEq for Enumeration {
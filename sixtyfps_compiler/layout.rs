@@ -10,9 +10,9 @@
 //! Datastructures used to represent layouts in the compiler
 
 use crate::diagnostics::BuildDiagnostics;
-use crate::expression_tree::{Expression, NamedReference, Path};
+use crate::expression_tree::{BindingExpression, Expression, NamedReference, Path};
 use crate::langtype::{PropertyLookupResult, Type};
-use crate::object_tree::ElementRc;
+use crate::object_tree::{Element, ElementRc};
 
 use std::rc::Rc;
 
@@ -154,22 +154,23 @@ pub fn new(element: &ElementRc, diag: &mut BuildDiagnostics) -> Self {
             fixed_width: false,
             fixed_height: false,
         };
-        let mut apply_size_constraint = |prop, binding, op: &mut Option<NamedReference>| {
-            if let Some(other_prop) = op {
-                diag.push_error(
-                    format!("Cannot specify both '{}' and '{}'", prop, other_prop.name()),
-                    binding,
-                )
-            }
-            *op = Some(NamedReference::new(element, prop))
-        };
+        let mut apply_size_constraint =
+            |prop, binding: &BindingExpression, op: &mut Option<NamedReference>| {
+                if let Some(other_prop) = op {
+                    diag.push_error(
+                        format!("Cannot specify both '{}' and '{}'", prop, other_prop.name()),
+                        binding,
+                    )
+                }
+                *op = Some(NamedReference::new(element, prop))
+            };
         let e = element.borrow();
-        e.bindings.get("height").map(|s| {
+        find_binding(&e, "height", |s| {
             constraints.fixed_height = true;
             apply_size_constraint("height", s, &mut constraints.min_height);
             apply_size_constraint("height", s, &mut constraints.max_height);
         });
-        e.bindings.get("width").map(|s| {
+        find_binding(&e, "width", |s| {
             if s.expression.ty() == Type::Percent {
                 apply_size_constraint("width", s, &mut constraints.min_width);
                 return;
@@ -301,7 +302,23 @@ pub fn visit_named_references(&mut self, visitor: &mut impl FnMut(&mut NamedRefe
 
 /// Return a named reference to a property if a binding is set on that property
 fn binding_reference(element: &ElementRc, name: &str) -> Option<NamedReference> {
-    element.borrow().bindings.contains_key(name).then(|| NamedReference::new(element, name))
+    element.borrow().is_binding_set(name, false).then(|| NamedReference::new(element, name))
+}
+
+/// Call the function with the binding of the property in this element, or in the root element
+/// of its base component
+fn find_binding<R>(
+    element: &Element,
+    name: &str,
+    f: impl FnOnce(&BindingExpression) -> R,
+) -> Option<R> {
+    if let Some(b) = element.bindings.get(name) {
+        Some(f(b))
+    } else if let Type::Component(base) = &element.base_type {
+        find_binding(&base.root_element.borrow(), name, f)
+    } else {
+        None
+    }
 }
 
 fn init_fake_property(
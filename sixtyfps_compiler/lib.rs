@@ -54,8 +54,18 @@ pub struct CompilerConfiguration {
     pub embed_resources: bool,
     /// The compiler will look in these paths for components used in the file to compile.
     pub include_paths: Vec<std::path::PathBuf>,
-    /// the name of the style. (eg: "native")
+    /// Map a library name to the directory containing that library. An import like
+    /// `@name/path/file.60` is looked up as `path/file.60` in the directory of the library `name`.
+    pub library_paths: std::collections::HashMap<String, std::path::PathBuf>,
+    /// the name of the style. (eg: "native"), or the path of a directory containing
+    /// a `sixtyfps_widgets.60` file which implements the widgets of the style.
     pub style: Option<String>,
+    /// The level of the lints. The lints which are not in this map are not reported.
+    pub lints: std::collections::HashMap<diagnostics::Lint, diagnostics::LintLevel>,
+    /// When true (the default), every component is inlined in the component that uses it.
+    /// Otherwise, only the usages which require it are inlined and the other components are
+    /// generated once as sub-components. Set `SIXTYFPS_INLINING=false` to opt into sub-components.
+    pub inline_all_elements: bool,
 
     /// Callback to load import files which is called if the file could not be found
     ///
@@ -83,15 +93,32 @@ pub fn new(output_format: crate::generator::OutputFormat) -> Self {
             }
         };
 
+        // An incorrect value is reported by compile_syntax_node
+        let inline_all_elements =
+            std::env::var("SIXTYFPS_INLINING").map_or(true, |var| var != "false");
+
         Self {
             embed_resources,
             include_paths: Default::default(),
+            library_paths: Default::default(),
             style: Default::default(),
+            lints: Default::default(),
+            inline_all_elements,
             open_import_fallback: Default::default(),
         }
     }
 }
 
+/// Parse a library path given as `name=path` on the command line of the tools, for
+/// example with their `-L` option.
+pub fn parse_library_path(s: &str) -> Result<(String, std::path::PathBuf), String> {
+    let mut split = s.splitn(2, '=');
+    match (split.next(), split.next()) {
+        (Some(name), Some(path)) if !name.is_empty() => Ok((name.into(), path.into())),
+        _ => Err(format!("Invalid library path '{}', expected 'name=path'", s)),
+    }
+}
+
 pub async fn compile_syntax_node(
     doc_node: parser::SyntaxNode,
     mut diagnostics: diagnostics::BuildDiagnostics,
@@ -103,6 +130,18 @@ pub async fn compile_syntax_node(
 
     let doc_node: parser::syntax_nodes::Document = doc_node.into();
 
+    if let Ok(var) = std::env::var("SIXTYFPS_INLINING") {
+        if var.parse::<bool>().is_err() {
+            diagnostics.push_error_with_span(
+                format!(
+                    "SIXTYFPS_INLINING has incorrect value '{}'. Must be either unset, 'true' or 'false'",
+                    var
+                ),
+                Default::default(),
+            );
+        }
+    }
+
     let mut loader =
         typeloader::TypeLoader::new(global_type_registry, &compiler_config, &mut diagnostics);
     let foreign_imports =
@@ -463,6 +463,10 @@ fn for_each_entry<R>(
             .or_else(|| f("sqrt", BuiltinFunctionReference(BuiltinFunction::Sqrt, sl())))
             .or_else(|| f("rgb", BuiltinMacroReference(BuiltinMacroFunction::Rgb, t.clone())))
             .or_else(|| f("rgba", BuiltinMacroReference(BuiltinMacroFunction::Rgb, t.clone())))
+            .or_else(|| f("hsv", BuiltinMacroReference(BuiltinMacroFunction::Hsv, t.clone())))
+            .or_else(|| f("hsva", BuiltinMacroReference(BuiltinMacroFunction::Hsv, t.clone())))
+            .or_else(|| f("hsl", BuiltinMacroReference(BuiltinMacroFunction::Hsl, t.clone())))
+            .or_else(|| f("hsla", BuiltinMacroReference(BuiltinMacroFunction::Hsl, t.clone())))
             .or_else(|| f("max", BuiltinMacroReference(BuiltinMacroFunction::Max, t.clone())))
             .or_else(|| f("min", BuiltinMacroReference(BuiltinMacroFunction::Min, t.clone())))
             .or_else(|| f("sin", BuiltinFunctionReference(BuiltinFunction::Sin, sl())))
@@ -519,8 +523,10 @@ fn for_each_entry<R>(
                 }
                 Type::Component(c) => c.root_element.for_each_entry(ctx, f),
                 Type::String => StringExpression(self).for_each_entry(ctx, f),
+                Type::Int32 | Type::Float32 => NumberExpression(self).for_each_entry(ctx, f),
                 Type::Color => ColorExpression(self).for_each_entry(ctx, f),
                 Type::Image => ImageExpression(self).for_each_entry(ctx, f),
+                Type::Array(_) => ArrayExpression(self).for_each_entry(ctx, f),
                 _ => None,
             },
         }
@@ -545,8 +551,10 @@ fn lookup(&self, ctx: &LookupCtx, name: &str) -> Option<LookupResult> {
                 }),
                 Type::Component(c) => c.root_element.lookup(ctx, name),
                 Type::String => StringExpression(self).lookup(ctx, name),
+                Type::Int32 | Type::Float32 => NumberExpression(self).lookup(ctx, name),
                 Type::Color => ColorExpression(self).lookup(ctx, name),
                 Type::Image => ImageExpression(self).lookup(ctx, name),
+                Type::Array(_) => ArrayExpression(self).lookup(ctx, name),
                 _ => None,
             },
         }
@@ -568,8 +576,46 @@ fn for_each_entry<R>(
                 ctx.current_token.as_ref().map(|t| t.to_source_location()),
             )),
         };
+        let string_length = || Expression::FunctionCall {
+            function: Box::new(Expression::BuiltinFunctionReference(
+                BuiltinFunction::StringLength,
+                ctx.current_token.as_ref().map(|t| t.to_source_location()),
+            )),
+            source_location: ctx.current_token.as_ref().map(|t| t.to_source_location()),
+            arguments: vec![self.0.clone()],
+        };
         None.or_else(|| f("is_float", member_function(BuiltinFunction::StringIsFloat)))
             .or_else(|| f("to_float", member_function(BuiltinFunction::StringToFloat)))
+            .or_else(|| f("to_upper", member_function(BuiltinFunction::StringToUpper)))
+            .or_else(|| f("to_lower", member_function(BuiltinFunction::StringToLower)))
+            .or_else(|| f("trim", member_function(BuiltinFunction::StringTrim)))
+            .or_else(|| f("contains", member_function(BuiltinFunction::StringContains)))
+            .or_else(|| f("starts_with", member_function(BuiltinFunction::StringStartsWith)))
+            .or_else(|| f("ends_with", member_function(BuiltinFunction::StringEndsWith)))
+            .or_else(|| f("substring", member_function(BuiltinFunction::StringSubstring)))
+            .or_else(|| f("length", string_length()))
+    }
+}
+struct NumberExpression<'a>(&'a Expression);
+impl<'a> LookupObject for NumberExpression<'a> {
+    fn for_each_entry<R>(
+        &self,
+        ctx: &LookupCtx,
+        f: &mut impl FnMut(&str, Expression) -> Option<R>,
+    ) -> Option<R> {
+        let member_function = |f: BuiltinFunction| Expression::MemberFunction {
+            base: Box::new(self.0.clone()),
+            base_node: ctx.current_token.clone(), // Note that this is not the base_node, but the function's node
+            member: Box::new(Expression::BuiltinFunctionReference(
+                f,
+                ctx.current_token.as_ref().map(|t| t.to_source_location()),
+            )),
+        };
+        None.or_else(|| f("to_fixed", member_function(BuiltinFunction::NumberToFixed)))
+            .or_else(|| f("to_grouped", member_function(BuiltinFunction::NumberToGrouped)))
+            .or_else(|| f("to_padded", member_function(BuiltinFunction::NumberToPadded)))
+            .or_else(|| f("to_percent", member_function(BuiltinFunction::NumberToPercent)))
+            .or_else(|| f("to_byte_size", member_function(BuiltinFunction::NumberToByteSize)))
     }
 }
 struct ColorExpression<'a>(&'a Expression);
@@ -587,8 +633,20 @@ fn for_each_entry<R>(
                 ctx.current_token.as_ref().map(|t| t.to_source_location()),
             )),
         };
+        let luminance = || Expression::FunctionCall {
+            function: Box::new(Expression::BuiltinFunctionReference(
+                BuiltinFunction::ColorLuminance,
+                ctx.current_token.as_ref().map(|t| t.to_source_location()),
+            )),
+            source_location: ctx.current_token.as_ref().map(|t| t.to_source_location()),
+            arguments: vec![self.0.clone()],
+        };
         None.or_else(|| f("brighter", member_function(BuiltinFunction::ColorBrighter)))
             .or_else(|| f("darker", member_function(BuiltinFunction::ColorDarker)))
+            .or_else(|| f("with_alpha", member_function(BuiltinFunction::ColorWithAlpha)))
+            .or_else(|| f("transparentize", member_function(BuiltinFunction::ColorTransparentize)))
+            .or_else(|| f("mix", member_function(BuiltinFunction::ColorMix)))
+            .or_else(|| f("luminance", luminance()))
     }
 }
 
@@ -614,3 +672,22 @@ fn for_each_entry<R>(
             .or_else(|| f("height", field_access("height")))
     }
 }
+
+struct ArrayExpression<'a>(&'a Expression);
+impl<'a> LookupObject for ArrayExpression<'a> {
+    fn for_each_entry<R>(
+        &self,
+        ctx: &LookupCtx,
+        f: &mut impl FnMut(&str, Expression) -> Option<R>,
+    ) -> Option<R> {
+        let array_length = || Expression::FunctionCall {
+            function: Box::new(Expression::BuiltinFunctionReference(
+                BuiltinFunction::ArrayLength,
+                ctx.current_token.as_ref().map(|t| t.to_source_location()),
+            )),
+            source_location: ctx.current_token.as_ref().map(|t| t.to_source_location()),
+            arguments: vec![self.0.clone()],
+        };
+        None.or_else(|| f("length", array_length()))
+    }
+}
@@ -77,6 +77,13 @@ fn is_constant_impl(&self, mut check_binding: bool) -> bool {
                 return false;
             }
         }
+        if e.enclosing_component
+            .upgrade()
+            .map_or(false, |c| c.is_sub_component.get() && Rc::ptr_eq(&c.root_element, &elem))
+        {
+            // could be set by the component which instantiates this sub-component
+            return false;
+        }
         drop(e);
 
         loop {
@@ -175,4 +182,9 @@ pub fn is_referenced(&self, name: &str) -> bool {
             false
         }
     }
+
+    /// Returns true if there is at least one NamedReference pointing to any property of this element.
+    pub fn is_any_referenced(&self) -> bool {
+        self.0.borrow().values().any(|nri| Rc::strong_count(nri) > 1)
+    }
 }
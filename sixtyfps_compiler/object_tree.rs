@@ -13,16 +13,18 @@
 
 use itertools::Either;
 
-use crate::diagnostics::{BuildDiagnostics, SourceLocation, Spanned};
+use crate::diagnostics::{
+    BuildDiagnostics, DiagnosticCode, DiagnosticLevel, SourceLocation, Spanned,
+};
 use crate::expression_tree::{self, BindingExpression, Expression, Unit};
 use crate::langtype::PropertyLookupResult;
-use crate::langtype::{BuiltinElement, NativeClass, Type};
+use crate::langtype::{BuiltinElement, Enumeration, NativeClass, Type};
 use crate::layout::{LayoutConstraints, Orientation};
 use crate::namedreference::NamedReference;
 use crate::parser::{identifier_text, syntax_nodes, SyntaxKind, SyntaxNode};
 use crate::typeloader::ImportedTypes;
 use crate::typeregister::TypeRegister;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::{BTreeMap, HashMap};
 use std::rc::{Rc, Weak};
 
@@ -44,7 +46,13 @@ pub struct Document {
     pub node: Option<syntax_nodes::Document>,
     pub inner_components: Vec<Rc<Component>>,
     pub inner_structs: Vec<Type>,
+    pub inner_enums: Vec<Rc<Enumeration>>,
     pub root_component: Rc<Component>,
+    /// The components for which an API is generated: one for each exported component, in the
+    /// order of the exports, followed by `root_component`.
+    /// The exported components are wrapped in a new component so that they can still be used by
+    /// the other components.
+    pub exported_roots: Vec<Rc<Component>>,
     pub local_registry: TypeRegister,
     /// A list of paths to .ttf/.ttc files that are supposed to be registered on
     /// startup for custom font use.
@@ -64,6 +72,7 @@ pub fn from_node(
         let mut local_registry = TypeRegister::new(parent_registry);
         let mut inner_components = vec![];
         let mut inner_structs = vec![];
+        let mut inner_enums = vec![];
 
         let mut process_component =
             |n: syntax_nodes::Component,
@@ -87,6 +96,44 @@ pub fn from_node(
                 local_registry.insert_type(ty.clone());
                 inner_structs.push(ty);
             };
+        let mut process_enum = |n: syntax_nodes::EnumDeclaration,
+                                diag: &mut BuildDiagnostics,
+                                local_registry: &mut TypeRegister| {
+            let name = match identifier_text(&n.DeclaredIdentifier()) {
+                Some(name) => name,
+                None => {
+                    assert!(diag.has_error());
+                    return;
+                }
+            };
+            let mut values: Vec<String> = vec![];
+            for value_node in n.EnumValue() {
+                let value = match identifier_text(&value_node) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                if values.contains(&value) {
+                    diag.push_error(format!("Duplicated enum value '{}'", value), &value_node);
+                    continue;
+                }
+                values.push(value);
+            }
+            if values.is_empty() {
+                diag.push_error(
+                    format!("The enum '{}' must have at least one value", name),
+                    &n.DeclaredIdentifier(),
+                );
+                return;
+            }
+            let enumeration = Rc::new(Enumeration {
+                name: name.clone(),
+                values,
+                default_value: 0,
+                node: Some(n),
+            });
+            local_registry.insert_type_with_name(Type::Enumeration(enumeration.clone()), name);
+            inner_enums.push(enumeration);
+        };
 
         for n in node.children() {
             match n.kind() {
@@ -94,6 +141,7 @@ pub fn from_node(
                 SyntaxKind::StructDeclaration => {
                     process_struct(n.into(), diag, &mut local_registry)
                 }
+                SyntaxKind::EnumDeclaration => process_enum(n.into(), diag, &mut local_registry),
                 SyntaxKind::ExportsList => {
                     for n in n.children() {
                         match n.kind() {
@@ -103,6 +151,9 @@ pub fn from_node(
                             SyntaxKind::StructDeclaration => {
                                 process_struct(n.into(), diag, &mut local_registry)
                             }
+                            SyntaxKind::EnumDeclaration => {
+                                process_enum(n.into(), diag, &mut local_registry)
+                            }
                             _ => {}
                         }
                     }
@@ -129,6 +180,35 @@ pub fn from_node(
             })
             .unwrap_or_default();
 
+        let mut exported_roots = vec![];
+        let mut wrapped_components: Vec<Rc<Component>> = vec![];
+        for (name, ty) in exports.0.iter() {
+            if let Type::Component(c) = ty {
+                if c.is_global()
+                    || Rc::ptr_eq(c, &root_component)
+                    // Components with a @children placeholder are meant to be used by other components
+                    || c.child_insertion_point.borrow().is_some()
+                    || wrapped_components.iter().any(|w| Rc::ptr_eq(w, c))
+                {
+                    continue;
+                }
+                if let Some((property, span)) = binding_requiring_parent(c) {
+                    diag.push_diagnostic_with_span(
+                        format!(
+                            "The exported component '{}' cannot be instantiated on its own because its root element sets '{}'. No API is generated for it",
+                            name, property
+                        ),
+                        span,
+                        DiagnosticLevel::Warning,
+                    );
+                    continue;
+                }
+                wrapped_components.push(c.clone());
+                exported_roots.push(wrap_exported_component(name, c));
+            }
+        }
+        exported_roots.push(root_component.clone());
+
         let custom_fonts = foreign_imports
             .into_iter()
             .filter_map(|import| {
@@ -150,8 +230,10 @@ pub fn from_node(
         Document {
             node: Some(node),
             root_component,
+            exported_roots,
             inner_components,
             inner_structs,
+            inner_enums,
             local_registry,
             custom_fonts,
             exports,
@@ -161,6 +243,74 @@ pub fn from_node(
     pub fn exports(&self) -> &Vec<(String, Type)> {
         &self.exports.0
     }
+
+    /// The union of the types used by all the `exported_roots`, so that the generators
+    /// only generate the types shared by several of them once.
+    pub fn used_types(&self) -> UsedSubTypes {
+        let mut result = UsedSubTypes::default();
+        for root in &self.exported_roots {
+            let used_types = root.used_types.borrow();
+            for g in &used_types.globals {
+                if !result.globals.iter().any(|x| Rc::ptr_eq(x, g)) {
+                    result.globals.push(g.clone());
+                }
+            }
+            for s in &used_types.structs {
+                if !result.structs.contains(s) {
+                    result.structs.push(s.clone());
+                }
+            }
+            for e in &used_types.enums {
+                if !result.enums.iter().any(|x| x.name == e.name) {
+                    result.enums.push(e.clone());
+                }
+            }
+            for c in &used_types.sub_components {
+                if !result.sub_components.iter().any(|x| Rc::ptr_eq(x, c)) {
+                    result.sub_components.push(c.clone());
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Some properties of the root element are only supported when the component is instantiated
+/// by another component. Returns the first binding of such a property in the root element
+/// of the component or of its base.
+fn binding_requiring_parent(component: &Rc<Component>) -> Option<(String, SourceLocation)> {
+    let mut root = component.root_element.clone();
+    loop {
+        if let Some((name, binding)) = root
+            .borrow()
+            .bindings
+            .iter()
+            .find(|(p, _)| *p == "opacity" || p.starts_with("drop_shadow_"))
+        {
+            return Some((name.clone(), binding.to_source_location()));
+        }
+        let base = match &root.borrow().base_type {
+            Type::Component(base) => base.root_element.clone(),
+            _ => return None,
+        };
+        root = base;
+    }
+}
+
+/// Create a component named `name` which only instantiates `component`, so that it can be
+/// compiled as a root component without modifying `component` itself.
+fn wrap_exported_component(name: &str, component: &Rc<Component>) -> Rc<Component> {
+    let wrapper = Rc::new(Component {
+        id: name.into(),
+        root_element: Rc::new(RefCell::new(Element {
+            id: "root".into(),
+            base_type: Type::Component(component.clone()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    });
+    wrapper.root_element.borrow_mut().enclosing_component = Rc::downgrade(&wrapper);
+    wrapper
 }
 
 #[derive(Debug)]
@@ -168,6 +318,8 @@ pub struct PopupWindow {
     pub component: Rc<Component>,
     pub x: NamedReference,
     pub y: NamedReference,
+    /// When the popup is a `Dialog`, the property of the parent holding its `DialogPresentation`
+    pub dialog_presentation: Option<NamedReference>,
 }
 
 type ChildrenInsertionPoint = (ElementRc, syntax_nodes::ChildrenPlaceholder);
@@ -179,6 +331,8 @@ pub struct UsedSubTypes {
     pub globals: Vec<Rc<Component>>,
     /// All the structs used by the component and its children.
     pub structs: Vec<Type>,
+    /// All the enums declared in .60 files and used by the component and its children.
+    pub enums: Vec<Rc<Enumeration>>,
     /// All the sub components use by this components and its children,
     /// and the amount of time it is used
     pub sub_components: Vec<Rc<Component>>,
@@ -218,6 +372,10 @@ pub struct Component {
     /// (This only make sense on the root component)
     pub used_types: RefCell<UsedSubTypes>,
     pub popup_windows: RefCell<Vec<PopupWindow>>,
+
+    /// Set when the elements using this component are not inlined but instantiate it as a
+    /// sub-component. The properties of its root element can then be set from the outside.
+    pub is_sub_component: Cell<bool>,
 }
 
 impl Component {
@@ -508,7 +666,7 @@ pub fn from_node(
                     Type::Invalid
                 }
                 Err(err) => {
-                    diag.push_error(err, &base_node);
+                    diag.push_error_with_code(DiagnosticCode::UnknownType, err, &base_node);
                     Type::Invalid
                 }
             }
@@ -541,7 +699,8 @@ pub fn from_node(
                     let prop_type = type_from_node(type_node.clone(), diag, tr);
 
                     if prop_type != Type::Invalid && !prop_type.is_property_type() {
-                        diag.push_error(
+                        diag.push_error_with_code(
+                            DiagnosticCode::UnknownType,
                             format!("'{}' is not a valid property type", prop_type),
                             &type_node,
                         );
@@ -578,7 +737,8 @@ pub fn from_node(
                     .insert(prop_name.to_string(), BindingExpression::new_uncompiled(csn.into()))
                     .is_some()
                 {
-                    diag.push_error(
+                    diag.push_error_with_code(
+                        DiagnosticCode::DuplicatedBinding,
                         "Duplicated property binding".into(),
                         &prop_decl.DeclaredIdentifier(),
                     );
@@ -589,7 +749,8 @@ pub fn from_node(
                     .insert(prop_name.into(), BindingExpression::new_uncompiled(csn.into()))
                     .is_some()
                 {
-                    diag.push_error(
+                    diag.push_error_with_code(
+                        DiagnosticCode::DuplicatedBinding,
                         "Duplicated property binding".into(),
                         &prop_decl.DeclaredIdentifier(),
                     );
@@ -654,7 +815,8 @@ pub fn from_node(
             if let Type::Callback { args, .. } = &property_type {
                 let num_arg = con_node.DeclaredIdentifier().count();
                 if num_arg > args.len() {
-                    diag.push_error(
+                    diag.push_error_with_code(
+                        DiagnosticCode::WrongArgumentCount,
                         format!(
                             "'{}' only has {} arguments, but {} were provided",
                             unresolved_name,
@@ -680,7 +842,8 @@ pub fn from_node(
                 )
                 .is_some()
             {
-                diag.push_error(
+                diag.push_error_with_code(
+                    DiagnosticCode::DuplicatedBinding,
                     "Duplicated callback".into(),
                     &con_node.child_token(SyntaxKind::Identifier).unwrap(),
                 );
@@ -721,7 +884,11 @@ pub fn from_node(
                                 )
                                 .is_some()
                             {
-                                diag.push_error("Duplicated animation".into(), &prop_name_token)
+                                diag.push_error_with_code(
+                                    DiagnosticCode::DuplicatedBinding,
+                                    "Duplicated animation".into(),
+                                    &prop_name_token,
+                                )
                             }
                         }
                     }
@@ -943,7 +1110,13 @@ fn parse_bindings(
             let PropertyLookupResult { resolved_name, property_type } =
                 self.lookup_property(&unresolved_name);
             if !property_type.is_property_type() {
-                diag.push_error(
+                let code = if property_type == Type::Invalid {
+                    DiagnosticCode::UnknownProperty
+                } else {
+                    DiagnosticCode::GenericError
+                };
+                diag.push_error_with_code(
+                    code,
                     match property_type {
                         Type::Invalid => {
                             if self.base_type != Type::Invalid {
@@ -980,7 +1153,11 @@ fn parse_bindings(
                 .insert(resolved_name.to_string(), BindingExpression::new_uncompiled(b))
                 .is_some()
             {
-                diag.push_error("Duplicated property binding".into(), &name_token);
+                diag.push_error_with_code(
+                    DiagnosticCode::DuplicatedBinding,
+                    "Duplicated property binding".into(),
+                    &name_token,
+                );
             }
         }
     }
@@ -1012,6 +1189,28 @@ pub fn builtin_type(&self) -> Option<Rc<BuiltinElement>> {
         }
     }
 
+    /// Returns the component this element is an instance of, if the element was not inlined.
+    /// (Repeated elements are not considered instances, even if their base is a component)
+    pub fn sub_component(&self) -> Option<&Rc<Component>> {
+        match &self.base_type {
+            Type::Component(c) if self.repeated.is_none() => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the property has a binding in this element or in the root element of its
+    /// base component. When `need_explicit` is true, the default bindings added by the passes
+    /// are not taken into account.
+    pub fn is_binding_set(&self, property_name: &str, need_explicit: bool) -> bool {
+        if self.bindings.get(property_name).map_or(false, |b| !need_explicit || b.priority > 0) {
+            true
+        } else if let Type::Component(base) = &self.base_type {
+            base.root_element.borrow().is_binding_set(property_name, need_explicit)
+        } else {
+            false
+        }
+    }
+
     pub fn layout_info_prop(&self, orientation: Orientation) -> Option<&NamedReference> {
         self.layout_info_prop.as_ref().map(|prop| match orientation {
             Orientation::Horizontal => &prop.0,
@@ -1032,7 +1231,8 @@ pub fn type_from_node(
         let prop_type = tr.lookup_qualified(&qualified_type.members);
 
         if prop_type == Type::Invalid {
-            diag.push_error(
+            diag.push_error_with_code(
+                DiagnosticCode::UnknownType,
                 format!("Unknown type '{}'", qualified_type.to_string()),
                 &qualified_type_node,
             );
@@ -1128,7 +1328,11 @@ fn lookup_property_from_qualified_name(
             let PropertyLookupResult { resolved_name, property_type } =
                 r.borrow().lookup_property(unresolved_prop_name.as_ref());
             if !property_type.is_property_type() {
-                diag.push_error(format!("'{}' is not a valid property", qualname), &node);
+                diag.push_error_with_code(
+                    DiagnosticCode::UnknownProperty,
+                    format!("'{}' is not a valid property", qualname),
+                    &node,
+                );
             }
             Some((NamedReference::new(r, &resolved_name), property_type))
         }
@@ -1137,19 +1341,28 @@ fn lookup_property_from_qualified_name(
                 let PropertyLookupResult { resolved_name, property_type } =
                     element.borrow().lookup_property(unresolved_prop_name.as_ref());
                 if !property_type.is_property_type() {
-                    diag.push_error(
+                    diag.push_error_with_code(
+                        DiagnosticCode::UnknownProperty,
                         format!("'{}' not found in '{}'", unresolved_prop_name, elem_id),
                         &node,
                     );
                 }
                 Some((NamedReference::new(&element, &resolved_name), property_type))
             } else {
-                diag.push_error(format!("'{}' is not a valid element id", elem_id), &node);
+                diag.push_error_with_code(
+                    DiagnosticCode::UnknownIdentifier,
+                    format!("'{}' is not a valid element id", elem_id),
+                    &node,
+                );
                 None
             }
         }
         _ => {
-            diag.push_error(format!("'{}' is not a valid property", qualname), &node);
+            diag.push_error_with_code(
+                DiagnosticCode::UnknownProperty,
+                format!("'{}' is not a valid property", qualname),
+                &node,
+            );
             None
         }
     }
@@ -1339,32 +1552,39 @@ fn visit_element_expressions_simple(
 
 /// Visit all the named reference in an element
 /// But does not recurse in sub-elements. (unlike [`visit_all_named_references`] which recurse)
+/// Visit all the named references in the expression and its sub-expressions
+pub fn visit_named_references_in_expression(
+    expr: &mut Expression,
+    vis: &mut impl FnMut(&mut NamedReference),
+) {
+    expr.visit_mut(|sub| visit_named_references_in_expression(sub, vis));
+    match expr {
+        Expression::PropertyReference(r) | Expression::CallbackReference(r) => vis(r),
+        Expression::TwoWayBinding(r, _) => vis(r),
+        Expression::LayoutCacheAccess { layout_cache_prop, .. } => vis(layout_cache_prop),
+        Expression::SolveLayout(l, _) => l.visit_named_references(vis),
+        Expression::ComputeLayoutInfo(l, _) => l.visit_named_references(vis),
+        // This is not really a named reference, but the result is the same, it need to be updated
+        // FIXME: this should probably be lowered into a PropertyReference
+        Expression::RepeaterModelReference { element }
+        | Expression::RepeaterIndexReference { element } => {
+            // FIXME: this is questionable
+            let mut nc = NamedReference::new(&element.upgrade().unwrap(), "$model");
+            vis(&mut nc);
+            debug_assert!(nc.element().borrow().repeated.is_some());
+            *element = Rc::downgrade(&nc.element());
+        }
+        _ => {}
+    }
+}
+
 pub fn visit_all_named_references_in_element(
     elem: &ElementRc,
     mut vis: impl FnMut(&mut NamedReference),
 ) {
-    fn recurse_expression(expr: &mut Expression, vis: &mut impl FnMut(&mut NamedReference)) {
-        expr.visit_mut(|sub| recurse_expression(sub, vis));
-        match expr {
-            Expression::PropertyReference(r) | Expression::CallbackReference(r) => vis(r),
-            Expression::TwoWayBinding(r, _) => vis(r),
-            Expression::LayoutCacheAccess { layout_cache_prop, .. } => vis(layout_cache_prop),
-            Expression::SolveLayout(l, _) => l.visit_named_references(vis),
-            Expression::ComputeLayoutInfo(l, _) => l.visit_named_references(vis),
-            // This is not really a named reference, but the result is the same, it need to be updated
-            // FIXME: this should probably be lowered into a PropertyReference
-            Expression::RepeaterModelReference { element }
-            | Expression::RepeaterIndexReference { element } => {
-                // FIXME: this is questionable
-                let mut nc = NamedReference::new(&element.upgrade().unwrap(), "$model");
-                vis(&mut nc);
-                debug_assert!(nc.element().borrow().repeated.is_some());
-                *element = Rc::downgrade(&nc.element());
-            }
-            _ => {}
-        }
-    }
-    visit_element_expressions(elem, |expr, _, _| recurse_expression(expr, &mut vis));
+    visit_element_expressions(elem, |expr, _, _| {
+        visit_named_references_in_expression(expr, &mut vis)
+    });
     let mut states = std::mem::take(&mut elem.borrow_mut().states);
     for s in &mut states {
         for (r, _) in &mut s.property_changes {
@@ -1418,22 +1638,36 @@ pub fn visit_all_named_references(
                 compo.popup_windows.borrow_mut().iter_mut().for_each(|p| {
                     vis(&mut p.x);
                     vis(&mut p.y);
+                    if let Some(presentation) = &mut p.dialog_presentation {
+                        vis(presentation);
+                    }
                 });
+                for expr in compo.setup_code.borrow_mut().iter_mut() {
+                    visit_named_references_in_expression(expr, vis);
+                }
             }
             compo
         },
     );
 }
 
-/// Visit all expression in this component and sub components
+/// Visit all expression in this component and sub components, including their setup code
 ///
 /// Does not recurse in the expression itself
 pub fn visit_all_expressions(
     component: &Component,
     mut vis: impl FnMut(&mut Expression, &dyn Fn() -> Type),
 ) {
-    recurse_elem_including_sub_components(component, &(), &mut |elem, _| {
+    recurse_elem_including_sub_components(component, &Weak::new(), &mut |elem, parent_compo| {
         visit_element_expressions(elem, |expr, _, ty| vis(expr, ty));
+        let compo = elem.borrow().enclosing_component.clone();
+        if !Weak::ptr_eq(parent_compo, &compo) {
+            let compo = compo.upgrade().unwrap();
+            for expr in compo.setup_code.borrow_mut().iter_mut() {
+                vis(expr, &|| Type::Void);
+            }
+        }
+        compo
     })
 }
 
@@ -1505,19 +1739,26 @@ struct NamedExport {
                 }
             },
         ));
-        exports.extend(doc.ExportsList().flat_map(|exports| exports.StructDeclaration()).map(
-            |st| {
-                let name = identifier_text(&st.DeclaredIdentifier()).unwrap_or_else(|| {
-                    debug_assert!(diag.has_error());
-                    String::new()
-                });
-                NamedExport {
-                    internal_name_ident: st.DeclaredIdentifier().into(),
-                    internal_name: name.clone(),
-                    exported_name: name,
-                }
-            },
-        ));
+        exports.extend(
+            doc.ExportsList()
+                .flat_map(|exports| {
+                    exports
+                        .StructDeclaration()
+                        .map(|st| st.DeclaredIdentifier())
+                        .chain(exports.EnumDeclaration().map(|en| en.DeclaredIdentifier()))
+                })
+                .map(|declared_identifier| {
+                    let name = identifier_text(&declared_identifier).unwrap_or_else(|| {
+                        debug_assert!(diag.has_error());
+                        String::new()
+                    });
+                    NamedExport {
+                        internal_name_ident: declared_identifier.into(),
+                        internal_name: name.clone(),
+                        exported_name: name,
+                    }
+                }),
+        );
 
         if exports.is_empty() {
             if let Some(internal_name) = inner_components.last().as_ref().map(|x| x.id.clone()) {
@@ -1532,6 +1773,7 @@ struct NamedExport {
         let mut resolve_export_to_inner_component_or_import =
             |export: &NamedExport| match type_registry.lookup(export.internal_name.as_str()) {
                 ty @ Type::Component(_) | ty @ Type::Struct { .. } => Some(ty),
+                Type::Enumeration(en) if en.node.is_some() => Some(Type::Enumeration(en)),
                 Type::Invalid => {
                     diag.push_error(
                         format!("'{}' not found", export.internal_name),
@@ -315,7 +315,7 @@ fn source_file(&self) -> Option<&SourceFile> {
     }
     // syntax kind
     {
-        Document -> [ *Component, *ExportsList, *ImportSpecifier, *StructDeclaration ],
+        Document -> [ *Component, *ExportsList, *ImportSpecifier, *StructDeclaration, *EnumDeclaration ],
         /// `DeclaredIdentifier := Element { ... }`
         Component -> [ DeclaredIdentifier, Element ],
         /// `id := Element { ... }`
@@ -350,7 +350,8 @@ fn source_file(&self) -> Option<&SourceFile> {
         // FIXME: the test should test that as alternative rather than several of them (but it can also be a literal)
         Expression-> [ ?Expression, ?FunctionCallExpression, ?SelfAssignment,
                        ?ConditionalExpression, ?QualifiedName, ?BinaryExpression, ?Array, ?ObjectLiteral,
-                       ?UnaryOpExpression, ?CodeBlock, ?StringTemplate, ?AtImageUrl, ?AtLinearGradient],
+                       ?UnaryOpExpression, ?CodeBlock, ?StringTemplate, ?AtImageUrl, ?AtLinearGradient,
+                       ?IndexExpression, ?MemberAccess],
         /// Concatenate the Expressions to make a string (usually expended from a template string)
         StringTemplate -> [*Expression],
         /// `@image-url("foo.png")`
@@ -359,6 +360,10 @@ fn source_file(&self) -> Option<&SourceFile> {
         AtLinearGradient -> [*Expression],
         /// expression()
         FunctionCallExpression -> [*Expression],
+        /// `expression[index]`
+        IndexExpression -> [2 Expression],
+        /// `expression.identifier`
+        MemberAccess -> [Expression],
         /// `expression += expression`
         SelfAssignment -> [2 Expression],
         /// `condition ? first : second`
@@ -384,7 +389,7 @@ fn source_file(&self) -> Option<&SourceFile> {
         /// There is an identifier "in" or "out", the DeclaredIdentifier is the state name
         Transition -> [DeclaredIdentifier, *PropertyAnimation],
         /// Export a set of declared components by name
-        ExportsList -> [ *ExportSpecifier, ?Component, *StructDeclaration ],
+        ExportsList -> [ *ExportSpecifier, ?Component, *StructDeclaration, *EnumDeclaration ],
         /// Declare the first identifier to be exported, either under its name or instead
         /// under the name of the second identifier.
         ExportSpecifier -> [ ExportIdentifier, ?ExportName ],
@@ -407,6 +412,10 @@ fn source_file(&self) -> Option<&SourceFile> {
         ArrayType -> [ Type ],
         /// `struct Foo := { ... }
         StructDeclaration -> [DeclaredIdentifier, ObjectType],
+        /// `enum Foo := { bar, baz }`
+        EnumDeclaration -> [DeclaredIdentifier, *EnumValue],
+        /// The identifier of a value in an EnumDeclaration
+        EnumValue -> [],
 
     }
 }
@@ -628,18 +637,23 @@ fn consume(&mut self) {
     fn error(&mut self, e: impl Into<String>) {
         let current_token = self.current_token();
         #[allow(unused_mut)]
-        let mut span = crate::diagnostics::Span::new(current_token.offset);
+        let mut span = crate::diagnostics::Span::new_with_length(
+            current_token.offset,
+            current_token.text.len(),
+        );
         #[cfg(feature = "proc_macro_span")]
         {
             span.span = current_token.span;
         }
 
-        self.diags.push_error_with_span(
+        self.diags.push_diagnostic_with_span_and_code(
             e.into(),
             crate::diagnostics::SourceLocation {
                 source_file: Some(self.source_file.clone()),
                 span,
             },
+            crate::diagnostics::DiagnosticLevel::Error,
+            crate::diagnostics::DiagnosticCode::SyntaxError,
         );
     }
 
@@ -778,7 +792,8 @@ pub fn into_node(self) -> Option<SyntaxNode> {
 
 impl Spanned for SyntaxNode {
     fn span(&self) -> crate::diagnostics::Span {
-        crate::diagnostics::Span::new(self.node.text_range().start().into())
+        let range = self.node.text_range();
+        crate::diagnostics::Span::new_with_length(range.start().into(), range.len().into())
     }
 
     fn source_file(&self) -> Option<&SourceFile> {
@@ -798,7 +813,8 @@ fn source_file(&self) -> Option<&SourceFile> {
 
 impl Spanned for SyntaxToken {
     fn span(&self) -> crate::diagnostics::Span {
-        crate::diagnostics::Span::new(self.token.text_range().start().into())
+        let range = self.token.text_range();
+        crate::diagnostics::Span::new_with_length(range.start().into(), range.len().into())
     }
 
     fn source_file(&self) -> Option<&SourceFile> {
@@ -10,7 +10,7 @@
 
 use super::element::{parse_element, parse_element_content};
 use super::prelude::*;
-use super::r#type::parse_struct_declaration;
+use super::r#type::{parse_enum_declaration, parse_struct_declaration};
 
 #[cfg_attr(test, parser_test)]
 /// ```test,Document
@@ -20,6 +20,7 @@
 /// Type := Base {} export { Type }
 /// import { Base } from "somewhere"; Type := Base {}
 /// struct Foo := { foo: foo }
+/// enum Foo := { foo, bar }
 /// ```
 pub fn parse_document(p: &mut impl Parser) -> bool {
     let mut p = p.start_node(SyntaxKind::Document);
@@ -41,6 +42,11 @@ pub fn parse_document(p: &mut impl Parser) -> bool {
                     return false;
                 }
             }
+            "enum" => {
+                if !parse_enum_declaration(&mut *p) {
+                    return false;
+                }
+            }
             _ => {
                 if !parse_component(&mut *p) {
                     return false;
@@ -116,6 +122,7 @@ pub fn parse_qualified_name(p: &mut impl Parser) -> bool {
 /// export { Type as Foo, AnotherType }
 /// export Foo := Item { }
 /// export struct Foo := { foo: bar }
+/// export enum Foo := { bar }
 /// ```
 fn parse_export(p: &mut impl Parser) -> bool {
     debug_assert_eq!(p.peek().as_str(), "export");
@@ -144,6 +151,8 @@ fn parse_export(p: &mut impl Parser) -> bool {
         }
     } else if p.peek().as_str() == "struct" {
         parse_struct_declaration(&mut *p)
+    } else if p.peek().as_str() == "enum" {
+        parse_enum_declaration(&mut *p)
     } else {
         parse_component(&mut *p)
     }
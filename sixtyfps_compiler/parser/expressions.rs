@@ -32,6 +32,11 @@
 /// aa == cc && bb && (xxx || fff) && 3 + aaa == bbb
 /// [array]
 /// {object:42}
+/// array[index]
+/// array[index].member
+/// array[0][1 + 1]
+/// foo.bar[index].baz.length
+/// (something).member
 /// ```
 pub fn parse_expression(p: &mut impl Parser) -> bool {
     parse_expression_helper(p, OperatorPrecedence::Default)
@@ -90,12 +95,34 @@ fn parse_expression_helper(p: &mut impl Parser, precedence: OperatorPrecedence)
         }
     }
 
-    if p.nth(0).kind() == SyntaxKind::LParent {
-        {
-            let _ = p.start_node_at(checkpoint.clone(), SyntaxKind::Expression);
+    loop {
+        match p.nth(0).kind() {
+            SyntaxKind::LParent => {
+                {
+                    let _ = p.start_node_at(checkpoint.clone(), SyntaxKind::Expression);
+                }
+                let mut p = p.start_node_at(checkpoint.clone(), SyntaxKind::FunctionCallExpression);
+                parse_function_arguments(&mut *p);
+            }
+            SyntaxKind::LBracket => {
+                {
+                    let _ = p.start_node_at(checkpoint.clone(), SyntaxKind::Expression);
+                }
+                let mut p = p.start_node_at(checkpoint.clone(), SyntaxKind::IndexExpression);
+                p.consume(); // "["
+                parse_expression(&mut *p);
+                p.expect(SyntaxKind::RBracket);
+            }
+            SyntaxKind::Dot => {
+                {
+                    let _ = p.start_node_at(checkpoint.clone(), SyntaxKind::Expression);
+                }
+                let mut p = p.start_node_at(checkpoint.clone(), SyntaxKind::MemberAccess);
+                p.consume(); // "."
+                p.expect(SyntaxKind::Identifier);
+            }
+            _ => break,
         }
-        let mut p = p.start_node_at(checkpoint.clone(), SyntaxKind::FunctionCallExpression);
-        parse_function_arguments(&mut *p);
     }
 
     if precedence >= OperatorPrecedence::Mul {
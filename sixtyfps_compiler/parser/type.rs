@@ -84,3 +84,33 @@ pub fn parse_struct_declaration(p: &mut impl Parser) -> bool {
     parse_type_object(&mut *p);
     true
 }
+
+#[cfg_attr(test, parser_test)]
+/// ```test,EnumDeclaration
+/// enum Foo := { bar, baz }
+/// enum Foo := { bar, xxx-yyy, }
+/// enum Empty := {}
+/// ```
+pub fn parse_enum_declaration(p: &mut impl Parser) -> bool {
+    debug_assert_eq!(p.peek().as_str(), "enum");
+    let mut p = p.start_node(SyntaxKind::EnumDeclaration);
+    p.consume(); // "enum"
+    {
+        let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+        p.expect(SyntaxKind::Identifier);
+    }
+    p.expect(SyntaxKind::ColonEqual);
+    if !p.expect(SyntaxKind::LBrace) {
+        return false;
+    }
+    while p.nth(0).kind() != SyntaxKind::RBrace {
+        {
+            let mut p = p.start_node(SyntaxKind::EnumValue);
+            p.expect(SyntaxKind::Identifier);
+        }
+        if !p.test(SyntaxKind::Comma) {
+            break;
+        }
+    }
+    p.expect(SyntaxKind::RBrace)
+}
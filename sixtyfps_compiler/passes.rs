@@ -12,12 +12,14 @@
 mod binding_analysis;
 mod check_expressions;
 mod check_public_api;
+mod check_shortcuts;
 mod clip;
 mod collect_custom_fonts;
 mod collect_globals;
 mod collect_structs;
 mod collect_subcomponents;
 mod compile_paths;
+mod const_propagation;
 mod deduplicate_property_read;
 mod default_geometry;
 mod embed_resources;
@@ -27,7 +29,9 @@
 mod generate_item_indices;
 mod infer_aliases_types;
 mod inlining;
+mod lint;
 mod lower_layout;
+mod lower_menus;
 mod lower_popups;
 mod lower_shadows;
 mod lower_states;
@@ -42,6 +46,10 @@
 mod unique_id;
 mod z_order;
 
+use by_address::ByAddress;
+use std::collections::HashSet;
+use std::rc::Rc;
+
 pub async fn run_passes(
     doc: &crate::object_tree::Document,
     diag: &mut crate::diagnostics::BuildDiagnostics,
@@ -55,63 +63,115 @@ pub async fn run_passes(
     check_expressions::check_expressions(doc, diag);
     unique_id::check_unique_id(doc, diag);
     check_public_api::check_public_api(root_component, diag);
+    check_shortcuts::check_shortcuts(doc, diag);
+    lint::lint(doc, &compiler_config.lints, diag);
 
-    collect_subcomponents::collect_subcomponents(root_component);
-    for component in root_component
-        .used_types
-        .borrow()
-        .sub_components
-        .iter()
-        .chain(std::iter::once(root_component))
-    {
-        compile_paths::compile_paths(component, &doc.local_registry, diag);
+    let mut path_compiled = HashSet::new();
+    for root in &doc.exported_roots {
+        collect_subcomponents::collect_subcomponents(root);
+        for component in root.used_types.borrow().sub_components.iter().chain(std::iter::once(root))
+        {
+            if path_compiled.insert(ByAddress(component.clone())) {
+                compile_paths::compile_paths(component, &doc.local_registry, diag);
+            }
+        }
     }
 
     if compiler_config.embed_resources {
-        embed_resources::embed_resources(root_component);
+        // All the resources are registered in the main component so that the generated symbols
+        // are unique
+        for root in &doc.exported_roots {
+            embed_resources::embed_resources(root, &root_component.embedded_file_resources);
+        }
     }
 
-    inlining::inline(doc);
-    focus_item::resolve_element_reference_in_set_focus_calls(root_component, diag);
-    focus_item::determine_initial_focus_item(root_component, diag);
-    focus_item::erase_forward_focus_properties(root_component);
-    flickable::handle_flickable(root_component, &global_type_registry.borrow());
-    lower_states::lower_states(root_component, &doc.local_registry, diag);
-    repeater_component::process_repeater_components(root_component);
-    lower_popups::lower_popups(root_component, &doc.local_registry, diag);
-    lower_layout::lower_layouts(root_component, &global_type_registry.borrow(), diag);
-    z_order::reorder_by_z_order(root_component, diag);
-    lower_shadows::lower_shadow_properties(root_component, &doc.local_registry, diag);
-    clip::handle_clip(root_component, &global_type_registry.borrow(), diag);
-    transform_and_opacity::handle_transform_and_opacity(
-        root_component,
-        &global_type_registry.borrow(),
-        diag,
-    );
-    default_geometry::default_geometry(root_component, diag);
-    materialize_fake_properties::materialize_fake_properties(root_component);
-    apply_default_properties_from_style::apply_default_properties_from_style(
-        root_component,
-        &mut type_loader,
-        diag,
-    )
-    .await;
-    ensure_window::ensure_window(root_component, &doc.local_registry);
-    collect_globals::collect_globals(root_component, diag);
-    unique_id::assign_unique_id(root_component);
-    binding_analysis::binding_analysis(root_component, diag);
-    deduplicate_property_read::deduplicate_property_read(root_component);
-    move_declarations::move_declarations(root_component, diag);
-    remove_aliases::remove_aliases(root_component, diag);
-    resolve_native_classes::resolve_native_classes(root_component);
-    remove_unused_properties::remove_unused_properties(root_component);
-    collect_structs::collect_structs(root_component, diag);
-    generate_item_indices::generate_item_indices(root_component);
-    collect_custom_fonts::collect_custom_fonts(
-        root_component,
-        std::iter::once(&*doc).chain(type_loader.all_documents()),
-        compiler_config.embed_resources,
+    inlining::inline(
+        doc,
+        if compiler_config.inline_all_elements {
+            inlining::InlineSelection::InlineAllComponents
+        } else {
+            inlining::InlineSelection::InlineOnlyRequiredComponents
+        },
     );
+
+    // The properties of the other exported components are only known once their component is
+    // inlined in the component wrapping them.
+    for root in doc.exported_roots.iter().filter(|root| !Rc::ptr_eq(root, root_component)) {
+        for decl in root.root_element.borrow_mut().property_declarations.values_mut() {
+            decl.expose_in_public_api = decl.property_type.ok_for_public_api();
+        }
+    }
+
+    // The components that were not inlined are processed first, so that they are final when
+    // the passes look at them from the components instantiating them.
+    // The sub-components shared by several exported components are only processed once.
+    let mut processed_components = Vec::new();
+    let mut processed = HashSet::new();
+    for root in &doc.exported_roots {
+        collect_subcomponents::collect_subcomponents(root);
+        let sub_components = root.used_types.borrow().sub_components.clone();
+        for component in sub_components
+            .iter()
+            .filter(|c| processed.insert(ByAddress((*c).clone())))
+            .chain(std::iter::once(root))
+        {
+            let is_root = Rc::ptr_eq(component, root);
+            focus_item::resolve_element_reference_in_set_focus_calls(component, diag);
+            if is_root {
+                focus_item::determine_initial_focus_item(component, diag);
+            }
+            focus_item::erase_forward_focus_properties(component);
+            flickable::handle_flickable(component, &global_type_registry.borrow());
+            lower_states::lower_states(component, &doc.local_registry, diag);
+            repeater_component::process_repeater_components(component);
+            lower_menus::lower_menus(component, &doc.local_registry, diag);
+            lower_popups::lower_popups(component, &doc.local_registry, diag);
+            lower_layout::lower_layouts(component, &global_type_registry.borrow(), diag);
+            z_order::reorder_by_z_order(component, diag);
+            lower_shadows::lower_shadow_properties(component, &doc.local_registry, diag);
+            clip::handle_clip(component, &global_type_registry.borrow(), diag);
+            transform_and_opacity::handle_transform_and_opacity(
+                component,
+                &global_type_registry.borrow(),
+                diag,
+            );
+            default_geometry::default_geometry(component, diag);
+            materialize_fake_properties::materialize_fake_properties(component);
+            apply_default_properties_from_style::apply_default_properties_from_style(
+                component,
+                &mut type_loader,
+                diag,
+            )
+            .await;
+            if is_root {
+                ensure_window::ensure_window(component, &doc.local_registry);
+            }
+            collect_globals::collect_globals(component, diag);
+            unique_id::assign_unique_id(component);
+            binding_analysis::binding_analysis(component, diag);
+            const_propagation::const_propagation(component);
+            deduplicate_property_read::deduplicate_property_read(component);
+            move_declarations::move_declarations(component, diag);
+            remove_aliases::remove_aliases(component, diag);
+            remove_unused_properties::remove_unused_properties(component);
+            generate_item_indices::generate_item_indices(component);
+            processed_components.push(component.clone());
+        }
+    }
+    // Only done at the end because the components instantiating a sub-component still need the
+    // builtin type of its root element.
+    for component in &processed_components {
+        resolve_native_classes::resolve_native_classes(component);
+    }
+    for root in &doc.exported_roots {
+        collect_structs::collect_structs(root, diag);
+        collect_custom_fonts::collect_custom_fonts(
+            root,
+            &root_component.embedded_file_resources,
+            std::iter::once(&*doc).chain(type_loader.all_documents()),
+            compiler_config.embed_resources,
+        );
+    }
 }
 
 /// Run the passes on imported documents
@@ -124,4 +184,6 @@ pub fn run_import_passes(
     resolving::resolve_expressions(doc, type_loader, diag);
     check_expressions::check_expressions(doc, diag);
     unique_id::check_unique_id(doc, diag);
+    check_shortcuts::check_shortcuts(doc, diag);
+    lint::lint(doc, &type_loader.compiler_config.lints, diag);
 }
@@ -35,6 +35,10 @@ pub async fn apply_default_properties_from_style<'a>(
         &(),
         &mut |elem, _| {
             let mut elem = elem.borrow_mut();
+            if elem.sub_component().is_some() {
+                // Already applied on the root element of the sub-component
+                return;
+            }
             match elem.native_class().as_ref().map(|nc| nc.class_name.as_str()) {
                 Some("TextInput") => {
                     elem.bindings.entry("text_cursor_width".into()).or_insert_with(|| {
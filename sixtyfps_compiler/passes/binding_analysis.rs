@@ -9,19 +9,38 @@
 LICENSE END */
 //! Compute binding analysis and attempt to find binding loops
 
+use std::collections::HashSet;
 use std::rc::Rc;
 
-use crate::diagnostics::BuildDiagnostics;
-use crate::diagnostics::Spanned;
+use by_address::ByAddress;
+
+use crate::diagnostics::{
+    BuildDiagnostics, Diagnostic, DiagnosticCode, DiagnosticLevel, SourceLocation, Spanned,
+};
 use crate::expression_tree::BuiltinFunction;
 use crate::expression_tree::Expression;
 use crate::langtype::Type;
 use crate::layout::LayoutItem;
 use crate::layout::Orientation;
 use crate::namedreference::NamedReference;
-use crate::object_tree::{Component, ElementRc};
+use crate::object_tree::{Component, Element, ElementRc};
+
+/// The instances of sub-components in which the bindings of a sub-component are analysed,
+/// outermost first. It is empty for the bindings of the component being analysed.
+type SubComponentInstances = Vec<ByAddress<ElementRc>>;
+
+/// A property whose binding is analysed, within the instances of sub-components it belongs to
+type AnalysedProperty = (SubComponentInstances, NamedReference);
 
-type PropertySet = linked_hash_set::LinkedHashSet<NamedReference>;
+type PropertySet = linked_hash_set::LinkedHashSet<AnalysedProperty>;
+
+#[derive(Default)]
+struct AnalysisContext {
+    currently_analysing: PropertySet,
+    /// The bindings of sub-components already analysed within an instance. Unlike the bindings
+    /// of the component, their analysis is not stored as it depends on the instance.
+    analysed_in_sub_components: HashSet<AnalysedProperty>,
+}
 
 pub fn binding_analysis(component: &Rc<Component>, diag: &mut BuildDiagnostics) {
     propagate_is_set_on_aliases(component);
@@ -41,8 +60,7 @@ pub fn binding_analysis(component: &Rc<Component>, diag: &mut BuildDiagnostics)
                 if binding.analysis.borrow().is_some() {
                     continue;
                 }
-                let mut set = PropertySet::default();
-                analyse_binding(e, name, &mut set, diag);
+                analyse_binding(e, name, &vec![], &mut AnalysisContext::default(), diag);
             }
         },
     );
@@ -51,10 +69,12 @@ pub fn binding_analysis(component: &Rc<Component>, diag: &mut BuildDiagnostics)
 fn analyse_binding(
     element: &ElementRc,
     name: &str,
-    currently_analysing: &mut PropertySet,
+    instances: &SubComponentInstances,
+    context: &mut AnalysisContext,
     diag: &mut BuildDiagnostics,
 ) {
-    let nr = NamedReference::new(element, name);
+    let nr = (instances.clone(), NamedReference::new(element, name));
+    let currently_analysing = &mut context.currently_analysing;
     if currently_analysing.back().map_or(false, |r| *r == nr)
         && matches!(element.borrow().bindings[name].expression, Expression::TwoWayBinding(..))
     {
@@ -64,11 +84,19 @@ fn analyse_binding(
     }
 
     if currently_analysing.contains(&nr) {
+        // The properties forming the loop, in the order in which they depend on each other
+        let chain = currently_analysing.iter().skip_while(|p| **p != nr).collect::<Vec<_>>();
+        let chain_text = chain
+            .iter()
+            .chain(std::iter::once(&&nr))
+            .map(|p| display_name(p))
+            .collect::<Vec<_>>()
+            .join(" -> ");
         for p in currently_analysing.iter().rev() {
-            let elem = p.element();
+            let elem = p.1.element();
             let elem = elem.borrow();
             if std::mem::replace(
-                &mut elem.bindings[p.name()]
+                &mut elem.bindings[p.1.name()]
                     .analysis
                     .borrow_mut()
                     .get_or_insert(Default::default())
@@ -78,14 +106,24 @@ fn analyse_binding(
                 break;
             }
 
-            let span = elem.bindings[p.name()]
-                .span
-                .clone()
-                .or_else(|| elem.node.as_ref().map(|n| n.to_source_location()));
-            diag.push_error(
-                format!("The binding for the property '{}' is part of a binding loop", p.name()),
-                &span,
+            let mut diagnostic = Diagnostic::new(
+                DiagnosticLevel::Error,
+                DiagnosticCode::BindingLoop,
+                format!(
+                    "The binding for the property '{}' is part of a binding loop ({})",
+                    p.1.name(),
+                    chain_text
+                ),
+                &binding_location(&elem, p.1.name()),
             );
+            for (i, dep) in chain.iter().enumerate() {
+                let next = chain.get(i + 1).copied().unwrap_or(&nr);
+                diagnostic = diagnostic.with_note(
+                    format!("'{}' depends on '{}'", display_name(dep), display_name(next)),
+                    &binding_location(&dep.1.element().borrow(), dep.1.name()),
+                );
+            }
+            diag.push_compiler_error(diagnostic);
 
             if *p == nr {
                 break;
@@ -93,21 +131,39 @@ fn analyse_binding(
         }
         return;
     }
+    if !instances.is_empty() && !context.analysed_in_sub_components.insert(nr.clone()) {
+        return;
+    }
     currently_analysing.insert(nr.clone());
 
     recurse_expression(&element.borrow().bindings[name], &mut |prop: &NamedReference| {
-        prop.element()
-            .borrow()
-            .property_analysis
-            .borrow_mut()
-            .entry(prop.name().into())
-            .or_default()
-            .is_read = true;
-        if let Some(binding) = prop.element().borrow().bindings.get(prop.name()) {
-            if binding.analysis.borrow().is_some() {
+        if instances.is_empty() {
+            prop.element()
+                .borrow()
+                .property_analysis
+                .borrow_mut()
+                .entry(prop.name().into())
+                .or_default()
+                .is_read = true;
+        }
+        let (instances, prop) = resolve_in_instances(instances.clone(), prop);
+        let elem = prop.element();
+        if let Some(binding) = elem.borrow().bindings.get(prop.name()) {
+            if instances.is_empty() && binding.analysis.borrow().is_some() {
                 return;
             }
-            analyse_binding(&prop.element(), prop.name(), currently_analysing, diag);
+            analyse_binding(&elem, prop.name(), &instances, context, diag);
+            return;
+        }
+        let base_type = elem.borrow().base_type.clone();
+        if let Type::Component(sub_component) = base_type {
+            // The property of an instance of a sub-component may be bound in the sub-component
+            let root = &sub_component.root_element;
+            if root.borrow().bindings.contains_key(prop.name()) {
+                let mut instances = instances;
+                instances.push(ByAddress(elem.clone()));
+                analyse_binding(root, prop.name(), &instances, context, diag);
+            }
         }
     });
 
@@ -121,10 +177,52 @@ fn analyse_binding(
         analysis.is_const = is_const;
     }
 
-    let o = currently_analysing.pop_back();
+    let o = context.currently_analysing.pop_back();
     assert_eq!(o.unwrap(), nr);
 }
 
+/// The properties of the root element of a sub-component are the properties of its instance
+fn resolve_in_instances(
+    mut instances: SubComponentInstances,
+    nr: &NamedReference,
+) -> AnalysedProperty {
+    let mut nr = nr.clone();
+    while let Some(instance) = instances.last() {
+        let is_root = match &instance.borrow().base_type {
+            Type::Component(c) => Rc::ptr_eq(&c.root_element, &nr.element()),
+            _ => false,
+        };
+        if !is_root {
+            break;
+        }
+        nr = NamedReference::new(instance, nr.name());
+        instances.pop();
+    }
+    (instances, nr)
+}
+
+/// The location of the binding of the property, or of its element if the binding was generated
+fn binding_location(elem: &Element, name: &str) -> Option<SourceLocation> {
+    elem.bindings[name].span.clone().or_else(|| elem.node.as_ref().map(|n| n.to_source_location()))
+}
+
+/// The name of a property as `element.property` for diagnostics, without the suffix added to
+/// the id by the unique_id pass
+fn display_name((instances, nr): &AnalysedProperty) -> String {
+    let (_, nr) = resolve_in_instances(instances.clone(), nr);
+    let elem = nr.element();
+    let elem = elem.borrow();
+    if elem
+        .enclosing_component
+        .upgrade()
+        .map_or(false, |c| Rc::ptr_eq(&c.root_element, &nr.element()))
+    {
+        return format!("root.{}", nr.name());
+    }
+    let id = elem.id.rfind('_').map_or(elem.id.as_str(), |pos| &elem.id[..pos]);
+    format!("{}.{}", id, nr.name())
+}
+
 // Same as in crate::visit_all_named_references_in_element, but not mut
 fn recurse_expression(expr: &Expression, vis: &mut impl FnMut(&NamedReference)) {
     expr.visit(|sub| recurse_expression(sub, vis));
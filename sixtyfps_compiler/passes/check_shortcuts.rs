@@ -0,0 +1,91 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+
+//! Pass that checks the Shortcut elements: their constant sequences must be valid, they cannot be
+//! in a layout, and two shortcuts that are always enabled cannot use the same sequence, as none of
+//! them would be activated.
+//!
+//! This must run on a document before inlining, as it reports on the code as written.
+
+use crate::diagnostics::{BuildDiagnostics, DiagnosticLevel};
+use crate::expression_tree::Expression;
+use crate::object_tree::*;
+use sixtyfps_common::key_sequence::KeySequence;
+
+pub fn check_shortcuts(doc: &Document, diag: &mut BuildDiagnostics) {
+    for component in &doc.inner_components {
+        let mut always_enabled = Vec::new();
+        recurse_elem(
+            &component.root_element,
+            &(false, None),
+            &mut |elem, (in_repeated, parent)| {
+                let in_repeated = *in_repeated || elem.borrow().repeated.is_some();
+                if elem.borrow().base_type.to_string() == "Shortcut" {
+                    check_shortcut(elem, in_repeated, parent, &mut always_enabled, diag);
+                }
+                (in_repeated, Some(elem.borrow().base_type.to_string()))
+            },
+        );
+    }
+}
+
+fn check_shortcut(
+    elem: &ElementRc,
+    in_repeated: bool,
+    parent: &Option<String>,
+    always_enabled: &mut Vec<KeySequence>,
+    diag: &mut BuildDiagnostics,
+) {
+    let e = elem.borrow();
+    if parent.as_ref().map_or(false, |p| {
+        matches!(
+            p.as_str(),
+            "GridLayout" | "Row" | "VerticalLayout" | "HorizontalLayout" | "PathLayout"
+        )
+    }) {
+        diag.push_error("A Shortcut cannot be placed in a layout".into(), &*e);
+    }
+
+    let (binding, sequence) = match e.bindings.get("sequence") {
+        Some(binding) => match &binding.expression {
+            Expression::StringLiteral(sequence) => (binding, sequence),
+            _ => return,
+        },
+        None => return,
+    };
+    let parsed = match KeySequence::parse(sequence) {
+        Some(parsed) => parsed,
+        None => {
+            diag.push_error(format!("'{}' is not a valid key sequence", sequence), &binding.span);
+            return;
+        }
+    };
+
+    let is_constant = |property: &str| !e.named_references.is_referenced(property);
+    let always_true = e
+        .bindings
+        .get("enabled")
+        .map_or(true, |b| matches!(b.expression, Expression::BoolLiteral(true)));
+    if in_repeated || !always_true || !is_constant("enabled") || !is_constant("sequence") {
+        return;
+    }
+    if always_enabled.contains(&parsed) {
+        diag.push_diagnostic(
+            format!(
+                "The key sequence '{}' is used by another Shortcut that is always enabled, none of them will be activated",
+                sequence
+            ),
+            &binding.span,
+            DiagnosticLevel::Warning,
+        );
+    } else {
+        always_enabled.push(parsed);
+    }
+}
@@ -14,12 +14,16 @@
     expression_tree::{BuiltinFunction, Expression, Unit},
     object_tree::*,
 };
-use std::collections::BTreeSet;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
 use std::rc::Rc;
 
 /// Fill the root_component's used_globals
+///
+/// When embedding the fonts, they are registered in `embedded_file_resources`.
 pub fn collect_custom_fonts<'a>(
     root_component: &Rc<Component>,
+    embedded_file_resources: &RefCell<HashMap<String, usize>>,
     all_docs: impl Iterator<Item = &'a crate::object_tree::Document> + 'a,
     embed_fonts: bool,
 ) {
@@ -39,7 +43,7 @@ pub fn collect_custom_fonts<'a>(
         Box::new(|font_path| {
             Expression::NumberLiteral(
                 {
-                    let mut resources = root_component.embedded_file_resources.borrow_mut();
+                    let mut resources = embedded_file_resources.borrow_mut();
                     let resource_id = match resources.get(font_path) {
                         Some(id) => *id,
                         None => {
@@ -30,6 +30,12 @@ pub fn collect_globals(root_component: &Rc<Component>, _diag: &mut BuildDiagnost
         }
     };
     visit_all_named_references(root_component, &mut maybe_collect_global);
+    // The globals used by the sub-components are passed to them by this component
+    recurse_elem_including_sub_components(root_component, &(), &mut |elem, _| {
+        if let Some(sub_component) = elem.borrow().sub_component() {
+            set.extend(sub_component.used_types.borrow().globals.iter().cloned().map(ByAddress));
+        }
+    });
     root_component.used_types.borrow_mut().globals = set.into_iter().map(|x| x.0).collect();
     root_component.used_types.borrow_mut().globals.sort_by(|a, b| a.id.cmp(&b.id));
 }
@@ -8,7 +8,7 @@
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
 
-//! Passes that fills the root component used_types.structs
+//! Passes that fills the root component used_types.structs and used_types.enums
 
 use crate::expression_tree::Expression;
 use crate::object_tree::*;
@@ -16,31 +16,43 @@
 use std::collections::BTreeMap;
 use std::rc::Rc;
 
-/// Fill the root_component's used_types.structs
+/// Fill the root_component's used_types.structs and used_types.enums
 pub fn collect_structs(root_component: &Rc<Component>, _diag: &mut BuildDiagnostics) {
     let mut hash = BTreeMap::new();
+    let mut enums = BTreeMap::new();
 
     let mut maybe_collect_object = |ty: &Type| {
-        visit_named_object(ty, &mut |name, sub_ty| {
-            hash.entry(name.clone()).or_insert_with(|| sub_ty.clone());
+        visit_named_object(ty, &mut |name, sub_ty| match sub_ty {
+            Type::Enumeration(e) => {
+                enums.entry(name.clone()).or_insert_with(|| e.clone());
+            }
+            _ => {
+                hash.entry(name.clone()).or_insert_with(|| sub_ty.clone());
+            }
         });
     };
 
-    recurse_elem_including_sub_components_no_borrow(root_component, &(), &mut |elem, _| {
-        for x in elem.borrow().property_declarations.values() {
-            maybe_collect_object(&x.property_type);
-        }
-    });
-
-    visit_all_expressions(root_component, |expr, _| {
-        expr.visit_recursive(&mut |expr| {
-            if let Expression::Struct { ty, .. } = expr {
-                maybe_collect_object(ty)
+    let sub_components = root_component.used_types.borrow().sub_components.clone();
+    for component in std::iter::once(root_component).chain(sub_components.iter()) {
+        recurse_elem_including_sub_components_no_borrow(component, &(), &mut |elem, _| {
+            for x in elem.borrow().property_declarations.values() {
+                maybe_collect_object(&x.property_type);
             }
-        })
-    });
+        });
+
+        visit_all_expressions(component, |expr, _| {
+            expr.visit_recursive(&mut |expr| match expr {
+                Expression::Struct { ty, .. } => maybe_collect_object(ty),
+                Expression::EnumerationValue(value) => {
+                    maybe_collect_object(&Type::Enumeration(value.enumeration.clone()))
+                }
+                _ => {}
+            })
+        });
+    }
 
     let mut used_types = root_component.used_types.borrow_mut();
+    used_types.enums = enums.into_values().collect();
     let used_struct = &mut used_types.structs;
     *used_struct = Vec::with_capacity(hash.len());
     while let Some(next) = hash.iter().next() {
@@ -77,6 +89,7 @@ fn visit_named_object(ty: &Type, visitor: &mut impl FnMut(&String, &Type)) {
             }
         }
         Type::Array(x) => visit_named_object(x, visitor),
+        Type::Enumeration(e) if e.node.is_some() => visitor(&e.name, ty),
         Type::Callback { return_type, args } => {
             if let Some(rt) = return_type {
                 visit_named_object(&rt, visitor);
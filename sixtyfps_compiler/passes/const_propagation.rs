@@ -0,0 +1,306 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+//! Evaluate the constant expressions at compile time, and replace the read of constant
+//! properties by their value.
+//!
+//! This pass must run after the binding analysis, so that we know which properties are
+//! constant, and it updates the analysis of the bindings which became constant.
+
+use crate::builtin_macros::{hsla_to_argb, hsva_to_argb};
+use crate::expression_tree::*;
+use crate::langtype::Type;
+use crate::object_tree::*;
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+pub fn const_propagation(component: &Component) {
+    let mut aliased = HashSet::new();
+    visit_all_expressions(component, |expr, _| {
+        expr.visit_recursive(&mut |e| {
+            if let Expression::TwoWayBinding(nr, _) = e {
+                aliased.insert(nr.clone());
+            }
+        })
+    });
+    let state = ConstPropagationState { aliased, visited: Default::default() };
+
+    recurse_elem_including_sub_components_no_borrow(component, &(), &mut |elem, _| {
+        let names = elem.borrow().bindings.keys().cloned().collect::<Vec<_>>();
+        for name in names {
+            simplify_binding(elem, &name, &state);
+        }
+    });
+    // The bindings were already handled, but there are other expressions in the element
+    // such as the model of the repeaters
+    visit_all_expressions(component, |expr, _| simplify_expression(expr, &state));
+}
+
+struct ConstPropagationState {
+    /// Properties that are the target of a two way binding: their value does not
+    /// necessarily come from their own binding
+    aliased: HashSet<NamedReference>,
+    /// The bindings that are already simplified, or in the process of being simplified
+    visited: RefCell<HashSet<NamedReference>>,
+}
+
+fn simplify_binding(elem: &ElementRc, name: &str, state: &ConstPropagationState) {
+    if !state.visited.borrow_mut().insert(NamedReference::new(elem, name)) {
+        return;
+    }
+    let mut expression = match elem.borrow().bindings.get(name) {
+        Some(binding) => binding.expression.clone(),
+        None => return,
+    };
+    simplify_expression(&mut expression, state);
+    let is_constant = expression.is_constant();
+    let mut elem = elem.borrow_mut();
+    let binding = elem.bindings.get_mut(name).unwrap();
+    binding.expression = expression;
+    if is_constant {
+        if let Some(analysis) = binding.analysis.borrow_mut().as_mut() {
+            analysis.is_const = true;
+        }
+    }
+}
+
+fn simplify_expression(expr: &mut Expression, state: &ConstPropagationState) {
+    expr.visit_mut(|e| simplify_expression(e, state));
+    if let Some(simplified) = fold(expr, state) {
+        *expr = simplified;
+    }
+}
+
+/// Return the simplified version of the expression, assuming its sub expressions were already
+/// simplified. Returns None if the expression can't be simplified.
+fn fold(expr: &Expression, state: &ConstPropagationState) -> Option<Expression> {
+    match expr {
+        Expression::PropertyReference(nr) => {
+            if state.aliased.contains(nr) || !nr.is_constant() {
+                return None;
+            }
+            let elem = nr.element();
+            simplify_binding(&elem, nr.name(), state);
+            let elem = elem.borrow();
+            let value = &elem.bindings.get(nr.name())?.expression;
+            (is_literal(value) && value.ty() == nr.ty()).then(|| value.clone())
+        }
+        Expression::Cast { from, to: Type::Float32 } => match **from {
+            Expression::NumberLiteral(value, Unit::None) => {
+                Some(Expression::NumberLiteral(value, Unit::None))
+            }
+            _ => None,
+        },
+        Expression::UnaryOp { sub, op } => match (&**sub, op) {
+            (Expression::BoolLiteral(b), '!') => Some(Expression::BoolLiteral(!b)),
+            (Expression::NumberLiteral(value, unit), '-') => {
+                Some(Expression::NumberLiteral(-value, *unit))
+            }
+            (Expression::NumberLiteral(value, unit), '+') => {
+                Some(Expression::NumberLiteral(*value, *unit))
+            }
+            _ => None,
+        },
+        Expression::BinaryExpression { lhs, rhs, op } => {
+            fold_binary_expression(expr, lhs, rhs, *op)
+        }
+        Expression::Condition { condition, true_expr, false_expr } => {
+            let result = match **condition {
+                Expression::BoolLiteral(true) => true_expr,
+                Expression::BoolLiteral(false) => false_expr,
+                _ => return None,
+            };
+            (result.ty() == expr.ty()).then(|| (**result).clone())
+        }
+        Expression::CodeBlock(sub) => fold_code_block(sub, state),
+        Expression::FunctionCall { function, arguments, .. } => match &**function {
+            Expression::BuiltinFunctionReference(f, _) => fold_builtin_function(f, arguments),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns true if the expression is a simple value that can be copied in place of a property read
+fn is_literal(expr: &Expression) -> bool {
+    match expr {
+        Expression::NumberLiteral(..)
+        | Expression::BoolLiteral(_)
+        | Expression::StringLiteral(_)
+        | Expression::EnumerationValue(_) => true,
+        Expression::Cast { from, to: Type::Int32 | Type::Color } => {
+            matches!(**from, Expression::NumberLiteral(_, Unit::None))
+        }
+        Expression::Cast { from, to: Type::Brush } => {
+            matches!(**from, Expression::Cast { to: Type::Color, .. }) && is_literal(from)
+        }
+        _ => false,
+    }
+}
+
+/// Returns the value of a number literal, normalized in the default unit of its type
+fn number_value(expr: &Expression) -> Option<(f64, Type)> {
+    match expr {
+        Expression::NumberLiteral(value, unit) => Some((unit.normalize(*value), unit.ty())),
+        _ => None,
+    }
+}
+
+/// Create a number literal of the given type, from a value in the default unit of that type
+fn number_literal(value: f64, ty: Type) -> Option<Expression> {
+    let unit = match ty {
+        Type::Float32 => Unit::None,
+        Type::Percent => Unit::Percent,
+        _ => ty.default_unit()?,
+    };
+    value.is_finite().then(|| Expression::NumberLiteral(value, unit))
+}
+
+fn fold_binary_expression(
+    expr: &Expression,
+    lhs: &Expression,
+    rhs: &Expression,
+    op: char,
+) -> Option<Expression> {
+    match (lhs, rhs) {
+        (Expression::BoolLiteral(a), Expression::BoolLiteral(b)) => match op {
+            '&' => Some(Expression::BoolLiteral(*a && *b)),
+            '|' => Some(Expression::BoolLiteral(*a || *b)),
+            '=' => Some(Expression::BoolLiteral(a == b)),
+            '!' => Some(Expression::BoolLiteral(a != b)),
+            _ => None,
+        },
+        (Expression::StringLiteral(a), Expression::StringLiteral(b)) => match op {
+            '+' => Some(Expression::StringLiteral(format!("{}{}", a, b))),
+            '=' => Some(Expression::BoolLiteral(a == b)),
+            '!' => Some(Expression::BoolLiteral(a != b)),
+            _ => None,
+        },
+        _ => {
+            let (a, lhs_ty) = number_value(lhs)?;
+            let (b, rhs_ty) = number_value(rhs)?;
+            let comparison =
+                |result: bool| (lhs_ty == rhs_ty).then(|| Expression::BoolLiteral(result));
+            match op {
+                '+' => number_literal(a + b, expr.ty()),
+                '-' => number_literal(a - b, expr.ty()),
+                '*' => number_literal(a * b, expr.ty()),
+                '/' if b != 0. => number_literal(a / b, expr.ty()),
+                '=' => comparison(a == b),
+                '!' => comparison(a != b),
+                '<' => comparison(a < b),
+                '>' => comparison(a > b),
+                '≤' => comparison(a <= b),
+                '≥' => comparison(a >= b),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Fold the code blocks which only store constant in local variables before using them,
+/// such as the ones generated by the `min` and `max` macros
+fn fold_code_block(sub: &[Expression], state: &ConstPropagationState) -> Option<Expression> {
+    let (last, stores) = sub.split_last()?;
+    let mut locals = Vec::with_capacity(stores.len());
+    for s in stores {
+        match s {
+            Expression::StoreLocalVariable { name, value } if is_literal(value) => {
+                locals.push((name.as_str(), &**value))
+            }
+            _ => return None,
+        }
+    }
+    let mut result = last.clone();
+    replace_local_variables(&mut result, &locals);
+    simplify_expression(&mut result, state);
+    if is_literal(&result) {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+fn replace_local_variables(expr: &mut Expression, locals: &[(&str, &Expression)]) {
+    if let Expression::ReadLocalVariable { name, .. } = expr {
+        if let Some((_, value)) = locals.iter().find(|(n, _)| n == name) {
+            *expr = (*value).clone();
+        }
+        return;
+    }
+    expr.visit_mut(|e| replace_local_variables(e, locals));
+}
+
+fn fold_builtin_function(f: &BuiltinFunction, arguments: &[Expression]) -> Option<Expression> {
+    let args = arguments.iter().map(number_value).collect::<Option<Vec<_>>>();
+    match (f, args.as_deref()) {
+        (BuiltinFunction::Abs, Some([(a, Type::Float32)])) => {
+            number_literal(a.abs(), Type::Float32)
+        }
+        (BuiltinFunction::Sqrt, Some([(a, Type::Float32)])) => {
+            number_literal(a.sqrt(), Type::Float32)
+        }
+        (BuiltinFunction::Sin, Some([(a, Type::Angle)])) => {
+            number_literal(a.to_radians().sin(), Type::Float32)
+        }
+        (BuiltinFunction::Cos, Some([(a, Type::Angle)])) => {
+            number_literal(a.to_radians().cos(), Type::Float32)
+        }
+        (BuiltinFunction::Tan, Some([(a, Type::Angle)])) => {
+            number_literal(a.to_radians().tan(), Type::Float32)
+        }
+        (BuiltinFunction::ASin, Some([(a, Type::Float32)])) => {
+            number_literal(a.asin().to_degrees(), Type::Angle)
+        }
+        (BuiltinFunction::ACos, Some([(a, Type::Float32)])) => {
+            number_literal(a.acos().to_degrees(), Type::Angle)
+        }
+        (BuiltinFunction::ATan, Some([(a, Type::Float32)])) => {
+            number_literal(a.atan().to_degrees(), Type::Angle)
+        }
+        (BuiltinFunction::Hsv | BuiltinFunction::Hsl, Some([(h, _), (s, _), (v, _), (a, _)])) => {
+            let (h, s, v, a) = (*h as f32, *s as f32, *v as f32, *a as f32);
+            let argb = if matches!(f, BuiltinFunction::Hsv) {
+                hsva_to_argb(h, s, v, a)
+            } else {
+                hsla_to_argb(h, s, v, a)
+            };
+            Some(color_literal(argb))
+        }
+        (BuiltinFunction::Rgb, _) => {
+            // The red, green and blue components are converted to int, which is done differently
+            // by each generator, so only fold the arguments which are already integers
+            let channel = |e: &Expression| match e {
+                Expression::Cast { from, to: Type::Int32 } => match **from {
+                    Expression::NumberLiteral(v, Unit::None) if v.fract() == 0. => {
+                        Some(v.clamp(0., 255.) as u32)
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+            let (r, g, b) = match arguments {
+                [r, g, b, _] => (channel(r)?, channel(g)?, channel(b)?),
+                _ => return None,
+            };
+            let (alpha, _) = number_value(arguments.last()?)?;
+            let a = (255. * alpha as f32).clamp(0., 255.) as u32;
+            Some(color_literal(a << 24 | r << 16 | g << 8 | b))
+        }
+        _ => None,
+    }
+}
+
+fn color_literal(argb: u32) -> Expression {
+    Expression::Cast {
+        from: Box::new(Expression::NumberLiteral(argb as _, Unit::None)),
+        to: Type::Color,
+    }
+}
+
@@ -40,8 +40,15 @@ pub fn default_geometry(root_component: &Rc<Component>, diag: &mut BuildDiagnost
 
             gen_layout_info_prop(elem);
 
-            let base_type = elem.borrow().base_type.clone();
-            if let (Some(parent), Type::Builtin(builtin_type)) = (parent, base_type) {
+            let builtin_type = match &elem.borrow().base_type {
+                Type::Builtin(builtin_type) => Some(builtin_type.clone()),
+                // An instance of a sub-component gets the default geometry of its root element
+                Type::Component(c) if elem.borrow().repeated.is_none() => {
+                    c.root_element.borrow().builtin_type()
+                }
+                _ => None,
+            };
+            if let (Some(parent), Some(builtin_type)) = (parent, builtin_type) {
                 match builtin_type.default_size_binding {
                     DefaultSizeBinding::None => {}
                     DefaultSizeBinding::ExpandsToParentGeometry => {
@@ -58,10 +65,7 @@ pub fn default_geometry(root_component: &Rc<Component>, diag: &mut BuildDiagnost
                                 property_type == Type::LogicalLength
                             });
 
-                            elem.borrow_mut()
-                                .bindings
-                                .get(property)
-                                .map_or(false, |b| b.priority > 0)
+                            elem.borrow().is_binding_set(property, true)
                         };
 
                         let width_specified = has_length_property_binding(elem, "width");
@@ -86,12 +90,13 @@ pub fn default_geometry(root_component: &Rc<Component>, diag: &mut BuildDiagnost
                         } else if is_image {
                             // If an image is in a layout and has no explicit width or height specified, change the default for image-fit
                             // to `contain`
-                            if !width_specified || !height_specified {
-                                let PropertyLookupResult {
-                                    resolved_name: image_fit_prop_name,
-                                    property_type: image_fit_prop_type,
-                                } = elem.borrow().lookup_property("image_fit");
-
+                            let PropertyLookupResult {
+                                resolved_name: image_fit_prop_name,
+                                property_type: image_fit_prop_type,
+                            } = elem.borrow().lookup_property("image_fit");
+                            if (!width_specified || !height_specified)
+                                && !elem.borrow().is_binding_set(&image_fit_prop_name, false)
+                            {
                                 elem.borrow_mut()
                                     .bindings
                                     .entry(image_fit_prop_name.into())
@@ -202,7 +207,7 @@ fn fix_percent_size(
 fn make_default_100(elem: &ElementRc, parent_element: &ElementRc, property: &str) {
     let PropertyLookupResult { resolved_name, property_type } =
         parent_element.borrow().lookup_property(property);
-    if property_type != Type::LogicalLength {
+    if property_type != Type::LogicalLength || elem.borrow().is_binding_set(&resolved_name, false) {
         return;
     }
     elem.borrow_mut().bindings.entry(resolved_name.to_string()).or_insert_with(|| {
@@ -212,6 +217,9 @@ fn make_default_100(elem: &ElementRc, parent_element: &ElementRc, property: &str
 }
 
 fn make_default_implicit(elem: &ElementRc, property: &str, orientation: Orientation) {
+    if elem.borrow().is_binding_set(property, false) {
+        return;
+    }
     elem.borrow_mut().bindings.entry(property.into()).or_insert_with(|| {
         Expression::StructFieldAccess {
             base: implicit_layout_info_call(elem, orientation).into(),
@@ -233,7 +241,7 @@ fn make_default_aspect_ratio_preserving_binding(
     missing_size_property: &str,
     given_size_property: &str,
 ) {
-    if elem.borrow().bindings.contains_key(missing_size_property) {
+    if elem.borrow().is_binding_set(missing_size_property, false) {
         return;
     }
 
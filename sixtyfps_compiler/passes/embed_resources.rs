@@ -13,9 +13,12 @@
 use std::collections::HashMap;
 use std::rc::Rc;
 
-pub fn embed_resources(component: &Rc<Component>) {
-    let global_embedded_resources = &component.embedded_file_resources;
-
+/// Embed the resources used by `component` and its sub-components, registering them in
+/// `global_embedded_resources`
+pub fn embed_resources(
+    component: &Rc<Component>,
+    global_embedded_resources: &RefCell<HashMap<String, usize>>,
+) {
     for component in
         component.used_types.borrow().sub_components.iter().chain(std::iter::once(component))
     {
@@ -8,16 +8,31 @@
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
 //! Assign the Element::item_index on each elements
+use std::rc::Rc;
+
 pub fn generate_item_indices(component: &std::rc::Rc<crate::object_tree::Component>) {
     let mut current_item_index: usize = 0;
-    crate::generator::build_array_helper(component, move |item_rc, _, _| {
+    crate::generator::build_array_helper(component, move |item_rc, _, _, sub_component_path| {
         let item = item_rc.borrow();
         if item.base_type == crate::langtype::Type::Void {
         } else {
             if let crate::langtype::Type::Component(c) = &item.base_type {
                 generate_item_indices(c);
             }
-            item.item_index.set(current_item_index).unwrap();
+            match sub_component_path {
+                // The items of sub-components already have their index within the sub-component.
+                // The instance itself gets the index of the root of the sub-component.
+                [] => item.item_index.set(current_item_index).unwrap(),
+                [instance]
+                    if item
+                        .enclosing_component
+                        .upgrade()
+                        .map_or(false, |c| Rc::ptr_eq(&c.root_element, item_rc)) =>
+                {
+                    instance.borrow().item_index.set(current_item_index).unwrap()
+                }
+                _ => (),
+            }
             current_item_index += 1;
         }
     });
@@ -14,22 +14,310 @@
 use crate::object_tree::*;
 use by_address::ByAddress;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
-pub fn inline(doc: &Document) {
-    fn inline_components_recursively(component: &Rc<Component>) {
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum InlineSelection {
+    /// Inline all the elements whose base type is a component
+    InlineAllComponents,
+    /// Only inline the elements which cannot be instances of a sub-component
+    InlineOnlyRequiredComponents,
+}
+
+pub fn inline(doc: &Document, inline_selection: InlineSelection) {
+    fn inline_components_recursively(
+        component: &Rc<Component>,
+        inline_selection: InlineSelection,
+        usage_count: &HashMap<ByAddress<Rc<Component>>, usize>,
+        exported_components: &HashSet<ByAddress<Rc<Component>>>,
+    ) {
+        let usage = ElementUsage::collect(component);
         recurse_elem(&component.root_element, &(), &mut |elem, _| {
             let base = elem.borrow().base_type.clone();
             if let Type::Component(c) = base {
                 // First, make sure that the component itself is properly inlined
-                inline_components_recursively(&c);
-                // Inline this component.
-                inline_element(elem, &c, component);
+                inline_components_recursively(
+                    &c,
+                    inline_selection,
+                    usage_count,
+                    exported_components,
+                );
+                if inline_selection == InlineSelection::InlineAllComponents
+                    // Nothing is duplicated when inlining a component used only once
+                    || usage_count.get(&ByAddress(c.clone())).map_or(true, |count| *count < 2)
+                    || element_require_inlining(elem, component, &usage)
+                    || component_requires_inlining(&c)
+                    // The struct generated for an exported component has the name of that
+                    // component, so it cannot also be a sub-component
+                    || exported_components.contains(&ByAddress(c.clone()))
+                {
+                    // Inline this component.
+                    inline_element(elem, &c, component);
+                }
             }
         })
     }
-    inline_components_recursively(&doc.root_component)
+    let mut usage_count = HashMap::new();
+    for root in &doc.exported_roots {
+        count_component_usages(root, &mut usage_count);
+    }
+    // The components wrapped by the additional exported roots
+    let exported_components = doc
+        .exported_roots
+        .iter()
+        .filter(|root| !Rc::ptr_eq(root, &doc.root_component))
+        .filter_map(|root| match &root.root_element.borrow().base_type {
+            Type::Component(c) => Some(ByAddress(c.clone())),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    for root in &doc.exported_roots {
+        inline_components_recursively(root, inline_selection, &usage_count, &exported_components)
+    }
+    let mut sub_components = HashSet::new();
+    for root in &doc.exported_roots {
+        mark_sub_components(root, &mut sub_components);
+    }
+}
+
+/// Mark the components which are still the base type of an element once everything is inlined
+fn mark_sub_components(
+    component: &Rc<Component>,
+    sub_components: &mut HashSet<ByAddress<Rc<Component>>>,
+) {
+    recurse_elem(&component.root_element, &(), &mut |elem, _| {
+        if let Type::Component(c) = &elem.borrow().base_type {
+            if sub_components.insert(ByAddress(c.clone())) {
+                mark_as_sub_component(c);
+                mark_sub_components(c, sub_components);
+            }
+        }
+    })
+}
+
+/// Count how many elements have each component as base type, in the component and in all
+/// the components it uses
+fn count_component_usages(
+    component: &Rc<Component>,
+    usage_count: &mut HashMap<ByAddress<Rc<Component>>, usize>,
+) {
+    recurse_elem(&component.root_element, &(), &mut |elem, _| {
+        if let Type::Component(c) = &elem.borrow().base_type {
+            let count = usage_count.entry(ByAddress(c.clone())).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                count_component_usages(c, usage_count);
+            }
+        }
+    })
+}
+
+/// How the elements of a component are used within that component
+#[derive(Default)]
+struct ElementUsage {
+    /// The properties of each element which are referenced in the component
+    referenced_properties: HashMap<ByAddress<ElementRc>, HashSet<String>>,
+    /// The elements referenced by an Expression::ElementReference (eg, `focus()`)
+    element_references: HashSet<ByAddress<ElementRc>>,
+    /// The elements whose properties are changed by states
+    changed_by_states: HashSet<ByAddress<ElementRc>>,
+}
+
+impl ElementUsage {
+    fn collect(component: &Rc<Component>) -> Self {
+        let mut usage = Self::default();
+        recurse_elem(&component.root_element, &(), &mut |elem, _| {
+            visit_all_named_references_in_element(elem, |nr| {
+                usage
+                    .referenced_properties
+                    .entry(element_key(nr.element()))
+                    .or_default()
+                    .insert(nr.name().to_owned());
+            });
+            visit_element_expressions(elem, |expr, _, _| {
+                expr.visit_recursive(&mut |e| {
+                    if let Expression::ElementReference(e) = e {
+                        if let Some(e) = e.upgrade() {
+                            usage.element_references.insert(element_key(e));
+                        }
+                    }
+                })
+            });
+            for state in &elem.borrow().states {
+                for (nr, _) in &state.property_changes {
+                    usage.changed_by_states.insert(element_key(nr.element()));
+                }
+            }
+        });
+        usage
+    }
+}
+
+/// Returns true if this element, whose base type is a component, cannot be an instance of a
+/// sub-component because of the way it is used in its component.
+fn element_require_inlining(
+    elem: &ElementRc,
+    component: &Rc<Component>,
+    usage: &ElementUsage,
+) -> bool {
+    if Rc::ptr_eq(elem, &component.root_element) {
+        // The component inherits from the other component
+        return true;
+    }
+    let key = element_key(elem.clone());
+    if usage.element_references.contains(&key) || usage.changed_by_states.contains(&key) {
+        return true;
+    }
+    let elem = elem.borrow();
+    if elem.repeated.is_some()
+        || !elem.children.is_empty()
+        || !elem.states.is_empty()
+        || !elem.transitions.is_empty()
+        || !elem.property_animations.is_empty()
+    {
+        return true;
+    }
+    let base_root_rc = match &elem.base_type {
+        Type::Component(c) => c.root_element.clone(),
+        _ => return true,
+    };
+    let base_root = base_root_rc.borrow();
+    let changed_by_base_states = |prop: &str| {
+        base_root.states.iter().any(|s| {
+            s.property_changes
+                .iter()
+                .any(|(nr, _)| nr.name() == prop && Rc::ptr_eq(&nr.element(), &base_root_rc))
+        })
+    };
+    for (prop, binding) in &elem.bindings {
+        if !is_sub_component_property(&base_root, prop)
+            || base_root.property_animations.contains_key(prop)
+            || changed_by_base_states(prop)
+            || (matches!(binding.expression, Expression::TwoWayBinding(..))
+                && base_root.is_binding_set(prop, false))
+        {
+            return true;
+        }
+    }
+    usage
+        .referenced_properties
+        .get(&key)
+        .map_or(false, |props| props.iter().any(|p| !is_sub_component_property(&base_root, p)))
+}
+
+/// Returns true if the property of the root element of a sub-component can be accessed or set
+/// from the component instantiating it
+fn is_sub_component_property(root: &Element, prop: &str) -> bool {
+    if root.property_declarations.contains_key(prop) {
+        return true;
+    }
+    if matches!(
+        prop,
+        "x" | "y"
+            | "width"
+            | "height"
+            | "min_width"
+            | "min_height"
+            | "max_width"
+            | "max_height"
+            | "preferred_width"
+            | "preferred_height"
+            | "horizontal_stretch"
+            | "vertical_stretch"
+            | "col"
+            | "row"
+            | "colspan"
+            | "rowspan"
+    ) {
+        // Handled by the layout or the geometry of the instantiating component
+        return true;
+    }
+    match root.builtin_type() {
+        // The properties of these elements don't all map to the native item as they are lowered
+        // by the passes.
+        Some(b)
+            if matches!(
+                b.name.as_str(),
+                "GridLayout" | "HorizontalLayout" | "VerticalLayout" | "PathLayout" | "Flickable"
+            ) =>
+        {
+            false
+        }
+        Some(b) => b.native_class.lookup_property(prop).is_some(),
+        None => false,
+    }
+}
+
+/// Returns true if the component cannot be generated as a sub-component, or is so small that
+/// it is not worth it.
+fn component_requires_inlining(component: &Rc<Component>) -> bool {
+    let root = component.root_element.borrow();
+    if root.children.is_empty() {
+        return true;
+    }
+    if root.builtin_type().map_or(true, |b| {
+        matches!(
+            b.name.as_str(),
+            "Window"
+                | "PopupWindow"
+                | "Dialog"
+                | "ContextMenu"
+                | "MenuBar"
+                | "Menu"
+                | "MenuItem"
+                | "MenuSeparator"
+        )
+    }) {
+        return true;
+    }
+    if root.bindings.iter().any(|(prop, binding)| {
+        matches!(prop.as_str(), "clip" | "opacity" | "z")
+            || prop.starts_with("drop_shadow_")
+            || (matches!(prop.as_str(), "width" | "height") && binding.ty() == Type::Percent)
+    }) {
+        // These are handled by the passes depending on the parent of the element
+        return true;
+    }
+    drop(root);
+
+    let mut result = false;
+    recurse_elem(&component.root_element, &(), &mut |elem, _| {
+        if result {
+            return;
+        }
+        {
+            let elem = elem.borrow();
+            // Repeater, popup and focus handling need the full item tree of the component
+            if elem.repeated.is_some()
+                || matches!(
+                    elem.base_type.to_string().as_str(),
+                    "PopupWindow" | "Dialog" | "ContextMenu" | "MenuBar"
+                )
+                || elem.bindings.contains_key("forward_focus")
+            {
+                result = true;
+                return;
+            }
+        }
+        visit_element_expressions(elem, |expr, _, _| {
+            expr.visit_recursive(&mut |e| {
+                if matches!(e, Expression::ElementReference(_)) {
+                    result = true;
+                }
+            })
+        });
+    });
+    result
+}
+
+/// The elements using this component will instantiate it as a sub-component.
+fn mark_as_sub_component(component: &Rc<Component>) {
+    component.is_sub_component.set(true);
+    // The properties of the root element can be set or read from the instantiating component
+    for decl in component.root_element.borrow_mut().property_declarations.values_mut() {
+        decl.expose_in_public_api = true;
+    }
 }
 
 fn clone_tuple<U: Clone, V: Clone>((u, v): (&U, &V)) -> (U, V) {
@@ -0,0 +1,294 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+
+//! Pass that reports the lints enabled in the CompilerConfiguration.
+//!
+//! This must run on a document before inlining, as it reports on the code as written.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::diagnostics::{
+    BuildDiagnostics, DiagnosticCode, DiagnosticLevel, Lint, LintLevel, Spanned,
+};
+use crate::expression_tree::Expression;
+use crate::langtype::Type;
+use crate::object_tree::*;
+use crate::parser::{identifier_text, SyntaxKind, SyntaxNode};
+
+struct LintContext<'a> {
+    lints: &'a HashMap<Lint, LintLevel>,
+    diag: &'a mut BuildDiagnostics,
+}
+
+impl LintContext<'_> {
+    fn is_enabled(&self, lint: Lint) -> bool {
+        self.lints.get(&lint).map_or(false, |level| *level != LintLevel::Allow)
+    }
+
+    fn report(&mut self, lint: Lint, message: String, source: &dyn Spanned) {
+        let level = match self.lints.get(&lint) {
+            Some(LintLevel::Warn) => DiagnosticLevel::Warning,
+            Some(LintLevel::Deny) => DiagnosticLevel::Error,
+            Some(LintLevel::Allow) | None => return,
+        };
+        self.diag.push_diagnostic_with_span_and_code(
+            message,
+            source.to_source_location(),
+            level,
+            DiagnosticCode::Lint(lint),
+        );
+    }
+}
+
+pub fn lint(doc: &Document, lints: &HashMap<Lint, LintLevel>, diag: &mut BuildDiagnostics) {
+    if lints.values().all(|level| *level == LintLevel::Allow) {
+        return;
+    }
+    // Don't report anything in the widgets that come with the compiler
+    if doc.node.as_ref().map_or(true, |n| n.source_file.path().starts_with("builtin:/")) {
+        return;
+    }
+    let mut ctx = LintContext { lints, diag };
+
+    let mut all_elements = vec![];
+    let mut referenced_elements = HashSet::new();
+    for component in &doc.inner_components {
+        recurse_elem(&component.root_element, &(), &mut |elem, _| {
+            visit_element_expressions(elem, |expr, _, _| {
+                expr.visit_recursive(&mut |e| {
+                    if let Expression::ElementReference(e) = e {
+                        if let Some(e) = e.upgrade() {
+                            referenced_elements.insert(Rc::as_ptr(&e));
+                        }
+                    }
+                })
+            });
+            all_elements.push((elem.clone(), component.clone()));
+        });
+    }
+
+    for (elem, component) in &all_elements {
+        let is_root = Rc::ptr_eq(elem, &component.root_element);
+        if is_root {
+            let is_public = Rc::ptr_eq(component, &doc.root_component)
+                || doc
+                    .exports()
+                    .iter()
+                    .any(|(_, ty)| matches!(ty, Type::Component(c) if Rc::ptr_eq(c, component)));
+            // The properties of the root element of an exported component are its public API
+            if !is_public {
+                let instances = all_elements
+                    .iter()
+                    .filter(|(e, _)| {
+                        matches!(&e.borrow().base_type, Type::Component(c) if Rc::ptr_eq(c, component))
+                    })
+                    .map(|(e, _)| e.clone())
+                    .collect::<Vec<_>>();
+                lint_unused_declarations(elem, &instances, &mut ctx);
+            }
+        } else {
+            lint_unused_declarations(elem, &[], &mut ctx);
+            lint_unused_id(elem, &referenced_elements, &mut ctx);
+            lint_invisible_element(elem, &mut ctx);
+        }
+        lint_states(elem, &mut ctx);
+    }
+}
+
+/// Report the properties that are never referenced and the callbacks that are never connected,
+/// neither in the element itself, nor in the `instances` of the component if this is its root
+fn lint_unused_declarations(elem: &ElementRc, instances: &[ElementRc], ctx: &mut LintContext) {
+    let e = elem.borrow();
+    for (name, decl) in &e.property_declarations {
+        let binding = e.bindings.get(name);
+        if binding.map_or(false, |b| matches!(b.expression, Expression::TwoWayBinding(..))) {
+            // Aliases are used through the property they point to
+            continue;
+        }
+        let declared_identifier = decl
+            .node
+            .as_ref()
+            .map(|n| -> SyntaxNode {
+                n.as_ref()
+                    .either(|x| x.DeclaredIdentifier().into(), |x| x.DeclaredIdentifier().into())
+            })
+            .and_then(|n| n.child_token(SyntaxKind::Identifier));
+        if matches!(decl.property_type, Type::Callback { .. }) {
+            if ctx.is_enabled(Lint::UnusedCallback)
+                && binding.is_none()
+                && !instances.iter().any(|i| i.borrow().bindings.contains_key(name))
+            {
+                ctx.report(
+                    Lint::UnusedCallback,
+                    format!("The callback '{}' is never connected", name),
+                    &declared_identifier,
+                );
+            }
+        } else if decl.property_type.is_property_type()
+            && ctx.is_enabled(Lint::UnusedProperty)
+            && !e.named_references.is_referenced(name)
+            && !instances.iter().any(|i| {
+                let i = i.borrow();
+                i.named_references.is_referenced(name) || i.bindings.contains_key(name)
+            })
+        {
+            ctx.report(
+                Lint::UnusedProperty,
+                format!("The property '{}' is never read or set", name),
+                &declared_identifier,
+            );
+        }
+    }
+}
+
+fn lint_unused_id(
+    elem: &ElementRc,
+    referenced_elements: &HashSet<*const std::cell::RefCell<Element>>,
+    ctx: &mut LintContext,
+) {
+    let e = elem.borrow();
+    if e.id.is_empty()
+        || !ctx.is_enabled(Lint::UnusedId)
+        || referenced_elements.contains(&Rc::as_ptr(elem))
+        || e.named_references.is_any_referenced()
+    {
+        return;
+    }
+    let id_token = e
+        .node
+        .as_ref()
+        .and_then(|n| n.parent())
+        .filter(|n| n.kind() == SyntaxKind::SubElement)
+        .and_then(|n| n.child_token(SyntaxKind::Identifier));
+    let message = format!("The id '{}' is never used", e.id);
+    match id_token {
+        Some(token) => ctx.report(Lint::UnusedId, message, &token),
+        None => ctx.report(Lint::UnusedId, message, &*e),
+    }
+}
+
+/// Report elements whose size is a constant 0, or which have a constant `visible: false`,
+/// provided that nothing else changes that property
+fn lint_invisible_element(elem: &ElementRc, ctx: &mut LintContext) {
+    if !ctx.is_enabled(Lint::InvisibleElement) {
+        return;
+    }
+    let e = elem.borrow();
+    let invisible_binding = |property: &str, is_invisible: fn(&Expression) -> bool| {
+        e.bindings
+            .get(property)
+            .filter(|b| is_invisible(b) && !e.named_references.is_referenced(property))
+    };
+    for property in &["width", "height"] {
+        if let Some(binding) = invisible_binding(property, is_zero) {
+            ctx.report(
+                Lint::InvisibleElement,
+                format!("The element is never visible because its {} is always 0", property),
+                &binding.span,
+            );
+            return;
+        }
+    }
+    if let Some(binding) = invisible_binding("visible", |e| {
+        matches!(strip_expression(e), Expression::BoolLiteral(false))
+    }) {
+        ctx.report(
+            Lint::InvisibleElement,
+            "The element is never visible because its visible property is always false".into(),
+            &binding.span,
+        );
+    }
+}
+
+fn lint_states(elem: &ElementRc, ctx: &mut LintContext) {
+    let e = elem.borrow();
+    if e.states.is_empty() {
+        return;
+    }
+
+    if ctx.is_enabled(Lint::ConstantStateCondition) {
+        for state in &e.states {
+            let condition = match &state.condition {
+                Some(condition) => condition,
+                None => continue,
+            };
+            let mut reads_property = false;
+            condition.visit_recursive(&mut |x| {
+                reads_property |= matches!(x, Expression::PropertyReference(_))
+            });
+            if !condition.is_constant() || reads_property {
+                continue;
+            }
+            let condition_node = e.node.as_ref().and_then(|n| {
+                n.States()
+                    .flat_map(|s| s.State())
+                    .find(|s| identifier_text(&s.DeclaredIdentifier()).as_ref() == Some(&state.id))
+                    .and_then(|s| s.Expression())
+            });
+            ctx.report(
+                Lint::ConstantStateCondition,
+                format!("The condition of the state '{}' is a constant", state.id),
+                &condition_node.map(SyntaxNode::from),
+            );
+        }
+    }
+
+    if ctx.is_enabled(Lint::OverriddenBinding) {
+        // The first state whose condition is true is active. The binding of the element can
+        // only never be observed if a state is always active and every state that can be active
+        // before it changes the property.
+        let is_constant = |state: &State, value: bool| {
+            state.condition.as_ref().map_or(
+                false,
+                |c| matches!(strip_expression(c), Expression::BoolLiteral(b) if *b == value),
+            )
+        };
+        let reachable_states =
+            e.states.iter().filter(|s| !is_constant(s, false)).collect::<Vec<_>>();
+        let always_active = match reachable_states.iter().position(|s| is_constant(s, true)) {
+            Some(always_active) => always_active,
+            None => return,
+        };
+        let (last, before) = reachable_states[..=always_active].split_last().unwrap();
+        for (nr, _) in &last.property_changes {
+            if !before.iter().all(|s| s.property_changes.iter().any(|(other, _)| other == nr)) {
+                continue;
+            }
+            let target = nr.element();
+            let target = target.borrow();
+            if let Some(binding) = target.bindings.get(nr.name()) {
+                if binding.span.is_some() {
+                    ctx.report(
+                        Lint::OverriddenBinding,
+                        format!(
+                            "The binding for the property '{}' is never used because it is overridden by the states",
+                            nr.name()
+                        ),
+                        &binding.span,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Look through the casts and the code blocks of a single expression
+fn strip_expression(e: &Expression) -> &Expression {
+    match e {
+        Expression::Cast { from, .. } => strip_expression(from),
+        Expression::CodeBlock(sub) if sub.len() == 1 => strip_expression(&sub[0]),
+        e => e,
+    }
+}
+
+fn is_zero(e: &Expression) -> bool {
+    matches!(strip_expression(e), Expression::NumberLiteral(value, _) if *value == 0.)
+}
@@ -26,6 +26,18 @@ pub fn lower_layouts<'a>(
     *component.root_constraints.borrow_mut() =
         LayoutConstraints::new(&component.root_element, diag);
 
+    // The instances of sub-components have the layout info of the root element of their component
+    recurse_elem_including_sub_components(component, &(), &mut |elem, _| {
+        let layout_info_prop = elem
+            .borrow()
+            .sub_component()
+            .and_then(|c| c.root_element.borrow().layout_info_prop.clone());
+        if let Some((h, v)) = layout_info_prop {
+            elem.borrow_mut().layout_info_prop =
+                Some((NamedReference::new(elem, h.name()), NamedReference::new(elem, v.name())));
+        }
+    });
+
     recurse_elem_including_sub_components(component, &(), &mut |elem, _| {
         let component = elem.borrow().enclosing_component.upgrade().unwrap();
         lower_element_layout(&component, elem, type_register, diag);
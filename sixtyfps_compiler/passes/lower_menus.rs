@@ -0,0 +1,829 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+//! Pass that lowers the MenuBar, ContextMenu, Menu, MenuItem and MenuSeparator elements.
+//!
+//! The properties of the entries of a context menu are moved to the parent of the menu, and the
+//! ContextMenu becomes a PopupWindow that renders the entries. Showing the menu uses a native menu
+//! instead when the platform has one. A MenuBar becomes a row of buttons showing such a menu, which
+//! collapses when the platform has native menus, as the menus are then in the native menu bar.
+
+use crate::diagnostics::BuildDiagnostics;
+use crate::expression_tree::{BuiltinFunction, Expression, NamedReference, Unit};
+use crate::langtype::Type;
+use crate::layout::Orientation;
+use crate::object_tree::*;
+use crate::typeregister::TypeRegister;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The properties of each kind of menu entry that are moved to the parent of the ContextMenu
+const ITEM_PROPERTIES: &[&str] =
+    &["title", "enabled", "checkable", "checked", "shortcut", "activated"];
+const SUBMENU_PROPERTIES: &[&str] = &["title", "enabled"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum EntryKind {
+    Item,
+    Separator,
+    SubMenu,
+}
+
+impl EntryKind {
+    fn from_element(elem: &ElementRc) -> Option<Self> {
+        match elem.borrow().base_type.to_string().as_str() {
+            "MenuItem" => Some(EntryKind::Item),
+            "MenuSeparator" => Some(EntryKind::Separator),
+            "Menu" => Some(EntryKind::SubMenu),
+            _ => None,
+        }
+    }
+
+    fn properties(self) -> &'static [&'static str] {
+        match self {
+            EntryKind::Item => ITEM_PROPERTIES,
+            EntryKind::Separator => &[],
+            EntryKind::SubMenu => SUBMENU_PROPERTIES,
+        }
+    }
+}
+
+/// An entry of a context menu, after its properties were moved to the parent of the menu
+struct LoweredEntry {
+    kind: EntryKind,
+    depth: usize,
+    /// The prefix of the name of the properties of this entry in the parent of the menu
+    prefix: String,
+}
+
+/// A ContextMenu that was lowered to a PopupWindow
+struct LoweredMenu {
+    menu: ElementRc,
+    parent: ElementRc,
+    entries: Vec<LoweredEntry>,
+}
+
+/// A MenuBar that was lowered to a Rectangle, with the Menu elements of the bar and the
+/// ContextMenu each of them shows
+struct LoweredMenuBar {
+    menu_bar: ElementRc,
+    menus: Vec<(ElementRc, ElementRc)>,
+}
+
+struct LoweringContext<'a> {
+    type_register: &'a TypeRegister,
+    popup_type: Type,
+    menu_count: usize,
+    /// The elements of the entries that were removed, with the parent their properties were
+    /// moved to, and the prefix of the name of these properties
+    moved_entries: Vec<(ElementRc, ElementRc, String)>,
+    menus: Vec<LoweredMenu>,
+    menu_bars: Vec<LoweredMenuBar>,
+}
+
+pub fn lower_menus(
+    component: &Rc<Component>,
+    type_register: &TypeRegister,
+    diag: &mut BuildDiagnostics,
+) {
+    let mut context_menus = Vec::new();
+    let mut menu_bars = Vec::new();
+    recurse_elem_including_sub_components_no_borrow(component, &None, &mut |elem,
+                                                                            parent: &Option<
+        ElementRc,
+    >| {
+        let base_type = elem.borrow().base_type.to_string();
+        match base_type.as_str() {
+            "ContextMenu" => {
+                check_entries(elem, false, diag);
+                if let Some(parent) = parent {
+                    context_menus.push((elem.clone(), parent.clone()));
+                }
+            }
+            "MenuBar" => {
+                check_entries(elem, true, diag);
+                menu_bars.push(elem.clone());
+            }
+            "Menu" => {
+                check_entries(elem, false, diag);
+                check_entry_parent(elem, parent, &["ContextMenu", "Menu", "MenuBar"], diag);
+            }
+            "MenuItem" | "MenuSeparator" => {
+                check_entry_parent(elem, parent, &["ContextMenu", "Menu"], diag);
+            }
+            _ => {}
+        }
+        Some(elem.clone())
+    });
+    if context_menus.is_empty() && menu_bars.is_empty() {
+        return;
+    }
+
+    let mut ctx = LoweringContext {
+        type_register,
+        popup_type: type_register.lookup_element("PopupWindow").unwrap(),
+        menu_count: 0,
+        moved_entries: Vec::new(),
+        menus: Vec::new(),
+        menu_bars: Vec::new(),
+    };
+
+    for menu_bar in menu_bars {
+        context_menus.extend(lower_menu_bar(&menu_bar, &mut ctx));
+    }
+    for (menu, parent) in context_menus {
+        lower_context_menu(&menu, &parent, &mut ctx);
+    }
+
+    let menu_entry_type = type_register.lookup("MenuEntry");
+    for menu_bar in &ctx.menu_bars {
+        setup_native_menu_bar(menu_bar, &ctx.menus, &menu_entry_type);
+    }
+
+    let LoweringContext { moved_entries, menus, .. } = ctx;
+    visit_all_named_references(component, &mut |nr| {
+        let element = nr.element();
+        if let Some((_, parent, prefix)) =
+            moved_entries.iter().find(|(entry, _, _)| Rc::ptr_eq(entry, &element))
+        {
+            let name = format!("{}_{}", prefix, nr.name());
+            if parent.borrow().property_declarations.contains_key(&name) {
+                *nr = NamedReference::new(parent, &name);
+            }
+        }
+    });
+
+    visit_all_expressions(component, |expr, _| replace_show_calls(expr, &menus, &menu_entry_type));
+}
+
+/// Report an error for the children of a ContextMenu or Menu that are not menu entries, or that
+/// are repeated
+fn check_entries(menu: &ElementRc, top_level: bool, diag: &mut BuildDiagnostics) {
+    for child in &menu.borrow().children {
+        let child = child.borrow();
+        if child.repeated.is_some() {
+            diag.push_error("Menu entries cannot be repeated or conditional".into(), &*child);
+        } else if top_level {
+            if child.base_type.to_string() != "Menu" {
+                diag.push_error("A MenuBar can only contain Menu elements".into(), &*child);
+            }
+        } else if !matches!(
+            child.base_type.to_string().as_str(),
+            "MenuItem" | "MenuSeparator" | "Menu"
+        ) {
+            diag.push_error(
+                "A menu can only contain MenuItem, MenuSeparator and Menu elements".into(),
+                &*child,
+            );
+        }
+    }
+}
+
+fn check_entry_parent(
+    entry: &ElementRc,
+    parent: &Option<ElementRc>,
+    allowed_parents: &[&str],
+    diag: &mut BuildDiagnostics,
+) {
+    // The children of the menu elements are checked by check_entries
+    let parent_type = parent.as_ref().map(|p| p.borrow().base_type.to_string());
+    if !parent_type.map_or(false, |p| matches!(p.as_str(), "ContextMenu" | "Menu" | "MenuBar")) {
+        let entry = entry.borrow();
+        diag.push_error(
+            format!(
+                "{} can only be used within a {}",
+                entry.base_type,
+                allowed_parents.join(" or ")
+            ),
+            &*entry,
+        );
+    }
+}
+
+/// Give an id to the element if it has none, the id is used to name the generated elements and properties
+fn ensure_id(elem: &ElementRc, ctx: &mut LoweringContext) -> String {
+    if elem.borrow().id.is_empty() {
+        ctx.menu_count += 1;
+        elem.borrow_mut().id = format!("menu{}", ctx.menu_count);
+    }
+    elem.borrow().id.clone()
+}
+
+fn new_element(
+    id: String,
+    base: &str,
+    enclosing: &ElementRc,
+    bindings: Vec<(&str, Expression)>,
+    children: Vec<ElementRc>,
+    ctx: &LoweringContext,
+) -> ElementRc {
+    Rc::new(RefCell::new(Element {
+        id,
+        base_type: ctx.type_register.lookup_element(base).unwrap(),
+        bindings: bindings.into_iter().map(|(k, v)| (k.into(), v.into())).collect(),
+        children,
+        enclosing_component: enclosing.borrow().enclosing_component.clone(),
+        ..Default::default()
+    }))
+}
+
+fn length(value: f64) -> Expression {
+    Expression::NumberLiteral(value, Unit::Px)
+}
+
+fn brush(argb: u32) -> Expression {
+    Expression::Cast {
+        from: Box::new(Expression::Cast {
+            from: Box::new(Expression::NumberLiteral(argb as f64, Unit::None)),
+            to: Type::Color,
+        }),
+        to: Type::Brush,
+    }
+}
+
+fn condition(condition: Expression, true_expr: Expression, false_expr: Expression) -> Expression {
+    Expression::Condition {
+        condition: Box::new(condition),
+        true_expr: Box::new(true_expr),
+        false_expr: Box::new(false_expr),
+    }
+}
+
+fn call(function: Expression, arguments: Vec<Expression>) -> Expression {
+    Expression::FunctionCall { function: Box::new(function), arguments, source_location: None }
+}
+
+fn builtin_call(function: BuiltinFunction, arguments: Vec<Expression>) -> Expression {
+    call(Expression::BuiltinFunctionReference(function, None), arguments)
+}
+
+/// The MenuBar becomes a Rectangle with a row of buttons, one for each Menu. Each Menu gets a
+/// ContextMenu child with its entries, shown below it when it is clicked. The buttons have no
+/// height when the platform has native menus.
+///
+/// Returns the ContextMenus that were created, with their parent.
+fn lower_menu_bar(menu_bar: &ElementRc, ctx: &mut LoweringContext) -> Vec<(ElementRc, ElementRc)> {
+    let bar_id = ensure_id(menu_bar, ctx);
+    let menus = std::mem::take(&mut menu_bar.borrow_mut().children);
+    let mut context_menus = Vec::new();
+    let has_native_menus = || builtin_call(BuiltinFunction::HasNativeMenus, vec![]);
+    for menu in &menus {
+        if menu.borrow().repeated.is_some() {
+            continue;
+        }
+        let menu_id = ensure_id(menu, ctx);
+        let entries = std::mem::take(&mut menu.borrow_mut().children);
+        let context_menu = new_element(
+            format!("{}_menu", menu_id),
+            "ContextMenu",
+            menu,
+            vec![("y", Expression::PropertyReference(NamedReference::new(menu, "height")))],
+            entries,
+            ctx,
+        );
+        let title = new_element(
+            format!("{}_title", menu_id),
+            "Text",
+            menu,
+            vec![
+                ("text", Expression::PropertyReference(NamedReference::new(menu, "title"))),
+                (
+                    "color",
+                    condition(
+                        Expression::PropertyReference(NamedReference::new(menu, "enabled")),
+                        brush(0xff000000),
+                        brush(0xff888888),
+                    ),
+                ),
+            ],
+            vec![],
+            ctx,
+        );
+        let title_height = condition(
+            has_native_menus(),
+            length(0.),
+            Expression::StructFieldAccess {
+                base: Box::new(builtin_call(
+                    BuiltinFunction::ImplicitLayoutInfo(Orientation::Vertical),
+                    vec![Expression::ElementReference(Rc::downgrade(&title))],
+                )),
+                name: "preferred".into(),
+            },
+        );
+        title.borrow_mut().bindings.insert("height".into(), title_height.into());
+        let vertical_padding = || condition(has_native_menus(), length(0.), length(4.));
+        let layout = new_element(
+            format!("{}_layout", menu_id),
+            "HorizontalLayout",
+            menu,
+            vec![
+                ("padding_left", length(8.)),
+                ("padding_right", length(8.)),
+                ("padding_top", vertical_padding()),
+                ("padding_bottom", vertical_padding()),
+            ],
+            vec![title],
+            ctx,
+        );
+        let touch_area = new_element(
+            format!("{}_touch", menu_id),
+            "TouchArea",
+            menu,
+            vec![
+                ("enabled", Expression::PropertyReference(NamedReference::new(menu, "enabled"))),
+                (
+                    "clicked",
+                    builtin_call(
+                        BuiltinFunction::ShowPopupWindow,
+                        vec![Expression::ElementReference(Rc::downgrade(&context_menu))],
+                    ),
+                ),
+            ],
+            vec![],
+            ctx,
+        );
+        let background = condition(
+            Expression::PropertyReference(NamedReference::new(&touch_area, "has_hover")),
+            brush(0xffdddddd),
+            brush(0),
+        );
+
+        let mut menu_mut = menu.borrow_mut();
+        for name in SUBMENU_PROPERTIES {
+            let property_type = menu_mut.lookup_property(name).property_type;
+            menu_mut.property_declarations.insert(name.to_string(), property_type.into());
+        }
+        menu_mut.base_type = ctx.type_register.lookup_element("Rectangle").unwrap();
+        menu_mut.bindings.insert("background".into(), background.into());
+        menu_mut.children = vec![layout, touch_area, context_menu.clone()];
+        drop(menu_mut);
+        context_menus.push((context_menu, menu.clone()));
+    }
+    ctx.menu_bars.push(LoweredMenuBar {
+        menu_bar: menu_bar.clone(),
+        menus: context_menus.iter().map(|(menu, parent)| (parent.clone(), menu.clone())).collect(),
+    });
+
+    let alignment = {
+        let layout_type = ctx.type_register.lookup_element("HorizontalLayout").unwrap();
+        let alignment_type = layout_type.lookup_property("alignment").property_type;
+        Expression::EnumerationValue(
+            alignment_type.as_enum().clone().try_value_from_string("start").unwrap(),
+        )
+    };
+    let layout = new_element(
+        format!("{}_layout", bar_id),
+        "HorizontalLayout",
+        menu_bar,
+        vec![("alignment", alignment)],
+        menus,
+        ctx,
+    );
+    let mut menu_bar = menu_bar.borrow_mut();
+    menu_bar.base_type = ctx.type_register.lookup_element("Rectangle").unwrap();
+    menu_bar.bindings.entry("background".into()).or_insert_with(|| brush(0xffeeeeee).into());
+    // Don't take more than the height of the menus when placed in a layout
+    menu_bar
+        .bindings
+        .entry("vertical_stretch".into())
+        .or_insert_with(|| Expression::NumberLiteral(0., Unit::None).into());
+    menu_bar.children = vec![layout];
+    context_menus
+}
+
+/// Collect the entries of the menu, depth first, and move their properties to `parent`
+fn collect_entries(
+    menu: &ElementRc,
+    parent: &ElementRc,
+    menu_id: &str,
+    depth: usize,
+    ctx: &mut LoweringContext,
+    entries: &mut Vec<LoweredEntry>,
+) {
+    let children = std::mem::take(&mut menu.borrow_mut().children);
+    for child in children {
+        let kind = match EntryKind::from_element(&child) {
+            Some(kind) if child.borrow().repeated.is_none() => kind,
+            // Already reported by check_entries
+            _ => continue,
+        };
+        let prefix = format!("{}_entry{}", menu_id, entries.len());
+        for name in kind.properties() {
+            let property_type = child.borrow().lookup_property(name).property_type;
+            let property_name = format!("{}_{}", prefix, name);
+            parent
+                .borrow_mut()
+                .property_declarations
+                .insert(property_name.clone(), property_type.into());
+            if let Some(binding) = child.borrow_mut().bindings.remove(*name) {
+                parent.borrow_mut().bindings.insert(property_name, binding);
+            }
+        }
+        ctx.moved_entries.push((child.clone(), parent.clone(), prefix.clone()));
+        entries.push(LoweredEntry { kind, depth, prefix });
+        if kind == EntryKind::SubMenu {
+            collect_entries(&child, parent, menu_id, depth + 1, ctx, entries);
+        }
+    }
+}
+
+/// Move the properties of the entries of the ContextMenu to its parent, and replace the entries
+/// with the elements rendering them when the menu is shown in a PopupWindow.
+fn lower_context_menu(menu: &ElementRc, parent: &ElementRc, ctx: &mut LoweringContext) {
+    let menu_id = ensure_id(menu, ctx);
+    let mut entries = Vec::new();
+    collect_entries(menu, parent, &menu_id, 0, ctx, &mut entries);
+
+    let property = |entry: &LoweredEntry, name: &str| {
+        Expression::PropertyReference(NamedReference::new(
+            parent,
+            &format!("{}_{}", entry.prefix, name),
+        ))
+    };
+
+    // The callback activating the entry at the given index
+    let activated_name = format!("{}_activated", menu_id);
+    let index = || Expression::FunctionParameterReference { index: 0, ty: Type::Int32 };
+    let mut handler = Vec::new();
+    for (i, entry) in entries.iter().enumerate().filter(|(_, e)| e.kind == EntryKind::Item) {
+        let toggle = Expression::SelfAssignment {
+            lhs: Box::new(property(entry, "checked")),
+            rhs: Box::new(Expression::UnaryOp {
+                sub: Box::new(property(entry, "checked")),
+                op: '!',
+            }),
+            op: '=',
+        };
+        handler.push(condition(
+            Expression::BinaryExpression {
+                lhs: Box::new(index()),
+                rhs: Box::new(Expression::NumberLiteral(i as f64, Unit::None)),
+                op: '=',
+            },
+            Expression::CodeBlock(vec![
+                condition(property(entry, "checkable"), toggle, Expression::CodeBlock(vec![])),
+                call(
+                    Expression::CallbackReference(NamedReference::new(
+                        parent,
+                        &format!("{}_activated", entry.prefix),
+                    )),
+                    vec![],
+                ),
+            ]),
+            Expression::CodeBlock(vec![]),
+        ));
+    }
+    {
+        let mut parent = parent.borrow_mut();
+        parent.property_declarations.insert(
+            activated_name.clone(),
+            Type::Callback { args: vec![Type::Int32], return_type: None }.into(),
+        );
+        parent.bindings.insert(activated_name.clone(), Expression::CodeBlock(handler).into());
+    }
+
+    let rows = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let id = format!("{}_row{}", menu_id, i);
+            if entry.kind == EntryKind::Separator {
+                return new_element(
+                    id,
+                    "Rectangle",
+                    menu,
+                    vec![
+                        ("background", brush(0xffcccccc)),
+                        ("min_height", length(1.)),
+                        ("max_height", length(1.)),
+                    ],
+                    vec![],
+                    ctx,
+                );
+            }
+            let text = |suffix: &str, text: Expression, color: Expression| {
+                new_element(
+                    format!("{}_{}", id, suffix),
+                    "Text",
+                    menu,
+                    vec![("text", text), ("color", color)],
+                    vec![],
+                    ctx,
+                )
+            };
+            let is_item = entry.kind == EntryKind::Item;
+            let check_mark = text(
+                "check",
+                if is_item {
+                    condition(
+                        property(entry, "checked"),
+                        Expression::StringLiteral("✓".into()),
+                        Expression::StringLiteral(String::new()),
+                    )
+                } else {
+                    Expression::StringLiteral(String::new())
+                },
+                brush(0xff000000),
+            );
+            check_mark.borrow_mut().bindings.insert("min_width".into(), length(12.).into());
+            let title = text(
+                "title",
+                property(entry, "title"),
+                condition(property(entry, "enabled"), brush(0xff000000), brush(0xff888888)),
+            );
+            title.borrow_mut().bindings.insert(
+                "horizontal_stretch".into(),
+                Expression::NumberLiteral(1., Unit::None).into(),
+            );
+            let trailing = text(
+                "shortcut",
+                if is_item {
+                    property(entry, "shortcut")
+                } else {
+                    Expression::StringLiteral("▸".into())
+                },
+                brush(0xff888888),
+            );
+            let layout = new_element(
+                format!("{}_layout", id),
+                "HorizontalLayout",
+                menu,
+                vec![
+                    ("padding_left", length(8. + 16. * entry.depth as f64)),
+                    ("padding_right", length(8.)),
+                    ("padding_top", length(4.)),
+                    ("padding_bottom", length(4.)),
+                    ("spacing", length(8.)),
+                ],
+                vec![check_mark, title, trailing],
+                ctx,
+            );
+            let mut children = vec![layout];
+            let mut bindings = vec![];
+            if is_item {
+                let touch_area = new_element(
+                    format!("{}_touch", id),
+                    "TouchArea",
+                    menu,
+                    vec![
+                        ("enabled", property(entry, "enabled")),
+                        (
+                            "clicked",
+                            call(
+                                Expression::CallbackReference(NamedReference::new(
+                                    parent,
+                                    &activated_name,
+                                )),
+                                vec![Expression::NumberLiteral(i as f64, Unit::None)],
+                            ),
+                        ),
+                    ],
+                    vec![],
+                    ctx,
+                );
+                bindings.push((
+                    "background",
+                    condition(
+                        Expression::PropertyReference(NamedReference::new(
+                            &touch_area,
+                            "has_hover",
+                        )),
+                        brush(0xffdddddd),
+                        brush(0),
+                    ),
+                ));
+                children.push(touch_area);
+            }
+            new_element(id, "Rectangle", menu, bindings, children, ctx)
+        })
+        .collect::<Vec<_>>();
+
+    let layout = new_element(
+        format!("{}_layout", menu_id),
+        "VerticalLayout",
+        menu,
+        vec![("padding", length(1.))],
+        rows,
+        ctx,
+    );
+    let frame = new_element(
+        format!("{}_frame", menu_id),
+        "Rectangle",
+        menu,
+        vec![
+            ("background", brush(0xffffffff)),
+            ("border_width", length(1.)),
+            ("border_color", brush(0xff888888)),
+        ],
+        vec![layout],
+        ctx,
+    );
+    {
+        let mut menu = menu.borrow_mut();
+        menu.base_type = ctx.popup_type.clone();
+        menu.children = vec![frame];
+    }
+
+    ctx.menus.push(LoweredMenu { menu: menu.clone(), parent: parent.clone(), entries });
+}
+
+/// Replace the calls to `show()` on a ContextMenu with a call showing a native menu when the
+/// platform has native menus, and the PopupWindow otherwise.
+fn replace_show_calls(expr: &mut Expression, menus: &[LoweredMenu], menu_entry_type: &Type) {
+    if let Expression::FunctionCall { function, arguments, .. } = expr {
+        if let (
+            Expression::BuiltinFunctionReference(BuiltinFunction::ShowPopupWindow, _),
+            [Expression::ElementReference(element)],
+        ) = (&**function, arguments.as_slice())
+        {
+            let element = element.upgrade().unwrap();
+            if let Some(menu) = menus.iter().find(|m| Rc::ptr_eq(&m.menu, &element)) {
+                *expr = show_menu_expression(menu, menu_entry_type);
+                return;
+            }
+        }
+    }
+    expr.visit_mut(|e| replace_show_calls(e, menus, menu_entry_type))
+}
+
+/// The `MenuEntry` of each entry of the menu, with a depth increased by `depth`
+fn menu_entry_expressions(
+    menu: &LoweredMenu,
+    menu_entry_type: &Type,
+    depth: usize,
+) -> Vec<Expression> {
+    let parent = &menu.parent;
+    let property = |entry: &LoweredEntry, name: &str| {
+        Expression::PropertyReference(NamedReference::new(
+            parent,
+            &format!("{}_{}", entry.prefix, name),
+        ))
+    };
+    menu.entries
+        .iter()
+        .map(|entry| {
+            let is_item = entry.kind == EntryKind::Item;
+            let has_title = entry.kind != EntryKind::Separator;
+            let values = [
+                (
+                    "title",
+                    if has_title {
+                        property(entry, "title")
+                    } else {
+                        Expression::StringLiteral(String::new())
+                    },
+                ),
+                (
+                    "shortcut",
+                    if is_item {
+                        property(entry, "shortcut")
+                    } else {
+                        Expression::StringLiteral(String::new())
+                    },
+                ),
+                (
+                    "enabled",
+                    if has_title {
+                        property(entry, "enabled")
+                    } else {
+                        Expression::BoolLiteral(false)
+                    },
+                ),
+                (
+                    "checkable",
+                    if is_item {
+                        property(entry, "checkable")
+                    } else {
+                        Expression::BoolLiteral(false)
+                    },
+                ),
+                (
+                    "checked",
+                    if is_item {
+                        property(entry, "checked")
+                    } else {
+                        Expression::BoolLiteral(false)
+                    },
+                ),
+                ("is_separator", Expression::BoolLiteral(entry.kind == EntryKind::Separator)),
+                ("has_submenu", Expression::BoolLiteral(entry.kind == EntryKind::SubMenu)),
+                ("depth", Expression::NumberLiteral((depth + entry.depth) as f64, Unit::None)),
+            ];
+            Expression::Struct {
+                ty: menu_entry_type.clone(),
+                values: values.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+            }
+        })
+        .collect()
+}
+
+fn show_menu_expression(menu: &LoweredMenu, menu_entry_type: &Type) -> Expression {
+    let menu_id = menu.menu.borrow().id.clone();
+    let show_popup = Expression::CodeBlock(vec![builtin_call(
+        BuiltinFunction::ShowPopupWindow,
+        vec![Expression::ElementReference(Rc::downgrade(&menu.menu))],
+    )]);
+    let show_native = Expression::CodeBlock(vec![builtin_call(
+        BuiltinFunction::ShowNativeMenu,
+        vec![
+            Expression::ElementReference(Rc::downgrade(&menu.menu)),
+            Expression::Array {
+                element_ty: menu_entry_type.clone(),
+                values: menu_entry_expressions(menu, menu_entry_type, 0),
+            },
+            Expression::CallbackReference(NamedReference::new(
+                &menu.parent,
+                &format!("{}_activated", menu_id),
+            )),
+        ],
+    )]);
+    condition(builtin_call(BuiltinFunction::HasNativeMenus, vec![]), show_native, show_popup)
+}
+
+/// Show the menus of the MenuBar in the native menu bar when the platform has native menus.
+///
+/// The entries of the native menu bar are the title of each menu followed by the entries of its
+/// ContextMenu, and the callback of the bar calls the callback of the ContextMenu of the entry.
+fn setup_native_menu_bar(menu_bar: &LoweredMenuBar, menus: &[LoweredMenu], menu_entry_type: &Type) {
+    let bar_id = menu_bar.menu_bar.borrow().id.clone();
+    let activated_name = format!("{}_activated", bar_id);
+    let index = || Expression::FunctionParameterReference { index: 0, ty: Type::Int32 };
+    let number = |value: usize| Expression::NumberLiteral(value as f64, Unit::None);
+    let binary = |lhs: Expression, op: char, rhs: Expression| Expression::BinaryExpression {
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+        op,
+    };
+
+    let mut entries = Vec::new();
+    let mut handler = Vec::new();
+    for (menu, context_menu) in &menu_bar.menus {
+        let lowered = match menus.iter().find(|m| Rc::ptr_eq(&m.menu, context_menu)) {
+            Some(lowered) => lowered,
+            None => continue,
+        };
+        let property = |name: &str| Expression::PropertyReference(NamedReference::new(menu, name));
+        let values = [
+            ("title", property("title")),
+            ("shortcut", Expression::StringLiteral(String::new())),
+            ("enabled", property("enabled")),
+            ("checkable", Expression::BoolLiteral(false)),
+            ("checked", Expression::BoolLiteral(false)),
+            ("is_separator", Expression::BoolLiteral(false)),
+            ("has_submenu", Expression::BoolLiteral(true)),
+            ("depth", number(0)),
+        ];
+        let first_entry = entries.len() + 1;
+        entries.push(Expression::Struct {
+            ty: menu_entry_type.clone(),
+            values: values.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+        });
+        entries.extend(menu_entry_expressions(lowered, menu_entry_type, 1));
+        handler.push(condition(
+            binary(
+                binary(index(), '≥', number(first_entry)),
+                '&',
+                binary(index(), '<', number(entries.len())),
+            ),
+            call(
+                Expression::CallbackReference(NamedReference::new(
+                    menu,
+                    &format!("{}_activated", context_menu.borrow().id),
+                )),
+                vec![binary(index(), '-', number(first_entry))],
+            ),
+            Expression::CodeBlock(vec![]),
+        ));
+    }
+
+    {
+        let mut bar = menu_bar.menu_bar.borrow_mut();
+        bar.property_declarations.insert(
+            activated_name.clone(),
+            Type::Callback { args: vec![Type::Int32], return_type: None }.into(),
+        );
+        bar.bindings.insert(activated_name.clone(), Expression::CodeBlock(handler).into());
+    }
+    let setup_code = condition(
+        builtin_call(BuiltinFunction::HasNativeMenus, vec![]),
+        builtin_call(
+            BuiltinFunction::SetNativeMenuBar,
+            vec![
+                Expression::Array { element_ty: menu_entry_type.clone(), values: entries },
+                Expression::CallbackReference(NamedReference::new(
+                    &menu_bar.menu_bar,
+                    &activated_name,
+                )),
+            ],
+        ),
+        Expression::CodeBlock(vec![]),
+    );
+    let component = menu_bar.menu_bar.borrow().enclosing_component.upgrade().unwrap();
+    component.setup_code.borrow_mut().push(setup_code);
+}
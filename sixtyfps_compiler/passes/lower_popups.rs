@@ -7,15 +7,19 @@
     This file is also available under commercial licensing terms.
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
-//! Passe that compute the layout constraint
+//! Passe that lowers the PopupWindow and Dialog elements to components of their own
 
 use crate::diagnostics::BuildDiagnostics;
-use crate::expression_tree::{Expression, NamedReference};
+use crate::expression_tree::{BuiltinFunction, Expression, NamedReference, Unit};
 use crate::langtype::Type;
 use crate::object_tree::*;
 use crate::typeregister::TypeRegister;
+use std::cell::RefCell;
 use std::rc::Rc;
 
+/// The padding around the content of a dialog, and the spacing between its buttons
+const DIALOG_SPACING: f64 = 8.;
+
 /// Currently this just removes the layout from the tree
 pub fn lower_popups(
     component: &Rc<Component>,
@@ -30,9 +34,13 @@ pub fn lower_popups(
         component,
         &Vec::new(),
         &mut |elem, parent_stack: &Vec<ElementRc>| {
-            let is_popup = elem.borrow().base_type.to_string() == "PopupWindow";
-            if is_popup {
-                lower_popup_window(elem, parent_stack, &window_type, diag);
+            let base_type = elem.borrow().base_type.to_string();
+            match base_type.as_str() {
+                "PopupWindow" => {
+                    lower_popup_window(elem, parent_stack, &window_type, None, diag);
+                }
+                "Dialog" => lower_dialog(elem, parent_stack, &window_type, type_register, diag),
+                _ => {}
             }
             // this could be implemented in a better way with less cloning of the state
             let mut parent_stack = parent_stack.clone();
@@ -46,6 +54,7 @@ fn lower_popup_window(
     popup_window_element: &ElementRc,
     parent_stack: &[ElementRc],
     window_type: &Type,
+    dialog_presentation: Option<NamedReference>,
     diag: &mut BuildDiagnostics,
 ) {
     let parent_element = match parent_stack.last() {
@@ -100,9 +109,221 @@ fn lower_popup_window(
         component: popup_comp,
         x: coord_x,
         y: coord_y,
+        dialog_presentation,
     });
 }
 
+/// Lay out the content of the dialog above a row with its standard buttons, and make the buttons
+/// report their kind in the `result` callback.
+/// A Dialog at the root of a component becomes the Window of that component, otherwise it is
+/// lowered like a PopupWindow.
+fn lower_dialog(
+    dialog: &ElementRc,
+    parent_stack: &[ElementRc],
+    window_type: &Type,
+    type_register: &TypeRegister,
+    diag: &mut BuildDiagnostics,
+) {
+    let is_root = parent_stack.is_empty();
+    {
+        let mut dialog = dialog.borrow_mut();
+        for name in ["result", "presentation"] {
+            let property_type = dialog.lookup_property(name).property_type;
+            dialog.property_declarations.insert(
+                name.into(),
+                PropertyDeclaration {
+                    property_type,
+                    expose_in_public_api: is_root && name == "result",
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    let (buttons, content): (Vec<_>, Vec<_>) =
+        std::mem::take(&mut dialog.borrow_mut().children).into_iter().partition(is_standard_button);
+
+    let new_element = |suffix: &str, base: &str, children: Vec<ElementRc>| {
+        Rc::new(RefCell::new(Element {
+            id: format!("{}_{}", dialog.borrow().id, suffix),
+            base_type: type_register.lookup_element(base).unwrap(),
+            children,
+            enclosing_component: dialog.borrow().enclosing_component.clone(),
+            ..Default::default()
+        }))
+    };
+    let spacing = || Expression::NumberLiteral(DIALOG_SPACING, Unit::Px);
+
+    let mut layout_children = vec![new_element("content", "Rectangle", content)];
+    if !buttons.is_empty() {
+        let row = new_element("buttons", "Rectangle", vec![]);
+        let size = |button: &ElementRc, size: &str| {
+            Expression::PropertyReference(NamedReference::new(
+                button,
+                &format!("preferred_{}", size),
+            ))
+        };
+        let rank = |button: &ElementRc| Expression::FunctionCall {
+            function: Box::new(Expression::BuiltinFunctionReference(
+                BuiltinFunction::StandardButtonRank,
+                None,
+            )),
+            arguments: vec![kind_reference(button)],
+            source_location: None,
+        };
+        for (i, button) in buttons.iter().enumerate() {
+            // The buttons with a higher rank are placed on the right of this button.
+            // Buttons of the same kind keep their order.
+            let mut x = Expression::BinaryExpression {
+                lhs: Box::new(Expression::PropertyReference(NamedReference::new(&row, "width"))),
+                rhs: Box::new(size(button, "width")),
+                op: '-',
+            };
+            for (j, other) in buttons.iter().enumerate().filter(|(j, _)| *j != i) {
+                x = Expression::BinaryExpression {
+                    lhs: Box::new(x),
+                    rhs: Box::new(Expression::Condition {
+                        condition: Box::new(Expression::BinaryExpression {
+                            lhs: Box::new(rank(other)),
+                            rhs: Box::new(rank(button)),
+                            op: if j > i { '≥' } else { '>' },
+                        }),
+                        true_expr: Box::new(Expression::BinaryExpression {
+                            lhs: Box::new(size(other, "width")),
+                            rhs: Box::new(spacing()),
+                            op: '+',
+                        }),
+                        false_expr: Box::new(Expression::NumberLiteral(0., Unit::Px)),
+                    }),
+                    op: '-',
+                };
+            }
+            let width = size(button, "width");
+            let height = size(button, "height");
+            let mut button_mut = button.borrow_mut();
+            button_mut.bindings.insert("x".into(), x.into());
+            button_mut.bindings.insert("y".into(), Expression::NumberLiteral(0., Unit::Px).into());
+            button_mut.bindings.insert("width".into(), width.into());
+            button_mut.bindings.insert("height".into(), height.into());
+            drop(button_mut);
+            report_result(button, dialog, is_root);
+        }
+
+        let max_height = buttons
+            .iter()
+            .map(|b| size(b, "height"))
+            .reduce(|a, b| crate::builtin_macros::min_max_expression(a, b, '>'))
+            .unwrap();
+        let min_width = buttons
+            .iter()
+            .map(|b| size(b, "width"))
+            .reduce(|a, b| Expression::BinaryExpression {
+                lhs: Box::new(Expression::BinaryExpression {
+                    lhs: Box::new(a),
+                    rhs: Box::new(spacing()),
+                    op: '+',
+                }),
+                rhs: Box::new(b),
+                op: '+',
+            })
+            .unwrap();
+        {
+            let mut row_mut = row.borrow_mut();
+            row_mut.bindings.insert("min_height".into(), max_height.clone().into());
+            row_mut.bindings.insert("max_height".into(), max_height.into());
+            row_mut.bindings.insert("min_width".into(), min_width.into());
+            row_mut.children = buttons;
+        }
+        layout_children.push(row);
+    }
+    let layout = new_element("layout", "VerticalLayout", layout_children);
+    for property in ["padding", "spacing"] {
+        layout.borrow_mut().bindings.insert(property.into(), spacing().into());
+    }
+    dialog.borrow_mut().children = vec![layout];
+
+    if is_root {
+        dialog.borrow_mut().bindings.remove("presentation");
+        dialog.borrow_mut().base_type = window_type.clone();
+        return;
+    }
+
+    // The presentation is needed by the parent to show the dialog before it is instantiated
+    let parent_element = parent_stack.last().unwrap();
+    let presentation_type = dialog.borrow().lookup_property("presentation").property_type;
+    let property_name = format!("{}_dialog_presentation", dialog.borrow().id);
+    parent_element
+        .borrow_mut()
+        .property_declarations
+        .insert(property_name.clone(), presentation_type.into());
+    if let Some(binding) = dialog.borrow_mut().bindings.remove("presentation") {
+        parent_element.borrow_mut().bindings.insert(property_name.clone(), binding);
+    }
+    let presentation = NamedReference::new(parent_element, &property_name);
+    lower_popup_window(dialog, parent_stack, window_type, Some(presentation), diag);
+}
+
+/// Returns true for the direct children of a Dialog that have a `kind` property of type `StandardButtonKind`
+fn is_standard_button(element: &ElementRc) -> bool {
+    element.borrow().repeated.is_none()
+        && matches!(
+            element.borrow().lookup_property("kind").property_type,
+            Type::Enumeration(e) if e.name == "StandardButtonKind" && e.node.is_none()
+        )
+}
+
+fn kind_reference(button: &ElementRc) -> Expression {
+    let kind = button.borrow().lookup_property("kind").resolved_name.into_owned();
+    Expression::PropertyReference(NamedReference::new(button, &kind))
+}
+
+/// Add a call to the `result` callback of the dialog to the `clicked` handler of the button, and close
+/// the dialog if it is not the root and the button does not apply the changes.
+fn report_result(button: &ElementRc, dialog: &ElementRc, is_root: bool) {
+    let clicked = button.borrow().lookup_property("clicked");
+    if !matches!(clicked.property_type, Type::Callback { .. }) {
+        return;
+    }
+    let clicked = clicked.resolved_name.into_owned();
+    let kind = kind_reference(button);
+    let mut handler = vec![Expression::FunctionCall {
+        function: Box::new(Expression::CallbackReference(NamedReference::new(dialog, "result"))),
+        arguments: vec![kind.clone()],
+        source_location: None,
+    }];
+    if !is_root {
+        let close = Expression::FunctionCall {
+            function: Box::new(Expression::BuiltinFunctionReference(
+                BuiltinFunction::CloseDialog,
+                None,
+            )),
+            arguments: vec![Expression::ElementReference(Rc::downgrade(dialog))],
+            source_location: None,
+        };
+        let apply = kind.ty().as_enum().clone().try_value_from_string("apply").unwrap();
+        let is_apply = Expression::BinaryExpression {
+            lhs: Box::new(kind),
+            rhs: Box::new(Expression::EnumerationValue(apply)),
+            op: '!',
+        };
+        handler.push(Expression::Condition {
+            condition: Box::new(is_apply),
+            true_expr: Box::new(close),
+            false_expr: Box::new(Expression::CodeBlock(vec![])),
+        });
+    }
+    let mut button = button.borrow_mut();
+    if let Some(existing) = button.bindings.get_mut(&clicked) {
+        let existing = std::mem::take(&mut existing.expression);
+        handler.insert(0, existing);
+    }
+    button
+        .bindings
+        .entry(clicked)
+        .or_insert_with(|| Expression::CodeBlock(vec![]).into())
+        .expression = Expression::CodeBlock(handler);
+}
+
 fn create_coordinate(
     popup_comp: &Rc<Component>,
     parent_stack: &[ElementRc],
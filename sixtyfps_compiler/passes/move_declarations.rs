@@ -96,9 +96,15 @@ pub fn move_declarations(component: &Rc<Component>, diag: &mut BuildDiagnostics)
     component.optimized_elements.borrow().iter().for_each(|e| move_bindings_and_animations(e));
 
     component.root_constraints.borrow_mut().visit_named_references(&mut |e| fixup_reference(e));
+    for expr in component.setup_code.borrow_mut().iter_mut() {
+        visit_named_references_in_expression(expr, &mut fixup_reference);
+    }
     component.popup_windows.borrow_mut().iter_mut().for_each(|p| {
         fixup_reference(&mut p.x);
         fixup_reference(&mut p.y);
+        if let Some(presentation) = &mut p.dialog_presentation {
+            fixup_reference(presentation);
+        }
         visit_all_named_references(&p.component, &mut |e| fixup_reference(e))
     });
 
@@ -17,9 +17,9 @@
 use crate::object_tree::{recurse_elem_including_sub_components, Component};
 
 pub fn resolve_native_classes(component: &Component) {
-    recurse_elem_including_sub_components(component, &(), &mut |elem, _| {
+    recurse_elem_including_sub_components(component, &(), &mut |elem_rc, _| {
         let new_native_class = {
-            let elem = elem.borrow();
+            let elem = elem_rc.borrow();
 
             let base_type = match &elem.base_type {
                 Type::Component(_) => {
@@ -34,24 +34,29 @@ pub fn resolve_native_classes(component: &Component) {
                 _ => panic!("This should not happen"),
             };
 
-            let analysis = elem.property_analysis.borrow();
-            let native_properties_used: HashSet<_> = elem
-                .bindings
-                .keys()
-                .chain(analysis.iter().filter(|(_, v)| v.is_read || v.is_set).map(|(k, _)| k))
-                .filter(|k| {
-                    !elem.property_declarations.contains_key(*k)
-                        && base_type.as_ref().properties.contains_key(*k)
-                })
-                .collect();
-
-            select_minimal_class_based_on_property_usage(
-                &elem.base_type.as_builtin().native_class,
-                native_properties_used.into_iter(),
-            )
+            if component.is_sub_component.get() && Rc::ptr_eq(&component.root_element, elem_rc) {
+                // The instantiating component may use any of the properties
+                base_type.native_class.clone()
+            } else {
+                let analysis = elem.property_analysis.borrow();
+                let native_properties_used: HashSet<_> = elem
+                    .bindings
+                    .keys()
+                    .chain(analysis.iter().filter(|(_, v)| v.is_read || v.is_set).map(|(k, _)| k))
+                    .filter(|k| {
+                        !elem.property_declarations.contains_key(*k)
+                            && base_type.as_ref().properties.contains_key(*k)
+                    })
+                    .collect();
+
+                select_minimal_class_based_on_property_usage(
+                    &elem.base_type.as_builtin().native_class,
+                    native_properties_used.into_iter(),
+                )
+            }
         };
 
-        elem.borrow_mut().base_type = Type::Native(new_native_class);
+        elem_rc.borrow_mut().base_type = Type::Native(new_native_class);
     })
 }
 
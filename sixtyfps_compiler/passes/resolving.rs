@@ -14,7 +14,7 @@
 //!
 //! Most of the code for the resolving actually lies in the expression_tree module
 
-use crate::diagnostics::{BuildDiagnostics, Spanned};
+use crate::diagnostics::{BuildDiagnostics, Diagnostic, DiagnosticCode, DiagnosticLevel, Spanned};
 use crate::expression_tree::*;
 use crate::langtype::{PropertyLookupResult, Type};
 use crate::lookup::{LookupCtx, LookupObject};
@@ -311,6 +311,8 @@ fn from_expression_node(node: syntax_nodes::Expression, ctx: &mut LookupCtx) ->
             .or_else(|| node.Array().map(|n| Self::from_array_node(n, ctx)))
             .or_else(|| node.CodeBlock().map(|n| Self::from_codeblock_node(n, ctx)))
             .or_else(|| node.StringTemplate().map(|n| Self::from_string_template_node(n, ctx)))
+            .or_else(|| node.IndexExpression().map(|n| Self::from_index_expression_node(n, ctx)))
+            .or_else(|| node.MemberAccess().map(|n| Self::from_member_access_node(n, ctx)))
             .unwrap_or(Self::Invalid)
     }
 
@@ -495,15 +497,32 @@ fn from_qualified_name_node(node: SyntaxNode, ctx: &mut LookupCtx) -> Self {
                     // Attempt to recover if the user wanted to write "-"
                     let first_str = &first.text()[0..minus_pos];
                     if global_lookup.lookup(ctx, first_str).is_some() {
-                        ctx.diag.push_error(format!("Unknown unqualified identifier '{}'. Use space before the '-' if you meant a subtraction", first.text()), &node);
+                        ctx.diag.push_compiler_error(
+                            Diagnostic::new(
+                                DiagnosticLevel::Error,
+                                DiagnosticCode::UnknownIdentifier,
+                                format!("Unknown unqualified identifier '{}'. Use space before the '-' if you meant a subtraction", first.text()),
+                                &node,
+                            )
+                            .with_suggestion(
+                                "Insert spaces around the '-'".into(),
+                                &first,
+                                subtraction_suggestion(first.text(), minus_pos),
+                            ),
+                        );
                         return Expression::Invalid;
                     }
                 }
 
                 if it.next().is_some() {
-                    ctx.diag.push_error(format!("Cannot access id '{}'", first.text()), &node);
+                    ctx.diag.push_error_with_code(
+                        DiagnosticCode::UnknownIdentifier,
+                        format!("Cannot access id '{}'", first.text()),
+                        &node,
+                    );
                 } else {
-                    ctx.diag.push_error(
+                    ctx.diag.push_error_with_code(
+                        DiagnosticCode::UnknownIdentifier,
                         format!("Unknown unqualified identifier '{}'", first.text()),
                         &node,
                     );
@@ -593,7 +612,8 @@ fn from_function_call_node(
         let arguments = match function.ty() {
             Type::Function { args, .. } | Type::Callback { args, .. } => {
                 if arguments.len() != args.len() {
-                    ctx.diag.push_error(
+                    ctx.diag.push_error_with_code(
+                        DiagnosticCode::WrongArgumentCount,
                         format!(
                             "The callback or function expects {} arguments, but {} are provided",
                             args.len(),
@@ -827,6 +847,41 @@ fn from_array_node(node: syntax_nodes::Array, ctx: &mut LookupCtx) -> Expression
         Expression::Array { element_ty, values }
     }
 
+    fn from_index_expression_node(
+        node: syntax_nodes::IndexExpression,
+        ctx: &mut LookupCtx,
+    ) -> Expression {
+        let (array_expr_n, index_expr_n) = node.Expression();
+        let array_expr = Self::from_expression_node(array_expr_n.clone(), ctx);
+        let index_expr = Self::from_expression_node(index_expr_n.clone(), ctx).maybe_convert_to(
+            Type::Int32,
+            &index_expr_n,
+            ctx.diag,
+        );
+        match array_expr.ty() {
+            Type::Array(_) => {
+                Expression::ArrayIndex { array: Box::new(array_expr), index: Box::new(index_expr) }
+            }
+            Type::Invalid => Expression::Invalid,
+            ty => {
+                ctx.diag.push_error(format!("{} is not an indexable type", ty), &array_expr_n);
+                Expression::Invalid
+            }
+        }
+    }
+
+    fn from_member_access_node(
+        node: syntax_nodes::MemberAccess,
+        ctx: &mut LookupCtx,
+    ) -> Expression {
+        let base = Self::from_expression_node(node.Expression(), ctx);
+        if base.ty() == Type::Invalid {
+            // An error was already reported
+            return Expression::Invalid;
+        }
+        maybe_lookup_object(base, node.child_token(SyntaxKind::Identifier).into_iter(), ctx)
+    }
+
     fn from_string_template_node(
         node: syntax_nodes::StringTemplate,
         ctx: &mut LookupCtx,
@@ -946,7 +1001,7 @@ fn continue_lookup_within_element(
             member: Box::new(member),
         }
     } else {
-        let mut err = |extra: &str| {
+        let mut err = |extra: &str, suggestion: Option<String>| {
             let what = match &elem.borrow().base_type {
                 Type::Void => {
                     let global = elem.borrow().enclosing_component.upgrade().unwrap();
@@ -960,25 +1015,43 @@ fn continue_lookup_within_element(
                     return;
                 }
             };
-            ctx.diag.push_error(
+            let mut diag = Diagnostic::new(
+                DiagnosticLevel::Error,
+                DiagnosticCode::UnknownProperty,
                 format!("{} does not have a property '{}'{}", what, second.text(), extra),
                 &second,
             );
+            if let Some(suggestion) = suggestion {
+                diag = diag.with_suggestion(
+                    "Insert spaces around the '-'".into(),
+                    &second,
+                    suggestion,
+                );
+            }
+            ctx.diag.push_compiler_error(diag);
         };
         if let Some(minus_pos) = second.text().find('-') {
             // Attempt to recover if the user wanted to write "-"
             if elem.borrow().lookup_property(&second.text()[0..minus_pos]).property_type
                 != Type::Invalid
             {
-                err(". Use space before the '-' if you meant a subtraction");
+                err(
+                    ". Use space before the '-' if you meant a subtraction",
+                    Some(subtraction_suggestion(second.text(), minus_pos)),
+                );
                 return Expression::Invalid;
             }
         }
-        err("");
+        err("", None);
         Expression::Invalid
     }
 }
 
+/// For an identifier such as `foo-bar` that was meant as a subtraction, return `foo - bar`
+fn subtraction_suggestion(identifier: &str, minus_pos: usize) -> String {
+    format!("{} - {}", &identifier[..minus_pos], &identifier[minus_pos + 1..])
+}
+
 fn maybe_lookup_object(
     mut base: Expression,
     it: impl Iterator<Item = crate::parser::SyntaxToken>,
@@ -8,7 +8,7 @@
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
 
-use crate::diagnostics::BuildDiagnostics;
+use crate::diagnostics::{BuildDiagnostics, Diagnostic, DiagnosticCode, DiagnosticLevel};
 use crate::langtype::Type;
 use crate::object_tree::*;
 use std::collections::HashMap;
@@ -63,8 +63,19 @@ fn check_unique_id_in_component(component: &Rc<Component>, diag: &mut BuildDiagn
         if !id.is_empty() {
             if let Some(other_loc) = seen_ids.get(id) {
                 debug_assert!(!Rc::ptr_eq(other_loc, elem));
-                diag.push_error(format!("duplicated element id '{}'", id), &*other_loc.borrow());
-                diag.push_error(format!("duplicated element id '{}'", id), &*elem_bor);
+                let other = other_loc.borrow();
+                let error = |this: &Element, that: &Element| {
+                    Diagnostic::new(
+                        DiagnosticLevel::Error,
+                        DiagnosticCode::DuplicatedElementId,
+                        format!("duplicated element id '{}'", id),
+                        this,
+                    )
+                    .with_note("the other element with this id is here".into(), that)
+                };
+                diag.push_compiler_error(error(&other, &elem_bor));
+                diag.push_compiler_error(error(&elem_bor, &other));
+                drop(other);
                 seen_ids.remove(id);
             } else {
                 seen_ids.insert(id.clone(), elem.clone());
@@ -52,10 +52,26 @@ fn syntax_tests() -> std::io::Result<()> {
 
 fn process_file(path: &std::path::Path) -> std::io::Result<bool> {
     let source = std::fs::read_to_string(&path)?;
-    std::panic::catch_unwind(|| process_file_source(path, source, false)).unwrap_or_else(|err| {
-        println!("Panic while processing {}: {:?}", path.display(), err);
-        Ok(false)
-    })
+    let mut success = true;
+    // The diagnostics must be the same whether the components are inlined or generated as
+    // sub-components
+    for inline_all_elements in [true, false].iter().copied() {
+        let source = source.clone();
+        success &= std::panic::catch_unwind(|| {
+            process_file_source(path, source, inline_all_elements, false)
+        })
+        .unwrap_or_else(|err| {
+            println!("Panic while processing {}: {:?}", path.display(), err);
+            Ok(false)
+        })?;
+        if !success {
+            if !inline_all_elements {
+                println!("{}: failed with sub-components", path.display());
+            }
+            break;
+        }
+    }
+    Ok(success)
 }
 
 fn process_diagnostics(
@@ -154,6 +170,7 @@ fn canonical(path: &Path) -> PathBuf {
 fn process_file_source(
     path: &std::path::Path,
     source: String,
+    inline_all_elements: bool,
     silent: bool,
 ) -> std::io::Result<bool> {
     let mut parse_diagnostics = sixtyfps_compilerlib::diagnostics::BuildDiagnostics::default();
@@ -164,6 +181,14 @@ fn process_file_source(
         sixtyfps_compilerlib::generator::OutputFormat::Interpreter,
     );
     compiler_config.style = Some("ugly".into());
+    compiler_config.inline_all_elements = inline_all_elements;
+    if path.parent().map_or(false, |dir| dir.ends_with("lints")) {
+        // The tests in the lints directory are compiled with all the lints enabled
+        compiler_config.lints = sixtyfps_compilerlib::diagnostics::Lint::ALL
+            .iter()
+            .map(|lint| (*lint, sixtyfps_compilerlib::diagnostics::LintLevel::Warn))
+            .collect();
+    }
     let compile_diagnostics = if !parse_diagnostics.has_error() {
         let (_, build_diags) = spin_on::spin_on(sixtyfps_compilerlib::compile_syntax_node(
             syntax_node.clone(),
@@ -204,7 +229,7 @@ fn process_file_source(
 /// Test that this actually fail when it should
 fn self_test() -> std::io::Result<()> {
     let fake_path = std::path::Path::new("fake.60");
-    let process = |str: &str| process_file_source(&fake_path, str.into(), true);
+    let process = |str: &str| process_file_source(&fake_path, str.into(), true, true);
 
     // this should succeed
     assert!(process(
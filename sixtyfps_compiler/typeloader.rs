@@ -13,7 +13,8 @@
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
-use crate::diagnostics::{BuildDiagnostics, Spanned};
+use crate::diagnostics::{BuildDiagnostics, DiagnosticCode, DiagnosticLevel, Spanned};
+use crate::langtype::Type;
 use crate::object_tree::{self, Document};
 use crate::parser::{syntax_nodes, NodeOrToken, SyntaxKind, SyntaxToken};
 use crate::typeregister::TypeRegister;
@@ -25,6 +26,8 @@ pub struct LoadedDocuments {
     /// maps from the canonical file name to the object_tree::Document
     docs: HashMap<PathBuf, Document>,
     currently_loading: HashSet<PathBuf>,
+    /// maps from the canonical file name to the canonical file names of the .60 files it imports
+    dependencies: HashMap<PathBuf, HashSet<PathBuf>>,
 }
 
 pub struct VirtualFile<'a> {
@@ -69,10 +72,17 @@ pub fn from_node(importident: syntax_nodes::ImportIdentifier) -> Self {
     }
 }
 
+/// For each export of a style, its properties and callbacks with their type
+type StyleApi = Vec<(String, Vec<(String, Type)>)>;
+
 pub struct TypeLoader<'a> {
     pub global_type_registry: Rc<RefCell<TypeRegister>>,
     pub compiler_config: &'a CompilerConfiguration,
     pub builtin_library: Option<&'a VirtualDirectory<'a>>,
+    /// The directory of the style, when the style is not one of the builtin styles
+    pub style_directory: Option<PathBuf>,
+    /// The API of the builtin widgets, which a style loaded from a directory must implement
+    style_api_reference: once_cell::unsync::OnceCell<StyleApi>,
     all_documents: LoadedDocuments,
 }
 
@@ -101,11 +111,37 @@ pub fn new(
 
         let builtin_library =
             crate::library::widget_library().iter().find(|x| x.0 == style).map(|x| x.1);
+        let style_directory = if builtin_library.is_none() {
+            let path = Path::new(style.as_ref());
+            if path.is_dir() {
+                Some(dunce::canonicalize(path).unwrap_or_else(|_| path.to_owned()))
+            } else {
+                // Not an error: the files which don't import the widgets can still be compiled
+                diag.push_diagnostic_with_span(
+                    format!(
+                        "Style {} is neither a builtin style ({}) nor a directory, the widgets are not available",
+                        style,
+                        crate::library::widget_library()
+                            .iter()
+                            .map(|x| x.0)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    Default::default(),
+                    DiagnosticLevel::Warning,
+                );
+                None
+            }
+        } else {
+            None
+        };
 
         Self {
             global_type_registry,
             compiler_config,
             builtin_library,
+            style_directory,
+            style_api_reference: Default::default(),
             all_documents: Default::default(),
         }
     }
@@ -119,13 +155,21 @@ pub async fn load_dependencies_recursively(
     ) -> Vec<ImportedTypes> {
         let dependencies = self.collect_dependencies(doc, diagnostics).await;
         let mut foreign_imports = vec![];
+        let mut dependency_paths = HashSet::new();
         for mut import in dependencies.into_iter() {
             if import.file.ends_with(".60") {
                 if let Some(imported_types) =
                     ImportedName::extract_imported_names(&import.imported_types)
                 {
-                    self.load_dependency(import, imported_types, registry_to_populate, diagnostics)
-                        .await;
+                    dependency_paths.extend(
+                        self.load_dependency(
+                            import,
+                            imported_types,
+                            registry_to_populate,
+                            diagnostics,
+                        )
+                        .await,
+                    );
                 } else {
                     diagnostics.push_error(
                     "Import names are missing. Please specify which types you would like to import"
@@ -142,6 +186,9 @@ pub async fn load_dependencies_recursively(
                 foreign_imports.push(import);
             }
         }
+        let path = doc.source_file.path();
+        let path_canon = dunce::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+        self.all_documents.dependencies.insert(path_canon, dependency_paths);
         foreign_imports
     }
 
@@ -177,7 +224,10 @@ pub fn resolve_import_path(
         let referencing_file_or_url =
             import_token.and_then(|tok| tok.source_file().map(|s| s.path()));
 
-        self.find_file_in_include_path(referencing_file_or_url, maybe_relative_path_or_url)
+        self.find_file_in_library_path(maybe_relative_path_or_url)
+            .or_else(|| {
+                self.find_file_in_include_path(referencing_file_or_url, maybe_relative_path_or_url)
+            })
             .map(|path| (path, None))
             .or_else(|| {
                 self.builtin_library.and_then(|library| {
@@ -192,6 +242,10 @@ pub fn resolve_import_path(
                         })
                 })
             })
+            .or_else(|| {
+                let candidate = self.style_directory.as_ref()?.join(maybe_relative_path_or_url);
+                candidate.exists().then(|| (candidate, None))
+            })
             .unwrap_or_else(|| {
                 (
                     referencing_file_or_url
@@ -235,8 +289,11 @@ async fn ensure_document_loaded<'b>(
         let is_builtin = is_builtin.map(|s| s.to_owned());
 
         if !self.all_documents.currently_loading.insert(path_canon.clone()) {
-            diagnostics
-                .push_error(format!("Recursive import of {}", path.display()), &import_token);
+            diagnostics.push_error_with_code(
+                DiagnosticCode::RecursiveImport,
+                format!("Recursive import of {}", path.display()),
+                &import_token,
+            );
             return None;
         }
 
@@ -252,13 +309,23 @@ async fn ensure_document_loaded<'b>(
         let source_code = match source_code_result {
             Ok(source) => source,
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
-                diagnostics.push_error(
-                    format!(
-                        "Cannot find requested import {} in the include search path",
-                        file_to_import
+                let (code, message) = match library_name(file_to_import) {
+                    Some(library) if !self.compiler_config.library_paths.contains_key(library) => (
+                        DiagnosticCode::UnknownLibrary,
+                        format!(
+                            "Cannot find requested import {}: there is no library named {}",
+                            file_to_import, library
+                        ),
                     ),
-                    &import_token,
-                );
+                    _ => (
+                        DiagnosticCode::ImportNotFound,
+                        format!(
+                            "Cannot find requested import {} in the include search path",
+                            file_to_import
+                        ),
+                    ),
+                };
+                diagnostics.push_error_with_code(code, message, &import_token);
                 return None;
             }
             Err(err) => {
@@ -273,9 +340,106 @@ async fn ensure_document_loaded<'b>(
         self.load_file(&path_canon, &path, source_code, diagnostics).await;
         let _ok = self.all_documents.currently_loading.remove(path_canon.as_path());
         assert!(_ok);
+        if self.style_directory.as_ref().map_or(false, |dir| {
+            path_canon.parent() == Some(dir) && path_canon.ends_with("sixtyfps_widgets.60")
+        }) {
+            self.check_style_api(&path_canon, &import_token, diagnostics);
+        }
         Some(path_canon)
     }
 
+    /// Check that the widgets of a style loaded from a directory have the same API as the
+    /// builtin widgets: every widget and global is exported, with all their properties and
+    /// callbacks of the same type, including the ones they inherit from their base component.
+    fn check_style_api(
+        &self,
+        path: &Path,
+        import_token: &Option<NodeOrToken>,
+        diagnostics: &mut BuildDiagnostics,
+    ) {
+        let style = match self.all_documents.docs.get(path) {
+            Some(style) => style,
+            None => return,
+        };
+        let reference = self.style_api_reference.get_or_init(|| self.load_style_api_reference());
+
+        for (name, reference_properties) in reference {
+            let style_type = match style.exports().iter().find(|(n, _)| n == name) {
+                Some((_, ty)) => ty,
+                None => {
+                    diagnostics.push_error_with_code(
+                        DiagnosticCode::InvalidStyle,
+                        format!("The style {} does not export {}", path.display(), name),
+                        import_token,
+                    );
+                    continue;
+                }
+            };
+            for (property, reference_ty) in reference_properties {
+                let ty = style_type.lookup_property(property).property_type;
+                if ty != *reference_ty {
+                    diagnostics.push_error_with_code(
+                        DiagnosticCode::InvalidStyle,
+                        format!(
+                            "{} from the style {} does not have a {} {} of type {}",
+                            name,
+                            path.display(),
+                            if matches!(reference_ty, Type::Callback { .. }) {
+                                "callback"
+                            } else {
+                                "property"
+                            },
+                            property,
+                            reference_ty
+                        ),
+                        import_token,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Parse the widgets of the ugly style and return, for each of its exports, the declared
+    /// properties and callbacks of the component and of its base components.
+    fn load_style_api_reference(&self) -> StyleApi {
+        let reference_contents = crate::library::widget_library()
+            .iter()
+            .find(|x| x.0 == "ugly")
+            .and_then(|x| x.1.iter().find(|file| file.path == "sixtyfps_widgets.60"))
+            .expect("the ugly style must exist")
+            .contents;
+        let mut reference_diag = BuildDiagnostics::default();
+        let reference_node: syntax_nodes::Document = crate::parser::parse(
+            reference_contents.into(),
+            Some(Path::new("builtin:/ugly/sixtyfps_widgets.60")),
+            &mut reference_diag,
+        )
+        .into();
+        let reference_registry =
+            Rc::new(RefCell::new(TypeRegister::new(&self.global_type_registry)));
+        let reference =
+            Document::from_node(reference_node, vec![], &mut reference_diag, &reference_registry);
+
+        reference
+            .exports()
+            .iter()
+            .map(|(name, ty)| {
+                let mut properties: Vec<(String, Type)> = vec![];
+                let mut ty = ty.clone();
+                while let Type::Component(c) = ty {
+                    let root = c.root_element.borrow();
+                    for (property, declaration) in &root.property_declarations {
+                        if !properties.iter().any(|(p, _)| p == property) {
+                            properties.push((property.clone(), declaration.property_type.clone()));
+                        }
+                    }
+                    ty = root.base_type.clone();
+                }
+                (name.clone(), properties)
+            })
+            .collect()
+    }
+
     /// Load a file, and its dependency not run the passes.
     ///
     /// the path must be the canonical path
@@ -324,25 +488,106 @@ pub async fn load_file(
         self.all_documents.docs.insert(path.to_owned(), doc);
     }
 
+    /// Load again a file whose content changed, as well as all the loaded documents that
+    /// depend on it, directly or indirectly. The other documents are not reloaded.
+    ///
+    /// The dependents are reloaded from the source they were loaded from, so that modifications
+    /// that were not saved to disk are kept. Returns the canonical paths of the reloaded dependents.
+    ///
+    /// the path must be the canonical path
+    pub async fn reload_file(
+        &mut self,
+        path: &Path,
+        source_path: &Path,
+        source_code: String,
+        diagnostics: &mut BuildDiagnostics,
+    ) -> Vec<PathBuf> {
+        let dependents = self.dependents(path);
+        let sources = dependents
+            .iter()
+            .filter_map(|dependent| {
+                let node = self.all_documents.docs.remove(dependent)?.node?;
+                Some((
+                    dependent.clone(),
+                    node.source_file.path().to_owned(),
+                    node.text().to_string(),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        self.load_file(path, source_path, source_code, diagnostics).await;
+        for (dependent, source_path, source_code) in sources {
+            if self.all_documents.docs.contains_key(&dependent) {
+                // Was already loaded again as a dependency of another dependent
+                continue;
+            }
+            // Errors in the changed file must not prevent the analysis of its dependents
+            let mut dependent_diagnostics = BuildDiagnostics::default();
+            self.load_file(&dependent, &source_path, source_code, &mut dependent_diagnostics).await;
+            diagnostics.all_loaded_files.append(&mut dependent_diagnostics.all_loaded_files);
+            for d in dependent_diagnostics {
+                diagnostics.push_compiler_error(d);
+            }
+        }
+        dependents
+    }
+
+    /// Return the canonical paths of all the loaded documents that import the given file,
+    /// directly or indirectly. The dependencies of a document come before that document.
+    pub fn dependents(&self, path: &Path) -> Vec<PathBuf> {
+        let dependencies = &self.all_documents.dependencies;
+        let mut dependents = HashSet::new();
+        let mut to_visit = vec![path.to_owned()];
+        while let Some(current) = to_visit.pop() {
+            for (doc, imports) in dependencies {
+                if imports.contains(&current) && dependents.insert(doc.clone()) {
+                    to_visit.push(doc.clone());
+                }
+            }
+        }
+        dependents.remove(path);
+
+        fn visit(
+            doc: &Path,
+            dependencies: &HashMap<PathBuf, HashSet<PathBuf>>,
+            dependents: &HashSet<PathBuf>,
+            visited: &mut HashSet<PathBuf>,
+            result: &mut Vec<PathBuf>,
+        ) {
+            if !dependents.contains(doc) || !visited.insert(doc.to_owned()) {
+                return;
+            }
+            for import in dependencies.get(doc).into_iter().flatten() {
+                visit(import, dependencies, dependents, visited, result);
+            }
+            result.push(doc.to_owned());
+        }
+        let mut visited = HashSet::new();
+        let mut result = vec![];
+        let mut sorted_dependents = dependents.iter().collect::<Vec<_>>();
+        // for a deterministic order
+        sorted_dependents.sort();
+        for doc in sorted_dependents {
+            visit(doc, dependencies, &dependents, &mut visited, &mut result);
+        }
+        result
+    }
+
     fn load_dependency<'b>(
         &'b mut self,
         import: ImportedTypes,
         imported_types: impl Iterator<Item = ImportedName> + 'b,
         registry_to_populate: &'b Rc<RefCell<TypeRegister>>,
         build_diagnostics: &'b mut BuildDiagnostics,
-    ) -> core::pin::Pin<Box<dyn std::future::Future<Output = ()> + 'b>> {
+    ) -> core::pin::Pin<Box<dyn std::future::Future<Output = Option<PathBuf>> + 'b>> {
         Box::pin(async move {
-            let doc_path = match self
+            let doc_path = self
                 .ensure_document_loaded(
                     &import.file,
                     Some(import.import_token.clone().into()),
                     build_diagnostics,
                 )
-                .await
-            {
-                Some(path) => path,
-                None => return,
-            };
+                .await?;
 
             let doc = self.all_documents.docs.get(&doc_path).unwrap();
             let exports = doc.exports();
@@ -359,7 +604,8 @@ fn load_dependency<'b>(
                 let imported_type = match imported_type {
                     Some(ty) => ty,
                     None => {
-                        build_diagnostics.push_error(
+                        build_diagnostics.push_error_with_code(
+                            DiagnosticCode::NoSuchExport,
                             format!(
                                 "No exported type called {} found in {}",
                                 import_name.external_name, import.file
@@ -374,6 +620,7 @@ fn load_dependency<'b>(
                     .borrow_mut()
                     .insert_type_with_name(imported_type, import_name.internal_name);
             }
+            Some(doc_path)
         })
     }
 
@@ -405,6 +652,13 @@ pub(crate) fn find_file_in_include_path(
             })
     }
 
+    /// Lookup a filename starting with `@library-name` in the library paths
+    fn find_file_in_library_path(&self, file_to_import: &str) -> Option<PathBuf> {
+        let library = library_name(file_to_import)?;
+        let library_path = self.compiler_config.library_paths.get(library)?;
+        Some(library_path.join(file_to_import[library.len() + 1..].trim_start_matches('/')))
+    }
+
     async fn collect_dependencies(
         &mut self,
         doc: &syntax_nodes::Document,
@@ -457,6 +711,12 @@ pub fn all_documents(&self) -> impl Iterator<Item = &object_tree::Document> + '_
     }
 }
 
+/// Return the name of the library for import paths starting with `@library-name`
+fn library_name(file_to_import: &str) -> Option<&str> {
+    let library = file_to_import.strip_prefix('@')?.split('/').next()?;
+    (!library.is_empty()).then(|| library)
+}
+
 #[test]
 fn test_dependency_loading() {
     let test_source_path: std::path::PathBuf =
@@ -496,6 +756,126 @@ fn test_dependency_loading() {
     assert!(!build_diagnostics.has_error());
 }
 
+#[test]
+fn test_library_import() {
+    let test_source_path: std::path::PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "typeloader"].iter().collect();
+
+    let mut compiler_config =
+        CompilerConfiguration::new(crate::generator::OutputFormat::Interpreter);
+    compiler_config.library_paths = [
+        ("library".to_owned(), test_source_path.join("incpath")),
+        ("helper".to_owned(), test_source_path.join("incpath")),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+    compiler_config.style = Some("ugly".into());
+
+    let mut test_diags = crate::diagnostics::BuildDiagnostics::default();
+    let doc_node =
+        crate::parser::parse_file(test_source_path.join("library_import.60"), &mut test_diags)
+            .unwrap();
+    let doc_node: syntax_nodes::Document = doc_node.into();
+
+    let global_registry = TypeRegister::builtin();
+    let registry = Rc::new(RefCell::new(TypeRegister::new(&global_registry)));
+    let mut build_diagnostics = BuildDiagnostics::default();
+    let mut loader = TypeLoader::new(global_registry, &compiler_config, &mut build_diagnostics);
+    spin_on::spin_on(loader.load_dependencies_recursively(
+        &doc_node,
+        &mut build_diagnostics,
+        &registry,
+    ));
+    assert!(!test_diags.has_error());
+    assert!(!build_diagnostics.has_error(), "{:?}", build_diagnostics.to_string_vec());
+    assert!(registry.borrow().lookup("AnotherType") != crate::langtype::Type::Invalid);
+    assert!(registry.borrow().lookup("SomeRect") != crate::langtype::Type::Invalid);
+
+    // An unknown library is reported
+    compiler_config.library_paths.remove("helper");
+    let global_registry = TypeRegister::builtin();
+    let registry = Rc::new(RefCell::new(TypeRegister::new(&global_registry)));
+    let mut build_diagnostics = BuildDiagnostics::default();
+    let mut loader = TypeLoader::new(global_registry, &compiler_config, &mut build_diagnostics);
+    spin_on::spin_on(loader.load_dependencies_recursively(
+        &doc_node,
+        &mut build_diagnostics,
+        &registry,
+    ));
+    let diags = build_diagnostics.to_string_vec();
+    assert_eq!(diags.len(), 1, "{:?}", diags);
+    assert!(diags[0].contains("there is no library named helper"), "{:?}", diags);
+}
+
+#[test]
+fn test_style_directory() {
+    let test_source_path: std::path::PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "typeloader"].iter().collect();
+
+    let load_with_style = |style: PathBuf| {
+        let mut compiler_config =
+            CompilerConfiguration::new(crate::generator::OutputFormat::Interpreter);
+        compiler_config.style = Some(style.to_string_lossy().into());
+        let mut test_diags = crate::diagnostics::BuildDiagnostics::default();
+        let doc_node =
+            crate::parser::parse_file(test_source_path.join("style_import.60"), &mut test_diags)
+                .unwrap();
+        let doc_node: syntax_nodes::Document = doc_node.into();
+        let global_registry = TypeRegister::builtin();
+        let registry = Rc::new(RefCell::new(TypeRegister::new(&global_registry)));
+        let mut build_diagnostics = BuildDiagnostics::default();
+        let mut loader = TypeLoader::new(global_registry, &compiler_config, &mut build_diagnostics);
+        spin_on::spin_on(loader.load_dependencies_recursively(
+            &doc_node,
+            &mut build_diagnostics,
+            &registry,
+        ));
+        build_diagnostics.to_string_vec()
+    };
+
+    // A directory with the same widgets as a builtin style is a valid style
+    let diags = load_with_style([env!("CARGO_MANIFEST_DIR"), "widgets", "ugly"].iter().collect());
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = load_with_style(test_source_path.join("incomplete_style"));
+    assert!(diags.iter().any(|d| d.contains("does not export CheckBox")), "{:?}", diags);
+    assert!(
+        diags.iter().any(|d| d.contains("Button from the style")
+            && !d.contains("StandardButton")
+            && d.contains("does not have a property text of type string")),
+        "{:?}",
+        diags
+    );
+    assert!(!diags.iter().any(|d| d.contains("clicked") && !d.contains("StandardButton")));
+    // The properties and callbacks inherited from Button are also checked
+    assert!(
+        diags.iter().any(|d| d.contains("StandardButton from the style")
+            && d.contains("does not have a property text of type string")),
+        "{:?}",
+        diags
+    );
+    assert!(
+        diags.iter().any(|d| d.contains("StandardButton from the style")
+            && d.contains("does not have a callback clicked")),
+        "{:?}",
+        diags
+    );
+    assert!(!diags.iter().any(|d| d.contains("kind")), "{:?}", diags);
+
+    let diags = load_with_style(test_source_path.join("does_not_exist"));
+    assert!(diags.iter().any(|d| d.contains("is neither a builtin style")), "{:?}", diags);
+
+    // An unknown style is only a warning, files not using the widgets still compile
+    let mut compiler_config =
+        CompilerConfiguration::new(crate::generator::OutputFormat::Interpreter);
+    compiler_config.style = Some("does_not_exist".into());
+    let mut build_diagnostics = BuildDiagnostics::default();
+    TypeLoader::new(TypeRegister::builtin(), &compiler_config, &mut build_diagnostics);
+    assert!(!build_diagnostics.has_error());
+    assert_eq!(build_diagnostics.to_string_vec().len(), 1);
+}
+
 #[test]
 fn test_load_from_callback_ok() {
     let ok = Rc::new(core::cell::Cell::new(false));
@@ -559,3 +939,63 @@ fn test_manual_import() {
     assert!(!build_diagnostics.has_error());
     assert!(maybe_button_type.is_some());
 }
+
+#[test]
+fn test_reload_dependents() {
+    let test_source_path: std::path::PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "typeloader"].iter().collect();
+    let canonical = |p: &[&str]| {
+        dunce::canonicalize(p.iter().fold(test_source_path.clone(), |path, x| path.join(x)))
+            .unwrap()
+    };
+    let main_path = canonical(&["dependency_test_main.60"]);
+    let local_path = canonical(&["dependency_local.60"]);
+    let incpath_path = canonical(&["incpath", "dependency_from_incpath.60"]);
+    let helper_path = canonical(&["incpath", "local_helper_type.60"]);
+
+    let mut compiler_config =
+        CompilerConfiguration::new(crate::generator::OutputFormat::Interpreter);
+    compiler_config.include_paths = vec![test_source_path.join("incpath")];
+    compiler_config.style = Some("ugly".into());
+    let global_registry = TypeRegister::builtin();
+    let mut build_diagnostics = BuildDiagnostics::default();
+    let mut loader = TypeLoader::new(global_registry, &compiler_config, &mut build_diagnostics);
+
+    let source = std::fs::read_to_string(&main_path).unwrap();
+    spin_on::spin_on(loader.load_file(&main_path, &main_path, source, &mut build_diagnostics));
+    assert!(!build_diagnostics.has_error());
+
+    assert_eq!(
+        loader.dependents(&helper_path),
+        vec![incpath_path.clone(), local_path.clone(), main_path.clone()]
+    );
+    assert_eq!(loader.dependents(&local_path), vec![main_path.clone()]);
+    assert!(loader.dependents(&main_path).is_empty());
+
+    // Removing the exported type from the helper is an error in the documents using it
+    let reloaded = spin_on::spin_on(loader.reload_file(
+        &helper_path,
+        &helper_path,
+        "export OtherRect := Rectangle {}".into(),
+        &mut build_diagnostics,
+    ));
+    assert_eq!(reloaded, vec![incpath_path.clone(), local_path, main_path.clone()]);
+    assert!(build_diagnostics.has_error());
+    assert!(loader.get_document(&main_path).is_some());
+
+    // Fixing the helper fixes the dependents
+    let mut build_diagnostics = BuildDiagnostics::default();
+    spin_on::spin_on(loader.reload_file(
+        &helper_path,
+        &helper_path,
+        "export SomeRect := Rectangle {}".into(),
+        &mut build_diagnostics,
+    ));
+    assert!(!build_diagnostics.has_error());
+    assert!(loader
+        .get_document(&incpath_path)
+        .unwrap()
+        .exports()
+        .iter()
+        .any(|(n, _)| n == "AnotherType"));
+}
@@ -56,6 +56,19 @@
     ("drop_shadow_color", Type::Color),
 ];
 
+/// The values of the `StandardButtonKind` enum, matching `sixtyfps_corelib::items::StandardButtonKind`
+const STANDARD_BUTTON_KIND_VALUES: &[&str] = &["ok", "cancel", "apply", "yes", "no"];
+
+/// The builtin `StandardButtonKind` enum, used as argument of the `StandardButtonRank` function
+pub(crate) fn standard_button_kind_type() -> Type {
+    Type::Enumeration(Rc::new(Enumeration {
+        name: "StandardButtonKind".into(),
+        values: STANDARD_BUTTON_KIND_VALUES.iter().cloned().map(String::from).collect(),
+        default_value: 0,
+        node: None,
+    }))
+}
+
 /// list of reserved property injected in every item
 pub fn reserved_properties() -> impl Iterator<Item = (&'static str, Type)> {
     RESERVED_GEOMETRY_PROPERTIES
@@ -155,6 +168,7 @@ pub fn builtin() -> Rc<RefCell<Self>> {
                     name: name.to_owned(),
                     values: values.iter().cloned().map(String::from).collect(),
                     default_value: 0,
+                    node: None,
                 })),
                 name.to_owned(),
             );
@@ -171,6 +185,8 @@ pub fn builtin() -> Rc<RefCell<Self>> {
         declare_enum("ImageFit", &["fill", "contain", "cover"]);
         declare_enum("EventResult", &["reject", "accept"]);
         declare_enum("FillRule", &["nonzero", "evenodd"]);
+        declare_enum("StandardButtonKind", STANDARD_BUTTON_KIND_VALUES);
+        declare_enum("DialogPresentation", &["window", "overlay"]);
 
         register.supported_property_animation_types.insert(Type::Float32.to_string());
         register.supported_property_animation_types.insert(Type::Int32.to_string());
@@ -188,16 +204,36 @@ pub fn builtin() -> Rc<RefCell<Self>> {
             .for_each(|ty| ty.collect_contextual_types(&mut context_restricted_types));
         register.context_restricted_types = context_restricted_types;
 
-        match &mut register.types.get_mut("PopupWindow").unwrap() {
+        // The ContextMenu is lowered to a PopupWindow by the lower_menus pass
+        for popup in ["PopupWindow", "ContextMenu"] {
+            match &mut register.types.get_mut(popup).unwrap() {
+                Type::Builtin(ref mut b) => {
+                    Rc::get_mut(b).unwrap().properties.insert(
+                        "show".into(),
+                        BuiltinPropertyInfo::new(BuiltinFunction::ShowPopupWindow.ty()),
+                    );
+                    Rc::get_mut(b).unwrap().member_functions.insert(
+                        "show".into(),
+                        Expression::BuiltinFunctionReference(
+                            BuiltinFunction::ShowPopupWindow,
+                            None,
+                        ),
+                    );
+                }
+                _ => unreachable!(),
+            };
+        }
+
+        match &mut register.types.get_mut("Dialog").unwrap() {
             Type::Builtin(ref mut b) => {
-                Rc::get_mut(b).unwrap().properties.insert(
-                    "show".into(),
-                    BuiltinPropertyInfo::new(BuiltinFunction::ShowPopupWindow.ty()),
-                );
-                Rc::get_mut(b).unwrap().member_functions.insert(
-                    "show".into(),
-                    Expression::BuiltinFunctionReference(BuiltinFunction::ShowPopupWindow, None),
-                );
+                let b = Rc::get_mut(b).unwrap();
+                for (name, function) in
+                    [("show", BuiltinFunction::ShowDialog), ("close", BuiltinFunction::CloseDialog)]
+                {
+                    b.properties.insert(name.into(), BuiltinPropertyInfo::new(function.ty()));
+                    b.member_functions
+                        .insert(name.into(), Expression::BuiltinFunctionReference(function, None));
+                }
             }
             _ => unreachable!(),
         };
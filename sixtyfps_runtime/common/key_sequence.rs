@@ -0,0 +1,132 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+/*!
+Parsing of the `sequence` property of the `Shortcut` element.
+
+The compiler uses it to check the constant sequences, and the runtime to match the key events.
+*/
+
+/// The key of a key sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// The left cursor key
+    Left,
+    /// The right cursor key
+    Right,
+    /// The home key
+    Home,
+    /// The end key
+    End,
+    /// The backspace key
+    Backspace,
+    /// The delete key
+    Delete,
+    /// The return key
+    Return,
+    /// The escape key
+    Escape,
+    /// One of the function keys F1 to F12
+    Function(u8),
+    /// The key typing this character, lower case for letters
+    Character(char),
+}
+
+/// A key sequence as written in the .60 file: `Ctrl` is the control key and `Meta` the meta
+/// key, it is up to the runtime to swap them on macOS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeySequence {
+    /// The control modifier
+    pub control: bool,
+    /// The meta modifier
+    pub meta: bool,
+    /// The alt modifier
+    pub alt: bool,
+    /// The shift modifier
+    pub shift: bool,
+    /// The key pressed with the modifiers
+    pub key: Key,
+}
+
+impl KeySequence {
+    /// Parses a key sequence such as `"Ctrl+S"`, returns None if it is not valid.
+    ///
+    /// The sequence is made of modifiers and a key separated by `+`. The names are case
+    /// insensitive and the spaces around them are ignored.
+    pub fn parse(sequence: &str) -> Option<Self> {
+        let sequence = sequence.trim();
+        let (modifier_names, key) = if sequence == "+" {
+            ("", "+")
+        } else if let Some(modifier_names) = sequence.strip_suffix("++") {
+            (modifier_names, "+")
+        } else {
+            sequence.rsplit_once('+').unwrap_or(("", sequence))
+        };
+
+        let mut result = KeySequence {
+            control: false,
+            meta: false,
+            alt: false,
+            shift: false,
+            key: Key::Character(' '),
+        };
+        for name in modifier_names.split('+').filter(|_| !modifier_names.is_empty()) {
+            let modifier = match name.trim().to_lowercase().as_str() {
+                "ctrl" | "control" => &mut result.control,
+                "meta" => &mut result.meta,
+                "alt" => &mut result.alt,
+                "shift" => &mut result.shift,
+                _ => return None,
+            };
+            *modifier = true;
+        }
+
+        result.key = match key.trim().to_lowercase().as_str() {
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "backspace" => Key::Backspace,
+            "delete" | "del" => Key::Delete,
+            "return" | "enter" => Key::Return,
+            "escape" | "esc" => Key::Escape,
+            "space" => Key::Character(' '),
+            name if name.starts_with('f') && name.len() > 1 => match name[1..].parse::<u8>() {
+                Ok(n) if (1..=12).contains(&n) => Key::Function(n),
+                _ => return None,
+            },
+            lower_key => {
+                let mut chars = lower_key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) if !ch.is_control() => Key::Character(ch),
+                    _ => return None,
+                }
+            }
+        };
+        Some(result)
+    }
+}
+
+#[test]
+fn test_parse() {
+    let save = KeySequence::parse("Ctrl+S").unwrap();
+    assert_eq!(save.key, Key::Character('s'));
+    assert!(save.control && !save.meta && !save.alt && !save.shift);
+    assert_eq!(Some(save), KeySequence::parse("control + s"));
+    assert_eq!(KeySequence::parse("F5"), KeySequence::parse("f05"));
+    assert_eq!(KeySequence::parse("Ctrl++").unwrap().key, Key::Character('+'));
+    assert_eq!(KeySequence::parse("+").unwrap().key, Key::Character('+'));
+    assert_eq!(KeySequence::parse("Space").unwrap().key, Key::Character(' '));
+
+    assert_eq!(KeySequence::parse(""), None);
+    assert_eq!(KeySequence::parse("Ctrl+"), None);
+    assert_eq!(KeySequence::parse("Hyper+S"), None);
+    assert_eq!(KeySequence::parse("F13"), None);
+    assert_eq!(KeySequence::parse("Ctrl+Save"), None);
+}
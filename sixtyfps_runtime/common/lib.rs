@@ -0,0 +1,17 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+/*!
+    This crate contains the code shared by the sixtyfps compiler and the corelib crate,
+    so that both interpret the .60 files in the same way
+*/
+
+#![warn(missing_docs)]
+
+pub mod key_sequence;
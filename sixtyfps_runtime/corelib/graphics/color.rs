@@ -137,6 +137,29 @@ pub fn from_rgb_f32(red: f32, green: f32, blue: f32) -> Self {
         Self::from_argb_f32(1.0, red, green, blue)
     }
 
+    /// Construct a color from the hue (in degrees), saturation, value (brightness), and alpha,
+    /// in the HSV color space. The saturation, value, and alpha are in the range 0..1.
+    pub fn from_hsva(hue: f32, saturation: f32, value: f32, alpha: f32) -> Self {
+        let hsva = HsvaColor {
+            h: hue.rem_euclid(360.),
+            s: saturation.clamp(0., 1.),
+            v: value.clamp(0., 1.),
+            alpha: alpha.clamp(0., 1.),
+        };
+        RgbaColor::<f32>::from(hsva).into()
+    }
+
+    /// Construct a color from the hue (in degrees), saturation, lightness, and alpha,
+    /// in the HSL color space. The saturation, lightness, and alpha are in the range 0..1.
+    pub fn from_hsla(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Self {
+        let saturation = saturation.clamp(0., 1.);
+        let lightness = lightness.clamp(0., 1.);
+        // HSL to HSV conversion from https://en.wikipedia.org/wiki/HSL_and_HSV#HSL_to_HSV
+        let value = lightness + saturation * lightness.min(1. - lightness);
+        let saturation = if value == 0. { 0. } else { 2. * (1. - lightness / value) };
+        Self::from_hsva(hue, saturation, value, alpha)
+    }
+
     /// Converts this color to an RgbaColor struct for easy destructuring.
     pub fn to_argb_u8(&self) -> RgbaColor<u8> {
         RgbaColor::from(*self)
@@ -193,6 +216,41 @@ pub fn darker(&self, factor: f32) -> Self {
         let rgba: RgbaColor<f32> = hsva.into();
         rgba.into()
     }
+
+    /// Returns a new version of this color with the alpha channel set to `alpha`,
+    /// in the range 0..1.
+    pub fn with_alpha(&self, alpha: f32) -> Self {
+        Self { alpha: (alpha.clamp(0., 1.) * 255.) as u8, ..*self }
+    }
+
+    /// Returns a new version of this color that is more transparent: the alpha channel
+    /// is multiplied by (1 - amount). So for example `transparentize(0.5)` halves the opacity.
+    pub fn transparentize(&self, amount: f32) -> Self {
+        let factor = (1. - amount).clamp(0., 1.);
+        Self { alpha: (self.alpha as f32 * factor) as u8, ..*self }
+    }
+
+    /// Returns the mix of this color with `other`, by interpolating each channel.
+    /// `factor` is the proportion of `other` in the range 0..1, so `mix(other, 0.)` returns
+    /// this color and `mix(other, 1.)` returns `other`.
+    pub fn mix(&self, other: &Color, factor: f32) -> Self {
+        self.interpolate(other, factor.clamp(0., 1.))
+    }
+
+    /// Returns the relative luminance of this color, as defined by WCAG 2.0, in the range 0..1.
+    /// The alpha channel is ignored. A luminance above 0.179 means that black text is more
+    /// readable than white text on a background of this color.
+    pub fn luminance(&self) -> f32 {
+        let linear = |c: u8| {
+            let c = c as f32 / 255.;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.red) + 0.7152 * linear(self.green) + 0.0722 * linear(self.blue)
+    }
 }
 
 impl InterpolatedPropertyValue for Color {
@@ -304,6 +362,32 @@ fn test_brighter_darker() {
     assert_eq!(blue.darker(0.5), Color::from_rgb_u8(0, 0, 85));
 }
 
+#[test]
+fn test_hsv_hsl() {
+    assert_eq!(Color::from_hsva(0., 1., 1., 1.), Color::from_rgb_u8(255, 0, 0));
+    assert_eq!(Color::from_hsva(120., 1., 0.5, 0.5), Color::from_argb_u8(127, 0, 127, 0));
+    assert_eq!(Color::from_hsva(-120., 1., 1., 1.), Color::from_rgb_u8(0, 0, 255));
+    assert_eq!(Color::from_hsla(0., 1., 0.5, 1.), Color::from_rgb_u8(255, 0, 0));
+    assert_eq!(Color::from_hsla(240., 1., 0.25, 1.), Color::from_rgb_u8(0, 0, 127));
+    assert_eq!(Color::from_hsla(0., 0., 1., 1.), Color::from_rgb_u8(255, 255, 255));
+    assert_eq!(Color::from_hsla(0., 0., 0., 1.), Color::from_rgb_u8(0, 0, 0));
+}
+
+#[test]
+fn test_alpha_mix_luminance() {
+    let red = Color::from_rgb_u8(255, 0, 0);
+    assert_eq!(red.with_alpha(0.5), Color::from_argb_u8(127, 255, 0, 0));
+    assert_eq!(red.transparentize(0.5), Color::from_argb_u8(127, 255, 0, 0));
+    assert_eq!(red.transparentize(2.), Color::from_argb_u8(0, 255, 0, 0));
+    let blue = Color::from_rgb_u8(0, 0, 255);
+    assert_eq!(red.mix(&blue, 0.), red);
+    assert_eq!(red.mix(&blue, 1.), blue);
+    assert_eq!(red.mix(&blue, 0.5), Color::from_rgb_u8(127, 0, 127));
+    assert_eq!(Color::from_rgb_u8(255, 255, 255).luminance(), 1.);
+    assert_eq!(Color::from_rgb_u8(0, 0, 0).luminance(), 0.);
+    assert!((red.luminance() - 0.2126).abs() < 0.0001);
+}
+
 #[cfg(feature = "ffi")]
 pub(crate) mod ffi {
     #![allow(unsafe_code)]
@@ -318,4 +402,55 @@ pub(crate) mod ffi {
     pub unsafe extern "C" fn sixtyfps_color_darker(col: &Color, factor: f32, out: *mut Color) {
         core::ptr::write(out, col.darker(factor))
     }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_color_from_hsva(
+        hue: f32,
+        saturation: f32,
+        value: f32,
+        alpha: f32,
+        out: *mut Color,
+    ) {
+        core::ptr::write(out, Color::from_hsva(hue, saturation, value, alpha))
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_color_from_hsla(
+        hue: f32,
+        saturation: f32,
+        lightness: f32,
+        alpha: f32,
+        out: *mut Color,
+    ) {
+        core::ptr::write(out, Color::from_hsla(hue, saturation, lightness, alpha))
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_color_with_alpha(col: &Color, alpha: f32, out: *mut Color) {
+        core::ptr::write(out, col.with_alpha(alpha))
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_color_transparentize(
+        col: &Color,
+        amount: f32,
+        out: *mut Color,
+    ) {
+        core::ptr::write(out, col.transparentize(amount))
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_color_mix(
+        col: &Color,
+        other: &Color,
+        factor: f32,
+        out: *mut Color,
+    ) {
+        core::ptr::write(out, col.mix(other, factor))
+    }
+
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_color_luminance(col: &Color) -> f32 {
+        col.luminance()
+    }
 }
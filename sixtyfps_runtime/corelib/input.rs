@@ -135,6 +135,9 @@ pub enum InternalKeyCode {
     Return,
     /// Code corresponding to the return key -- encoded as 0x1b ASCII (escape)
     Escape,
+    /// Code corresponding to the function keys F1 to F12 -- encoded in the private use area,
+    /// starting at U+F704 for F1 (like on macOS)
+    Function(u8),
 }
 
 const LEFT_CODE: char = '\u{000E}'; // shift out
@@ -145,6 +148,7 @@ pub enum InternalKeyCode {
 const DELETE_CODE: char = '\u{007F}'; // cancel
 const RETURN_CODE: char = '\u{000A}'; // \n
 const ESCAPE_CODE: char = '\u{001B}'; // esc
+const F1_CODE: u32 = 0xF704; // private use area
 
 impl InternalKeyCode {
     /// Encodes the internal key code as string
@@ -158,6 +162,9 @@ pub fn encode_to_string(&self) -> SharedString {
             InternalKeyCode::Delete => DELETE_CODE,
             InternalKeyCode::Return => RETURN_CODE,
             InternalKeyCode::Escape => ESCAPE_CODE,
+            InternalKeyCode::Function(n) => {
+                core::char::from_u32(F1_CODE + (*n as u32).saturating_sub(1)).unwrap()
+            }
         }
         .to_string()
         .into()
@@ -177,6 +184,9 @@ pub fn try_decode_from_string(str: &SharedString) -> Option<Self> {
                 DELETE_CODE => Self::Delete,
                 RETURN_CODE => Self::Return,
                 ESCAPE_CODE => Self::Escape,
+                ch if (F1_CODE..F1_CODE + 12).contains(&(ch as u32)) => {
+                    Self::Function((ch as u32 - F1_CODE + 1) as u8)
+                }
                 _ => return None,
             })
         } else {
@@ -232,6 +242,95 @@ pub struct KeyEvent {
     pub event_type: KeyEventType,
 }
 
+/// A key combination, as set in the `sequence` property of a `Shortcut` element.
+///
+/// The sequence is made of modifiers and a key separated by `+`, for example `"Ctrl+Shift+S"`
+/// or `"F5"`. Like in Qt, `Ctrl` stands for the command key on macOS, and `Meta` for the
+/// control key there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySequence {
+    /// The modifiers that need to be pressed together with the key.
+    pub modifiers: KeyboardModifiers,
+    /// The text of the key, lower case for letters, or the encoding of an [`InternalKeyCode`].
+    pub text: SharedString,
+}
+
+impl KeySequence {
+    /// Parses a key sequence such as `"Ctrl+S"`, returns None if it is not valid.
+    pub fn parse(sequence: &str) -> Option<Self> {
+        use sixtyfps_common::key_sequence::{Key, KeySequence as ParsedKeySequence};
+        let parsed = ParsedKeySequence::parse(sequence)?;
+        let (control, meta) = if cfg!(target_os = "macos") {
+            (parsed.meta, parsed.control)
+        } else {
+            (parsed.control, parsed.meta)
+        };
+        let modifiers = KeyboardModifiers { alt: parsed.alt, control, shift: parsed.shift, meta };
+        let text = match parsed.key {
+            Key::Left => InternalKeyCode::Left.encode_to_string(),
+            Key::Right => InternalKeyCode::Right.encode_to_string(),
+            Key::Home => InternalKeyCode::Home.encode_to_string(),
+            Key::End => InternalKeyCode::End.encode_to_string(),
+            Key::Backspace => InternalKeyCode::Back.encode_to_string(),
+            Key::Delete => InternalKeyCode::Delete.encode_to_string(),
+            Key::Return => InternalKeyCode::Return.encode_to_string(),
+            Key::Escape => InternalKeyCode::Escape.encode_to_string(),
+            Key::Function(n) => InternalKeyCode::Function(n).encode_to_string(),
+            Key::Character(ch) => ch.to_string().into(),
+        };
+        Some(Self { modifiers, text })
+    }
+
+    /// Returns true if the key event is a press of this key sequence.
+    ///
+    /// The case of letters is ignored. For the other printable keys, the shift modifier is only
+    /// compared when the sequence contains it, as it may be needed to type the key.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        if event.event_type != KeyEventType::KeyPressed
+            || event.text.to_lowercase() != self.text.as_str()
+        {
+            return false;
+        }
+        let mut modifiers = event.modifiers;
+        let is_printable = self.text.chars().all(|ch| !ch.is_alphabetic())
+            && InternalKeyCode::try_decode_from_string(&self.text).is_none();
+        if is_printable && !self.modifiers.shift {
+            modifiers.shift = false;
+        }
+        modifiers == self.modifiers
+    }
+}
+
+/// The state of a `Shortcut` element: its key sequence, only parsed again after the
+/// `sequence` property changed.
+pub struct ShortcutData {
+    sequence: std::cell::RefCell<Option<KeySequence>>,
+    sequence_tracker: Pin<Box<crate::properties::PropertyTracker>>,
+}
+
+impl Default for ShortcutData {
+    fn default() -> Self {
+        Self {
+            sequence: Default::default(),
+            sequence_tracker: Box::pin(crate::properties::PropertyTracker::default()),
+        }
+    }
+}
+
+impl ShortcutData {
+    /// Returns true if the key event is a press of the sequence of the shortcut.
+    pub fn matches(&self, shortcut: Pin<&crate::items::Shortcut>, event: &KeyEvent) -> bool {
+        if let Some(sequence) = self
+            .sequence_tracker
+            .as_ref()
+            .evaluate_if_dirty(|| KeySequence::parse(&shortcut.sequence()))
+        {
+            *self.sequence.borrow_mut() = sequence;
+        }
+        self.sequence.borrow().as_ref().map_or(false, |sequence| sequence.matches(event))
+    }
+}
+
 /// Represents how an item's key_event handler dealt with a key event.
 /// An accepted event results in no further event propagation.
 #[repr(C)]
@@ -500,3 +599,50 @@ pub fn stop(&self) {
         self.cursor_blink_timer.stop()
     }
 }
+
+#[test]
+fn test_key_sequence() {
+    let key_event = |text: &str, control: bool, shift: bool| KeyEvent {
+        text: text.into(),
+        modifiers: KeyboardModifiers {
+            control: control && !cfg!(target_os = "macos"),
+            meta: control && cfg!(target_os = "macos"),
+            shift,
+            alt: false,
+        },
+        event_type: KeyEventType::KeyPressed,
+    };
+
+    let save = KeySequence::parse("Ctrl+S").unwrap();
+    assert_eq!(Some(save.clone()), KeySequence::parse("control + s"));
+    assert!(save.matches(&key_event("s", true, false)));
+    assert!(!save.matches(&key_event("s", false, false)));
+    assert!(!save.matches(&key_event("S", true, true)));
+    assert!(!save.matches(&KeyEvent {
+        event_type: KeyEventType::KeyReleased,
+        ..key_event("s", true, false)
+    }));
+    assert!(KeySequence::parse("Ctrl+Shift+S").unwrap().matches(&key_event("S", true, true)));
+
+    let reload = KeySequence::parse("F5").unwrap();
+    assert!(reload.matches(&key_event(
+        &InternalKeyCode::Function(5).encode_to_string(),
+        false,
+        false
+    )));
+    assert_eq!(
+        InternalKeyCode::try_decode_from_string(&reload.text),
+        Some(InternalKeyCode::Function(5))
+    );
+    assert!(KeySequence::parse("Escape").unwrap().matches(&key_event("\u{001B}", false, false)));
+
+    let zoom_in = KeySequence::parse("Ctrl++").unwrap();
+    assert!(zoom_in.matches(&key_event("+", true, true)));
+    assert!(KeySequence::parse("?").unwrap().matches(&key_event("?", false, true)));
+
+    assert_eq!(KeySequence::parse(""), None);
+    assert_eq!(KeySequence::parse("Ctrl+"), None);
+    assert_eq!(KeySequence::parse("Hyper+S"), None);
+    assert_eq!(KeySequence::parse("F13"), None);
+    assert_eq!(KeySequence::parse("Ctrl+Save"), None);
+}
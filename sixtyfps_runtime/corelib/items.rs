@@ -552,6 +552,115 @@ impl ItemConsts for FocusScope {
     fn sixtyfps_get_FocusScopeVTable() -> FocusScopeVTable for FocusScope
 }
 
+/// The implementation of the `Shortcut` element: a key sequence activated from anywhere in the
+/// window, resolved by [`crate::window::Window::process_key_input`]
+#[repr(C)]
+#[derive(FieldOffsets, Default, SixtyFPSElement)]
+#[pin]
+pub struct Shortcut {
+    pub sequence: Property<SharedString>,
+    pub enabled: Property<bool>,
+    pub activated: Callback<VoidArg>,
+    data: ShortcutDataBox,
+
+    /// FIXME: remove this
+    pub cached_rendering_data: CachedRenderingData,
+}
+
+impl Item for Shortcut {
+    fn init(self: Pin<&Self>, _window: &ComponentWindow) {}
+
+    fn geometry(self: Pin<&Self>) -> Rect {
+        Default::default()
+    }
+
+    fn layouting_info(
+        self: Pin<&Self>,
+        _orientation: Orientation,
+        _window: &ComponentWindow,
+    ) -> LayoutInfo {
+        LayoutInfo::default()
+    }
+
+    fn input_event_filter_before_children(
+        self: Pin<&Self>,
+        _: MouseEvent,
+        _window: &ComponentWindow,
+        _self_rc: &ItemRc,
+    ) -> InputEventFilterResult {
+        InputEventFilterResult::ForwardAndIgnore
+    }
+
+    fn input_event(
+        self: Pin<&Self>,
+        _: MouseEvent,
+        _window: &ComponentWindow,
+        _self_rc: &ItemRc,
+    ) -> InputEventResult {
+        InputEventResult::EventIgnored
+    }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent, _window: &ComponentWindow) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent, _window: &ComponentWindow) {}
+
+    fn render(self: Pin<&Self>, _backend: &mut ItemRendererRef) {}
+}
+
+impl ItemConsts for Shortcut {
+    const cached_rendering_data_offset: const_field_offset::FieldOffset<
+        Shortcut,
+        CachedRenderingData,
+    > = Shortcut::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
+}
+
+impl Shortcut {
+    /// Returns true if the shortcut is enabled and its sequence matches the key event
+    pub fn matches(self: Pin<&Self>, event: &KeyEvent) -> bool {
+        self.enabled() && self.data.matches(self, event)
+    }
+}
+
+declare_item_vtable! {
+    fn sixtyfps_get_ShortcutVTable() -> ShortcutVTable for Shortcut
+}
+
+#[repr(C)]
+/// Wraps the internal data structure for the Shortcut
+pub struct ShortcutDataBox(core::ptr::NonNull<crate::input::ShortcutData>);
+
+impl Default for ShortcutDataBox {
+    fn default() -> Self {
+        ShortcutDataBox(Box::leak(Box::new(crate::input::ShortcutData::default())).into())
+    }
+}
+impl Drop for ShortcutDataBox {
+    fn drop(&mut self) {
+        // Safety: the self.0 was constructed from a Box::leak in ShortcutDataBox::default
+        unsafe {
+            Box::from_raw(self.0.as_ptr());
+        }
+    }
+}
+impl core::ops::Deref for ShortcutDataBox {
+    type Target = crate::input::ShortcutData;
+    fn deref(&self) -> &Self::Target {
+        // Safety: initialized in ShortcutDataBox::default
+        unsafe { self.0.as_ref() }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sixtyfps_shortcut_data_init(data: *mut ShortcutDataBox) {
+    std::ptr::write(data, ShortcutDataBox::default());
+}
+#[no_mangle]
+pub unsafe extern "C" fn sixtyfps_shortcut_data_free(data: *mut ShortcutDataBox) {
+    std::ptr::drop_in_place(data);
+}
+
 #[repr(C)]
 #[derive(FieldOffsets, Default, SixtyFPSElement)]
 #[pin]
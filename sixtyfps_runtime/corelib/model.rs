@@ -33,26 +33,53 @@ pub struct ModelPeer {
 
 /// Dispatch notifications from a [`Model`] to one or several [`ModelPeer`].
 /// Typically, you would want to put this in the implementation of the Model
-#[derive(Default)]
+///
+/// It also keeps track of the bindings which depend on the number of rows or on the data
+/// of some rows of the model (see [`Model::track_row_count_changes`] and
+/// [`Model::track_row_data_changes`]), and marks them as dirty when the model changes.
 pub struct ModelNotify {
     inner: RefCell<weak_table::PtrWeakHashSet<Weak<RefCell<ModelPeerInner>>>>,
+    row_count_dirty_property: Pin<Box<Property<()>>>,
+    row_data_dirty_property: Pin<Box<Property<()>>>,
+    /// The rows read by the bindings depending on `row_data_dirty_property`
+    tracked_rows: RefCell<Vec<usize>>,
+}
+
+impl Default for ModelNotify {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+            row_count_dirty_property: Box::pin(Default::default()),
+            row_data_dirty_property: Box::pin(Default::default()),
+            tracked_rows: Default::default(),
+        }
+    }
 }
 
 impl ModelNotify {
     /// Notify the peers that a specific row was changed
     pub fn row_changed(&self, row: usize) {
+        if self.tracked_rows.borrow().contains(&row) {
+            self.mark_row_data_dirty();
+        }
         for peer in self.inner.borrow().iter() {
             peer.borrow_mut().row_changed(row)
         }
     }
     /// Notify the peers that rows were added
     pub fn row_added(&self, index: usize, count: usize) {
+        self.row_count_dirty_property.mark_dirty();
+        // The rows after the index were moved
+        self.mark_row_data_dirty();
         for peer in self.inner.borrow().iter() {
             peer.borrow_mut().row_added(index, count)
         }
     }
     /// Notify the peers that rows were removed
     pub fn row_removed(&self, index: usize, count: usize) {
+        self.row_count_dirty_property.mark_dirty();
+        // The rows after the index were moved
+        self.mark_row_data_dirty();
         for peer in self.inner.borrow().iter() {
             peer.borrow_mut().row_removed(index, count)
         }
@@ -61,6 +88,31 @@ pub fn row_removed(&self, index: usize, count: usize) {
     pub fn attach(&self, peer: ModelPeer) {
         peer.inner.upgrade().map(|rc| self.inner.borrow_mut().insert(rc));
     }
+
+    /// Register a dependency of the binding currently evaluated on the number of rows.
+    /// The binding is marked as dirty when rows are added or removed.
+    pub fn track_row_count_changes(&self) {
+        self.row_count_dirty_property.as_ref().get();
+    }
+
+    /// Register a dependency of the binding currently evaluated on the data of the row.
+    /// The binding is marked as dirty when this row is changed, or when rows are added or removed.
+    pub fn track_row_data_changes(&self, row: usize) {
+        {
+            let mut tracked_rows = self.tracked_rows.borrow_mut();
+            if !tracked_rows.contains(&row) {
+                tracked_rows.push(row);
+            }
+        }
+        self.row_data_dirty_property.as_ref().get();
+    }
+
+    fn mark_row_data_dirty(&self) {
+        // All the bindings depending on the rows are dirty: they register their rows again
+        // when they are evaluated.
+        self.tracked_rows.borrow_mut().clear();
+        self.row_data_dirty_property.mark_dirty();
+    }
 }
 
 /// A Model is providing Data for the Repeater or ListView elements of the `.60` language
@@ -78,6 +130,31 @@ fn set_row_data(&self, _row: usize, _data: Self::Data) {}
     /// Should forward to the internal [`ModelNotify::attach`]
     fn attach_peer(&self, peer: ModelPeer);
 
+    /// Register a dependency of the binding currently evaluated on the number of rows.
+    ///
+    /// This is used for `model.length` in the `.60` language. Models which notify their changes
+    /// with a [`ModelNotify`] should forward to [`ModelNotify::track_row_count_changes`].
+    /// The default implementation does nothing, so the bindings are not updated when rows
+    /// are added or removed.
+    fn track_row_count_changes(&self) {}
+
+    /// Register a dependency of the binding currently evaluated on the data of the row.
+    ///
+    /// This is used for `model[index]` in the `.60` language. Models which notify their changes
+    /// with a [`ModelNotify`] should forward to [`ModelNotify::track_row_data_changes`].
+    fn track_row_data_changes(&self, _row: usize) {}
+
+    /// Returns the data of the row, or `None` if the row is out of range, and registers a
+    /// dependency of the binding currently evaluated on the data of the row.
+    fn row_data_tracked(&self, row: usize) -> Option<Self::Data> {
+        self.track_row_data_changes(row);
+        if row < self.row_count() {
+            Some(self.row_data(row))
+        } else {
+            None
+        }
+    }
+
     /// Returns an iterator visiting all elements of the model.
     fn iter(&self) -> ModelIterator<Self::Data>
     where
@@ -169,6 +246,14 @@ fn set_row_data(&self, row: usize, data: Self::Data) {
     fn attach_peer(&self, peer: ModelPeer) {
         self.notify.attach(peer);
     }
+
+    fn track_row_count_changes(&self) {
+        self.notify.track_row_count_changes()
+    }
+
+    fn track_row_data_changes(&self, row: usize) {
+        self.notify.track_row_data_changes(row)
+    }
 }
 
 impl Model for usize {
@@ -267,6 +352,18 @@ fn attach_peer(&self, peer: ModelPeer) {
             model.attach_peer(peer);
         }
     }
+
+    fn track_row_count_changes(&self) {
+        if let Some(model) = self.0.as_ref() {
+            model.track_row_count_changes();
+        }
+    }
+
+    fn track_row_data_changes(&self, row: usize) {
+        if let Some(model) = self.0.as_ref() {
+            model.track_row_data_changes(row);
+        }
+    }
 }
 
 /// Component that can be instantiated by a repeater.
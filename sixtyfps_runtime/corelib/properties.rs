@@ -99,6 +99,9 @@ fn big_list() {
 
 use crate::items::PropertyAnimation;
 
+#[cfg(feature = "debug-bindings")]
+pub mod debug;
+
 /// The return value of a binding
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum BindingResult {
@@ -153,6 +156,69 @@ unsafe fn evaluate(self: Pin<&Self>, value: *mut ()) -> BindingResult {
 
 scoped_tls_hkt::scoped_thread_local!(static CURRENT_BINDING : for<'a> Pin<&'a BindingHolder>);
 
+thread_local! {
+    /// The names given with [`Property::set_debug_name`]
+    static DEBUG_NAMES: RefCell<std::collections::HashMap<*const PropertyHandle, String>> =
+        Default::default();
+    /// The handler set with [`set_binding_loop_handler`]
+    static BINDING_LOOP_HANDLER: RefCell<Option<Box<dyn Fn(&str)>>> = Default::default();
+}
+
+/// Set the function called when a binding loop is detected while evaluating a binding.
+///
+/// The argument of the function is a message naming the properties involved in the loop.
+/// The property whose binding is being evaluated again then keeps its previous value.
+/// By default, the message is printed with [`debug_log!`](crate::debug_log).
+pub fn set_binding_loop_handler(handler: impl Fn(&str) + 'static) {
+    BINDING_LOOP_HANDLER.with(|h| *h.borrow_mut() = Some(Box::new(handler)));
+}
+
+/// Same as [`Property::set_debug_name`] for a type erased property.
+///
+/// # Safety
+/// `property` must point to a `Property<T>`
+pub unsafe fn set_property_debug_name(property: *const (), name: String) {
+    // Property is repr(C) and its handle is the first field
+    let handle = property as *const PropertyHandle;
+    DEBUG_NAMES.with(|names| names.borrow_mut().insert(handle, name));
+}
+
+fn debug_name(handle: *const PropertyHandle) -> String {
+    DEBUG_NAMES.with(|names| {
+        names.borrow().get(&handle).cloned().unwrap_or_else(|| format!("<property {:p}>", handle))
+    })
+}
+
+/// Report that the binding of `handle` is evaluated again while it is being evaluated
+fn report_binding_loop(handle: *const PropertyHandle) {
+    // Walk back from the innermost binding being evaluated to the binding of `handle`
+    let mut chain = vec![debug_name(handle)];
+    if CURRENT_BINDING.is_set() {
+        CURRENT_BINDING.with(|cur_binding| {
+            let mut binding = cur_binding.get_ref() as *const BindingHolder;
+            // Safety: the bindings that are being evaluated are alive
+            while let Some(b) = unsafe { binding.as_ref() } {
+                let property = b.evaluated_property.get();
+                if property.is_null() {
+                    break;
+                }
+                chain.push(debug_name(property));
+                if property == handle {
+                    break;
+                }
+                binding = b.caller.get();
+            }
+        });
+    }
+    chain.reverse();
+    let chain = chain.join(" -> ");
+    let message = format!("Binding loop detected: {}", chain);
+    BINDING_LOOP_HANDLER.with(|h| match &*h.borrow() {
+        Some(handler) => handler(&message),
+        None => crate::debug_log!("{}", message),
+    });
+}
+
 #[repr(C)]
 struct BindingHolder<B = ()> {
     /// Access to the list of binding which depends on this binding
@@ -163,6 +229,11 @@ struct BindingHolder<B = ()> {
     vtable: &'static BindingVTable,
     /// The binding is dirty and need to be re_evaluated
     dirty: Cell<bool>,
+    /// The property whose binding is being evaluated, or null when it is not being evaluated
+    evaluated_property: Cell<*const PropertyHandle>,
+    /// The binding that was being evaluated when the evaluation of this one started.
+    /// Used to report the properties of a binding loop.
+    caller: Cell<*const BindingHolder>,
     pinned: PhantomPinned,
     binding: B,
 }
@@ -236,6 +307,8 @@ impl<B: BindingCallable> HasBindingVTable for B {
         dep_nodes: Default::default(),
         vtable: <B as HasBindingVTable>::VT,
         dirty: Cell::new(true), // starts dirty so it evaluates the property when used
+        evaluated_property: Cell::new(core::ptr::null()),
+        caller: Cell::new(core::ptr::null()),
         pinned: PhantomPinned,
         binding,
     };
@@ -357,6 +430,16 @@ impl PropertyHandle {
     fn lock_flag(&self) -> bool {
         self.handle.get() & 0b1 == 1
     }
+    /// Returns true if the binding of this property is being evaluated.
+    /// The handle is then locked, but the binding only has a shared reference to the value.
+    fn is_evaluating_binding(&self) -> bool {
+        let handle = self.handle.get();
+        // Safety: the handle points to a binding, of which only a Cell is read
+        handle & 0b11 == 0b11
+            && unsafe {
+                !(*((handle & !0b11) as *const BindingHolder)).evaluated_property.get().is_null()
+            }
+    }
     /// Sets the lock_flag.
     /// Safety: the lock flag must not be unset if there exist reference to what's inside the cell
     unsafe fn set_lock_flag(&self, set: bool) {
@@ -387,6 +470,8 @@ fn remove_binding(&self) {
             unsafe {
                 self.set_lock_flag(true);
                 let binding = (val & !0b11) as *mut BindingHolder;
+                #[cfg(feature = "debug-bindings")]
+                debug::binding_dropped(binding);
                 DependencyListHead::mem_move(
                     (&mut (*binding).dependencies) as *mut _ as *mut _,
                     self.handle.as_ptr() as *mut _,
@@ -438,15 +523,29 @@ fn dependencies(&self) -> *mut DependencyListHead {
     // `value` is the content of the unsafe cell and will be only dereferenced if the
     // handle is not locked. (Upholding the requirements of UnsafeCell)
     unsafe fn update<T>(&self, value: *mut T) {
+        if self.is_evaluating_binding() {
+            // This is a binding loop: keep the previous value instead of evaluating the binding again.
+            report_binding_loop(self);
+            return;
+        }
         let remove = self.access(|binding| {
             if let Some(mut binding) = binding {
                 if binding.dirty.get() {
                     // clear all the nodes so that we can start from scratch
                     *binding.dep_nodes.borrow_mut() = Default::default();
+                    #[cfg(feature = "debug-bindings")]
+                    debug::binding_evaluated(self, &*binding as *const BindingHolder);
+                    binding.evaluated_property.set(self);
+                    if CURRENT_BINDING.is_set() {
+                        CURRENT_BINDING
+                            .with(|cur_binding| binding.caller.set(cur_binding.get_ref()));
+                    }
                     let r = (binding.vtable.evaluate)(
                         binding.as_mut().get_unchecked_mut() as *mut BindingHolder,
                         value as *mut (),
                     );
+                    binding.evaluated_property.set(core::ptr::null());
+                    binding.caller.set(core::ptr::null());
                     binding.dirty.set(false);
                     if r == BindingResult::RemoveBinding {
                         return true;
@@ -462,20 +561,40 @@ unsafe fn update<T>(&self, value: *mut T) {
 
     /// Register this property as a dependency to the current binding being evaluated
     fn register_as_dependency_to_current_binding(self: Pin<&Self>) {
+        if self.is_evaluating_binding() {
+            // In a binding loop, the binding already depends on this property
+            return;
+        }
         if CURRENT_BINDING.is_set() {
             CURRENT_BINDING.with(|cur_binding| {
+                #[cfg(feature = "debug-bindings")]
+                debug::dependency_registered(cur_binding.get_ref(), self.get_ref());
                 cur_binding.register_self_as_dependency(self.dependencies());
             });
         }
     }
 
     fn mark_dirty(&self) {
-        unsafe { mark_dependencies_dirty(self.dependencies()) };
+        #[cfg(feature = "debug-bindings")]
+        debug::with_change_origin(self, || unsafe { mark_dependencies_dirty(self.dependencies()) });
+        #[cfg(not(feature = "debug-bindings"))]
+        unsafe {
+            mark_dependencies_dirty(self.dependencies())
+        };
     }
 }
 
 impl Drop for PropertyHandle {
     fn drop(&mut self) {
+        // Ignore the error if the thread local was already destroyed
+        let _ = DEBUG_NAMES.try_with(|names| {
+            let mut names = names.borrow_mut();
+            if !names.is_empty() {
+                names.remove(&(self as *const Self));
+            }
+        });
+        #[cfg(feature = "debug-bindings")]
+        debug::property_dropped(self);
         self.remove_binding();
         debug_assert!(self.handle.get() & 0b11 == 0);
         unsafe {
@@ -492,6 +611,10 @@ unsafe fn mark_dependencies_dirty(dependencies: *mut DependencyListHead) {
         next = node.next.get();
         let binding = &*node.binding;
         let was_dirty = binding.dirty.replace(true);
+        #[cfg(feature = "debug-bindings")]
+        if !was_dirty {
+            debug::binding_marked_dirty(node.binding);
+        }
         (binding.vtable.mark_dirty)(node.binding, was_dirty);
         mark_dependencies_dirty(binding.dependencies.as_ptr() as *mut DependencyListHead)
     }
@@ -556,8 +679,10 @@ pub fn new(value: T) -> Self {
     /// If the function is called directly or indirectly from a binding evaluation
     /// of another Property, a dependency will be registered.
     ///
-    /// Panics if this property is get while evaluating its own binding or
-    /// cloning the value.
+    /// If this property is get while evaluating its own binding, the binding loop is reported
+    /// (see [`set_binding_loop_handler`]) and the previous value is returned.
+    ///
+    /// Panics if this property is get while its value is being set, compared, cloned or dropped.
     pub fn get(self: Pin<&Self>) -> T {
         unsafe { self.handle.update(self.value.get()) };
         let handle = unsafe { Pin::new_unchecked(&self.handle) };
@@ -593,6 +718,10 @@ pub fn get_untracked(self: Pin<&Self>) -> T {
 
     /// Get the value without registering any dependencies or executing any binding
     fn get_internal(&self) -> T {
+        if self.handle.is_evaluating_binding() {
+            // This is a binding loop reported by PropertyHandle::update
+            return unsafe { (*self.value.get()).clone() };
+        }
         self.handle.access(|_| {
             // Safety: PropertyHandle::access ensure that the value is locked
             unsafe { (*self.value.get()).clone() }
@@ -657,19 +786,50 @@ pub fn set_binding(&self, binding: impl Binding<T> + 'static) {
         // Safety: This will make a binding callable for the type T
         unsafe {
             self.handle.set_binding(move |val: *mut ()| {
-                let val = &mut *(val as *mut T);
-                *val = binding.evaluate(val);
+                let new_value = binding.evaluate(&*(val as *const T));
+                *(val as *mut T) = new_value;
                 BindingResult::KeepBinding
             });
         }
         self.handle.mark_dirty();
     }
 
+    /// Give a name to this property, used to identify it when reporting a binding loop.
+    ///
+    /// The property must not be moved afterwards.
+    pub fn set_debug_name(&self, name: impl Into<String>) {
+        unsafe { set_property_debug_name(self as *const Self as *const (), name.into()) }
+    }
+
+    /// Return the dependencies, the dependents, and the current value of this property.
+    /// The binding is not evaluated, even if it is dirty.
+    #[cfg(feature = "debug-bindings")]
+    pub fn debug_info(&self) -> debug::PropertyDebugInfo
+    where
+        T: core::fmt::Debug,
+    {
+        let mut info = debug::handle_debug_info(&self.handle);
+        if !self.handle.lock_flag() {
+            // Safety: PropertyHandle::access ensure that the value is locked
+            info.value =
+                Some(self.handle.access(|_| unsafe { format!("{:?}", *self.value.get()) }));
+        }
+        info
+    }
+
     /// Any of the properties accessed during the last evaluation of the closure called
     /// from the last call to evaluate is potentially dirty.
     pub fn is_dirty(&self) -> bool {
         self.handle.access(|binding| binding.map_or(false, |b| b.dirty.get()))
     }
+
+    /// Mark the bindings depending on this property as dirty, without changing its value.
+    ///
+    /// This is useful with a `Property<()>` which represents a state that is not stored in
+    /// a property.
+    pub fn mark_dirty(&self) {
+        self.handle.mark_dirty()
+    }
 }
 
 impl<T: Clone + InterpolatedPropertyValue + 'static> Property<T> {
@@ -713,8 +873,8 @@ pub fn set_animated_binding(
             original_binding: PropertyHandle {
                 handle: Cell::new(
                     (alloc_binding_holder(move |val: *mut ()| unsafe {
-                        let val = &mut *(val as *mut T);
-                        *(val as *mut T) = binding.evaluate(val);
+                        let new_value = binding.evaluate(&*(val as *const T));
+                        *(val as *mut T) = new_value;
                         BindingResult::KeepBinding
                     }) as usize)
                         | 0b10,
@@ -746,8 +906,8 @@ pub fn set_animated_binding_for_transition(
             original_binding: PropertyHandle {
                 handle: Cell::new(
                     (alloc_binding_holder(move |val: *mut ()| unsafe {
-                        let val = &mut *(val as *mut T);
-                        *(val as *mut T) = binding.evaluate(val);
+                        let new_value = binding.evaluate(&*(val as *const T));
+                        *(val as *mut T) = new_value;
                         BindingResult::KeepBinding
                     }) as usize)
                         | 0b10,
@@ -904,6 +1064,54 @@ fn property_two_ways_test_binding() {
     assert_eq!(depends.as_ref().get(), 55 + 9 + 8);
 }
 
+#[test]
+fn property_binding_loop_test() {
+    let reported = Rc::new(RefCell::new(Vec::<String>::new()));
+    set_binding_loop_handler({
+        let reported = reported.clone();
+        move |message| reported.borrow_mut().push(message.into())
+    });
+
+    let a = Rc::pin(Property::new(0));
+    let b = Rc::pin(Property::new(0));
+    a.set_debug_name("a");
+    b.set_debug_name("b");
+    a.as_ref().set_binding({
+        let b = b.clone();
+        move || b.as_ref().get() + 1
+    });
+    b.as_ref().set_binding({
+        let a = a.clone();
+        move || a.as_ref().get() + 1
+    });
+
+    // The loop is reported, and the inner access to `a` returns its previous value
+    assert_eq!(a.as_ref().get(), 2);
+    assert_eq!(b.as_ref().get(), 1);
+    assert_eq!(*reported.borrow(), vec!["Binding loop detected: a -> b -> a".to_owned()]);
+}
+
+#[test]
+#[should_panic(expected = "Recursion detected")]
+fn property_get_while_compared_test() {
+    #[derive(Clone, Default)]
+    struct Reentrant(Cell<*const Property<Reentrant>>);
+    impl PartialEq for Reentrant {
+        fn eq(&self, other: &Self) -> bool {
+            for p in &[self.0.get(), other.0.get()] {
+                if let Some(p) = unsafe { p.as_ref() } {
+                    unsafe { Pin::new_unchecked(p) }.get();
+                }
+            }
+            false
+        }
+    }
+
+    let prop = Rc::pin(Property::new(Reentrant::default()));
+    // Comparing the new value with the old one gets the property
+    prop.set(Reentrant(Cell::new(&*prop as *const _)));
+}
+
 struct PropertyValueAnimationData<T> {
     from_value: T,
     to_value: T,
@@ -1404,6 +1612,8 @@ fn default() -> Self {
             dep_nodes: Default::default(),
             vtable: VT,
             dirty: Cell::new(true), // starts dirty so it evaluates the property when used
+            evaluated_property: Cell::new(core::ptr::null()),
+            caller: Cell::new(core::ptr::null()),
             pinned: PhantomPinned,
             binding: (),
         };
@@ -1413,6 +1623,8 @@ fn default() -> Self {
 
 impl<ChangeHandler> Drop for PropertyTracker<ChangeHandler> {
     fn drop(&mut self) {
+        #[cfg(feature = "debug-bindings")]
+        debug::binding_dropped(&self.holder as *const _ as *const BindingHolder);
         unsafe {
             DependencyListHead::drop(self.holder.dependencies.as_ptr() as *mut DependencyListHead);
         }
@@ -1451,6 +1663,8 @@ pub fn evaluate<R>(self: Pin<&Self>, f: impl FnOnce() -> R) -> R {
     pub fn evaluate_as_dependency_root<R>(self: Pin<&Self>, f: impl FnOnce() -> R) -> R {
         // clear all the nodes so that we can start from scratch
         *self.holder.dep_nodes.borrow_mut() = Default::default();
+        #[cfg(feature = "debug-bindings")]
+        debug::tracker_evaluated(&self.holder as *const _ as *const BindingHolder);
 
         // Safety: it is safe to project the holder as we don't implement drop or unpin
         let pinned_holder = unsafe {
@@ -1507,6 +1721,8 @@ impl<B: PropertyChangeHandler> HasBindingVTable for B {
             dep_nodes: Default::default(),
             vtable: <ChangeHandler as HasBindingVTable>::VT,
             dirty: Cell::new(true), // starts dirty so it evaluates the property when used
+            evaluated_property: Cell::new(core::ptr::null()),
+            caller: Cell::new(core::ptr::null()),
             pinned: PhantomPinned,
             binding: handler,
         };
@@ -1797,6 +2013,12 @@ unsafe fn intercept_set_binding(
         handle.0.set_binding_impl(binding.cast());
     }
 
+    /// Mark the bindings depending on the property behind this handle as dirty
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_property_mark_dirty(handle: &PropertyHandleOpaque) {
+        handle.0.mark_dirty()
+    }
+
     /// Returns whether the property behind this handle is marked as dirty
     #[no_mangle]
     pub extern "C" fn sixtyfps_property_is_dirty(handle: &PropertyHandleOpaque) -> bool {
@@ -2043,6 +2265,8 @@ pub struct PropertyTrackerOpaque {
         dep_nodes: [usize; 2],
         vtable: usize,
         dirty: bool,
+        evaluated_property: usize,
+        caller: usize,
     }
 
     static_assertions::assert_eq_align!(PropertyTrackerOpaque, PropertyTracker);
@@ -0,0 +1,283 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+/*!
+Inspection of the properties and bindings at runtime.
+
+This module is only available with the `debug-bindings` feature. It allows to query the
+dependencies and dependents of a property ([`property_debug_info`]), to trace the evaluations
+of the bindings ([`set_trace_handler`]), and to count the evaluations during each frame
+([`set_frame_statistics_handler`]).
+
+The properties are identified by the name given with [`super::Property::set_debug_name`].
+*/
+
+use super::{BindingHolder, DependencyListHead, DependencyNode, PropertyHandle};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Called with the message describing the evaluation of a binding
+pub type TraceHandler = dyn Fn(&str);
+/// Called with the names of the properties whose binding was evaluated, and the number of evaluations
+pub type FrameStatisticsHandler = dyn Fn(&[(String, usize)]);
+
+#[derive(Default)]
+struct DebugState {
+    /// The property owning each binding, recorded when the binding is evaluated
+    owners: HashMap<*const BindingHolder, *const PropertyHandle>,
+    /// The properties read during the last evaluation of each binding or property tracker
+    dependencies: HashMap<*const BindingHolder, Vec<*const PropertyHandle>>,
+    /// The property whose change marked each binding as dirty
+    dirty_causes: HashMap<*const BindingHolder, *const PropertyHandle>,
+    /// The number of evaluations of the binding of each property since the last frame
+    evaluation_counts: HashMap<*const PropertyHandle, usize>,
+    /// The property being changed while its dependents are marked as dirty
+    change_origin: Option<*const PropertyHandle>,
+    trace_handler: Option<Rc<TraceHandler>>,
+    frame_statistics_handler: Option<Rc<FrameStatisticsHandler>>,
+}
+
+thread_local!(static STATE: RefCell<DebugState> = Default::default());
+
+/// The information returned by [`property_debug_info`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PropertyDebugInfo {
+    /// The name given to the property, or its address
+    pub name: String,
+    /// The current value of the property, without evaluating the binding if it is dirty
+    pub value: Option<String>,
+    /// Whether the property has a binding
+    pub has_binding: bool,
+    /// Whether the binding needs to be evaluated again
+    pub is_dirty: bool,
+    /// The number of evaluations of the binding since the last frame
+    pub evaluation_count: usize,
+    /// The properties read during the last evaluation of the binding
+    pub dependencies: Vec<String>,
+    /// The bindings which read this property during their last evaluation
+    pub dependents: Vec<String>,
+}
+
+impl std::fmt::Display for PropertyDebugInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(value) = &self.value {
+            write!(f, " = {}", value)?;
+        }
+        if self.is_dirty {
+            write!(f, " (dirty)")?;
+        }
+        writeln!(f)?;
+        if self.has_binding {
+            writeln!(
+                f,
+                "  binding evaluated {} time(s) since the last frame",
+                self.evaluation_count
+            )?;
+            writeln!(f, "  depends on: {}", self.dependencies.join(", "))?;
+        }
+        writeln!(f, "  used by: {}", self.dependents.join(", "))
+    }
+}
+
+/// Return the debug information of a type erased property. The value is not set.
+///
+/// # Safety
+/// `property` must point to a `Property<T>`
+pub unsafe fn property_debug_info(property: *const ()) -> PropertyDebugInfo {
+    handle_debug_info(&*(property as *const PropertyHandle))
+}
+
+pub(super) fn handle_debug_info(handle: &PropertyHandle) -> PropertyDebugInfo {
+    let handle_ptr = handle as *const PropertyHandle;
+    let bits = handle.handle.get();
+    let binding =
+        if bits & 0b10 == 0b10 { Some((bits & !0b11) as *const BindingHolder) } else { None };
+    let dependency_list = match binding {
+        Some(binding) => unsafe { (*binding).dependencies.as_ptr() as *const DependencyListHead },
+        None => handle.handle.as_ptr() as *const DependencyListHead,
+    };
+
+    STATE.with(|state| {
+        let state = state.borrow();
+        let name_of_binding = |binding: *const BindingHolder| match state.owners.get(&binding) {
+            Some(owner) => super::debug_name(*owner),
+            None => "<property tracker>".to_owned(),
+        };
+        let mut dependents = vec![];
+        // Safety: the list only contains valid nodes
+        let mut next = unsafe { (*dependency_list).0.get() as *const DependencyNode };
+        while let Some(node) = unsafe { next.as_ref() } {
+            dependents.push(name_of_binding(node.binding));
+            next = node.next.get();
+        }
+        PropertyDebugInfo {
+            name: super::debug_name(handle_ptr),
+            value: None,
+            has_binding: binding.is_some(),
+            is_dirty: binding.map_or(false, |b| unsafe { (*b).dirty.get() }),
+            evaluation_count: state.evaluation_counts.get(&handle_ptr).copied().unwrap_or(0),
+            dependencies: binding
+                .and_then(|b| state.dependencies.get(&b))
+                .map(|deps| deps.iter().map(|p| super::debug_name(*p)).collect())
+                .unwrap_or_default(),
+            dependents,
+        }
+    })
+}
+
+/// Set the function called each time the binding of a property is evaluated, with a message
+/// giving the name of the property, and the property whose change caused the evaluation.
+/// Pass `None` to stop tracing.
+pub fn set_trace_handler(handler: Option<Box<TraceHandler>>) {
+    STATE.with(|state| state.borrow_mut().trace_handler = handler.map(Rc::from));
+}
+
+/// Set the function called after each frame with the number of evaluations of the binding
+/// of each property during that frame, sorted by decreasing count.
+/// Pass `None` to disable it.
+pub fn set_frame_statistics_handler(handler: Option<Box<FrameStatisticsHandler>>) {
+    STATE.with(|state| state.borrow_mut().frame_statistics_handler = handler.map(Rc::from));
+}
+
+/// Return the number of evaluations of the binding of each property since the last frame,
+/// sorted by decreasing count, and start counting again.
+pub fn take_evaluation_counts() -> Vec<(String, usize)> {
+    let counts = STATE.with(|state| std::mem::take(&mut state.borrow_mut().evaluation_counts));
+    let mut counts =
+        counts.into_iter().map(|(p, count)| (super::debug_name(p), count)).collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Called by the window when a frame was drawn
+pub(crate) fn frame_finished() {
+    let handler = STATE.with(|state| state.borrow().frame_statistics_handler.clone());
+    let counts = take_evaluation_counts();
+    if let Some(handler) = handler {
+        handler(&counts);
+    }
+}
+
+/// Called before the binding of `property` is evaluated
+pub(super) fn binding_evaluated(property: *const PropertyHandle, binding: *const BindingHolder) {
+    let (handler, cause, first) = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let first = state.owners.insert(binding, property).is_none();
+        state.dependencies.remove(&binding);
+        *state.evaluation_counts.entry(property).or_default() += 1;
+        (state.trace_handler.clone(), state.dirty_causes.remove(&binding), first)
+    });
+    if let Some(handler) = handler {
+        let reason = match cause {
+            Some(cause) => format!(" because {} changed", super::debug_name(cause)),
+            None if first => " for the first time".to_owned(),
+            None => String::new(),
+        };
+        handler(&format!("Evaluating {}{}", super::debug_name(property), reason));
+    }
+}
+
+/// Called when a property tracker starts a new evaluation
+pub(super) fn tracker_evaluated(tracker: *const BindingHolder) {
+    STATE.with(|state| state.borrow_mut().dependencies.remove(&tracker));
+}
+
+/// Called when `property` is read while evaluating `binding`
+pub(super) fn dependency_registered(
+    binding: *const BindingHolder,
+    property: *const PropertyHandle,
+) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let dependencies = state.dependencies.entry(binding).or_default();
+        if !dependencies.contains(&property) {
+            dependencies.push(property);
+        }
+    });
+}
+
+/// Run `f`, which marks the dependents of `property` as dirty
+pub(super) fn with_change_origin(property: *const PropertyHandle, f: impl FnOnce()) {
+    let previous = STATE.with(|state| state.borrow_mut().change_origin.replace(property));
+    scopeguard::defer! {
+        STATE.with(|state| state.borrow_mut().change_origin = previous);
+    }
+    f()
+}
+
+/// Called when `binding` is marked as dirty
+pub(super) fn binding_marked_dirty(binding: *const BindingHolder) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if let Some(origin) = state.change_origin {
+            state.dirty_causes.entry(binding).or_insert(origin);
+        }
+    });
+}
+
+/// Called when a binding or a property tracker is destroyed
+pub(super) fn binding_dropped(binding: *const BindingHolder) {
+    // Ignore the error if the thread local was already destroyed
+    let _ = STATE.try_with(|state| {
+        let mut state = state.borrow_mut();
+        state.owners.remove(&binding);
+        state.dependencies.remove(&binding);
+        state.dirty_causes.remove(&binding);
+    });
+}
+
+/// Called when a property is destroyed
+pub(super) fn property_dropped(property: *const PropertyHandle) {
+    let _ = STATE.try_with(|state| state.borrow_mut().evaluation_counts.remove(&property));
+}
+
+#[test]
+fn property_debug_info_test() {
+    use super::Property;
+    let traces = Rc::new(RefCell::new(Vec::<String>::new()));
+    set_trace_handler(Some(Box::new({
+        let traces = traces.clone();
+        move |message| traces.borrow_mut().push(message.into())
+    })));
+
+    let a = Rc::pin(Property::new(1));
+    let b = Rc::pin(Property::new(0));
+    a.set_debug_name("a");
+    b.set_debug_name("b");
+    b.as_ref().set_binding({
+        let a = a.clone();
+        move || a.as_ref().get() * 2
+    });
+    assert_eq!(b.as_ref().get(), 2);
+    a.set(5);
+    assert_eq!(b.as_ref().get(), 10);
+    set_trace_handler(None);
+
+    assert_eq!(
+        *traces.borrow(),
+        vec!["Evaluating b for the first time".to_owned(), "Evaluating b because a changed".into()]
+    );
+    assert_eq!(
+        b.debug_info(),
+        PropertyDebugInfo {
+            name: "b".into(),
+            value: Some("10".into()),
+            has_binding: true,
+            is_dirty: false,
+            evaluation_count: 2,
+            dependencies: vec!["a".into()],
+            dependents: vec![],
+        }
+    );
+    assert_eq!(a.debug_info().dependents, vec!["b".to_owned()]);
+    assert_eq!(take_evaluation_counts(), vec![("b".to_owned(), 2)]);
+    assert_eq!(b.debug_info().evaluation_count, 0);
+}
@@ -297,6 +297,117 @@ fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
     }
 }
 
+/// Formats `n` with exactly `digits` digits after the decimal point.
+///
+/// This is the `to-fixed()` function of the .60 language.
+pub fn format_fixed(n: f64, digits: i32) -> SharedString {
+    format!("{:.*}", digits.max(0) as usize, n).into()
+}
+
+/// Formats `n` with exactly `digits` digits after the decimal point, and with a comma
+/// separating each group of thousands of the integral part.
+///
+/// This is the `to-grouped()` function of the .60 language.
+pub fn format_grouped(n: f64, digits: i32) -> SharedString {
+    let fixed = format_fixed(n, digits);
+    let (sign, unsigned) = match fixed.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", fixed.as_str()),
+    };
+    let int_len = unsigned.find('.').unwrap_or(unsigned.len());
+    if !unsigned[..int_len].bytes().all(|b| b.is_ascii_digit()) {
+        // NaN or infinity
+        return fixed;
+    }
+    let mut result = String::with_capacity(fixed.len() + int_len / 3);
+    result.push_str(sign);
+    for (i, c) in unsigned[..int_len].chars().enumerate() {
+        if i > 0 && (int_len - i) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result.push_str(&unsigned[int_len..]);
+    result.into()
+}
+
+/// Rounds `n` to an integer and pads it with leading zeros so that it has at least `width` characters.
+///
+/// This is the `to-padded()` function of the .60 language.
+pub fn format_padded(n: f64, width: i32) -> SharedString {
+    format!("{:0width$}", n.round() as i64, width = width.max(0) as usize).into()
+}
+
+/// Formats the ratio `n` as a percentage with `digits` digits after the decimal point,
+/// so that `0.256` with one digit gives `25.6%`.
+///
+/// This is the `to-percent()` function of the .60 language.
+pub fn format_percent(n: f64, digits: i32) -> SharedString {
+    let mut result = format_fixed(n * 100., digits);
+    result.push_str("%");
+    result
+}
+
+/// Formats the amount of bytes `n` with a binary unit, such as `512 B`, `1.5 KiB`, or `20.0 MiB`.
+///
+/// This is the `to-byte-size()` function of the .60 language.
+pub fn format_byte_size(n: f64) -> SharedString {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    // The unit is chosen after rounding, so that 1023.6 bytes give `1.0 KiB` and not `1024 B`
+    if n.round().abs() < 1024. {
+        return format!("{} B", n.round()).into();
+    }
+    let mut value = n / 1024.;
+    let mut unit = 0;
+    while (value * 10.).round().abs() >= 10240. && unit < UNITS.len() - 1 {
+        value /= 1024.;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit]).into()
+}
+
+/// Returns the number of characters (unicode scalar values) in `s`.
+///
+/// This is the `length` property of the strings in the .60 language.
+pub fn string_length(s: &str) -> i32 {
+    s.chars().count() as i32
+}
+
+/// Returns the part of `s` that starts at the character index `start` and that is at most
+/// `length` characters long. Indices outside of the string are clamped.
+///
+/// This is the `substring()` function of the .60 language.
+pub fn string_substring(s: &str, start: i32, length: i32) -> SharedString {
+    s.chars().skip(start.max(0) as usize).take(length.max(0) as usize).collect::<String>().into()
+}
+
+#[test]
+fn formatting_test() {
+    assert_eq!(format_fixed(3.14159, 2), "3.14");
+    assert_eq!(format_fixed(2., 0), "2");
+    assert_eq!(format_fixed(-1.5, -1), "-2");
+    assert_eq!(format_grouped(1234567.891, 2), "1,234,567.89");
+    assert_eq!(format_grouped(-123456., 0), "-123,456");
+    assert_eq!(format_grouped(999., 1), "999.0");
+    assert_eq!(format_grouped(f64::NAN, 0), "NaN");
+    assert_eq!(format_padded(7., 3), "007");
+    assert_eq!(format_padded(-7., 3), "-07");
+    assert_eq!(format_padded(1234., 2), "1234");
+    assert_eq!(format_percent(0.256, 1), "25.6%");
+    assert_eq!(format_percent(1., 0), "100%");
+    assert_eq!(format_byte_size(512.), "512 B");
+    assert_eq!(format_byte_size(1536.), "1.5 KiB");
+    assert_eq!(format_byte_size(20. * 1024. * 1024.), "20.0 MiB");
+    assert_eq!(format_byte_size(1023.4), "1023 B");
+    assert_eq!(format_byte_size(1023.6), "1.0 KiB");
+    assert_eq!(format_byte_size(1024. * 1023.96), "1.0 MiB");
+    assert_eq!(string_length("héllo"), 5);
+    assert_eq!(string_substring("héllo wörld", 1, 4), "éllo");
+    assert_eq!(string_substring("héllo", 3, 10), "lo");
+    assert_eq!(string_substring("héllo", -2, 2), "hé");
+    assert_eq!(string_substring("héllo", 10, 2), "");
+}
+
 #[test]
 fn simple_test() {
     let x = SharedString::from("hello world!");
@@ -390,6 +501,71 @@ fn test_sixtyfps_shared_string_from_number() {
         }
     }
 
+    /// Set `out` to `n` formatted by [`format_fixed`]
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_string_format_fixed(out: &mut SharedString, n: f64, digits: i32) {
+        *out = format_fixed(n, digits);
+    }
+
+    /// Set `out` to `n` formatted by [`format_grouped`]
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_string_format_grouped(out: &mut SharedString, n: f64, digits: i32) {
+        *out = format_grouped(n, digits);
+    }
+
+    /// Set `out` to `n` formatted by [`format_padded`]
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_string_format_padded(out: &mut SharedString, n: f64, width: i32) {
+        *out = format_padded(n, width);
+    }
+
+    /// Set `out` to `n` formatted by [`format_percent`]
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_string_format_percent(out: &mut SharedString, n: f64, digits: i32) {
+        *out = format_percent(n, digits);
+    }
+
+    /// Set `out` to `n` formatted by [`format_byte_size`]
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_string_format_byte_size(out: &mut SharedString, n: f64) {
+        *out = format_byte_size(n);
+    }
+
+    /// Set `out` to the upper case version of `s`
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_string_to_uppercase(out: &mut SharedString, s: &SharedString) {
+        *out = s.to_uppercase().into();
+    }
+
+    /// Set `out` to the lower case version of `s`
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_string_to_lowercase(out: &mut SharedString, s: &SharedString) {
+        *out = s.to_lowercase().into();
+    }
+
+    /// Set `out` to `s` without its leading and trailing whitespace
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_string_trim(out: &mut SharedString, s: &SharedString) {
+        *out = s.trim().into();
+    }
+
+    /// Returns the number of characters in `s`, see [`string_length`]
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_string_length(s: &SharedString) -> i32 {
+        string_length(s)
+    }
+
+    /// Set `out` to the result of [`string_substring`]
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_string_substring(
+        out: &mut SharedString,
+        s: &SharedString,
+        start: i32,
+        length: i32,
+    ) {
+        *out = string_substring(s, start, length);
+    }
+
     /// Append some bytes to an existing shared string
     ///
     /// bytes must be a valid utf8 array of size `len`, without null bytes inside
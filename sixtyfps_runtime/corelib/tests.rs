@@ -87,6 +87,7 @@ pub extern "C" fn send_keyboard_string_sequence(
     }
 }
 
+// In a macro so that cbindgen does not see it
 cfg_if::cfg_if! {
     if #[cfg(target_arch = "wasm32")] {
         use wasm_bindgen::prelude::*;
@@ -96,15 +97,18 @@ pub extern "C" fn send_keyboard_string_sequence(
             #[wasm_bindgen(js_namespace = console)]
             pub fn log(s: &str);
         }
-
-        #[macro_export]
-        macro_rules! debug_log {
-            ($($t:tt)*) => ($crate::tests::log(&format_args!($($t)*).to_string()))
-        }
-    } else {
-        #[macro_export]
-        macro_rules! debug_log {
-            ($($t:tt)*) => (eprintln!($($t)*))
-        }
     }
 }
+
+/// Print a debug message on the console of the browser with wasm, and on the standard error
+/// output otherwise.
+// Not defined by another macro, so that this crate can also use it as `crate::debug_log!`
+#[macro_export]
+macro_rules! debug_log {
+    ($($t:tt)*) => {{
+        #[cfg(target_arch = "wasm32")]
+        $crate::tests::log(&format_args!($($t)*).to_string());
+        #[cfg(not(target_arch = "wasm32"))]
+        eprintln!($($t)*);
+    }}
+}
@@ -49,6 +49,34 @@ pub trait PlatformWindow {
     /// Close the active popup if any
     fn close_popup(&self);
 
+    /// Show a modal dialog, either as a separate top-level window or on top of the window's
+    /// component, depending on `presentation`. Any previously shown dialog is closed.
+    fn show_dialog(&self, dialog: &ComponentRc, presentation: DialogPresentation);
+    /// Close the active dialog if any
+    fn close_dialog(&self);
+    /// Returns the component shown on top of the window's component which receives the key
+    /// events instead of it, such as a dialog presented as an overlay.
+    fn key_input_component(&self) -> Option<ComponentRc>;
+    /// Returns the popup shown with [`Self::show_popup`] if it receives its key events through
+    /// the window, so that its `Shortcut` items can be activated.
+    fn active_popup(&self) -> Option<ComponentRc>;
+
+    /// Returns true if the backend can show the menus with the native menus of the platform,
+    /// in which case [`Self::show_native_menu`] is used instead of a popup, and the menu bars
+    /// are shown with [`Self::set_native_menu_bar`].
+    fn has_native_menus(&self) -> bool;
+    /// Show a native menu with the given entries at the given position. This returns immediately,
+    /// `activated` is called later with the index of the entry activated by the user, and is
+    /// dropped without being called if the menu is closed without activating an entry.
+    fn show_native_menu(
+        &self,
+        entries: &[MenuEntry],
+        position: Point,
+        activated: Box<dyn FnOnce(usize)>,
+    );
+    /// Show the menus of `menu_bar` in the native menu bar of the window.
+    fn set_native_menu_bar(&self, menu_bar: NativeMenuBar);
+
     /// Request for the event loop to wake up and call [`Window::update_window_properties()`].
     fn request_window_properties_update(&self);
     /// Request for the given title string to be set to the windowing system for use as window title.
@@ -70,6 +98,174 @@ fn font_metrics(
     fn as_any(&self) -> &dyn core::any::Any;
 }
 
+/// The role of a button in a dialog, which determines its text and its position
+/// in the row of buttons.
+#[derive(Copy, Clone, Debug, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub enum StandardButtonKind {
+    /// The button accepting the dialog
+    ok,
+    /// The button rejecting the dialog
+    cancel,
+    /// The button applying the changes without closing the dialog
+    apply,
+    /// The button answering yes to the question asked by the dialog
+    yes,
+    /// The button answering no to the question asked by the dialog
+    no,
+}
+
+impl Default for StandardButtonKind {
+    fn default() -> Self {
+        Self::ok
+    }
+}
+
+/// How a dialog is presented when it is shown
+#[derive(Copy, Clone, Debug, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub enum DialogPresentation {
+    /// The dialog is a separate top-level window, modal to the window showing it
+    window,
+    /// The dialog is drawn centered on top of the window showing it, which does not
+    /// receive input while the dialog is shown
+    overlay,
+}
+
+impl Default for DialogPresentation {
+    fn default() -> Self {
+        Self::window
+    }
+}
+
+/// Returns the position of a standard button in the row of buttons of a dialog, according to the
+/// conventions of the platform the program runs on. The button with the highest rank is the right-most.
+///
+/// The order is the one of Qt's QDialogButtonBox: the macOS layout on Apple platforms, the KDE or GNOME
+/// layout depending on the `XDG_CURRENT_DESKTOP` environment variable on the other Unix systems, and
+/// the Windows layout otherwise.
+pub fn standard_button_rank(kind: StandardButtonKind) -> i32 {
+    static ORDER: once_cell::sync::Lazy<&'static [StandardButtonKind]> =
+        once_cell::sync::Lazy::new(|| {
+            if cfg!(any(target_os = "macos", target_os = "ios")) {
+                standard_button_order("macOS")
+            } else if cfg!(all(unix, not(target_os = "android"))) {
+                standard_button_order(&std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default())
+            } else {
+                standard_button_order("")
+            }
+        });
+    ORDER.iter().position(|k| *k == kind).unwrap() as i32
+}
+
+/// The buttons from left to right for the desktop `desktop`, which is "macOS", or a colon separated
+/// list of desktop names as in `XDG_CURRENT_DESKTOP`.
+fn standard_button_order(desktop: &str) -> &'static [StandardButtonKind] {
+    use StandardButtonKind::*;
+    // The desktops for which Qt uses its GNOME theme
+    const GTK_BASED: &[&str] = &["GNOME", "X-CINNAMON", "UNITY", "MATE", "XFCE", "LXDE"];
+    let desktop = desktop.to_uppercase();
+    if desktop == "MACOS" {
+        return &[apply, cancel, ok, no, yes];
+    }
+    match desktop.split(':').find(|name| *name == "KDE" || GTK_BASED.contains(name)) {
+        Some("KDE") => &[yes, no, ok, apply, cancel],
+        Some(_) => &[apply, cancel, ok, no, yes],
+        None => &[yes, ok, no, cancel, apply],
+    }
+}
+
+#[test]
+fn test_standard_button_order() {
+    use StandardButtonKind::*;
+    assert_eq!(standard_button_order(""), [yes, ok, no, cancel, apply]);
+    assert_eq!(standard_button_order("macOS"), [apply, cancel, ok, no, yes]);
+    assert_eq!(standard_button_order("KDE"), [yes, no, ok, apply, cancel]);
+    assert_eq!(standard_button_order("ubuntu:GNOME"), [apply, cancel, ok, no, yes]);
+    assert_eq!(standard_button_order("XFCE"), [apply, cancel, ok, no, yes]);
+    assert_eq!(standard_button_order("sway"), [yes, ok, no, cancel, apply]);
+}
+
+/// Returns true if `item` is one of the items of `component`, or of a component instantiated by it
+fn is_item_in_component(item: &ItemRc, component: &ComponentRc) -> bool {
+    let mut item = item.clone();
+    loop {
+        if vtable::VRc::ptr_eq(&item.component(), component) {
+            return true;
+        }
+        match item.parent_item().upgrade() {
+            Some(parent) => item = parent,
+            None => return false,
+        }
+    }
+}
+
+/// An entry of a menu, as given to [`PlatformWindow::show_native_menu`].
+///
+/// The menu tree is flattened: the entries of a sub-menu follow the entry of that
+/// sub-menu, with a `depth` one higher.
+#[repr(C)]
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct MenuEntry {
+    /// True if the entry has a check mark when it is checked
+    pub checkable: bool,
+    /// The state of the check mark of a checkable entry
+    pub checked: bool,
+    /// The level of the sub-menu this entry is in, 0 for the entries of the menu itself
+    pub depth: i32,
+    /// False if the entry cannot be activated
+    pub enabled: bool,
+    /// True if the entry is the title of a sub-menu
+    pub has_submenu: bool,
+    /// True if the entry is a separator, which has no title
+    pub is_separator: bool,
+    /// The keyboard shortcut shown next to the title, for example "Ctrl+S"
+    pub shortcut: SharedString,
+    /// The text of the entry
+    pub title: SharedString,
+}
+
+/// The menus of a menu bar, as given to [`PlatformWindow::set_native_menu_bar`].
+pub struct NativeMenuBar {
+    /// Returns the current entries of the menus. The entries with a depth of 0 are the titles
+    /// of the menus of the bar, followed by the entries of that menu.
+    pub entries: Box<dyn Fn() -> Vec<MenuEntry>>,
+    /// Called with the index of the entry activated by the user
+    pub activated: Box<dyn Fn(usize)>,
+}
+
+/// Returns the size of the Window item at the root of `component`. When the width or height of that item
+/// is not set, the preferred size of the component is used instead, and that size is also set on the item.
+///
+/// This is used by the backends to size the popups and dialogs.
+pub fn resolve_window_size(component: &ComponentRc) -> crate::graphics::Size {
+    let component = ComponentRc::borrow_pin(component);
+    let root_item = component.as_ref().get_item_ref(0);
+    let layout_info_h = component.as_ref().layout_info(crate::layout::Orientation::Horizontal);
+    let layout_info_v = component.as_ref().layout_info(crate::layout::Orientation::Vertical);
+    match ItemRef::downcast_pin::<crate::items::Window>(root_item) {
+        Some(window_item) => {
+            let mut w = window_item.width();
+            let mut h = window_item.height();
+            if w <= 0. {
+                w = layout_info_h.preferred_bounded();
+                window_item.width.set(w);
+            }
+            if h <= 0. {
+                h = layout_info_v.preferred_bounded();
+                window_item.height.set(h);
+            }
+            crate::graphics::Size::new(w, h)
+        }
+        None => crate::graphics::Size::new(
+            layout_info_h.preferred_bounded(),
+            layout_info_v.preferred_bounded(),
+        ),
+    }
+}
+
 struct WindowPropertiesTracker {
     window_weak: Weak<Window>,
 }
@@ -113,6 +309,11 @@ pub struct Window {
 
     focus_item: RefCell<ItemWeak>,
     cursor_blinker: RefCell<pin_weak::rc::PinWeak<crate::input::TextCursorBlinker>>,
+
+    /// A component drawn on top of the window's component, with its position (see [`Self::set_overlay`])
+    overlay: RefCell<Option<(ComponentRc, Point)>>,
+    /// The filter set with [`Self::set_mouse_event_filter`]
+    mouse_event_filter: RefCell<Option<Box<dyn Fn(&MouseEvent) -> bool>>>,
 }
 
 impl Drop for Window {
@@ -137,6 +338,8 @@ pub fn new(
             meta_properties_tracker: Rc::pin(Default::default()),
             focus_item: Default::default(),
             cursor_blinker: Default::default(),
+            overlay: Default::default(),
+            mouse_event_filter: Default::default(),
         });
         let window_weak = Rc::downgrade(&window);
         window.platform_window.set(platform_window_fn(&window_weak)).ok().unwrap();
@@ -192,6 +395,9 @@ pub fn try_component(&self) -> Option<ComponentRc> {
     /// * `what`: The type of mouse event.
     /// * `component`: The SixtyFPS compiled component that provides the tree of items.
     pub fn process_mouse_input(self: Rc<Self>, event: MouseEvent) {
+        if self.mouse_event_filter.borrow().as_ref().map_or(false, |filter| filter(&event)) {
+            return;
+        }
         crate::animations::update_animations();
         let component = self.component.borrow().upgrade().unwrap();
         self.mouse_input_state.set(crate::input::process_mouse_input(
@@ -204,11 +410,25 @@ pub fn process_mouse_input(self: Rc<Self>, event: MouseEvent) {
     /// Receive a key event and pass it to the items of the component to
     /// change their state.
     ///
+    /// The event is first delivered to the focus item and its parents. If none of them
+    /// accepts it, the first enabled `Shortcut` item whose sequence matches it is activated.
+    /// While a dialog is shown on top of the component, only the dialog receives the event.
+    ///
     /// Arguments:
     /// * `event`: The key event received by the windowing system.
     /// * `component`: The SixtyFPS compiled component that provides the tree of items.
     pub fn process_key_input(self: Rc<Self>, event: &KeyEvent) {
+        let component = match self.platform_window.get().unwrap().key_input_component() {
+            Some(component) => component,
+            None => match self.try_component() {
+                Some(component) => component,
+                None => return,
+            },
+        };
         let mut item = self.focus_item.borrow().clone();
+        if !item.upgrade().map_or(false, |item| is_item_in_component(&item, &component)) {
+            item = Default::default();
+        }
         while let Some(focus_item) = item.upgrade() {
             let window = &ComponentWindow::new(self.clone());
             if focus_item.borrow().as_ref().key_event(event, window)
@@ -218,6 +438,36 @@ pub fn process_key_input(self: Rc<Self>, event: &KeyEvent) {
             }
             item = focus_item.parent_item();
         }
+        self.activate_shortcut(event, &component);
+    }
+
+    /// Activates the first enabled `Shortcut` item, in tree order, matching the key event.
+    /// The active popup is searched before the component.
+    fn activate_shortcut(&self, event: &KeyEvent, component: &ComponentRc) {
+        let popup = self.platform_window.get().unwrap().active_popup();
+        for component in popup.iter().chain(core::iter::once(component)) {
+            let mut matching = None;
+            crate::item_tree::visit_items(
+                component,
+                crate::item_tree::TraversalOrder::BackToFront,
+                |component, item, index, _| match ItemRef::downcast_pin::<crate::items::Shortcut>(
+                    item,
+                ) {
+                    Some(shortcut) if shortcut.matches(event) => {
+                        matching = Some(ItemRc::new(component.clone(), index));
+                        crate::item_tree::ItemVisitorResult::Abort
+                    }
+                    _ => crate::item_tree::ItemVisitorResult::Continue(()),
+                },
+                (),
+            );
+            if let Some(item) = matching {
+                let item = item.borrow();
+                let shortcut = ItemRef::downcast_pin::<crate::items::Shortcut>(item).unwrap();
+                crate::items::Shortcut::FIELD_OFFSETS.activated.apply_pin(shortcut).call(&());
+                return;
+            }
+        }
     }
 
     /// Installs a binding on the specified property that's toggled whenever the text cursor is supposed to be visible or not.
@@ -285,14 +535,40 @@ pub fn update_window_properties(self: Rc<Self>) {
         }
     }
 
+    /// Draw the `overlay` component on top of the window's component, at the given position.
+    /// The overlay does not receive any input event.
+    ///
+    /// This is meant for tools, for example to highlight an element.
+    pub fn set_overlay(&self, overlay: Option<(ComponentRc, Point)>) {
+        self.overlay.replace(overlay);
+        self.request_redraw();
+    }
+
+    /// Return the component set with [`Self::set_overlay`], to be rendered by the backend
+    pub fn overlay(&self) -> Option<(ComponentRc, Point)> {
+        self.overlay.borrow().clone()
+    }
+
+    /// Set a function called with every mouse event before it is passed to the component.
+    /// When the function returns true, the event is not passed to the component.
+    ///
+    /// This is meant for tools, for example to select an element with the mouse.
+    /// The filter must not call this function.
+    pub fn set_mouse_event_filter(&self, filter: Option<Box<dyn Fn(&MouseEvent) -> bool>>) {
+        self.mouse_event_filter.replace(filter);
+    }
+
     /// Calls draw_fn using a [`crate::properties::PropertyTracker`], which is set up to issue a call to [`PlatformWindow::request_redraw`]
     /// when any properties accessed during drawing change.
     pub fn draw_tracked<R>(self: Rc<Self>, draw_fn: impl FnOnce() -> R) -> R {
-        if let Some(redraw_tracker) = self.redraw_tracker.get() {
+        let r = if let Some(redraw_tracker) = self.redraw_tracker.get() {
             redraw_tracker.as_ref().evaluate_as_dependency_root(|| draw_fn())
         } else {
             draw_fn()
-        }
+        };
+        #[cfg(feature = "debug-bindings")]
+        crate::properties::debug::frame_finished();
+        r
     }
 }
 
@@ -373,6 +649,44 @@ pub fn show_popup(&self, popup: &ComponentRc, position: Point) {
     pub fn close_popup(&self) {
         self.0.platform_window.get().unwrap().clone().close_popup()
     }
+
+    /// Show a modal dialog
+    pub fn show_dialog(&self, dialog: &ComponentRc, presentation: DialogPresentation) {
+        self.0.platform_window.get().unwrap().clone().show_dialog(dialog, presentation)
+    }
+    /// Close the active dialog if any
+    pub fn close_dialog(&self) {
+        self.0.platform_window.get().unwrap().clone().close_dialog()
+    }
+
+    /// Returns true if the menus are shown with [`Self::show_native_menu`]
+    pub fn has_native_menus(&self) -> bool {
+        self.0.platform_window.get().unwrap().has_native_menus()
+    }
+    /// Show a native menu, `activated` is called with the index of the entry activated by the user
+    pub fn show_native_menu(
+        &self,
+        entries: &[MenuEntry],
+        position: Point,
+        activated: impl FnOnce(usize) + 'static,
+    ) {
+        self.0.platform_window.get().unwrap().show_native_menu(
+            entries,
+            position,
+            Box::new(activated),
+        )
+    }
+    /// Show a native menu bar with the menus returned by `entries`, see [`NativeMenuBar`]
+    pub fn set_native_menu_bar(
+        &self,
+        entries: impl Fn() -> Vec<MenuEntry> + 'static,
+        activated: impl Fn(usize) + 'static,
+    ) {
+        self.0.platform_window.get().unwrap().set_native_menu_bar(NativeMenuBar {
+            entries: Box::new(entries),
+            activated: Box::new(activated),
+        })
+    }
 }
 
 /// This module contains the functions needed to interface with the event loop and window traits
@@ -498,4 +812,91 @@ pub mod ffi {
         let window = &*(handle as *const ComponentWindow);
         window.close_popup();
     }
+
+    /// Show a dialog.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_component_window_show_dialog(
+        handle: *const ComponentWindowOpaque,
+        dialog: &ComponentRc,
+        presentation: DialogPresentation,
+    ) {
+        let window = &*(handle as *const ComponentWindow);
+        window.show_dialog(dialog, presentation);
+    }
+    /// Close the current dialog
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_component_window_close_dialog(
+        handle: *const ComponentWindowOpaque,
+    ) {
+        let window = &*(handle as *const ComponentWindow);
+        window.close_dialog();
+    }
+
+    /// Returns true if the menus are shown with native menus.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_component_window_has_native_menus(
+        handle: *const ComponentWindowOpaque,
+    ) -> bool {
+        let window = &*(handle as *const ComponentWindow);
+        window.has_native_menus()
+    }
+    /// The user data of a callback, dropped with `drop_user_data`
+    struct UserData {
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+    }
+
+    impl Drop for UserData {
+        fn drop(&mut self) {
+            if let Some(x) = self.drop_user_data {
+                x(self.user_data)
+            }
+        }
+    }
+
+    /// Show a native menu. `activated` is called with the index of the activated entry.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_component_window_show_native_menu(
+        handle: *const ComponentWindowOpaque,
+        entries: &crate::SharedVector<MenuEntry>,
+        position: crate::graphics::Point,
+        activated: extern "C" fn(*mut c_void, i32),
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+    ) {
+        let window = &*(handle as *const ComponentWindow);
+        let data = UserData { user_data, drop_user_data };
+        window.show_native_menu(entries.as_slice(), position, move |index| {
+            activated(data.user_data, index as i32)
+        })
+    }
+
+    /// Show a native menu bar. `entries` fills its second argument with the current entries of
+    /// the menus, and `activated` is called with the index of the activated entry.
+    #[no_mangle]
+    pub unsafe extern "C" fn sixtyfps_component_window_set_native_menu_bar(
+        handle: *const ComponentWindowOpaque,
+        entries: extern "C" fn(*mut c_void, &mut crate::SharedVector<MenuEntry>),
+        activated: extern "C" fn(*mut c_void, i32),
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+    ) {
+        let window = &*(handle as *const ComponentWindow);
+        let data = Rc::new(UserData { user_data, drop_user_data });
+        let entries_data = data.clone();
+        window.set_native_menu_bar(
+            move || {
+                let mut result = crate::SharedVector::default();
+                entries(entries_data.user_data, &mut result);
+                result.as_slice().to_vec()
+            },
+            move |index| activated(data.user_data, index as i32),
+        )
+    }
+
+    /// Returns the position of a standard button in the button row of a dialog, see [`standard_button_rank`]
+    #[no_mangle]
+    pub extern "C" fn sixtyfps_standard_button_rank(kind: StandardButtonKind) -> i32 {
+        standard_button_rank(kind)
+    }
 }
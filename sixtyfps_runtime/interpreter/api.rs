@@ -256,6 +256,7 @@ fn try_into(self) -> Result<$name, ()> {
 declare_value_struct_conversion!(struct sixtyfps_corelib::input::KeyboardModifiers { control, alt, shift, meta });
 declare_value_struct_conversion!(struct sixtyfps_corelib::input::KeyEvent { event_type, text, modifiers });
 declare_value_struct_conversion!(struct sixtyfps_corelib::layout::LayoutInfo { min, max, min_percent, max_percent, preferred, stretch });
+declare_value_struct_conversion!(struct sixtyfps_corelib::window::MenuEntry { title, shortcut, enabled, checkable, checked, is_separator, has_submenu, depth });
 
 /// Implement From / TryInto for Value that convert an `enum` to/from `Value::EnumerationValue`
 ///
@@ -302,6 +303,8 @@ fn try_into(self) -> Result<$ty, ()> {
 declare_value_enum_conversion!(sixtyfps_corelib::input::KeyEventType, KeyEventType);
 declare_value_enum_conversion!(sixtyfps_corelib::items::EventResult, EventResult);
 declare_value_enum_conversion!(sixtyfps_corelib::items::FillRule, FillRule);
+declare_value_enum_conversion!(sixtyfps_corelib::window::StandardButtonKind, StandardButtonKind);
+declare_value_enum_conversion!(sixtyfps_corelib::window::DialogPresentation, DialogPresentation);
 
 impl From<sixtyfps_corelib::animations::Instant> for Value {
     fn from(value: sixtyfps_corelib::animations::Instant) -> Self {
@@ -445,7 +448,24 @@ pub fn include_paths(&self) -> &Vec<std::path::PathBuf> {
         &self.config.include_paths
     }
 
+    /// Sets the library paths used for looking up `@library/file.60` imports to the specified
+    /// map of library names to directories.
+    pub fn set_library_paths(
+        &mut self,
+        library_paths: std::collections::HashMap<String, std::path::PathBuf>,
+    ) {
+        self.config.library_paths = library_paths;
+    }
+
+    /// Returns the library paths the component compiler is currently configured with.
+    pub fn library_paths(&self) -> &std::collections::HashMap<String, std::path::PathBuf> {
+        &self.config.library_paths
+    }
+
     /// Sets the style to be used for widgets.
+    ///
+    /// The style is either the name of a builtin style, or the path of a directory containing
+    /// a `sixtyfps_widgets.60` file that exports the same widgets as the builtin styles.
     pub fn set_style(&mut self, style: String) {
         self.config.style = Some(style);
     }
@@ -541,6 +561,51 @@ pub async fn build_from_source(
         self.diagnostics = diag.into_iter().collect();
         c.ok().map(|inner| ComponentDefinition { inner: inner.into() })
     }
+
+    /// Compile a .60 file into a ComponentDefinition for each of its exported components
+    ///
+    /// Returns a map of the compiled `ComponentDefinition`s, indexed by the name of the
+    /// component, if there were no errors. The last component of the file is always
+    /// part of the map, even if it is not exported.
+    ///
+    /// The diagnostics are collected the same way as in [`Self::build_from_path`].
+    pub async fn build_all_from_path<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Option<HashMap<String, ComponentDefinition>> {
+        let path = path.as_ref();
+        let source = match sixtyfps_compilerlib::diagnostics::load_from_path(path) {
+            Ok(s) => s,
+            Err(d) => {
+                self.diagnostics = vec![d];
+                return None;
+            }
+        };
+        self.build_all_from_source(source, path.into()).await
+    }
+
+    /// Compile some .60 code into a ComponentDefinition for each of its exported components
+    ///
+    /// Returns a map of the compiled `ComponentDefinition`s, indexed by the name of the
+    /// component, if there were no errors.
+    ///
+    /// The `path` argument and the diagnostics are handled the same way as in
+    /// [`Self::build_from_source`].
+    pub async fn build_all_from_source(
+        &mut self,
+        source_code: String,
+        path: PathBuf,
+    ) -> Option<HashMap<String, ComponentDefinition>> {
+        let (c, diag) =
+            crate::dynamic_component::load_all(source_code, path, self.config.clone()).await;
+        self.diagnostics = diag.into_iter().collect();
+        c.ok().map(|definitions| {
+            definitions
+                .into_iter()
+                .map(|(name, inner)| (name, ComponentDefinition { inner }))
+                .collect()
+        })
+    }
 }
 
 /// ComponentDefinition is a representation of a compiled component from .60 markup.
@@ -699,13 +764,37 @@ pub fn get_property(&self, name: &str) -> Result<Value, GetPropertyError> {
             .map_err(|()| GetPropertyError::NoSuchProperty)
     }
 
+    /// Return the value of a public property of this component, together with the properties
+    /// its binding depends on, and the bindings which depend on it.
+    ///
+    /// The binding of the property is evaluated if it is dirty.
+    /// This function is only available with the `debug-bindings` feature.
+    #[cfg(feature = "debug-bindings")]
+    pub fn debug_property(
+        &self,
+        name: &str,
+    ) -> Result<sixtyfps_corelib::properties::debug::PropertyDebugInfo, GetPropertyError> {
+        let value = self.get_property(name)?;
+        generativity::make_guard!(guard);
+        let comp = self.inner.unerase(guard);
+        let mut info = comp
+            .description()
+            .property_debug_info(comp.borrow(), name)
+            .map_err(|()| GetPropertyError::NoSuchProperty)?;
+        info.value = Some(format!("{:?}", value));
+        Ok(info)
+    }
+
     /// Set the value for a public property of this component
     pub fn set_property(&self, name: &str, value: Value) -> Result<(), SetPropertyError> {
         generativity::make_guard!(guard);
         let comp = self.inner.unerase(guard);
-        comp.description()
-            .set_property(comp.borrow(), name, value)
-            .map_err(|()| todo!("set_property don't return the right error type"))
+        comp.description().set_property(comp.borrow(), name, value).map_err(|()| {
+            match comp.description().get_property(comp.borrow(), name) {
+                Ok(_) => SetPropertyError::WrongType,
+                Err(()) => SetPropertyError::NoSuchProperty,
+            }
+        })
     }
 
     /// Set a handler for the callback with the given name. A callback with that
@@ -784,7 +873,7 @@ pub fn hide(&self) {
     /// and [`Self::hide`].
     pub fn run(&self) {
         self.show();
-        sixtyfps_rendering_backend_default::backend().run_event_loop(
+        crate::backend().run_event_loop(
             sixtyfps_corelib::backend::EventLoopQuitBehavior::QuitOnLastWindowClosed,
         );
         self.hide();
@@ -814,6 +903,157 @@ pub fn window(&self) -> sixtyfps_corelib::window::ComponentWindow {
         let comp = self.inner.unerase(guard);
         comp.window()
     }
+
+    /// Return the component as a type erased ComponentRc, for example to be passed to
+    /// the window as an overlay.
+    /// This method is internal because the ComponentRc is not a public type
+    #[doc(hidden)]
+    pub fn component_rc(&self) -> sixtyfps_corelib::component::ComponentRc {
+        vtable::VRc::into_dyn(self.inner.clone())
+    }
+
+    /// Return the top-most element at the position `x`, `y` of the window, in logical pixels.
+    ///
+    /// This is meant for tools that inspect the elements, such as the inspector of the viewer.
+    pub fn element_at(&self, x: f32, y: f32) -> Option<ElementHandle> {
+        use sixtyfps_corelib::graphics::Point;
+        use sixtyfps_corelib::item_tree::{ItemVisitorResult, TraversalOrder};
+        let component = self.component_rc();
+        let position = Point::new(x, y);
+        let mut result = None;
+        sixtyfps_corelib::item_tree::visit_items(
+            &component,
+            TraversalOrder::BackToFront,
+            |component, item, index, origin: &Point| {
+                let geometry = item.as_ref().geometry().translate(origin.to_vector());
+                if geometry.contains(position) {
+                    result = Some(sixtyfps_corelib::items::ItemRc::new(component.clone(), index));
+                }
+                ItemVisitorResult::Continue(geometry.origin)
+            },
+            Point::default(),
+        );
+        result.map(|item| ElementHandle { item })
+    }
+}
+
+/// An element of a [`ComponentInstance`], as returned by [`ComponentInstance::element_at`].
+///
+/// This is meant for tools that inspect the elements, such as the inspector of the viewer.
+#[derive(Clone)]
+pub struct ElementHandle {
+    item: sixtyfps_corelib::items::ItemRc,
+}
+
+impl ElementHandle {
+    fn with_item<R>(
+        &self,
+        f: impl FnOnce(&crate::dynamic_component::ItemWithinComponent) -> R,
+    ) -> R {
+        let component = self.item.component();
+        let component = vtable::VRc::borrow_pin(&component);
+        let component =
+            vtable::VRef::downcast_pin::<crate::dynamic_component::ErasedComponentBox>(component)
+                .expect("all the components of an interpreted component are dynamic components");
+        generativity::make_guard!(guard);
+        let component = component.unerase(guard);
+        // Compare the addresses, since the items of the sub-components have their own indices
+        let item_ptr = self.item.borrow().as_ptr();
+        let mut found = None;
+        component.borrow_instance().visit_items(&mut |item_within_component, item| {
+            if item.as_ptr() == item_ptr {
+                found = Some(item_within_component);
+            }
+        });
+        f(found.expect("the item must be in its component"))
+    }
+
+    /// The id of the element as written in the .60 file, or an empty string if it has none
+    pub fn id(&self) -> String {
+        use sixtyfps_compilerlib::parser::SyntaxKind;
+        self.with_item(|item| {
+            item.elem
+                .borrow()
+                .node
+                .as_ref()
+                .and_then(|n| n.parent())
+                .filter(|n| n.kind() == SyntaxKind::SubElement)
+                .and_then(|n| n.child_token(SyntaxKind::Identifier))
+                .map_or(String::new(), |t| t.text().to_string())
+        })
+    }
+
+    /// The name of the type of the element as written in the .60 file, for example `Button`
+    pub fn type_name(&self) -> String {
+        self.with_item(|item| {
+            let elem = item.elem.borrow();
+            elem.node
+                .as_ref()
+                .and_then(|n| n.QualifiedName())
+                .map(|q| q.text().to_string().trim().to_owned())
+                .unwrap_or_else(|| elem.base_type.to_string())
+        })
+    }
+
+    /// The geometry of the element, relative to the window
+    pub fn geometry(&self) -> sixtyfps_corelib::graphics::Rect {
+        let mut geometry = self.item.borrow().as_ref().geometry();
+        let mut parent = self.item.parent_item().upgrade();
+        while let Some(item) = parent {
+            geometry.origin += item.borrow().as_ref().geometry().origin.to_vector();
+            parent = item.parent_item().upgrade();
+        }
+        geometry
+    }
+
+    /// The path of the .60 file, and the line and column (starting at 1) where the element is declared
+    pub fn source_location(&self) -> Option<(PathBuf, usize, usize)> {
+        use sixtyfps_compilerlib::diagnostics::Spanned;
+        use sixtyfps_compilerlib::parser::SyntaxKind;
+        self.with_item(|item| {
+            let elem = item.elem.borrow();
+            let node = elem.node.as_ref()?;
+            let location =
+                match node.QualifiedName().and_then(|q| q.child_token(SyntaxKind::Identifier)) {
+                    Some(token) => token.to_source_location(),
+                    None => node.to_source_location(),
+                };
+            let ((line, column), _) = location.line_column_range()?;
+            Some((location.source_file.as_ref()?.path().to_owned(), line, column))
+        })
+    }
+
+    /// The properties of the builtin element, and their current value, sorted by name
+    pub fn properties(&self) -> Vec<(String, Value)> {
+        self.with_item(|item| {
+            let mut properties = item
+                .rtti
+                .properties
+                .iter()
+                .map(|(name, prop)| (name.replace('_', "-"), prop.get(self.item.borrow())))
+                .collect::<Vec<_>>();
+            properties.sort_by(|a, b| a.0.cmp(&b.0));
+            properties
+        })
+    }
+
+    /// Change the value of a property of the builtin element.
+    /// The value must have the same type as the current value of the property.
+    pub fn set_property(&self, name: &str, value: Value) -> Result<(), SetPropertyError> {
+        self.with_item(|item| {
+            let prop = item
+                .rtti
+                .properties
+                .get(name.replace('-', "_").as_str())
+                .ok_or(SetPropertyError::NoSuchProperty)?;
+            let value_type = prop.get(self.item.borrow()).value_type();
+            if value_type == ValueType::Other || value_type != value.value_type() {
+                return Err(SetPropertyError::WrongType);
+            }
+            prop.set(self.item.borrow(), value, None);
+            Ok(())
+        })
+    }
 }
 
 /// A Weak references to a dynamic SixtyFPS components.
@@ -879,7 +1119,7 @@ pub enum CallCallbackError {
 /// events from the windowing system in order to render to the screen
 /// and react to user input.
 pub fn run_event_loop() {
-    sixtyfps_rendering_backend_default::backend()
+    crate::backend()
         .run_event_loop(sixtyfps_corelib::backend::EventLoopQuitBehavior::QuitOnLastWindowClosed);
 }
 
@@ -958,7 +1198,125 @@ fn component_definition_properties2() {
     assert_eq!(callbacks[0], "hello");
 }
 
+#[test]
+fn component_compiler_build_all() {
+    let mut compiler = ComponentCompiler::default();
+    let definitions = spin_on::spin_on(
+        compiler.build_all_from_source(
+            r#"
+    global Config := { property <int> scale: 2; }
+    export AboutBox := Rectangle {
+        property <string> version: "1.0";
+    }
+    NotExported := Rectangle {}
+    export MainWindow := Rectangle {
+        property <int> scale: Config.scale;
+        AboutBox {}
+    }"#
+            .into(),
+            "".into(),
+        ),
+    )
+    .unwrap();
+
+    let mut names = definitions.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["AboutBox", "MainWindow"]);
+
+    let about = definitions["AboutBox"].create();
+    assert_eq!(about.get_property("version").unwrap(), Value::from(SharedString::from("1.0")));
+    let main = definitions["MainWindow"].create();
+    assert_eq!(main.get_property("scale").unwrap(), Value::Number(2.));
+}
+
+#[cfg(feature = "debug-bindings")]
+#[test]
+fn component_debug_property() {
+    let mut compiler = ComponentCompiler::default();
+    let comp_def = spin_on::spin_on(
+        compiler.build_from_source(
+            r#"
+    export Dummy := Rectangle {
+        property <int> base: 4;
+        property <int> double: base * 2;
+        property <int> quadruple: double * 2;
+    }"#
+            .into(),
+            "".into(),
+        ),
+    )
+    .unwrap();
+    let instance = comp_def.create();
+
+    let info = instance.debug_property("double").unwrap();
+    assert_eq!(info.value.as_deref(), Some("Value::Number(8.0)"));
+    assert!(info.has_binding);
+    assert_eq!(info.dependencies.len(), 1);
+    assert!(info.dependencies[0].ends_with(".base"), "{:?}", info.dependencies);
+    assert!(info.dependents.is_empty());
+
+    instance.get_property("quadruple").unwrap();
+    let info = instance.debug_property("double").unwrap();
+    assert_eq!(info.dependents.len(), 1);
+    assert!(info.dependents[0].ends_with(".quadruple"), "{:?}", info.dependents);
+
+    assert_eq!(instance.debug_property("not_there"), Err(GetPropertyError::NoSuchProperty));
+}
+
 #[cfg(feature = "ffi")]
 #[allow(missing_docs)]
 #[path = "ffi.rs"]
 pub(crate) mod ffi;
+
+#[test]
+fn component_element_at() {
+    let mut compiler = ComponentCompiler::default();
+    let comp_def = spin_on::spin_on(
+        compiler.build_from_source(
+            r#"
+    export Dummy := Rectangle {
+        width: 100px;
+        height: 100px;
+        Rectangle {
+            x: 10px;
+            y: 10px;
+            width: 50px;
+            height: 50px;
+            inner := Rectangle {
+                x: 5px;
+                y: 5px;
+                width: 20px;
+                height: 20px;
+                background: red;
+            }
+        }
+    }"#
+            .into(),
+            "test.60".into(),
+        ),
+    )
+    .unwrap();
+    let instance = comp_def.create();
+
+    let element = instance.element_at(20., 20.).unwrap();
+    assert_eq!(element.id(), "inner");
+    assert_eq!(element.type_name(), "Rectangle");
+    assert_eq!(
+        element.geometry(),
+        sixtyfps_corelib::graphics::Rect::new((15., 15.).into(), (20., 20.).into())
+    );
+    let (path, line, column) = element.source_location().unwrap();
+    assert_eq!((path.to_str(), line, column), (Some("test.60"), 10, 22));
+
+    element.set_property("width", Value::Number(30.)).unwrap();
+    assert_eq!(element.geometry().width(), 30.);
+    assert!(element.properties().contains(&("width".into(), Value::Number(30.))));
+    assert_eq!(element.set_property("width", Value::Bool(true)), Err(SetPropertyError::WrongType));
+    assert_eq!(
+        element.set_property("no-such-property", Value::Bool(true)),
+        Err(SetPropertyError::NoSuchProperty)
+    );
+
+    assert_eq!(instance.element_at(12., 12.).unwrap().id(), "");
+    assert!(instance.element_at(200., 200.).is_none());
+}
@@ -12,7 +12,7 @@
 use core::convert::TryInto;
 use core::ptr::NonNull;
 use dynamic_type::{Instance, InstanceBox};
-use sixtyfps_compilerlib::expression_tree::{Expression, NamedReference};
+use sixtyfps_compilerlib::expression_tree::{BindingExpression, Expression, NamedReference};
 use sixtyfps_compilerlib::langtype::Type;
 use sixtyfps_compilerlib::object_tree::{Element, ElementRc};
 use sixtyfps_compilerlib::*;
@@ -72,14 +72,8 @@ fn drop(&mut self) {
         let instance_ref = self.borrow_instance();
         match eval::window_ref(instance_ref) {
             Some(window) => {
-                let items = self
-                    .component_type
-                    .items
-                    .values()
-                    .map(|item_within_component| unsafe {
-                        item_within_component.item_from_component(instance_ref.as_ptr())
-                    })
-                    .collect::<Vec<_>>();
+                let mut items = vec![];
+                instance_ref.visit_items(&mut |_, item| items.push(item));
 
                 window.free_graphics_resources(&Slice::from_slice(items.as_slice()));
             }
@@ -91,7 +85,7 @@ fn drop(&mut self) {
 pub(crate) struct ItemWithinComponent {
     offset: usize,
     pub(crate) rtti: Rc<ItemRTTI>,
-    elem: ElementRc,
+    pub(crate) elem: ElementRc,
 }
 
 impl ItemWithinComponent {
@@ -124,6 +118,14 @@ pub(crate) struct RepeaterWithinComponent<'par_id, 'sub_id> {
     offset: FieldOffset<Instance<'par_id>, Repeater<ErasedComponentBox>>,
 }
 
+/// An instance of a sub-component, embedded within the instance of a component
+pub(crate) struct SubComponentWithinComponent {
+    /// Offset of the instance of the sub-component
+    offset: usize,
+    /// The description of the sub-component
+    description: ErasedComponentDescription,
+}
+
 impl RepeatedComponent for ErasedComponentBox {
     type Data = Value;
 
@@ -290,6 +292,8 @@ pub struct ComponentDescription<'id> {
     repeater: Vec<ErasedRepeaterWithinComponent<'id>>,
     /// Map the Element::id of the repeater to the index in the `repeater` vec
     pub repeater_names: HashMap<String, usize>,
+    /// Map the Element::id of the instances of sub-components to their instance
+    pub(crate) sub_components: HashMap<String, SubComponentWithinComponent>,
     /// Offset to a Option<ComponentPinRef>
     pub(crate) parent_component_offset:
         Option<FieldOffset<Instance<'id>, Option<ComponentRefPin<'id>>>>,
@@ -329,11 +333,11 @@ pub fn create(
         #[cfg(target_arch = "wasm32")] canvas_id: String,
     ) -> vtable::VRc<ComponentVTable, ErasedComponentBox> {
         #[cfg(not(target_arch = "wasm32"))]
-        let window = sixtyfps_rendering_backend_default::backend().create_window();
+        let window = crate::backend().create_window();
         #[cfg(target_arch = "wasm32")]
         let window = {
             // Ensure that the backend is initialized
-            sixtyfps_rendering_backend_default::backend();
+            crate::backend();
             sixtyfps_rendering_backend_gl::create_gl_window_with_canvas_id(canvas_id)
         };
         self.create_with_existing_window(window)
@@ -434,6 +438,34 @@ pub fn get_property(&self, component: ComponentRefPin, name: &str) -> Result<Val
         }
     }
 
+    /// Return the debug information of a property, without its value
+    ///
+    /// Returns an error if the component is not an instance corresponding to this ComponentDescription,
+    /// or if the property with this name does not exist in this component
+    #[cfg(feature = "debug-bindings")]
+    pub fn property_debug_info(
+        &self,
+        component: ComponentRefPin,
+        name: &str,
+    ) -> Result<sixtyfps_corelib::properties::debug::PropertyDebugInfo, ()> {
+        if !core::ptr::eq((&self.ct) as *const _, component.get_vtable() as *const _) {
+            return Err(());
+        }
+        generativity::make_guard!(guard);
+        // Safety: we just verified that the component has the right vtable
+        let c = unsafe { InstanceRef::from_pin_ref(component, guard) };
+        let root = &self.original.root_element;
+        let alias = root.borrow().property_declarations.get(name).and_then(|d| d.is_alias.clone());
+        let nr = alias.unwrap_or_else(|| NamedReference::new(root, name));
+        if !nr.element().borrow().lookup_property(nr.name()).property_type.is_property_type() {
+            return Err(());
+        }
+        // Safety: get_property_ptr returns a pointer to a Property
+        Ok(unsafe {
+            sixtyfps_corelib::properties::debug::property_debug_info(get_property_ptr(&nr, c))
+        })
+    }
+
     /// Sets an handler for a callback
     ///
     /// Returns an error if the component is not an instance corresponding to this ComponentDescription,
@@ -635,17 +667,50 @@ fn rtti_for<T: 'static + Default + rtti::BuiltinItem + vtable::HasStaticVTable<I
 pub async fn load<'id>(
     source: String,
     path: std::path::PathBuf,
-    mut compiler_config: CompilerConfiguration,
+    compiler_config: CompilerConfiguration,
     guard: generativity::Guard<'id>,
 ) -> (Result<Rc<ComponentDescription<'id>>, ()>, sixtyfps_compilerlib::diagnostics::BuildDiagnostics)
 {
+    let (doc, diag) = compile(source, path, compiler_config).await;
+    (doc.map(|doc| generate_component(&doc.root_component, guard)), diag)
+}
+
+/// Create a ComponentDescription for each exported component of the source, indexed by the
+/// name of the component.
+pub async fn load_all(
+    source: String,
+    path: std::path::PathBuf,
+    compiler_config: CompilerConfiguration,
+) -> (
+    Result<HashMap<String, ErasedComponentDescription>, ()>,
+    sixtyfps_compilerlib::diagnostics::BuildDiagnostics,
+) {
+    let (doc, diag) = compile(source, path, compiler_config).await;
+    let descriptions = doc.map(|doc| {
+        doc.exported_roots
+            .iter()
+            .map(|root| {
+                generativity::make_guard!(guard);
+                (root.id.clone(), generate_component(root, guard).into())
+            })
+            .collect()
+    });
+    (descriptions, diag)
+}
+
+async fn compile(
+    source: String,
+    path: std::path::PathBuf,
+    mut compiler_config: CompilerConfiguration,
+) -> (Result<object_tree::Document, ()>, sixtyfps_compilerlib::diagnostics::BuildDiagnostics) {
     if compiler_config.style.is_none() && std::env::var("SIXTYFPS_STYLE").is_err() {
         // Defaults to native if it exists:
-        compiler_config.style = Some(if sixtyfps_rendering_backend_default::HAS_NATIVE_STYLE {
-            "native".to_owned()
-        } else {
-            "ugly".to_owned()
-        });
+        #[cfg(feature = "backend-default")]
+        let has_native_style = sixtyfps_rendering_backend_default::HAS_NATIVE_STYLE;
+        #[cfg(not(feature = "backend-default"))]
+        let has_native_style = false;
+        compiler_config.style =
+            Some(if has_native_style { "native".to_owned() } else { "ugly".to_owned() });
     }
 
     let mut diag = BuildDiagnostics::default();
@@ -657,7 +722,7 @@ pub async fn load<'id>(
     if diag.has_error() {
         return (Err(()), diag);
     }
-    (Ok(generate_component(&doc.root_component, guard)), diag)
+    (Ok(doc), diag)
 }
 
 pub(crate) fn generate_component<'id>(
@@ -676,6 +741,7 @@ pub(crate) fn generate_component<'id>(
                 rtti_for::<BorderRectangle>(),
                 rtti_for::<TouchArea>(),
                 rtti_for::<FocusScope>(),
+                rtti_for::<Shortcut>(),
                 rtti_for::<Path>(),
                 rtti_for::<Flickable>(),
                 rtti_for::<Window>(),
@@ -706,7 +772,12 @@ fn push(rtti: &mut HashMap<&str, Rc<ItemRTTI>>) {
                 Next::push(rtti);
             }
         }
-        sixtyfps_rendering_backend_default::NativeWidgets::push(&mut rtti);
+        #[cfg(feature = "backend-default")]
+        type NativeWidgets = sixtyfps_rendering_backend_default::NativeWidgets;
+        // Only the native style uses native widgets
+        #[cfg(not(feature = "backend-default"))]
+        type NativeWidgets = ();
+        NativeWidgets::push(&mut rtti);
     }
     let rtti = Rc::new(rtti);
 
@@ -716,10 +787,44 @@ fn push(rtti: &mut HashMap<&str, Rc<ItemRTTI>>) {
 
     let mut repeater = vec![];
     let mut repeater_names = HashMap::new();
+    let mut sub_components = HashMap::<String, SubComponentWithinComponent>::new();
 
-    generator::build_array_helper(component, |rc_item, child_offset, parent_index| {
+    generator::build_array_helper(component, |rc_item, child_offset, parent_index, sub_path| {
         let item = rc_item.borrow();
-        if item.base_type == Type::Void {
+        if let [instance, nested @ ..] = sub_path {
+            if nested.is_empty() && !sub_components.contains_key(&instance.borrow().id) {
+                let sub = instance.borrow().sub_component().unwrap().clone();
+                generativity::make_guard!(guard);
+                let description = generate_component(&sub, guard);
+                let offset = builder.add_sub_type(description.dynamic_type.clone());
+                sub_components.insert(
+                    instance.borrow().id.clone(),
+                    SubComponentWithinComponent { offset, description: description.into() },
+                );
+            }
+            // Find the offset of the item by walking down the instances of sub-components.
+            // The 'static descriptions are only used to read the offsets.
+            let outer = &sub_components[&instance.borrow().id];
+            let mut offset = outer.offset;
+            let mut description = &outer.description.0;
+            for instance in nested {
+                let sub = &description.sub_components[&instance.borrow().id];
+                offset += sub.offset;
+                description = &sub.description.0;
+            }
+            let item_within_component = &description.items[&item.id];
+            tree_array.push(ItemTreeNode::Item {
+                item: unsafe {
+                    vtable::VOffset::from_raw(
+                        item_within_component.rtti.vtable,
+                        offset + item_within_component.offset,
+                    )
+                },
+                children_index: child_offset,
+                children_count: item.children.len() as u32,
+                parent_index,
+            });
+        } else if item.base_type == Type::Void {
             assert!(component.is_global());
         } else if let Some(repeated) = &item.repeated {
             tree_array.push(ItemTreeNode::DynamicTree { index: repeater.len(), parent_index });
@@ -822,6 +927,7 @@ fn animated_property_info<T: Clone + Default + InterpolatedPropertyValue + 'stat
             Type::Struct { .. } => property_info::<Value>(),
             Type::Array(_) => property_info::<Value>(),
             Type::Percent => property_info::<f32>(),
+            Type::Enumeration(e) if e.node.is_some() => property_info::<Value>(),
             Type::Enumeration(e) => match e.name.as_ref() {
                 "LayoutAlignment" => property_info::<sixtyfps_corelib::layout::LayoutAlignment>(),
                 "TextHorizontalAlignment" => {
@@ -834,6 +940,12 @@ fn animated_property_info<T: Clone + Default + InterpolatedPropertyValue + 'stat
                 "TextOverflow" => property_info::<sixtyfps_corelib::items::TextOverflow>(),
                 "ImageFit" => property_info::<sixtyfps_corelib::items::ImageFit>(),
                 "FillRule" => property_info::<sixtyfps_corelib::items::FillRule>(),
+                "StandardButtonKind" => {
+                    property_info::<sixtyfps_corelib::window::StandardButtonKind>()
+                }
+                "DialogPresentation" => {
+                    property_info::<sixtyfps_corelib::window::DialogPresentation>()
+                }
                 _ => panic!("unknown enum"),
             },
             Type::LayoutCache => property_info::<SharedVector<f32>>(),
@@ -902,6 +1014,7 @@ fn animated_property_info<T: Clone + Default + InterpolatedPropertyValue + 'stat
         original: component.clone(),
         repeater,
         repeater_names,
+        sub_components,
         parent_component_offset,
         window_offset,
         extra_data_offset,
@@ -988,6 +1101,19 @@ pub fn instantiate<'id>(
             .map(|g| crate::global_component::instantiate(g))
             .collect();
     }
+    if !component_type.sub_components.is_empty() {
+        let globals = match parent_ctx {
+            Some(parent) => {
+                generativity::make_guard!(guard);
+                let parent = unsafe { InstanceRef::from_pin_ref(parent, guard) };
+                generativity::make_guard!(guard);
+                let root = eval::root_component_instance(parent, guard);
+                root.component_type.extra_data_offset.apply(root.as_ref()).globals.clone()
+            }
+            None => component_type.extra_data_offset.apply(instance.as_mut()).globals.clone(),
+        };
+        init_sub_component_data(&component_type, instance.as_mut(), &window, &globals);
+    }
     *component_type.window_offset.apply_mut(instance.as_mut()) = window;
 
     let component_box = ComponentBox { instance, component_type: component_type.clone() };
@@ -1001,198 +1127,7 @@ pub fn instantiate<'id>(
         );
     }
 
-    // Some properties are generated as Value, but for which the default constructed Value must be initialized
-    for (prop_name, decl) in &component_type.original.root_element.borrow().property_declarations {
-        if !matches!(decl.property_type, Type::Struct { .. } | Type::Array(_)) {
-            continue;
-        }
-        if let Some(b) = component_type.original.root_element.borrow().bindings.get(prop_name) {
-            if !matches!(b.expression, Expression::TwoWayBinding(..)) {
-                continue;
-            }
-        }
-        let p = component_type.custom_properties.get(prop_name).unwrap();
-        unsafe {
-            let item = Pin::new_unchecked(&*instance_ref.as_ptr().add(p.offset));
-            p.prop.set(item, eval::default_value_for_type(&decl.property_type), None).unwrap();
-        }
-    }
-
-    generator::handle_property_bindings_init(
-        &component_type.original,
-        |elem, prop_name, binding| unsafe {
-            let is_root = Rc::ptr_eq(
-                elem,
-                &elem.borrow().enclosing_component.upgrade().unwrap().root_element,
-            );
-            let elem = elem.borrow();
-            let is_const = binding.analysis.borrow().as_ref().map_or(false, |a| a.is_const);
-
-            let property_type = elem.lookup_property(prop_name).property_type;
-            if let Type::Callback { .. } = property_type {
-                let expr = binding.expression.clone();
-                let component_type = component_type.clone();
-                let instance = component_box.instance.as_ptr();
-                let c = Pin::new_unchecked(vtable::VRef::from_raw(
-                    NonNull::from(&component_type.ct).cast(),
-                    instance.cast(),
-                ));
-                if let Some(callback_offset) =
-                    component_type.custom_callbacks.get(prop_name).filter(|_| is_root)
-                {
-                    let callback = callback_offset.apply(instance_ref.as_ref());
-                    callback.set_handler(move |args| {
-                        generativity::make_guard!(guard);
-                        let mut local_context = eval::EvalLocalContext::from_function_arguments(
-                            InstanceRef::from_pin_ref(c, guard),
-                            args.to_vec(),
-                        );
-                        eval::eval_expression(&expr, &mut local_context)
-                    })
-                } else {
-                    let item_within_component = &component_type.items[&elem.id];
-                    let item = item_within_component.item_from_component(instance_ref.as_ptr());
-                    if let Some(callback) = item_within_component.rtti.callbacks.get(prop_name) {
-                        callback.set_handler(
-                            item,
-                            Box::new(move |args| {
-                                generativity::make_guard!(guard);
-                                let mut local_context =
-                                    eval::EvalLocalContext::from_function_arguments(
-                                        InstanceRef::from_pin_ref(c, guard),
-                                        args.iter().cloned().collect(),
-                                    );
-                                eval::eval_expression(&expr, &mut local_context)
-                            }),
-                        )
-                    } else {
-                        panic!("unknown callback {}", prop_name)
-                    }
-                }
-            } else if let Some(PropertiesWithinComponent { offset, prop: prop_info, .. }) =
-                component_type.custom_properties.get(prop_name).filter(|_| is_root)
-            {
-                let c = Pin::new_unchecked(vtable::VRef::from_raw(
-                    NonNull::from(&component_type.ct).cast(),
-                    component_box.instance.as_ptr().cast(),
-                ));
-
-                let is_state_info = match property_type {
-                    Type::Struct { name: Some(name), .. } if name.ends_with("::StateInfo") => true,
-                    _ => false,
-                };
-                if is_state_info {
-                    let prop = Pin::new_unchecked(
-                        &*(instance_ref.as_ptr().add(*offset)
-                            as *const Property<sixtyfps_corelib::properties::StateInfo>),
-                    );
-                    let e = binding.expression.clone();
-                    sixtyfps_corelib::properties::set_state_binding(prop, move || {
-                        generativity::make_guard!(guard);
-                        eval::eval_expression(
-                            &e,
-                            &mut eval::EvalLocalContext::from_component_instance(
-                                InstanceRef::from_pin_ref(c, guard),
-                            ),
-                        )
-                        .try_into()
-                        .unwrap()
-                    });
-                    return;
-                }
-
-                let maybe_animation = animation_for_property(
-                    instance_ref,
-                    &component_type.original.root_element.borrow(),
-                    prop_name,
-                );
-                let item = Pin::new_unchecked(&*instance_ref.as_ptr().add(*offset));
-
-                let mut e = Some(&binding.expression);
-                while let Some(Expression::TwoWayBinding(nr, next)) = &e {
-                    // Safety: The compiler must have ensured that the properties exist and are of the same type
-                    prop_info.link_two_ways(item, get_property_ptr(&nr, instance_ref));
-                    e = next.as_deref();
-                }
-                if let Some(e) = e {
-                    if is_const || e.is_constant() {
-                        let v = eval::eval_expression(
-                            e,
-                            &mut eval::EvalLocalContext::from_component_instance(instance_ref),
-                        );
-                        prop_info.set(item, v, None).unwrap();
-                    } else {
-                        let e = e.clone();
-                        prop_info
-                            .set_binding(
-                                item,
-                                Box::new(move || {
-                                    generativity::make_guard!(guard);
-                                    eval::eval_expression(
-                                        &e,
-                                        &mut eval::EvalLocalContext::from_component_instance(
-                                            InstanceRef::from_pin_ref(c, guard),
-                                        ),
-                                    )
-                                }),
-                                maybe_animation,
-                            )
-                            .unwrap();
-                    }
-                }
-            } else {
-                let item_within_component = &component_type.items[&elem.id];
-                let item = item_within_component.item_from_component(instance_ref.as_ptr());
-                if let Some(prop_rtti) = item_within_component.rtti.properties.get(prop_name) {
-                    let maybe_animation = animation_for_property(instance_ref, &elem, prop_name);
-                    let mut e = Some(&binding.expression);
-                    while let Some(Expression::TwoWayBinding(nr, next)) = &e {
-                        // Safety: The compiler must have ensured that the properties exist and are of the same type
-                        prop_rtti.link_two_ways(item, get_property_ptr(&nr, instance_ref));
-                        e = next.as_deref();
-                    }
-                    if let Some(e) = e {
-                        if is_const || e.is_constant() {
-                            prop_rtti.set(
-                                item,
-                                eval::eval_expression(
-                                    e,
-                                    &mut eval::EvalLocalContext::from_component_instance(
-                                        instance_ref,
-                                    ),
-                                ),
-                                maybe_animation.as_animation(),
-                            );
-                        } else {
-                            let e = e.clone();
-                            let component_type = component_type.clone();
-                            let instance = component_box.instance.as_ptr();
-                            let c = Pin::new_unchecked(vtable::VRef::from_raw(
-                                NonNull::from(&component_type.ct).cast(),
-                                instance.cast(),
-                            ));
-
-                            prop_rtti.set_binding(
-                                item,
-                                Box::new(move || {
-                                    generativity::make_guard!(guard);
-                                    eval::eval_expression(
-                                        &e,
-                                        &mut eval::EvalLocalContext::from_component_instance(
-                                            InstanceRef::from_pin_ref(c, guard),
-                                        ),
-                                    )
-                                }),
-                                maybe_animation,
-                            );
-                        }
-                    }
-                } else {
-                    panic!("unknown property {}", prop_name);
-                }
-            }
-        },
-    );
+    unsafe { init_bindings(instance_ref) };
 
     for rep_in_comp in &component_type.repeater {
         generativity::make_guard!(guard);
@@ -1234,6 +1169,243 @@ pub fn instantiate<'id>(
     comp_rc
 }
 
+/// Set the window and the globals of the instances of the sub-components, recursively
+fn init_sub_component_data(
+    component_type: &ComponentDescription,
+    instance: &mut Instance,
+    window: &Option<ComponentWindow>,
+    globals: &HashMap<String, Pin<Rc<dyn crate::global_component::GlobalComponent>>>,
+) {
+    for sub in component_type.sub_components.values() {
+        generativity::make_guard!(guard);
+        let sub_type = sub.description.unerase(guard);
+        // Safety: the instance of the sub-component was created at this offset with this description
+        let sub_instance = unsafe {
+            &mut *((instance as *mut Instance as *mut u8).add(sub.offset) as *mut Instance)
+        };
+        *sub_type.window_offset.apply_mut(sub_instance) = window.clone();
+        sub_type.extra_data_offset.apply_mut(sub_instance).globals = globals.clone();
+        init_sub_component_data(sub_type, sub_instance, window, globals);
+    }
+}
+
+/// Initialize the properties and install the bindings of a component instance, after having
+/// done so for the instances of its sub-components.
+///
+/// Safety: the instance must be pinned and must outlive the bindings
+unsafe fn init_bindings(instance_ref: InstanceRef) {
+    for instance_id in instance_ref.component_type.sub_components.keys() {
+        generativity::make_guard!(guard);
+        init_bindings(instance_ref.sub_component(instance_id, guard));
+    }
+
+    let component_type = instance_ref.component_type;
+    // Some properties are generated as Value, but for which the default constructed Value must be initialized
+    for (prop_name, decl) in &component_type.original.root_element.borrow().property_declarations {
+        match &decl.property_type {
+            Type::Struct { .. } | Type::Array(_) => {}
+            Type::Enumeration(e) if e.node.is_some() => {}
+            _ => continue,
+        }
+        if let Some(b) = component_type.original.root_element.borrow().bindings.get(prop_name) {
+            if !matches!(b.expression, Expression::TwoWayBinding(..)) {
+                continue;
+            }
+        }
+        let p = component_type.custom_properties.get(prop_name).unwrap();
+        let item = Pin::new_unchecked(&*instance_ref.as_ptr().add(p.offset));
+        p.prop.set(item, eval::default_value_for_type(&decl.property_type), None).unwrap();
+    }
+
+    // Name all the properties declared in the root element, so they can be identified in the
+    // binding loops. The other properties are named when their binding is set.
+    for (prop_name, p) in &component_type.custom_properties {
+        sixtyfps_corelib::properties::set_property_debug_name(
+            instance_ref.as_ptr().add(p.offset) as *const (),
+            format!("{}.{}", component_type.original.root_element.borrow().id, prop_name),
+        )
+    }
+
+    generator::handle_property_bindings_init(
+        &component_type.original,
+        |elem, prop_name, binding| install_binding(instance_ref, elem, prop_name, binding),
+    );
+}
+
+/// Install the binding of the property `prop_name` of `elem`, which is evaluated in the context
+/// of `instance_ref`. If `elem` is an instance of a sub-component, the binding is set on the
+/// property of the sub-component's instance.
+unsafe fn install_binding(
+    instance_ref: InstanceRef,
+    elem: &ElementRc,
+    prop_name: &str,
+    binding: &BindingExpression,
+) {
+    let c = Pin::new_unchecked(vtable::VRef::from_raw(
+        NonNull::from(&instance_ref.component_type.ct).cast(),
+        NonNull::from(instance_ref.instance.get_ref()).cast(),
+    ));
+    generativity::make_guard!(guard);
+    let (target, target_elem, target_name) =
+        eval::resolve_sub_component_property(instance_ref, elem, prop_name, guard);
+    let target_name = target_name.as_str();
+    let component_type = target.component_type;
+    let is_root = Rc::ptr_eq(
+        &target_elem,
+        &target_elem.borrow().enclosing_component.upgrade().unwrap().root_element,
+    );
+    let target_elem = target_elem.borrow();
+    let elem = elem.borrow();
+    let is_const = binding.analysis.borrow().as_ref().map_or(false, |a| a.is_const);
+
+    let property_type = elem.lookup_property(prop_name).property_type;
+    if let Type::Callback { .. } = property_type {
+        let expr = binding.expression.clone();
+        if let Some(callback_offset) =
+            component_type.custom_callbacks.get(target_name).filter(|_| is_root)
+        {
+            let callback = callback_offset.apply(target.as_ref());
+            callback.set_handler(move |args| {
+                generativity::make_guard!(guard);
+                let mut local_context = eval::EvalLocalContext::from_function_arguments(
+                    InstanceRef::from_pin_ref(c, guard),
+                    args.to_vec(),
+                );
+                eval::eval_expression(&expr, &mut local_context)
+            })
+        } else {
+            let item_within_component = &component_type.items[&target_elem.id];
+            let item = item_within_component.item_from_component(target.as_ptr());
+            if let Some(callback) = item_within_component.rtti.callbacks.get(target_name) {
+                callback.set_handler(
+                    item,
+                    Box::new(move |args| {
+                        generativity::make_guard!(guard);
+                        let mut local_context = eval::EvalLocalContext::from_function_arguments(
+                            InstanceRef::from_pin_ref(c, guard),
+                            args.iter().cloned().collect(),
+                        );
+                        eval::eval_expression(&expr, &mut local_context)
+                    }),
+                )
+            } else {
+                panic!("unknown callback {}", prop_name)
+            }
+        }
+    } else if let Some(PropertiesWithinComponent { offset, prop: prop_info, .. }) =
+        component_type.custom_properties.get(target_name).filter(|_| is_root)
+    {
+        let is_state_info = match property_type {
+            Type::Struct { name: Some(name), .. } if name.ends_with("::StateInfo") => true,
+            _ => false,
+        };
+        if is_state_info {
+            let prop = Pin::new_unchecked(
+                &*(target.as_ptr().add(*offset)
+                    as *const Property<sixtyfps_corelib::properties::StateInfo>),
+            );
+            let e = binding.expression.clone();
+            sixtyfps_corelib::properties::set_state_binding(prop, move || {
+                generativity::make_guard!(guard);
+                eval::eval_expression(
+                    &e,
+                    &mut eval::EvalLocalContext::from_component_instance(
+                        InstanceRef::from_pin_ref(c, guard),
+                    ),
+                )
+                .try_into()
+                .unwrap()
+            });
+            return;
+        }
+
+        let maybe_animation = animation_for_property(instance_ref, &elem, prop_name);
+        let item = Pin::new_unchecked(&*target.as_ptr().add(*offset));
+
+        let mut e = Some(&binding.expression);
+        while let Some(Expression::TwoWayBinding(nr, next)) = &e {
+            // Safety: The compiler must have ensured that the properties exist and are of the same type
+            prop_info.link_two_ways(item, get_property_ptr(&nr, instance_ref));
+            e = next.as_deref();
+        }
+        if let Some(e) = e {
+            if is_const || e.is_constant() {
+                let v = eval::eval_expression(
+                    e,
+                    &mut eval::EvalLocalContext::from_component_instance(instance_ref),
+                );
+                prop_info.set(item, v, None).unwrap();
+            } else {
+                sixtyfps_corelib::properties::set_property_debug_name(
+                    &*item as *const u8 as *const (),
+                    format!("{}.{}", elem.id, prop_name),
+                );
+                let e = e.clone();
+                prop_info
+                    .set_binding(
+                        item,
+                        Box::new(move || {
+                            generativity::make_guard!(guard);
+                            eval::eval_expression(
+                                &e,
+                                &mut eval::EvalLocalContext::from_component_instance(
+                                    InstanceRef::from_pin_ref(c, guard),
+                                ),
+                            )
+                        }),
+                        maybe_animation,
+                    )
+                    .unwrap();
+            }
+        }
+    } else {
+        let item_within_component = &component_type.items[&target_elem.id];
+        let item = item_within_component.item_from_component(target.as_ptr());
+        if let Some(prop_rtti) = item_within_component.rtti.properties.get(target_name) {
+            let maybe_animation = animation_for_property(instance_ref, &elem, prop_name);
+            let mut e = Some(&binding.expression);
+            while let Some(Expression::TwoWayBinding(nr, next)) = &e {
+                // Safety: The compiler must have ensured that the properties exist and are of the same type
+                prop_rtti.link_two_ways(item, get_property_ptr(&nr, instance_ref));
+                e = next.as_deref();
+            }
+            if let Some(e) = e {
+                if is_const || e.is_constant() {
+                    prop_rtti.set(
+                        item,
+                        eval::eval_expression(
+                            e,
+                            &mut eval::EvalLocalContext::from_component_instance(instance_ref),
+                        ),
+                        maybe_animation.as_animation(),
+                    );
+                } else {
+                    sixtyfps_corelib::properties::set_property_debug_name(
+                        item.as_ptr().add(prop_rtti.offset()) as *const (),
+                        format!("{}.{}", elem.id, prop_name),
+                    );
+                    let e = e.clone();
+                    prop_rtti.set_binding(
+                        item,
+                        Box::new(move || {
+                            generativity::make_guard!(guard);
+                            eval::eval_expression(
+                                &e,
+                                &mut eval::EvalLocalContext::from_component_instance(
+                                    InstanceRef::from_pin_ref(c, guard),
+                                ),
+                            )
+                        }),
+                        maybe_animation,
+                    );
+                }
+            }
+        } else {
+            panic!("unknown property {}", prop_name);
+        }
+    }
+}
+
 pub(crate) fn get_property_ptr(nr: &NamedReference, instance: InstanceRef) -> *const () {
     let element = nr.element();
     generativity::make_guard!(guard);
@@ -1244,6 +1416,16 @@ pub(crate) fn get_property_ptr(nr: &NamedReference, instance: InstanceRef) -> *c
     );
     match enclosing_component {
         eval::ComponentInstance::InstanceRef(enclosing_component) => {
+            if element.borrow().sub_component().is_some() {
+                generativity::make_guard!(guard);
+                let (sub_instance, element, name) = eval::resolve_sub_component_property(
+                    enclosing_component,
+                    &element,
+                    nr.name(),
+                    guard,
+                );
+                return get_property_ptr(&NamedReference::new(&element, &name), sub_instance);
+            }
             let element = element.borrow();
             if element.id == element.enclosing_component.upgrade().unwrap().root_element.borrow().id
             {
@@ -1291,13 +1473,21 @@ pub fn window(&self) -> ComponentWindow {
     pub fn run_setup_code(&self) {
         generativity::make_guard!(guard);
         let compo_box = self.unerase(guard);
-        let instance_ref = compo_box.borrow_instance();
-        for extra_init_code in self.0.component_type.original.setup_code.borrow().iter() {
-            eval::eval_expression(
-                extra_init_code,
-                &mut eval::EvalLocalContext::from_component_instance(instance_ref),
-            );
-        }
+        run_setup_code(compo_box.borrow_instance());
+    }
+}
+
+/// Run the setup code of the instances of the sub-components, and then of the instance itself
+fn run_setup_code(instance_ref: InstanceRef) {
+    for instance_id in instance_ref.component_type.sub_components.keys() {
+        generativity::make_guard!(guard);
+        run_setup_code(instance_ref.sub_component(instance_id, guard));
+    }
+    for extra_init_code in instance_ref.component_type.original.setup_code.borrow().iter() {
+        eval::eval_expression(
+            extra_init_code,
+            &mut eval::EvalLocalContext::from_component_instance(instance_ref),
+        );
     }
 }
 impl<'id> From<ComponentBox<'id>> for ErasedComponentBox {
@@ -1454,6 +1644,43 @@ pub fn self_weak(
         let extra_data = self.component_type.extra_data_offset.apply(self.as_ref());
         &extra_data.self_weak
     }
+
+    /// Return the instance of the sub-component instantiated by the element with the given id
+    pub(crate) fn sub_component<'new_id>(
+        self,
+        instance_id: &str,
+        guard: generativity::Guard<'new_id>,
+    ) -> InstanceRef<'a, 'new_id> {
+        let sub = &self.component_type.sub_components[instance_id];
+        let component_type = sub.description.unerase(guard);
+        // Safety: the instance of the sub-component was created at this offset with this description
+        InstanceRef {
+            instance: unsafe {
+                Pin::new_unchecked(&*(self.as_ptr().add(sub.offset) as *const Instance<'new_id>))
+            },
+            component_type,
+        }
+    }
+
+    /// Call the visitor for all the items of this instance, including the ones of the
+    /// instances of sub-components
+    pub(crate) fn visit_items(
+        self,
+        visitor: &mut dyn FnMut(&'a ItemWithinComponent, Pin<ItemRef<'a>>),
+    ) {
+        for item_within_component in self.component_type.items.values() {
+            visitor(item_within_component, unsafe {
+                item_within_component.item_from_component(self.as_ptr())
+            });
+        }
+        for instance_id in self.component_type.sub_components.keys() {
+            // we need a 'static guard in order to be able to re-borrow with lifetime 'a.
+            // Safety: This is the only 'static Id in scope.
+            let static_guard =
+                unsafe { generativity::Guard::new(generativity::Id::<'static>::new()) };
+            self.sub_component(instance_id, static_guard).visit_items(visitor);
+        }
+    }
 }
 
 /// Show the popup at the given location
@@ -1472,3 +1699,18 @@ pub fn show_popup(
     parent_window
         .show_popup(&vtable::VRc::into_dyn(inst), sixtyfps_corelib::graphics::Point::new(x, y));
 }
+
+/// Show the dialog with the given presentation
+pub fn show_dialog(
+    dialog: &object_tree::PopupWindow,
+    presentation: sixtyfps_corelib::window::DialogPresentation,
+    parent_comp: ComponentRefPin,
+    parent_window: ComponentWindow,
+) {
+    generativity::make_guard!(guard);
+    // FIXME: we should compile once and keep the cached compiled component
+    let compiled = generate_component(&dialog.component, guard);
+    let inst = instantiate(compiled, Some(parent_comp), Some(parent_window.clone()));
+    inst.run_setup_code();
+    parent_window.show_dialog(&vtable::VRc::into_dyn(inst), presentation);
+}
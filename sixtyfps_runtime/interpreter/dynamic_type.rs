@@ -66,6 +66,9 @@ pub struct TypeInfo<'id> {
     /// be constructed and dropped correctly.
     /// The first FieldInfo must be related to the `Rc<TypeInfo>` member at the beginning
     fields: Vec<FieldInfo>,
+    /// The instances of other dynamic types contained within this type, with their offset.
+    /// Invariant: the offset must point to a field big enough for the type, within `mem_layout`
+    sub_types: Vec<(usize, Rc<TypeInfo<'static>>)>,
 
     #[allow(unused)]
     id: Id<'id>,
@@ -80,12 +83,13 @@ pub struct TypeBuilder<'id> {
     /// Size in byte of the type so far (not including the trailing padding)
     size: usize,
     fields: Vec<FieldInfo>,
+    sub_types: Vec<(usize, Rc<TypeInfo<'static>>)>,
     id: Id<'id>,
 }
 
 impl<'id> TypeBuilder<'id> {
     pub fn new(id: generativity::Guard<'id>) -> Self {
-        let mut s = Self { align: 1, size: 0, fields: vec![], id: id.into() };
+        let mut s = Self { align: 1, size: 0, fields: vec![], sub_types: vec![], id: id.into() };
         type T<'id> = Rc<TypeInfo<'id>>;
         s.add_field(StaticTypeInfo {
             construct: None,
@@ -118,11 +122,27 @@ pub fn add_field(&mut self, ty: StaticTypeInfo) -> usize {
         len_rounded_up
     }
 
+    /// Add a field containing an instance of another dynamic type.
+    ///
+    /// The instance is created and dropped together with the instance of this type.
+    /// Returns the offset, in bytes, of the instance within the dynamic type.
+    pub fn add_sub_type<'sub_id>(&mut self, ty: Rc<TypeInfo<'sub_id>>) -> usize {
+        let offset =
+            self.add_field(StaticTypeInfo { construct: None, drop: None, mem_layout: ty.layout() });
+        // Safety: the lifetime is only used to brand the instances, and the sub type is
+        // only ever accessed through instances of this type.
+        let ty =
+            unsafe { core::mem::transmute::<Rc<TypeInfo<'sub_id>>, Rc<TypeInfo<'static>>>(ty) };
+        self.sub_types.push((offset, ty));
+        offset
+    }
+
     pub fn build(self) -> Rc<TypeInfo<'id>> {
         let size = self.size.wrapping_add(self.align).wrapping_sub(1) & !self.align.wrapping_sub(1);
         Rc::new(TypeInfo {
             mem_layout: core::alloc::Layout::from_size_align(size, self.align).unwrap(),
             fields: self.fields,
+            sub_types: self.sub_types,
             id: self.id,
         })
     }
@@ -154,6 +174,9 @@ pub unsafe fn create_instance_in_place(self: Rc<Self>, mem: *mut Instance<'id>)
                 ctor(mem.add(f.offset));
             }
         }
+        for (offset, sub_type) in &self.sub_types {
+            sub_type.clone().create_instance_in_place(mem.add(*offset) as *mut Instance);
+        }
     }
 
     /// Drop and free the memory of this instance
@@ -167,6 +190,9 @@ pub unsafe fn drop_in_place(instance: *mut Instance) {
                 dtor(mem.add(f.offset));
             }
         }
+        for (offset, _) in &type_info.sub_types {
+            Self::drop_in_place(mem.add(*offset) as *mut Instance);
+        }
     }
 
     /// Drop and free the memory of this instance
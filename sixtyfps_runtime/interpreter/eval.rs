@@ -9,7 +9,7 @@
 LICENSE END */
 use crate::api::{Struct, Value};
 use crate::dynamic_component::InstanceRef;
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
 use core::iter::FromIterator;
 use core::pin::Pin;
 use corelib::graphics::{GradientStop, LinearGradientBrush, PathElement};
@@ -169,6 +169,15 @@ pub fn eval_expression(e: &Expression, local_context: &mut EvalLocalContext) ->
                 Value::Void
             }
         }
+        Expression::ArrayIndex { array, index } => {
+            let index: i32 = eval_expression(index, local_context).try_into().unwrap();
+            let value = match (eval_expression(array, local_context), usize::try_from(index)) {
+                (Value::Array(array), Ok(index)) => array.get(index).cloned(),
+                (Value::Model(model), Ok(index)) => model.row_data_tracked(index),
+                _ => None,
+            };
+            value.unwrap_or_else(|| default_value_for_type(&e.ty()))
+        }
         Expression::Cast { from, to } => {
             let v = eval_expression(&*from, local_context);
             match (v, to) {
@@ -303,6 +312,109 @@ pub fn eval_expression(e: &Expression, local_context: &mut EvalLocalContext) ->
                     panic!("internal error: argument to SetFocusItem must be an element")
                 }
             }
+            Expression::BuiltinFunctionReference(BuiltinFunction::ShowDialog, _) => {
+                if arguments.len() != 1 {
+                    panic!("internal error: incorrect argument count to ShowDialog")
+                }
+                let component = match  local_context.component_instance  {
+                    ComponentInstance::InstanceRef(c) => c,
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot show a dialog from a global component")
+                };
+                if let Expression::ElementReference(dialog) = &arguments[0] {
+                    let dialog = dialog.upgrade().unwrap();
+                    let dialog_comp = dialog.borrow().enclosing_component.upgrade().unwrap();
+                    let parent_component = dialog_comp.parent_element.upgrade().unwrap().borrow().enclosing_component.upgrade().unwrap();
+                    let popup_list = parent_component.popup_windows.borrow();
+                    let popup = popup_list.iter().find(|p| Rc::ptr_eq(&p.component, &dialog_comp)).unwrap();
+                    let presentation = popup.dialog_presentation.as_ref().unwrap();
+                    let presentation = load_property_helper(local_context.component_instance, &presentation.element(), presentation.name()).unwrap();
+                    crate::dynamic_component::show_dialog(popup, presentation.try_into().unwrap(), component.borrow(), window_ref(component).unwrap());
+                    Value::Void
+                } else {
+                    panic!("internal error: argument to ShowDialog must be an element")
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::CloseDialog, _) => {
+                let component = match  local_context.component_instance  {
+                    ComponentInstance::InstanceRef(c) => c,
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot close a dialog from a global component")
+                };
+                window_ref(component).unwrap().close_dialog();
+                Value::Void
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::HasNativeMenus, _) => {
+                let component = match  local_context.component_instance  {
+                    ComponentInstance::InstanceRef(c) => c,
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot show a menu from a global component")
+                };
+                Value::Bool(window_ref(component).unwrap().has_native_menus())
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::ShowNativeMenu, _) => {
+                if arguments.len() != 3 {
+                    panic!("internal error: incorrect argument count to ShowNativeMenu")
+                }
+                let component = match  local_context.component_instance  {
+                    ComponentInstance::InstanceRef(c) => c,
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot show a menu from a global component")
+                };
+                if let (Expression::ElementReference(menu), Expression::Array { values, .. }) = (&arguments[0], &arguments[1]) {
+                    let menu = menu.upgrade().unwrap();
+                    let menu_comp = menu.borrow().enclosing_component.upgrade().unwrap();
+                    let parent_component = menu_comp.parent_element.upgrade().unwrap().borrow().enclosing_component.upgrade().unwrap();
+                    let popup_list = parent_component.popup_windows.borrow();
+                    let popup = popup_list.iter().find(|p| Rc::ptr_eq(&p.component, &menu_comp)).unwrap();
+                    let x = load_property_helper(local_context.component_instance, &popup.x.element(), popup.x.name()).unwrap();
+                    let y = load_property_helper(local_context.component_instance, &popup.y.element(), popup.y.name()).unwrap();
+                    let entries = values
+                        .iter()
+                        .map(|e| eval_expression(e, local_context).try_into().unwrap())
+                        .collect::<Vec<corelib::window::MenuEntry>>();
+                    let position = corelib::graphics::Point::new(x.try_into().unwrap(), y.try_into().unwrap());
+                    let activated = menu_activated_callback(&arguments[2], component);
+                    window_ref(component).unwrap().show_native_menu(&entries, position, activated);
+                    Value::Void
+                } else {
+                    panic!("internal error: arguments to ShowNativeMenu must be an element and an array")
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::SetNativeMenuBar, _) => {
+                if arguments.len() != 2 {
+                    panic!("internal error: incorrect argument count to SetNativeMenuBar")
+                }
+                let component = match  local_context.component_instance  {
+                    ComponentInstance::InstanceRef(c) => c,
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot show a menu bar from a global component")
+                };
+                if let Expression::Array { values, .. } = &arguments[0] {
+                    let values = values.clone();
+                    let self_weak = component.self_weak().get().unwrap().clone();
+                    let entries = move || {
+                        let self_rc = match self_weak.upgrade() {
+                            Some(self_rc) => self_rc,
+                            None => return Vec::new(),
+                        };
+                        generativity::make_guard!(guard);
+                        let self_ref = self_rc.unerase(guard);
+                        let mut local_context = EvalLocalContext::from_component_instance(self_ref.borrow_instance());
+                        values
+                            .iter()
+                            .map(|e| eval_expression(e, &mut local_context).try_into().unwrap())
+                            .collect::<Vec<corelib::window::MenuEntry>>()
+                    };
+                    let activated = menu_activated_callback(&arguments[1], component);
+                    window_ref(component).unwrap().set_native_menu_bar(entries, activated);
+                    Value::Void
+                } else {
+                    panic!("internal error: the first argument to SetNativeMenuBar must be an array")
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::StandardButtonRank, _) => {
+                if arguments.len() != 1 {
+                    panic!("internal error: incorrect argument count to StandardButtonRank")
+                }
+                let kind = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                Value::Number(sixtyfps_corelib::window::standard_button_rank(kind) as f64)
+            }
             Expression::BuiltinFunctionReference(BuiltinFunction::StringIsFloat, _) => {
                 if arguments.len() != 1 {
                     panic!("internal error: incorrect argument count to StringIsFloat")
@@ -323,6 +435,87 @@ pub fn eval_expression(e: &Expression, local_context: &mut EvalLocalContext) ->
                     panic!("Argument not a string");
                 }
             }
+            Expression::BuiltinFunctionReference(
+                f @ (BuiltinFunction::NumberToFixed
+                | BuiltinFunction::NumberToGrouped
+                | BuiltinFunction::NumberToPadded
+                | BuiltinFunction::NumberToPercent),
+                _,
+            ) => {
+                if arguments.len() != 2 {
+                    panic!("internal error: incorrect argument count to {:?}", f)
+                }
+                let n: f64 = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                let digits: i32 = eval_expression(&arguments[1], local_context).try_into().unwrap();
+                let format = match f {
+                    BuiltinFunction::NumberToFixed => sixtyfps_corelib::string::format_fixed,
+                    BuiltinFunction::NumberToGrouped => sixtyfps_corelib::string::format_grouped,
+                    BuiltinFunction::NumberToPadded => sixtyfps_corelib::string::format_padded,
+                    _ => sixtyfps_corelib::string::format_percent,
+                };
+                Value::String(format(n, digits))
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::NumberToByteSize, _) => {
+                if arguments.len() != 1 {
+                    panic!("internal error: incorrect argument count to NumberToByteSize")
+                }
+                let n: f64 = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                Value::String(sixtyfps_corelib::string::format_byte_size(n))
+            }
+            Expression::BuiltinFunctionReference(
+                f @ (BuiltinFunction::StringToUpper
+                | BuiltinFunction::StringToLower
+                | BuiltinFunction::StringTrim
+                | BuiltinFunction::StringLength),
+                _,
+            ) => {
+                if arguments.len() != 1 {
+                    panic!("internal error: incorrect argument count to {:?}", f)
+                }
+                if let Value::String(s) = eval_expression(&arguments[0], local_context) {
+                    match f {
+                        BuiltinFunction::StringToUpper => Value::String(s.to_uppercase().into()),
+                        BuiltinFunction::StringToLower => Value::String(s.to_lowercase().into()),
+                        BuiltinFunction::StringTrim => Value::String(s.trim().into()),
+                        _ => Value::Number(sixtyfps_corelib::string::string_length(&s) as f64),
+                    }
+                } else {
+                    panic!("Argument not a string");
+                }
+            }
+            Expression::BuiltinFunctionReference(
+                f @ (BuiltinFunction::StringContains
+                | BuiltinFunction::StringStartsWith
+                | BuiltinFunction::StringEndsWith),
+                _,
+            ) => {
+                if arguments.len() != 2 {
+                    panic!("internal error: incorrect argument count to {:?}", f)
+                }
+                match (
+                    eval_expression(&arguments[0], local_context),
+                    eval_expression(&arguments[1], local_context),
+                ) {
+                    (Value::String(s), Value::String(other)) => Value::Bool(match f {
+                        BuiltinFunction::StringContains => s.contains(other.as_str()),
+                        BuiltinFunction::StringStartsWith => s.starts_with(other.as_str()),
+                        _ => s.ends_with(other.as_str()),
+                    }),
+                    _ => panic!("Arguments not strings"),
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::StringSubstring, _) => {
+                if arguments.len() != 3 {
+                    panic!("internal error: incorrect argument count to StringSubstring")
+                }
+                if let Value::String(s) = eval_expression(&arguments[0], local_context) {
+                    let start: i32 = eval_expression(&arguments[1], local_context).try_into().unwrap();
+                    let length: i32 = eval_expression(&arguments[2], local_context).try_into().unwrap();
+                    Value::String(sixtyfps_corelib::string::string_substring(&s, start, length))
+                } else {
+                    panic!("Argument not a string");
+                }
+            }
             Expression::BuiltinFunctionReference(BuiltinFunction::ColorBrighter, _) => {
                 if arguments.len() != 2 {
                     panic!("internal error: incorrect argument count to ColorBrighter")
@@ -351,6 +544,48 @@ pub fn eval_expression(e: &Expression, local_context: &mut EvalLocalContext) ->
                     panic!("First argument not a color");
                 }
             }
+            Expression::BuiltinFunctionReference(
+                f @ (BuiltinFunction::ColorWithAlpha | BuiltinFunction::ColorTransparentize),
+                _,
+            ) => {
+                if arguments.len() != 2 {
+                    panic!("internal error: incorrect argument count to {:?}", f)
+                }
+                if let Value::Brush(Brush::SolidColor(col)) = eval_expression(&arguments[0], local_context) {
+                    let factor: f32 = eval_expression(&arguments[1], local_context).try_into().unwrap();
+                    match f {
+                        BuiltinFunction::ColorWithAlpha => col.with_alpha(factor).into(),
+                        _ => col.transparentize(factor).into(),
+                    }
+                } else {
+                    panic!("First argument not a color");
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::ColorMix, _) => {
+                if arguments.len() != 3 {
+                    panic!("internal error: incorrect argument count to ColorMix")
+                }
+                match (
+                    eval_expression(&arguments[0], local_context),
+                    eval_expression(&arguments[1], local_context),
+                ) {
+                    (Value::Brush(Brush::SolidColor(col)), Value::Brush(Brush::SolidColor(other))) => {
+                        let factor: f32 = eval_expression(&arguments[2], local_context).try_into().unwrap();
+                        col.mix(&other, factor).into()
+                    }
+                    _ => panic!("Arguments not colors"),
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::ColorLuminance, _) => {
+                if arguments.len() != 1 {
+                    panic!("internal error: incorrect argument count to ColorLuminance")
+                }
+                if let Value::Brush(Brush::SolidColor(col)) = eval_expression(&arguments[0], local_context) {
+                    Value::Number(col.luminance() as f64)
+                } else {
+                    panic!("First argument not a color");
+                }
+            }
             Expression::BuiltinFunctionReference(BuiltinFunction::ImageSize, _) => {
                 if arguments.len() != 1 {
                     panic!("internal error: incorrect argument count to ColorDarker")
@@ -377,6 +612,20 @@ pub fn eval_expression(e: &Expression, local_context: &mut EvalLocalContext) ->
                 let a: u8 = (255. * a).max(0.).min(255.) as u8;
                 Value::Brush(Brush::SolidColor(Color::from_argb_u8(a, r, g, b)))
             }
+            Expression::BuiltinFunctionReference(f @ (BuiltinFunction::Hsv | BuiltinFunction::Hsl), _) => {
+                if arguments.len() != 4 {
+                    panic!("internal error: incorrect argument count to {:?}", f)
+                }
+                let h: f32 = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                let s: f32 = eval_expression(&arguments[1], local_context).try_into().unwrap();
+                let v: f32 = eval_expression(&arguments[2], local_context).try_into().unwrap();
+                let a: f32 = eval_expression(&arguments[3], local_context).try_into().unwrap();
+                let color = match f {
+                    BuiltinFunction::Hsv => Color::from_hsva(h, s, v, a),
+                    _ => Color::from_hsla(h, s, v, a),
+                };
+                Value::Brush(Brush::SolidColor(color))
+            }
             Expression::BuiltinFunctionReference(BuiltinFunction::ImplicitLayoutInfo(orient), _) => {
                 let component = match  local_context.component_instance  {
                     ComponentInstance::InstanceRef(c) => c,
@@ -388,6 +637,9 @@ pub fn eval_expression(e: &Expression, local_context: &mut EvalLocalContext) ->
                     let item = item.upgrade().unwrap();
                     let enclosing_component =
                         enclosing_component_for_element(&item, component, guard);
+                    generativity::make_guard!(guard);
+                    let (enclosing_component, item, _) =
+                        resolve_sub_component_property(enclosing_component, &item, "", guard);
                     let component_type = enclosing_component.component_type;
                     let item_info = &component_type.items[item.borrow().id.as_str()];
                     let item_ref = unsafe { item_info.item_from_component(enclosing_component.as_ptr()) };
@@ -398,6 +650,20 @@ pub fn eval_expression(e: &Expression, local_context: &mut EvalLocalContext) ->
                     panic!("internal error: incorrect arguments to ImplicitLayoutInfo {:?}", arguments);
                 }
             }
+            Expression::BuiltinFunctionReference(BuiltinFunction::ArrayLength, _) => {
+                if arguments.len() != 1 {
+                    panic!("internal error: incorrect argument count to ArrayLength")
+                }
+                match eval_expression(&arguments[0], local_context) {
+                    Value::Array(array) => Value::Number(array.len() as _),
+                    Value::Model(model) => {
+                        model.track_row_count_changes();
+                        Value::Number(model.row_count() as _)
+                    }
+                    Value::Void => Value::Number(0.),
+                    x => panic!("Invalid model {:?}", x),
+                }
+            }
             Expression::BuiltinFunctionReference(BuiltinFunction::RegisterCustomFontByPath, _) => {
                 if arguments.len() != 1 {
                     panic!("internal error: incorrect argument count to RegisterCustomFontByPath")
@@ -574,6 +840,17 @@ fn eval_assignment(lhs: &Expression, op: char, rhs: Value, local_context: &mut E
                         store_property(enclosing_component, &element, nr.name(), rhs).unwrap();
                         return;
                     }
+                    if element.borrow().sub_component().is_some() {
+                        let value = load_property(enclosing_component, &element, nr.name());
+                        store_property(
+                            enclosing_component,
+                            &element,
+                            nr.name(),
+                            eval(value.unwrap()),
+                        )
+                        .unwrap();
+                        return;
+                    }
 
                     let component = element.borrow().enclosing_component.upgrade().unwrap();
                     if element.borrow().id == component.root_element.borrow().id {
@@ -662,6 +939,12 @@ fn load_property_helper(
     generativity::make_guard!(guard);
     match enclosing_component_instance_for_element(&element, component_instance, guard) {
         ComponentInstance::InstanceRef(enclosing_component) => {
+            if element.borrow().sub_component().is_some() {
+                generativity::make_guard!(guard);
+                let (sub_instance, element, name) =
+                    resolve_sub_component_property(enclosing_component, element, name, guard);
+                return load_property(sub_instance, &element, &name);
+            }
             let element = element.borrow();
             if element.id == element.enclosing_component.upgrade().unwrap().root_element.borrow().id
             {
@@ -692,6 +975,12 @@ pub fn store_property(
 ) -> Result<(), ()> {
     generativity::make_guard!(guard);
     let enclosing_component = enclosing_component_for_element(&element, component_instance, guard);
+    if element.borrow().sub_component().is_some() {
+        generativity::make_guard!(guard);
+        let (sub_instance, element, name) =
+            resolve_sub_component_property(enclosing_component, element, name, guard);
+        return store_property(sub_instance, &element, &name, value);
+    }
     let maybe_animation = crate::dynamic_component::animation_for_property(
         enclosing_component,
         &element.borrow(),
@@ -714,6 +1003,28 @@ pub fn store_property(
     Ok(())
 }
 
+/// Returns a closure calling the callback of a menu with the index of the activated entry, as
+/// given to the ShowNativeMenu and SetNativeMenuBar functions
+fn menu_activated_callback(callback: &Expression, component: InstanceRef) -> impl Fn(usize) {
+    let callback = match callback {
+        Expression::CallbackReference(nr) => nr.clone(),
+        _ => panic!("internal error: the menu callback must be a callback reference"),
+    };
+    let self_weak = component.self_weak().get().unwrap().clone();
+    move |index| {
+        if let Some(self_rc) = self_weak.upgrade() {
+            generativity::make_guard!(guard);
+            let self_ref = self_rc.unerase(guard);
+            invoke_callback(
+                ComponentInstance::InstanceRef(self_ref.borrow_instance()),
+                &callback.element(),
+                callback.name(),
+                &[Value::Number(index as f64)],
+            );
+        }
+    }
+}
+
 pub(crate) fn invoke_callback(
     component_instance: ComponentInstance,
     element: &ElementRc,
@@ -723,6 +1034,21 @@ pub(crate) fn invoke_callback(
     generativity::make_guard!(guard);
     match enclosing_component_instance_for_element(element, component_instance, guard) {
         ComponentInstance::InstanceRef(enclosing_component) => {
+            if element.borrow().sub_component().is_some() {
+                generativity::make_guard!(guard);
+                let (sub_instance, element, name) = resolve_sub_component_property(
+                    enclosing_component,
+                    element,
+                    callback_name,
+                    guard,
+                );
+                return invoke_callback(
+                    ComponentInstance::InstanceRef(sub_instance),
+                    &element,
+                    &name,
+                    args,
+                );
+            }
             let component_type = enclosing_component.component_type;
             let item_info = &component_type.items[element.borrow().id.as_str()];
             let item = unsafe { item_info.item_from_component(enclosing_component.as_ptr()) };
@@ -743,7 +1069,7 @@ pub(crate) fn invoke_callback(
     }
 }
 
-fn root_component_instance<'a, 'old_id, 'new_id>(
+pub(crate) fn root_component_instance<'a, 'old_id, 'new_id>(
     component: InstanceRef<'a, 'old_id>,
     guard: generativity::Guard<'new_id>,
 ) -> InstanceRef<'a, 'new_id> {
@@ -803,6 +1129,58 @@ pub fn enclosing_component_for_element<'a, 'old_id, 'new_id>(
     }
 }
 
+/// Resolve the property `name` of `element`, given the instance of the component that holds
+/// the element, to the element and the instance that actually hold the property.
+///
+/// If the element is an instance of a sub-component, its properties are the ones of the root
+/// element of the sub-component (or what they alias) within the instance of the sub-component.
+/// Other elements are returned as is.
+pub(crate) fn resolve_sub_component_property<'a, 'new_id>(
+    component: InstanceRef<'a, '_>,
+    element: &ElementRc,
+    name: &str,
+    guard: generativity::Guard<'new_id>,
+) -> (InstanceRef<'a, 'new_id>, ElementRc, String) {
+    let sub_component = element.borrow().sub_component().cloned();
+    match sub_component {
+        Some(sub_component) => {
+            // we need a 'static guard in order to be able to re-borrow with lifetime 'a.
+            // Safety: This is the only 'static Id in scope.
+            let static_guard =
+                unsafe { generativity::Guard::new(generativity::Id::<'static>::new()) };
+            let sub_instance = component.sub_component(&element.borrow().id, static_guard);
+            let alias = sub_component
+                .root_element
+                .borrow()
+                .property_declarations
+                .get(name)
+                .and_then(|decl| decl.is_alias.clone());
+            match alias {
+                Some(alias) => resolve_sub_component_property(
+                    sub_instance,
+                    &alias.element(),
+                    alias.name(),
+                    guard,
+                ),
+                None => resolve_sub_component_property(
+                    sub_instance,
+                    &sub_component.root_element,
+                    name,
+                    guard,
+                ),
+            }
+        }
+        // Safety: new_id is an unique id
+        None => (
+            unsafe {
+                std::mem::transmute::<InstanceRef<'a, '_>, InstanceRef<'a, 'new_id>>(component)
+            },
+            element.clone(),
+            name.into(),
+        ),
+    }
+}
+
 /// Return the component instance which hold the given element.
 /// The difference with enclosing_component_for_element is that it takes the GlobalComponent into account.
 pub(crate) fn enclosing_component_instance_for_element<'a, 'old_id, 'new_id>(
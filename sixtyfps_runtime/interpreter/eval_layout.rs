@@ -351,9 +351,15 @@ pub(crate) fn get_layout_info(
     window: &ComponentWindow,
     orientation: Orientation,
 ) -> core_layout::LayoutInfo {
+    let elem_rc = elem;
     let elem = elem.borrow();
     if let Some(nr) = elem.layout_info_prop(orientation) {
         eval::load_property(component, &nr.element(), nr.name()).unwrap().try_into().unwrap()
+    } else if elem.sub_component().is_some() {
+        generativity::make_guard!(guard);
+        let (sub_instance, sub_root, _) =
+            eval::resolve_sub_component_property(component, elem_rc, "", guard);
+        get_layout_info(&sub_root, sub_instance, window, orientation)
     } else {
         let item = &component
             .component_type
@@ -465,6 +465,14 @@ fn attach_peer(&self, peer: ModelPeer) {
         self.0.get_notify().as_model_notify().attach(peer);
     }
 
+    fn track_row_count_changes(&self) {
+        self.0.get_notify().as_model_notify().track_row_count_changes();
+    }
+
+    fn track_row_data_changes(&self, row: usize) {
+        self.0.get_notify().as_model_notify().track_row_data_changes(row);
+    }
+
     fn set_row_data(&self, row: usize, data: Value) {
         let val: &ValueOpaque = unsafe { std::mem::transmute::<&Value, &ValueOpaque>(&data) };
         self.0.set_row_data(row, val);
@@ -473,10 +481,10 @@ fn set_row_data(&self, row: usize, data: Value) {
 
 #[repr(C)]
 #[cfg(target_pointer_width = "64")]
-pub struct ModelNotifyOpaque([usize; 6]);
+pub struct ModelNotifyOpaque([usize; 12]);
 #[repr(C)]
 #[cfg(target_pointer_width = "32")]
-pub struct ModelNotifyOpaque([usize; 10]);
+pub struct ModelNotifyOpaque([usize; 16]);
 /// Asserts that ModelNotifyOpaque is at least as large as ModelNotify, otherwise this would overflow
 const _: usize = std::mem::size_of::<ModelNotifyOpaque>() - std::mem::size_of::<ModelNotify>();
 
@@ -546,7 +554,7 @@ pub struct CDiagnostic {
 }
 
 #[repr(C)]
-pub struct ComponentCompilerOpaque([usize; 12]);
+pub struct ComponentCompilerOpaque([usize; 24]);
 /// Asserts that ComponentCompilerOpaque is as large as ComponentCompiler and has the same alignment, to make transmute safe.
 const _: [(); std::mem::size_of::<ComponentCompilerOpaque>()] =
     [(); std::mem::size_of::<ComponentCompiler>()];
@@ -52,9 +52,12 @@ fn instantiate(name: &str) -> Pin<Rc<dyn GlobalComponent>> {
                     }
                 }
             }
-            let g = sixtyfps_rendering_backend_default::NativeGlobals::instantiate(
-                b.native_class.class_name.as_ref(),
-            );
+            #[cfg(feature = "backend-default")]
+            type NativeGlobals = sixtyfps_rendering_backend_default::NativeGlobals;
+            // Only the native style uses native globals
+            #[cfg(not(feature = "backend-default"))]
+            type NativeGlobals = ();
+            let g = NativeGlobals::instantiate(b.native_class.class_name.as_ref());
             (name.clone(), g)
         }
         CompiledGlobal::Component(description) => {
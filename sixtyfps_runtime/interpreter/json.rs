@@ -0,0 +1,307 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+//! Conversion of the [`Value`]s from and to JSON
+
+use crate::api::{ComponentInstance, Struct, Value, ValueType};
+use serde_json::Value as JsonValue;
+use sixtyfps_compilerlib::langtype::Type as LangType;
+use sixtyfps_corelib::graphics::{Brush, Color, Image};
+use sixtyfps_corelib::ImageInner;
+
+/// Error returned when a JSON value cannot be converted to a [`Value`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum JsonError {
+    /// There is no public property with the given name
+    #[error("no such property: {0}")]
+    NoSuchProperty(String),
+    /// The struct at the given path has no field of this name
+    #[error("{path}: unknown field '{field}'")]
+    UnknownField {
+        /// The path of the struct in the JSON document, like `items[2]`
+        path: String,
+        /// The name of the field
+        field: String,
+    },
+    /// The JSON value at the given path does not have the expected type
+    #[error("{path}: expected {expected}")]
+    InvalidValue {
+        /// The path of the value in the JSON document, like `items[2].color`
+        path: String,
+        /// A description of the expected value
+        expected: String,
+    },
+}
+
+fn invalid(path: &str, expected: impl Into<String>) -> JsonError {
+    JsonError::InvalidValue { path: path.into(), expected: expected.into() }
+}
+
+impl Value {
+    /// Convert the value to JSON.
+    ///
+    /// Structs become objects, arrays and models become arrays, colors become `"#rrggbbaa"`
+    /// strings, enumeration values become the name of the value, and images loaded from a file
+    /// become the path of the file.
+    pub fn to_json(&self) -> JsonValue {
+        match self {
+            Value::Void => JsonValue::Null,
+            Value::Number(n) => {
+                if n.fract() == 0. && n.abs() < i64::MAX as f64 {
+                    JsonValue::from(*n as i64)
+                } else {
+                    serde_json::Number::from_f64(*n).map_or(JsonValue::Null, JsonValue::Number)
+                }
+            }
+            Value::String(s) => JsonValue::String(s.as_str().into()),
+            Value::Bool(b) => JsonValue::Bool(*b),
+            Value::Image(image) => match <&ImageInner>::from(image) {
+                ImageInner::AbsoluteFilePath(path) => JsonValue::String(path.as_str().into()),
+                _ => JsonValue::Null,
+            },
+            Value::Array(array) => JsonValue::Array(array.iter().map(Value::to_json).collect()),
+            Value::Model(model) => JsonValue::Array(
+                (0..model.row_count()).map(|row| model.row_data(row).to_json()).collect(),
+            ),
+            Value::Struct(s) => JsonValue::Object(
+                s.iter().map(|(name, value)| (name.to_string(), value.to_json())).collect(),
+            ),
+            Value::Brush(brush) => {
+                let c = brush.color();
+                JsonValue::String(format!(
+                    "#{:02x}{:02x}{:02x}{:02x}",
+                    c.red(),
+                    c.green(),
+                    c.blue(),
+                    c.alpha()
+                ))
+            }
+            Value::EnumerationValue(_, value) => JsonValue::String(value.clone()),
+            Value::PathElements(_) | Value::EasingCurve(_) | Value::LayoutCache(_) => {
+                JsonValue::Null
+            }
+        }
+    }
+
+    /// Convert a JSON value to a value of the given type.
+    ///
+    /// This is the inverse of [`Self::to_json`]. Colors are parsed from `"#rrggbb"` or
+    /// `"#rrggbbaa"` strings, and images are loaded from the path in a string.
+    /// As the type of the fields of a struct is not known, they are converted according to the
+    /// JSON type. Use [`ComponentInstance::set_property_from_json`] to convert a value
+    /// according to the declared type of a property.
+    pub fn from_json(json: &JsonValue, ty: ValueType) -> Result<Self, JsonError> {
+        let ty = match ty {
+            ValueType::Void => LangType::Void,
+            ValueType::Number => LangType::Float32,
+            ValueType::String => LangType::String,
+            ValueType::Bool => LangType::Bool,
+            ValueType::Brush => LangType::Brush,
+            ValueType::Image => LangType::Image,
+            ValueType::Array | ValueType::Model => LangType::Array(Box::new(LangType::Invalid)),
+            ValueType::Struct => {
+                let fields = match json {
+                    JsonValue::Object(object) => object
+                        .keys()
+                        .map(|name| (normalize_name(name), LangType::Invalid))
+                        .collect(),
+                    _ => Default::default(),
+                };
+                LangType::Struct { fields, name: None, node: None }
+            }
+            ValueType::Other => return Err(invalid("", "a value of a public type")),
+        };
+        Self::from_json_with_type(json, &ty)
+    }
+
+    /// Convert a JSON value to a value of the given type of the .60 language.
+    /// `LangType::Invalid` means that the type is deduced from the JSON value.
+    #[doc(hidden)]
+    pub fn from_json_with_type(json: &JsonValue, ty: &LangType) -> Result<Self, JsonError> {
+        from_json_at(json, ty, "")
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+fn field_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.into()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+fn from_json_at(json: &JsonValue, ty: &LangType, path: &str) -> Result<Value, JsonError> {
+    if json.is_null() && !matches!(ty, LangType::Invalid) {
+        return Ok(crate::eval::default_value_for_type(ty));
+    }
+    match ty {
+        LangType::Invalid => Ok(match json {
+            JsonValue::Null => Value::Void,
+            JsonValue::Bool(b) => Value::Bool(*b),
+            JsonValue::Number(n) => Value::Number(n.as_f64().unwrap_or_default()),
+            JsonValue::String(s) => Value::String(s.as_str().into()),
+            JsonValue::Array(_) => {
+                from_json_at(json, &LangType::Array(Box::new(LangType::Invalid)), path)?
+            }
+            JsonValue::Object(object) => Value::Struct(
+                object
+                    .iter()
+                    .map(|(name, json)| {
+                        let path = field_path(path, name);
+                        Ok((normalize_name(name), from_json_at(json, &LangType::Invalid, &path)?))
+                    })
+                    .collect::<Result<Struct, JsonError>>()?,
+            ),
+        }),
+        LangType::Float32
+        | LangType::Int32
+        | LangType::Duration
+        | LangType::Angle
+        | LangType::PhysicalLength
+        | LangType::LogicalLength
+        | LangType::Percent
+        | LangType::UnitProduct(_) => {
+            json.as_f64().map(Value::Number).ok_or_else(|| invalid(path, "a number"))
+        }
+        LangType::String => {
+            json.as_str().map(|s| Value::String(s.into())).ok_or_else(|| invalid(path, "a string"))
+        }
+        LangType::Bool => json.as_bool().map(Value::Bool).ok_or_else(|| invalid(path, "a boolean")),
+        LangType::Color | LangType::Brush => json
+            .as_str()
+            .and_then(sixtyfps_compilerlib::literals::parse_color_literal)
+            .map(|argb| Value::Brush(Brush::SolidColor(Color::from_argb_encoded(argb))))
+            .ok_or_else(|| invalid(path, "a color as \"#rrggbb\" or \"#rrggbbaa\"")),
+        LangType::Image => match json.as_str() {
+            Some("") => Ok(Value::Image(Default::default())),
+            Some(s) => Image::load_from_path(std::path::Path::new(s))
+                .map(Value::Image)
+                .map_err(|_| invalid(path, "the path of an image")),
+            None => Err(invalid(path, "the path of an image")),
+        },
+        LangType::Array(element_type) => match json {
+            JsonValue::Array(array) => array
+                .iter()
+                .enumerate()
+                .map(|(index, json)| {
+                    from_json_at(json, element_type, &format!("{}[{}]", path, index))
+                })
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+            _ => Err(invalid(path, "an array")),
+        },
+        LangType::Struct { fields, .. } => {
+            let object = json.as_object().ok_or_else(|| invalid(path, "an object"))?;
+            let mut value = match crate::eval::default_value_for_type(ty) {
+                Value::Struct(s) => s,
+                _ => unreachable!(),
+            };
+            for (name, json) in object {
+                let normalized = normalize_name(name);
+                let field_type = fields.get(&normalized).ok_or_else(|| {
+                    JsonError::UnknownField { path: path.into(), field: name.clone() }
+                })?;
+                value.set_field(
+                    normalized,
+                    from_json_at(json, field_type, &field_path(path, name))?,
+                );
+            }
+            Ok(Value::Struct(value))
+        }
+        LangType::Enumeration(enumeration) => json
+            .as_str()
+            .and_then(|s| {
+                let s = normalize_name(s);
+                enumeration.values.iter().find(|v| normalize_name(v) == s)
+            })
+            .map(|v| Value::EnumerationValue(enumeration.name.clone(), v.clone()))
+            .ok_or_else(|| invalid(path, format!("one of the values of {}", enumeration.name))),
+        _ => Err(invalid(path, "a value of a public type")),
+    }
+}
+
+impl ComponentInstance {
+    /// Set the value of a public property from JSON, converted according to the declared
+    /// type of the property. See [`Value::to_json`] for the representation of the values.
+    ///
+    /// This function is only available with the `json` feature.
+    pub fn set_property_from_json(&self, name: &str, json: &JsonValue) -> Result<(), JsonError> {
+        let name = normalize_name(name);
+        let value = self.value_from_json(&name, json)?;
+        self.set_property(&name, value).map_err(|_| JsonError::NoSuchProperty(name))
+    }
+
+    /// Set the public properties from the fields of a JSON object. The values are converted
+    /// according to the declared type of the properties.
+    ///
+    /// Nothing is set if one of the values cannot be converted.
+    /// This function is only available with the `json` feature.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use sixtyfps_interpreter::{ComponentCompiler, Value, SharedString};
+    /// let code = r#"
+    ///     MyWin := Window {
+    ///         property <string> name;
+    ///         property <[{label: string, checked: bool}]> items;
+    ///     }
+    /// "#;
+    /// let definition = spin_on::spin_on(
+    ///     ComponentCompiler::default().build_from_source(code.into(), Default::default()));
+    /// let instance = definition.unwrap().create();
+    /// let json = serde_json::json!({ "name": "John", "items": [{ "label": "x", "checked": true }]});
+    /// instance.set_properties_from_json(&json).unwrap();
+    /// assert_eq!(instance.get_property("name").unwrap(), Value::from(SharedString::from("John")));
+    /// assert_eq!(instance.properties_to_json(), json);
+    /// ```
+    pub fn set_properties_from_json(&self, json: &JsonValue) -> Result<(), JsonError> {
+        let object = json.as_object().ok_or_else(|| invalid("", "an object"))?;
+        let values = object
+            .iter()
+            .map(|(name, json)| {
+                let name = normalize_name(name);
+                let value = self.value_from_json(&name, json)?;
+                Ok((name, value))
+            })
+            .collect::<Result<Vec<_>, JsonError>>()?;
+        for (name, value) in values {
+            self.set_property(&name, value).map_err(|_| JsonError::NoSuchProperty(name))?;
+        }
+        Ok(())
+    }
+
+    /// Return a JSON object with the value of each public property.
+    ///
+    /// This function is only available with the `json` feature.
+    pub fn properties_to_json(&self) -> JsonValue {
+        JsonValue::Object(
+            self.definition()
+                .properties()
+                .filter_map(|(name, _)| {
+                    Some((name.clone(), self.get_property(&name).ok()?.to_json()))
+                })
+                .collect(),
+        )
+    }
+
+    fn value_from_json(&self, name: &str, json: &JsonValue) -> Result<Value, JsonError> {
+        let ty = self
+            .definition()
+            .properties_and_callbacks()
+            .find(|(n, ty)| n == name && ty.is_property_type())
+            .map(|(_, ty)| ty)
+            .ok_or_else(|| JsonError::NoSuchProperty(name.into()))?;
+        from_json_at(json, &ty, name)
+    }
+}
@@ -70,6 +70,12 @@
 ## Features
 
 **display-diagnostics**: enable the `[print_diagnostics]` function to show diagnostic in the console output
+
+**json**: enable the conversions of the [`Value`]s from and to JSON (see [`Value::to_json`]), and the functions to
+set and get the properties of a [`ComponentInstance`] as JSON
+
+**backend-default** (enabled by default): create the windows with the default rendering backend, and support the
+native style. Without it, the application must initialize a backend before creating a component
 */
 #![warn(missing_docs)]
 #![doc(html_logo_url = "https://sixtyfps.io/resources/logo.drawio.svg")]
@@ -80,10 +86,14 @@
 mod eval;
 mod eval_layout;
 mod global_component;
+#[cfg(feature = "json")]
+mod json;
 mod value_model;
 
 #[doc(inline)]
 pub use api::*;
+#[cfg(feature = "json")]
+pub use json::JsonError;
 
 /// This function can be used to register a custom TrueType font with SixtyFPS,
 /// for use with the `font-family` property. The provided path must refer to a valid TrueType
@@ -91,7 +101,17 @@
 pub(crate) fn register_font_from_path<P: AsRef<std::path::Path>>(
     path: P,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    sixtyfps_rendering_backend_default::backend().register_font_from_path(path.as_ref())
+    backend().register_font_from_path(path.as_ref())
+}
+
+/// The default backend, or the backend initialized by the application when the default
+/// backend is not enabled
+pub(crate) fn backend() -> &'static dyn sixtyfps_corelib::backend::Backend {
+    #[cfg(feature = "backend-default")]
+    return sixtyfps_rendering_backend_default::backend();
+    #[cfg(not(feature = "backend-default"))]
+    return sixtyfps_corelib::backend::instance()
+        .expect("A backend must be initialized when the backend-default feature is disabled");
 }
 
 /// (Re-export from corelib.)
@@ -47,3 +47,59 @@ fn reuse_window() {
         instance
     };
 }
+
+#[cfg(feature = "json")]
+#[test]
+fn properties_from_json() {
+    use crate::{ComponentCompiler, JsonError, SharedString, Value};
+    let code = r#"
+        struct Entry := { label: string, highlight: color }
+        MainWindow := Window {
+            property <string> heading: "foo";
+            property <int> count: 3;
+            property <[Entry]> entries;
+            property <TextHorizontalAlignment> alignment;
+            callback clicked;
+        }
+    "#;
+    let mut compiler = ComponentCompiler::default();
+    let definition = spin_on::spin_on(compiler.build_from_source(code.into(), Default::default()));
+    assert!(compiler.diagnostics().is_empty(), "{:?}", compiler.diagnostics());
+    let instance = definition.unwrap().create();
+
+    let json = serde_json::json!({
+        "heading": "bar",
+        "entries": [{ "label": "a", "highlight": "#ff0000" }, { "label": "b" }],
+        "alignment": "right",
+    });
+    instance.set_properties_from_json(&json).unwrap();
+    assert_eq!(instance.get_property("heading").unwrap(), Value::from(SharedString::from("bar")));
+    assert_eq!(
+        instance.properties_to_json(),
+        serde_json::json!({
+            "heading": "bar",
+            "count": 3,
+            "entries": [
+                { "label": "a", "highlight": "#ff0000ff" },
+                { "label": "b", "highlight": "#00000000" }
+            ],
+            "alignment": "right",
+        })
+    );
+
+    // Nothing is set if one of the values is invalid
+    let json = serde_json::json!({ "heading": "baz", "entries": [{ "label": 42 }] });
+    assert_eq!(
+        instance.set_properties_from_json(&json),
+        Err(JsonError::InvalidValue { path: "entries[0].label".into(), expected: "a string".into() })
+    );
+    assert_eq!(instance.get_property("heading").unwrap(), Value::from(SharedString::from("bar")));
+    assert_eq!(
+        instance.set_property_from_json("entries", &serde_json::json!([{ "colour": "#fff" }])),
+        Err(JsonError::UnknownField { path: "entries[0]".into(), field: "colour".into() })
+    );
+    assert_eq!(
+        instance.set_property_from_json("clicked", &serde_json::json!(1)),
+        Err(JsonError::NoSuchProperty("clicked".into()))
+    );
+}
@@ -60,6 +60,20 @@ fn attach_peer(&self, peer: sixtyfps_corelib::model::ModelPeer) {
         self.notify.attach(peer)
     }
 
+    fn track_row_count_changes(&self) {
+        if let Value::Model(ref model_ptr) = *self.value.borrow() {
+            model_ptr.track_row_count_changes()
+        }
+        self.notify.track_row_count_changes()
+    }
+
+    fn track_row_data_changes(&self, row: usize) {
+        if let Value::Model(ref model_ptr) = *self.value.borrow() {
+            model_ptr.track_row_data_changes(row)
+        }
+        self.notify.track_row_data_changes(row)
+    }
+
     fn set_row_data(&self, row: usize, data: Self::Data) {
         match &mut *self.value.borrow_mut() {
             Value::Array(a) => {
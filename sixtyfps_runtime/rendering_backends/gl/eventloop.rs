@@ -418,6 +418,17 @@ pub fn run(quit_behavior: sixtyfps_corelib::backend::EventLoopQuitBehavior) {
                                         winit::event::VirtualKeyCode::Escape => {
                                             Some(InternalKeyCode::Escape)
                                         }
+                                        code if (winit::event::VirtualKeyCode::F1
+                                            ..=winit::event::VirtualKeyCode::F12)
+                                            .contains(&code) =>
+                                        {
+                                            Some(InternalKeyCode::Function(
+                                                (code as u32
+                                                    - winit::event::VirtualKeyCode::F1 as u32
+                                                    + 1)
+                                                    as u8,
+                                            ))
+                                        }
                                         _ => None,
                                     }
                                 })
@@ -435,7 +446,7 @@ pub fn run(quit_behavior: sixtyfps_corelib::backend::EventLoopQuitBehavior) {
                                     text,
                                     modifiers: window.current_keyboard_modifiers(),
                                 };
-                                window.self_weak.upgrade().unwrap().process_key_input(&event);
+                                window.process_key_input(&event);
                             };
                         }
                     });
@@ -444,27 +455,35 @@ pub fn run(quit_behavior: sixtyfps_corelib::backend::EventLoopQuitBehavior) {
                     ref window_id,
                     event: winit::event::WindowEvent::ReceivedCharacter(ch),
                 } => {
-                    if !ch.is_control() {
-                        corelib::animations::update_animations();
-                        ALL_WINDOWS.with(|windows| {
-                            if let Some(Some(window)) =
-                                windows.borrow().get(&window_id).map(|weakref| weakref.upgrade())
-                            {
-                                let modifiers = window.current_keyboard_modifiers();
+                    corelib::animations::update_animations();
+                    ALL_WINDOWS.with(|windows| {
+                        if let Some(Some(window)) =
+                            windows.borrow().get(&window_id).map(|weakref| weakref.upgrade())
+                        {
+                            let modifiers = window.current_keyboard_modifiers();
+                            // Ctrl+A to Ctrl+Z are received as the ASCII control characters 1 to 26
+                            let ch = match ch as u32 {
+                                code @ 1..=26 if modifiers.control => {
+                                    (b'a' + code as u8 - 1) as char
+                                }
+                                _ => ch,
+                            };
+                            if ch.is_control() {
+                                return;
+                            }
 
-                                let mut event = KeyEvent {
-                                    event_type: KeyEventType::KeyPressed,
-                                    text: ch.to_string().into(),
-                                    modifiers,
-                                };
+                            let mut event = KeyEvent {
+                                event_type: KeyEventType::KeyPressed,
+                                text: ch.to_string().into(),
+                                modifiers,
+                            };
 
-                                window.self_weak.upgrade().unwrap().process_key_input(&event);
+                            window.process_key_input(&event);
 
-                                event.event_type = KeyEventType::KeyReleased;
-                                window.self_weak.upgrade().unwrap().process_key_input(&event);
-                            }
-                        });
-                    }
+                            event.event_type = KeyEventType::KeyReleased;
+                            window.process_key_input(&event);
+                        }
+                    });
                 }
                 winit::event::Event::WindowEvent {
                     ref window_id,
@@ -16,11 +16,13 @@
 use const_field_offset::FieldOffsets;
 use corelib::component::ComponentRc;
 use corelib::graphics::*;
-use corelib::input::{KeyboardModifiers, MouseEvent};
+use corelib::input::{KeyEvent, KeyboardModifiers, MouseEvent};
 use corelib::items::ItemRef;
 use corelib::layout::Orientation;
 use corelib::slice::Slice;
-use corelib::window::{ComponentWindow, PlatformWindow};
+use corelib::window::{
+    ComponentWindow, DialogPresentation, MenuEntry, NativeMenuBar, PlatformWindow,
+};
 use corelib::Property;
 use corelib::SharedString;
 use sixtyfps_corelib as corelib;
@@ -33,11 +35,19 @@
 
 type WindowFactoryFn = dyn Fn(winit::window::WindowBuilder) -> Backend;
 
+/// The dialog currently shown by a GraphicsWindow
+enum ActiveDialog {
+    /// The dialog's component is drawn centered on top of the window's component
+    Overlay(ComponentRc),
+    /// The dialog is shown in a window of its own
+    Window(Rc<corelib::window::Window>),
+}
+
 /// GraphicsWindow is an implementation of the [PlatformWindow][`crate::eventloop::PlatformWindow`] trait. This is
 /// typically instantiated by entry factory functions of the different graphics back ends.
 pub struct GraphicsWindow {
     pub(crate) self_weak: Weak<corelib::window::Window>,
-    window_factory: Box<WindowFactoryFn>,
+    window_factory: Rc<WindowFactoryFn>,
     map_state: RefCell<GraphicsWindowBackendState>,
     properties: Pin<Box<WindowProperties>>,
     keyboard_modifiers: std::cell::Cell<KeyboardModifiers>,
@@ -46,6 +56,10 @@ pub struct GraphicsWindow {
     /// Current popup's component and position
     /// FIXME: the popup should actually be another window, not just some overlay
     active_popup: std::cell::RefCell<Option<(ComponentRc, Point)>>,
+    /// The modal dialog shown by this window, if any
+    active_dialog: RefCell<Option<ActiveDialog>>,
+    /// When this window shows a dialog, the window that showed it
+    dialog_parent: RefCell<Weak<corelib::window::Window>>,
 
     default_font_properties: Pin<Rc<Property<FontRequest>>>,
 
@@ -64,6 +78,13 @@ impl GraphicsWindow {
     pub(crate) fn new(
         window_weak: &Weak<corelib::window::Window>,
         graphics_backend_factory: impl Fn(winit::window::WindowBuilder) -> Backend + 'static,
+    ) -> Rc<Self> {
+        Self::new_with_factory(window_weak, Rc::new(graphics_backend_factory))
+    }
+
+    fn new_with_factory(
+        window_weak: &Weak<corelib::window::Window>,
+        window_factory: Rc<WindowFactoryFn>,
     ) -> Rc<Self> {
         let default_font_properties_prop = Rc::pin(Property::default());
         default_font_properties_prop.set_binding({
@@ -88,12 +109,14 @@ pub(crate) fn new(
 
         Rc::new(Self {
             self_weak: window_weak.clone(),
-            window_factory: Box::new(graphics_backend_factory),
+            window_factory,
             map_state: RefCell::new(GraphicsWindowBackendState::Unmapped),
             properties: Box::pin(WindowProperties::default()),
             keyboard_modifiers: Default::default(),
             mouse_input_state: Default::default(),
             active_popup: Default::default(),
+            active_dialog: Default::default(),
+            dialog_parent: Default::default(),
             default_font_properties: default_font_properties_prop,
             graphics_cache: Default::default(),
             texture_cache: Default::default(),
@@ -314,9 +337,19 @@ pub fn draw(self: Rc<Self>) {
                 &mut renderer,
                 Point::default(),
             );
+            if let Some(ActiveDialog::Overlay(dialog)) = &*self.active_dialog.borrow() {
+                corelib::item_rendering::render_component_items(
+                    dialog,
+                    &mut renderer,
+                    self.overlay_dialog_position(dialog),
+                );
+            }
             if let Some(popup) = &*self.active_popup.borrow() {
                 corelib::item_rendering::render_component_items(&popup.0, &mut renderer, popup.1);
             }
+            if let Some((overlay, position)) = runtime_window.overlay() {
+                corelib::item_rendering::render_component_items(&overlay, &mut renderer, position);
+            }
             window.backend.borrow_mut().flush_renderer(renderer);
         })
     }
@@ -339,7 +372,15 @@ pub fn process_mouse_input(self: Rc<Self>, mut event: MouseEvent) {
             }
             popup.0.clone()
         } else {
-            self.component()
+            match &*self.active_dialog.borrow() {
+                // The window showing a modal dialog does not receive any input
+                Some(ActiveDialog::Window(_)) => return,
+                Some(ActiveDialog::Overlay(dialog)) => {
+                    event.translate(-self.overlay_dialog_position(dialog).to_vector());
+                    dialog.clone()
+                }
+                None => self.component(),
+            }
         };
 
         self.mouse_input_state.set(corelib::input::process_mouse_input(
@@ -358,6 +399,28 @@ pub fn process_mouse_input(self: Rc<Self>, mut event: MouseEvent) {
         }
     }
 
+    /// Same as Window::process_key_input, but the window showing a modal dialog in a window
+    /// of its own does not receive any key event.
+    pub fn process_key_input(&self, event: &KeyEvent) {
+        if matches!(&*self.active_dialog.borrow(), Some(ActiveDialog::Window(_))) {
+            return;
+        }
+        self.self_weak.upgrade().unwrap().process_key_input(event);
+    }
+
+    /// Returns the position of a dialog shown on top of this window: it is centered.
+    fn overlay_dialog_position(&self, dialog: &ComponentRc) -> Point {
+        let component_rc = self.component();
+        let window_size = ComponentRc::borrow_pin(&component_rc)
+            .as_ref()
+            .get_item_ref(0)
+            .as_ref()
+            .geometry()
+            .size;
+        let dialog_size = corelib::window::resolve_window_size(dialog);
+        ((window_size - dialog_size) / 2.).to_vector().to_point()
+    }
+
     /// Returns the currently active keyboard notifiers.
     pub fn current_keyboard_modifiers(&self) -> KeyboardModifiers {
         self.keyboard_modifiers.get()
@@ -442,6 +505,67 @@ fn close_popup(&self) {
         self.request_redraw();
     }
 
+    fn show_dialog(&self, dialog: &ComponentRc, presentation: DialogPresentation) {
+        self.close_dialog();
+        let active_dialog = match presentation {
+            DialogPresentation::overlay => ActiveDialog::Overlay(dialog.clone()),
+            DialogPresentation::window => {
+                let window_factory = self.window_factory.clone();
+                let dialog_window = corelib::window::Window::new(|window| {
+                    GraphicsWindow::new_with_factory(window, window_factory)
+                });
+                if let Some(graphics_window) = dialog_window.as_any().downcast_ref::<Self>() {
+                    *graphics_window.dialog_parent.borrow_mut() = self.self_weak.clone();
+                }
+                dialog_window.set_component(dialog);
+                ComponentWindow::new(dialog_window.clone()).show();
+                ActiveDialog::Window(dialog_window)
+            }
+        };
+        *self.active_dialog.borrow_mut() = Some(active_dialog);
+        self.request_redraw();
+    }
+
+    fn close_dialog(&self) {
+        let active_dialog = self.active_dialog.take();
+        if let Some(ActiveDialog::Window(dialog_window)) = &active_dialog {
+            if let Some(graphics_window) = dialog_window.as_any().downcast_ref::<Self>() {
+                graphics_window.dialog_parent.take();
+            }
+            ComponentWindow::new(dialog_window.clone()).hide();
+        }
+        if active_dialog.is_some() {
+            // The dialog may be closed from one of its own callbacks, so it must outlive them
+            corelib::timers::Timer::single_shot(Default::default(), move || drop(active_dialog));
+            self.request_redraw();
+        }
+    }
+
+    fn key_input_component(&self) -> Option<ComponentRc> {
+        match &*self.active_dialog.borrow() {
+            Some(ActiveDialog::Overlay(dialog)) => Some(dialog.clone()),
+            _ => None,
+        }
+    }
+
+    fn active_popup(&self) -> Option<ComponentRc> {
+        self.active_popup.borrow().as_ref().map(|(popup, _)| popup.clone())
+    }
+
+    fn has_native_menus(&self) -> bool {
+        false
+    }
+
+    fn show_native_menu(
+        &self,
+        _entries: &[MenuEntry],
+        _position: Point,
+        _activated: Box<dyn FnOnce(usize)>,
+    ) {
+    }
+
+    fn set_native_menu_bar(&self, _menu_bar: NativeMenuBar) {}
+
     fn request_window_properties_update(&self) {
         match &*self.map_state.borrow() {
             GraphicsWindowBackendState::Unmapped => {
@@ -512,7 +636,12 @@ fn show(self: Rc<Self>) {
     }
 
     fn hide(self: Rc<Self>) {
+        let dialog_parent = self.dialog_parent.take();
         self.unmap_window();
+        // A dialog closed by the window manager must be closed in the window that showed it too
+        if let Some(parent) = dialog_parent.upgrade() {
+            parent.close_dialog();
+        }
     }
 
     fn font_metrics(
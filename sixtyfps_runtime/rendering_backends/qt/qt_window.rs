@@ -16,7 +16,7 @@
 use sixtyfps_corelib::items::{self, FillRule, ItemRef, TextOverflow, TextWrap};
 use sixtyfps_corelib::layout::Orientation;
 use sixtyfps_corelib::slice::Slice;
-use sixtyfps_corelib::window::PlatformWindow;
+use sixtyfps_corelib::window::{DialogPresentation, MenuEntry, NativeMenuBar, PlatformWindow};
 use sixtyfps_corelib::{component::ComponentRc, SharedString};
 use sixtyfps_corelib::{ImageInner, PathData, Property};
 
@@ -68,6 +68,9 @@ struct TimerHandler : QObject {
 
     struct SixtyFPSWidget : QWidget {
         void *rust_window;
+        // The menu bar shown above the content of the window, if any. The contents margins
+        // of the widget reserve its space.
+        QMenuBar *menu_bar = nullptr;
 
         SixtyFPSWidget() {
             setMouseTracking(true);
@@ -76,7 +79,8 @@ struct SixtyFPSWidget : QWidget {
 
         void paintEvent(QPaintEvent *) override {
             QPainter painter(this);
-            painter.setClipRect(rect());
+            painter.setClipRect(contentsRect());
+            painter.translate(contentsRect().topLeft());
             painter.setRenderHints(QPainter::Antialiasing | QPainter::SmoothPixmapTransform);
             auto painter_ptr = &painter;
             rust!(SFPS_paintEvent [rust_window: &QtWindow as "void*", painter_ptr: &mut QPainter as "QPainter*"] {
@@ -84,27 +88,30 @@ struct SixtyFPSWidget : QWidget {
             });
         }
 
-        void resizeEvent(QResizeEvent *event) override {
-            QSize size = event->size();
+        void resizeEvent(QResizeEvent *) override {
+            if (menu_bar) {
+                menu_bar->setGeometry(0, 0, width(), contentsMargins().top());
+            }
+            QSize size = contentsRect().size();
             rust!(SFPS_resizeEvent [rust_window: &QtWindow as "void*", size: qttypes::QSize as "QSize"] {
                 rust_window.resize_event(size)
             });
         }
 
         void mousePressEvent(QMouseEvent *event) override {
-            QPoint pos = event->pos();
+            QPoint pos = event->pos() - contentsRect().topLeft();
             rust!(SFPS_mousePressEvent [rust_window: &QtWindow as "void*", pos: qttypes::QPoint as "QPoint"] {
                 let pos = Point::new(pos.x as _, pos.y as _);
                 rust_window.mouse_event(MouseEvent::MousePressed{ pos })
             });
         }
         void mouseReleaseEvent(QMouseEvent *event) override {
-            QPoint pos = event->pos();
+            QPoint pos = event->pos() - contentsRect().topLeft();
             rust!(SFPS_mouseReleaseEvent [rust_window: &QtWindow as "void*", pos: qttypes::QPoint as "QPoint"] {
                 let pos = Point::new(pos.x as _, pos.y as _);
                 rust_window.mouse_event(MouseEvent::MouseReleased{ pos})
             });
-            if (auto p = dynamic_cast<const SixtyFPSWidget*>(parent())) {
+            if (auto p = dynamic_cast<const SixtyFPSWidget*>(parent()); p && windowType() == Qt::Popup) {
                 // FIXME: better way to close the popup
                 void *parent_window = p->rust_window;
                 rust!(SFPS_mouseReleaseEventPopup [parent_window: &QtWindow as "void*", pos: qttypes::QPoint as "QPoint"] {
@@ -112,15 +119,25 @@ struct SixtyFPSWidget : QWidget {
                 });
             }
         }
+        void closeEvent(QCloseEvent *event) override {
+            if (auto p = dynamic_cast<const SixtyFPSWidget*>(parent()); p && windowType() == Qt::Dialog) {
+                // The dialog was closed by the window manager
+                void *parent_window = p->rust_window;
+                rust!(SFPS_closeEventDialog [parent_window: &QtWindow as "void*"] {
+                    parent_window.close_dialog();
+                });
+            }
+            QWidget::closeEvent(event);
+        }
         void mouseMoveEvent(QMouseEvent *event) override {
-            QPoint pos = event->pos();
+            QPoint pos = event->pos() - contentsRect().topLeft();
             rust!(SFPS_mouseMoveEvent [rust_window: &QtWindow as "void*", pos: qttypes::QPoint as "QPoint"] {
                 let pos = Point::new(pos.x as _, pos.y as _);
                 rust_window.mouse_event(MouseEvent::MouseMoved{pos})
             });
         }
         void wheelEvent(QWheelEvent *event) override {
-            QPointF pos = event->position();
+            QPointF pos = event->position() - contentsRect().topLeft();
             QPoint delta = event->pixelDelta();
             if (delta.isNull()) {
                 delta = event->angleDelta();
@@ -171,7 +188,7 @@ struct SixtyFPSWidget : QWidget {
                 }
             });
             if (!preferred_size.isEmpty()) {
-                return preferred_size;
+                return preferred_size + QSize(0, contentsMargins().top());
             } else {
                 return QWidget::sizeHint();
             }
@@ -963,6 +980,9 @@ pub struct QtWindow {
     pub(crate) self_weak: Weak<sixtyfps_corelib::window::Window>,
 
     popup_window: RefCell<Option<(Rc<sixtyfps_corelib::window::Window>, ComponentRc)>>,
+    dialog_window:
+        RefCell<Option<(Rc<sixtyfps_corelib::window::Window>, ComponentRc, DialogPresentation)>>,
+    native_menu_bar: RefCell<Option<Rc<NativeMenuBar>>>,
 
     cache: QtRenderingCache,
 
@@ -979,6 +999,8 @@ pub fn new(window_weak: &Weak<sixtyfps_corelib::window::Window>) -> Rc<Self> {
             widget_ptr,
             self_weak: window_weak.clone(),
             popup_window: Default::default(),
+            dialog_window: Default::default(),
+            native_menu_bar: Default::default(),
             cache: Default::default(),
             scale_factor: Box::pin(Property::new(1.)),
         });
@@ -1026,6 +1048,13 @@ fn paint_event(&self, painter: &mut QPainter) {
                 &mut renderer,
                 Point::default(),
             );
+            if let Some((overlay, position)) = runtime_window.overlay() {
+                sixtyfps_corelib::item_rendering::render_component_items(
+                    &overlay,
+                    &mut renderer,
+                    position,
+                );
+            }
 
             sixtyfps_corelib::animations::CURRENT_ANIMATION_DRIVER.with(|driver| {
                 if !driver.has_active_animations() {
@@ -1055,6 +1084,10 @@ fn resize_event(&self, size: qttypes::QSize) {
     }
 
     fn mouse_event(&self, event: MouseEvent) {
+        if matches!(&*self.dialog_window.borrow(), Some((_, _, DialogPresentation::overlay))) {
+            // The dialog shown on top of this window is modal
+            return;
+        }
         self.self_weak.upgrade().unwrap().process_mouse_input(event);
         timer_event();
     }
@@ -1081,6 +1114,15 @@ fn key_event(&self, key: i32, text: qttypes::QString, modif: u32, released: bool
         timer_event();
     }
 
+    /// Fill the menu of the native menu bar whose title is the entry at `index` with the
+    /// current entries, as they may have changed since the menu was last shown
+    fn refresh_native_menu_bar_menu(&self, menu: *mut std::ffi::c_void, index: usize) {
+        let menu_bar = self.native_menu_bar.borrow().clone();
+        if let Some(menu_bar) = menu_bar {
+            fill_native_menu_bar_menu(menu, &(menu_bar.entries)(), index);
+        }
+    }
+
     /// Set the min/max sizes on the QWidget
     fn apply_geometry_constraint(
         &self,
@@ -1093,12 +1135,14 @@ fn apply_geometry_constraint(
         let mut max_width: f32 = constraints_h.max.max(constraints_h.min);
         let mut max_height: f32 = constraints_v.max.max(constraints_v.min);
         cpp! {unsafe [widget_ptr as "QWidget*",  min_width as "float", min_height as "float", mut max_width as "float", mut max_height as "float"] {
-            widget_ptr->setMinimumSize(QSize(min_width, min_height));
+            // The space of the menu bar is not part of the constraints of the component
+            int menu_bar_height = widget_ptr->contentsMargins().top();
+            widget_ptr->setMinimumSize(QSize(min_width, min_height + menu_bar_height));
             if (max_width > QWIDGETSIZE_MAX)
                 max_width = QWIDGETSIZE_MAX;
-            if (max_height > QWIDGETSIZE_MAX)
-                max_height = QWIDGETSIZE_MAX;
-            widget_ptr->setMaximumSize(QSize(max_width, max_height).expandedTo({1,1}));
+            if (max_height > QWIDGETSIZE_MAX - menu_bar_height)
+                max_height = QWIDGETSIZE_MAX - menu_bar_height;
+            widget_ptr->setMaximumSize(QSize(max_width, max_height + menu_bar_height).expandedTo({1,1}));
         }};
     }
 
@@ -1165,7 +1209,9 @@ fn apply_window_properties(&self, window_item: Pin<&items::Window>) {
         if size.width == 0 || size.height == 0 {
             let existing_size = cpp!(unsafe [widget_ptr as "QWidget*"] -> qttypes::QSize as "QSize" {
                 auto sizeHint = widget_ptr->sizeHint();
-                return sizeHint.isValid() ? sizeHint : widget_ptr->size();
+                return sizeHint.isValid()
+                    ? sizeHint - QSize(0, widget_ptr->contentsMargins().top())
+                    : widget_ptr->contentsRect().size();
             });
             if size.width == 0 {
                 window_item.width.set(existing_size.width as _);
@@ -1178,8 +1224,8 @@ fn apply_window_properties(&self, window_item: Pin<&items::Window>) {
         }
         let background: u32 = window_item.background().as_argb_encoded();
         cpp! {unsafe [widget_ptr as "QWidget*",  title as "QString", size as "QSize", background as "QRgb"] {
-            if (size != widget_ptr->size()) {
-                widget_ptr->resize(size.expandedTo({1, 1}));
+            if (size != widget_ptr->contentsRect().size()) {
+                widget_ptr->resize(size.expandedTo({1, 1}) + QSize(0, widget_ptr->contentsMargins().top()));
             }
             widget_ptr->setWindowTitle(title);
             auto pal = widget_ptr->palette();
@@ -1239,7 +1285,7 @@ fn show_popup(&self, popup: &sixtyfps_corelib::component::ComponentRc, position:
         let widget_ptr = self.widget_ptr();
         cpp! {unsafe [widget_ptr as "QWidget*", popup_ptr as "QWidget*", pos as "QPoint", size as "QSize"] {
             popup_ptr->setParent(widget_ptr, Qt::Popup);
-            popup_ptr->setGeometry(QRect(pos + widget_ptr->geometry().topLeft(), size));
+            popup_ptr->setGeometry(QRect(pos + widget_ptr->geometry().topLeft() + widget_ptr->contentsRect().topLeft(), size));
             popup_ptr->show();
         }};
         self.popup_window.replace(Some((window, popup.clone())));
@@ -1249,6 +1295,144 @@ fn close_popup(&self) {
         self.popup_window.replace(None);
     }
 
+    fn show_dialog(&self, dialog: &ComponentRc, presentation: DialogPresentation) {
+        self.close_dialog();
+        let size = sixtyfps_corelib::window::resolve_window_size(dialog);
+        let size = qttypes::QSize { width: size.width as _, height: size.height as _ };
+
+        let window = sixtyfps_corelib::window::Window::new(|window| QtWindow::new(window));
+        let dialog_window: &QtWindow =
+            <dyn std::any::Any>::downcast_ref(window.as_ref().as_any()).unwrap();
+        window.set_component(dialog);
+        let dialog_ptr = dialog_window.widget_ptr();
+        let overlay = presentation == DialogPresentation::overlay;
+        let widget_ptr = self.widget_ptr();
+        cpp! {unsafe [widget_ptr as "QWidget*", dialog_ptr as "QWidget*", overlay as "bool", size as "QSize"] {
+            QRect geometry(QPoint(), size);
+            if (overlay) {
+                dialog_ptr->setParent(widget_ptr);
+                geometry.moveCenter(widget_ptr->contentsRect().center());
+                // The key events go to the dialog and not to the window under it
+                dialog_ptr->setFocus();
+            } else {
+                dialog_ptr->setParent(widget_ptr, Qt::Dialog);
+                dialog_ptr->setWindowModality(Qt::WindowModal);
+                geometry.moveCenter(widget_ptr->geometry().center());
+            }
+            dialog_ptr->setGeometry(geometry);
+            dialog_ptr->show();
+        }};
+        self.dialog_window.replace(Some((window, dialog.clone(), presentation)));
+    }
+
+    fn close_dialog(&self) {
+        if let Some(dialog_window) = self.dialog_window.take() {
+            let widget_ptr =
+                <dyn std::any::Any>::downcast_ref::<QtWindow>(dialog_window.0.as_any())
+                    .unwrap()
+                    .widget_ptr();
+            cpp! {unsafe [widget_ptr as "QWidget*"] {
+                widget_ptr->hide();
+            }};
+            // The dialog may be closed from one of its own callbacks, so it must outlive them
+            sixtyfps_corelib::timers::Timer::single_shot(Default::default(), move || {
+                drop(dialog_window)
+            });
+        }
+    }
+
+    fn key_input_component(&self) -> Option<ComponentRc> {
+        // The popups and the dialogs are widgets of their own, which receive their key events
+        None
+    }
+
+    fn active_popup(&self) -> Option<ComponentRc> {
+        // The popups are windows of their own, which activate their shortcuts themselves
+        None
+    }
+
+    fn has_native_menus(&self) -> bool {
+        true
+    }
+
+    fn show_native_menu(
+        &self,
+        entries: &[MenuEntry],
+        position: Point,
+        activated: Box<dyn FnOnce(usize)>,
+    ) {
+        let widget_ptr = self.widget_ptr();
+        let menu = cpp! {unsafe [widget_ptr as "QWidget*"] -> *mut std::ffi::c_void as "QMenu*" {
+            return new QMenu(widget_ptr);
+        }};
+        fill_native_menu(menu, entries, 0, 0);
+        let activated = Box::into_raw(Box::new(Some(activated)));
+        let pos = qttypes::QPoint { x: position.x as _, y: position.y as _ };
+        cpp! {unsafe [widget_ptr as "QWidget*", menu as "QMenu*", pos as "QPoint", activated as "void*"] {
+            // The actions of the sub-menus are also reported by the triggered signal of the menu
+            QObject::connect(menu, &QMenu::triggered, [activated](QAction *action) {
+                int index = action->data().toInt();
+                rust!(SFPS_nativeMenuTriggered [activated: *mut Option<Box<dyn FnOnce(usize)>> as "void*", index: i32 as "int"] {
+                    if let Some(activated) = (*activated).take() {
+                        activated(index as usize)
+                    }
+                });
+            });
+            // The menu is hidden before the triggered signal is emitted, so it must be deleted later
+            QObject::connect(menu, &QMenu::aboutToHide, menu, &QObject::deleteLater);
+            QObject::connect(menu, &QObject::destroyed, [activated] {
+                rust!(SFPS_nativeMenuDestroyed [activated: *mut Option<Box<dyn FnOnce(usize)>> as "void*"] {
+                    drop(Box::from_raw(activated))
+                });
+            });
+            menu->popup(widget_ptr->mapToGlobal(pos + widget_ptr->contentsRect().topLeft()));
+        }};
+    }
+
+    fn set_native_menu_bar(&self, menu_bar: NativeMenuBar) {
+        let entries = (menu_bar.entries)();
+        self.native_menu_bar.replace(Some(Rc::new(menu_bar)));
+        let widget_ptr = self.widget_ptr();
+        cpp! {unsafe [widget_ptr as "SixtyFPSWidget*"] {
+            delete widget_ptr->menu_bar;
+            auto menu_bar = new QMenuBar(widget_ptr);
+            widget_ptr->menu_bar = menu_bar;
+            void *rust_window = widget_ptr->rust_window;
+            QObject::connect(menu_bar, &QMenuBar::triggered, [rust_window](QAction *action) {
+                int index = action->data().toInt();
+                rust!(SFPS_nativeMenuBarTriggered [rust_window: &QtWindow as "void*", index: i32 as "int"] {
+                    let menu_bar = rust_window.native_menu_bar.borrow().clone();
+                    if let Some(menu_bar) = menu_bar {
+                        (menu_bar.activated)(index as usize)
+                    }
+                });
+            });
+        }};
+        for (index, _) in entries.iter().enumerate().filter(|(_, entry)| entry.depth == 0) {
+            let index = index as i32;
+            let menu = cpp! {unsafe [widget_ptr as "SixtyFPSWidget*", index as "int"] -> *mut std::ffi::c_void as "QMenu*" {
+                auto menu = widget_ptr->menu_bar->addMenu(QString());
+                QObject::connect(menu, &QMenu::aboutToShow, [rust_window = widget_ptr->rust_window, menu, index] {
+                    rust!(SFPS_nativeMenuBarAboutToShow [rust_window: &QtWindow as "void*", menu: *mut std::ffi::c_void as "QMenu*", index: i32 as "int"] {
+                        rust_window.refresh_native_menu_bar_menu(menu, index as usize)
+                    });
+                });
+                return menu;
+            }};
+            fill_native_menu_bar_menu(menu, &entries, index as usize);
+        }
+        cpp! {unsafe [widget_ptr as "SixtyFPSWidget*"] {
+            auto menu_bar = widget_ptr->menu_bar;
+            // With a menu bar of the platform, such as on macOS, the widget is not shown
+            int height = menu_bar->isNativeMenuBar() ? 0 : menu_bar->sizeHint().height();
+            QSize size = widget_ptr->contentsRect().size();
+            widget_ptr->setContentsMargins(0, height, 0, 0);
+            widget_ptr->resize(size + QSize(0, height));
+            menu_bar->setGeometry(0, 0, widget_ptr->width(), height);
+            menu_bar->show();
+        }};
+    }
+
     fn font_metrics(
         &self,
         _item_graphics_cache: &sixtyfps_corelib::item_rendering::CachedRenderingData,
@@ -1263,6 +1447,73 @@ fn as_any(&self) -> &dyn std::any::Any {
     }
 }
 
+/// Add the entries to the QMenu, the entries with a depth of `base_depth` being added to the
+/// menu itself. The data of each action is the index of its entry plus `first_index`.
+fn fill_native_menu(
+    menu: *mut std::ffi::c_void,
+    entries: &[MenuEntry],
+    first_index: usize,
+    base_depth: i32,
+) {
+    // The menus of each depth level of the entry being added
+    let mut menus = vec![menu];
+    for (index, entry) in entries.iter().enumerate() {
+        menus.truncate((entry.depth - base_depth).max(0) as usize + 1);
+        let parent = *menus.last().unwrap();
+        let title: qttypes::QString = entry.title.as_str().into();
+        let shortcut: qttypes::QString = entry.shortcut.as_str().into();
+        let MenuEntry { enabled, checkable, checked, is_separator, has_submenu, .. } = *entry;
+        let index = (first_index + index) as i32;
+        let submenu = cpp! {unsafe [parent as "QMenu*", title as "QString", shortcut as "QString",
+                enabled as "bool", checkable as "bool", checked as "bool", is_separator as "bool",
+                has_submenu as "bool", index as "int"] -> *mut std::ffi::c_void as "QMenu*" {
+            if (is_separator) {
+                parent->addSeparator();
+                return nullptr;
+            }
+            if (has_submenu) {
+                auto submenu = parent->addMenu(title);
+                submenu->setEnabled(enabled);
+                return submenu;
+            }
+            auto action = parent->addAction(title);
+            action->setData(index);
+            action->setEnabled(enabled);
+            action->setCheckable(checkable);
+            action->setChecked(checked);
+            if (!shortcut.isEmpty()) {
+                action->setShortcut(QKeySequence(shortcut));
+                // The shortcut is only shown, the key events are handled by the component
+                action->setShortcutContext(Qt::WidgetShortcut);
+            }
+            return nullptr;
+        }};
+        if has_submenu {
+            menus.push(submenu);
+        }
+    }
+}
+
+/// Replace the content of the menu of a native menu bar with the entries of the menu whose
+/// title is the entry at `index`
+fn fill_native_menu_bar_menu(menu: *mut std::ffi::c_void, entries: &[MenuEntry], index: usize) {
+    let title_entry = match entries.get(index) {
+        Some(entry) => entry,
+        None => return,
+    };
+    let title: qttypes::QString = title_entry.title.as_str().into();
+    let enabled = title_entry.enabled;
+    cpp! {unsafe [menu as "QMenu*", title as "QString", enabled as "bool"] {
+        menu->setTitle(title);
+        menu->setEnabled(enabled);
+        qDeleteAll(menu->findChildren<QMenu*>(QString(), Qt::FindDirectChildrenOnly));
+        menu->clear();
+    }};
+    let menu_entries = &entries[index + 1..];
+    let len = menu_entries.iter().position(|entry| entry.depth <= 0).unwrap_or(menu_entries.len());
+    fill_native_menu(menu, &menu_entries[..len], index + 1, 1);
+}
+
 fn get_font(request: FontRequest) -> QFont {
     let family: qttypes::QString = request.family.unwrap_or_default().as_str().into();
     let pixel_size: f32 = request.pixel_size.unwrap_or(0.);
@@ -1385,12 +1636,19 @@ fn qt_key_to_string(key: key_generated::Qt_Key, event_text: String) -> SharedStr
         key_generated::Qt_Key_Key_End => Some(InternalKeyCode::End),
         key_generated::Qt_Key_Key_Home => Some(InternalKeyCode::Home),
         key_generated::Qt_Key_Key_Return => Some(InternalKeyCode::Return),
+        key_generated::Qt_Key_Key_Escape => Some(InternalKeyCode::Escape),
+        key if (key_generated::Qt_Key_Key_F1..=key_generated::Qt_Key_Key_F12).contains(&key) => {
+            Some(InternalKeyCode::Function((key - key_generated::Qt_Key_Key_F1 + 1) as u8))
+        }
         _ => None,
     } {
         return special_key_code.encode_to_string();
     };
 
-    if !event_text.is_empty() {
+    // With the control modifier, the text of the letters is an ASCII control character
+    let is_control_letter = event_text.chars().all(|ch| ch.is_control())
+        && (key_generated::Qt_Key_Key_A..=key_generated::Qt_Key_Key_Z).contains(&key);
+    if !event_text.is_empty() && !is_control_letter {
         return event_text.into();
     }
 
@@ -0,0 +1,316 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+//! Font selection, text layout, and conversion of the glyphs to paths
+
+use sixtyfps_corelib::graphics::{FontMetrics, FontRequest, Size};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub const DEFAULT_FONT_SIZE: f32 = 12.;
+pub const DEFAULT_FONT_WEIGHT: i32 = 400; // CSS normal
+
+/// The font used when the requested family is not available, so that the rendering
+/// does not depend on the fonts installed on the system. (Shared with the GL backend)
+const FALLBACK_FAMILY: &str = "DejaVu Sans";
+const FALLBACK_FONT: &[u8] = include_bytes!("fonts/DejaVuSans.ttf");
+
+struct FontDatabase {
+    db: fontdb::Database,
+    system_fonts_loaded: bool,
+    loaded: HashMap<fontdb::ID, Rc<FontData>>,
+}
+
+thread_local! {
+    static FONT_DB: RefCell<FontDatabase> = RefCell::new({
+        let mut db = fontdb::Database::new();
+        db.load_font_data(FALLBACK_FONT.into());
+        FontDatabase { db, system_fonts_loaded: false, loaded: Default::default() }
+    })
+}
+
+pub fn register_font_from_memory(data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    FONT_DB.with(|db| db.borrow_mut().db.load_font_data(data.into()));
+    Ok(())
+}
+
+pub fn register_font_from_path(path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    FONT_DB.with(|db| db.borrow_mut().db.load_font_file(path).map_err(|e| e.into()))
+}
+
+struct FontData {
+    bytes: Vec<u8>,
+    index: u32,
+}
+
+/// A font face at a given size
+#[derive(Clone)]
+pub struct Font {
+    data: Rc<FontData>,
+    pixel_size: f32,
+    letter_spacing: f32,
+}
+
+impl Font {
+    /// Select the font matching the request, which must be merged with the defaults of the window
+    pub fn new(request: &FontRequest) -> Self {
+        let data = FONT_DB.with(|db| {
+            let mut db = db.borrow_mut();
+            let query = |db: &fontdb::Database| {
+                let families = request
+                    .family
+                    .iter()
+                    .map(|family| fontdb::Family::Name(family))
+                    .chain(std::iter::once(fontdb::Family::Name(FALLBACK_FAMILY)))
+                    .collect::<Vec<_>>();
+                db.query(&fontdb::Query {
+                    families: &families,
+                    weight: fontdb::Weight(
+                        request.weight.unwrap_or(DEFAULT_FONT_WEIGHT).clamp(1, 1000) as u16,
+                    ),
+                    ..Default::default()
+                })
+            };
+            let mut id = query(&db.db);
+            let is_fallback = |db: &fontdb::Database, id: Option<fontdb::ID>| match id
+                .and_then(|id| db.face(id))
+            {
+                Some(face) => face.family == FALLBACK_FAMILY,
+                None => true,
+            };
+            if request.family.is_some() && !db.system_fonts_loaded && is_fallback(&db.db, id) {
+                // Only load the system fonts when they are needed
+                db.system_fonts_loaded = true;
+                db.db.load_system_fonts();
+                id = query(&db.db);
+            }
+            let id = id.expect("the fallback font is always available");
+            if let Some(data) = db.loaded.get(&id) {
+                return data.clone();
+            }
+            let data = Rc::new(
+                db.db
+                    .with_face_data(id, |bytes, index| FontData { bytes: bytes.to_vec(), index })
+                    .expect("the font data must be readable"),
+            );
+            db.loaded.insert(id, data.clone());
+            data
+        });
+        Self {
+            data,
+            pixel_size: request.pixel_size.unwrap_or(DEFAULT_FONT_SIZE),
+            letter_spacing: request.letter_spacing.unwrap_or_default(),
+        }
+    }
+
+    fn face(&self) -> ttf_parser::Face<'_> {
+        ttf_parser::Face::from_slice(&self.data.bytes, self.data.index)
+            .expect("the font was already parsed by the font database")
+    }
+
+    fn scale(face: &ttf_parser::Face) -> f32 {
+        1. / face.units_per_em().unwrap_or(1000) as f32
+    }
+
+    /// The distance from the top of a line to the baseline
+    pub fn ascent(&self) -> f32 {
+        let face = self.face();
+        face.ascender() as f32 * Self::scale(&face) * self.pixel_size
+    }
+
+    pub fn height(&self) -> f32 {
+        let face = self.face();
+        (face.ascender() - face.descender() + face.line_gap()) as f32
+            * Self::scale(&face)
+            * self.pixel_size
+    }
+
+    /// The position of each character of the text, and the total width
+    fn advances<'a>(
+        &self,
+        face: &'a ttf_parser::Face<'a>,
+        text: &'a str,
+    ) -> impl Iterator<Item = (usize, char, f32)> + 'a {
+        let scale = Self::scale(face) * self.pixel_size;
+        let letter_spacing = self.letter_spacing;
+        text.char_indices().map(move |(index, c)| {
+            let advance = face
+                .glyph_index(c)
+                .and_then(|glyph| face.glyph_hor_advance(glyph))
+                .unwrap_or_default() as f32
+                * scale;
+            (index, c, advance + letter_spacing)
+        })
+    }
+
+    pub fn text_width(&self, text: &str) -> f32 {
+        let face = self.face();
+        self.advances(&face, text).map(|(_, _, advance)| advance).sum()
+    }
+
+    /// Split the text in lines, at the new lines, and at the spaces to fit in `max_width` if set
+    pub fn lines<'a>(&self, text: &'a str, max_width: Option<f32>) -> Vec<&'a str> {
+        let max_width = match max_width {
+            Some(max_width) => max_width,
+            None => return text.split('\n').collect(),
+        };
+        let face = self.face();
+        let mut lines = vec![];
+        for paragraph in text.split('\n') {
+            let mut start = 0;
+            let mut width = 0.;
+            let mut last_space = None;
+            for (index, c, advance) in self.advances(&face, paragraph) {
+                if width + advance > max_width && index > start && c != ' ' {
+                    match last_space.filter(|space| *space > start) {
+                        Some(space) => {
+                            lines.push(&paragraph[start..space]);
+                            start = space + 1;
+                        }
+                        None => {
+                            lines.push(&paragraph[start..index]);
+                            start = index;
+                        }
+                    }
+                    width = self.text_width(&paragraph[start..index]);
+                    last_space = None;
+                }
+                if c == ' ' {
+                    last_space = Some(index);
+                }
+                width += advance;
+            }
+            lines.push(&paragraph[start..]);
+        }
+        lines
+    }
+
+    /// Return the text truncated with an ellipsis so that it fits in `max_width`
+    pub fn elide(&self, text: &str, max_width: f32) -> String {
+        if self.text_width(text) <= max_width {
+            return text.into();
+        }
+        let available = max_width - self.text_width("…");
+        let face = self.face();
+        let mut width = 0.;
+        let end = self
+            .advances(&face, text)
+            .find(|(_, _, advance)| {
+                width += advance;
+                width > available
+            })
+            .map_or(text.len(), |(index, _, _)| index);
+        format!("{}…", &text[..end])
+    }
+
+    /// The byte offset of the character at the given x position of the text
+    pub fn text_offset_for_x_position(&self, text: &str, x: f32) -> usize {
+        let face = self.face();
+        let mut current_x = 0.;
+        for (index, _, advance) in self.advances(&face, text) {
+            if current_x + advance / 2. >= x {
+                return index;
+            }
+            current_x += advance;
+        }
+        text.len()
+    }
+
+    /// Add the outlines of the glyphs of a line of text to the path, starting at the top left
+    /// corner `x`, `y`. Return the x position of each character, followed by the end of the line.
+    pub fn append_text_path(
+        &self,
+        builder: &mut tiny_skia::PathBuilder,
+        text: &str,
+        x: f32,
+        y: f32,
+    ) -> Vec<f32> {
+        let face = self.face();
+        let scale = Self::scale(&face) * self.pixel_size;
+        let baseline = y + self.ascent();
+        let mut positions = vec![];
+        let mut current_x = x;
+        for (_, c, advance) in self.advances(&face, text) {
+            positions.push(current_x);
+            if let Some(glyph) = face.glyph_index(c) {
+                let mut outline =
+                    GlyphOutline { builder: &mut *builder, x: current_x, y: baseline, scale };
+                face.outline_glyph(glyph, &mut outline);
+            }
+            current_x += advance;
+        }
+        positions.push(current_x);
+        positions
+    }
+}
+
+/// Forward the outline of a glyph to the path builder, converting from font units with
+/// the y axis going up.
+struct GlyphOutline<'a> {
+    builder: &'a mut tiny_skia::PathBuilder,
+    x: f32,
+    y: f32,
+    scale: f32,
+}
+
+impl GlyphOutline<'_> {
+    fn map(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.x + x * self.scale, self.y - y * self.scale)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphOutline<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.builder.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.builder.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x, y) = self.map(x, y);
+        self.builder.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x2, y2) = self.map(x2, y2);
+        let (x, y) = self.map(x, y);
+        self.builder.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+pub struct SoftwareFontMetrics {
+    pub font: Font,
+}
+
+impl FontMetrics for SoftwareFontMetrics {
+    fn text_size(&self, text: &str, max_width: Option<f32>) -> Size {
+        let lines = self.font.lines(text, max_width);
+        let width = lines.iter().map(|line| self.font.text_width(line)).fold(0., f32::max);
+        Size::new(width, lines.len() as f32 * self.font.height())
+    }
+
+    fn line_height(&self) -> f32 {
+        self.font.height()
+    }
+
+    fn text_offset_for_x_position(&self, text: &str, x: f32) -> usize {
+        self.font.text_offset_for_x_position(text, x)
+    }
+}
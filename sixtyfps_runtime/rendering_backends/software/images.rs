@@ -0,0 +1,103 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+//! Decoding of the images into pixmaps
+
+use image::GenericImageView;
+use sixtyfps_corelib::graphics::Size;
+use sixtyfps_corelib::ImageInner;
+
+/// The size of the image, without decoding it entirely when possible
+pub fn image_size(resource: &ImageInner) -> Size {
+    match resource {
+        ImageInner::None => Default::default(),
+        ImageInner::EmbeddedRgbaImage { width, height, .. } => Size::new(*width as _, *height as _),
+        #[cfg(feature = "svg")]
+        ImageInner::AbsoluteFilePath(path) if path.ends_with(".svg") => {
+            std::fs::read(path.as_str()).ok().map_or_else(Default::default, |data| svg_size(&data))
+        }
+        #[cfg(feature = "svg")]
+        ImageInner::EmbeddedData(data) if data.starts_with(b"<svg") => svg_size(data.as_slice()),
+        ImageInner::AbsoluteFilePath(path) => image::image_dimensions(path.as_str())
+            .map(|(width, height)| Size::new(width as _, height as _))
+            .unwrap_or_default(),
+        ImageInner::EmbeddedData(data) => image::load_from_memory(data.as_slice())
+            .map(|image| {
+                let (width, height) = image.dimensions();
+                Size::new(width as _, height as _)
+            })
+            .unwrap_or_default(),
+    }
+}
+
+/// Whether the image is rendered from a SVG document
+pub fn is_svg(resource: &ImageInner) -> bool {
+    match resource {
+        ImageInner::AbsoluteFilePath(path) => cfg!(feature = "svg") && path.ends_with(".svg"),
+        ImageInner::EmbeddedData(data) => cfg!(feature = "svg") && data.starts_with(b"<svg"),
+        _ => false,
+    }
+}
+
+/// Decode the image. The SVG images are rendered with the size `svg_size`, in physical pixels.
+#[cfg_attr(not(feature = "svg"), allow(unused_variables))]
+pub fn load_image(resource: &ImageInner, svg_size: (u32, u32)) -> Option<tiny_skia::Pixmap> {
+    let image = match resource {
+        ImageInner::None => return None,
+        ImageInner::EmbeddedRgbaImage { width, height, data } => {
+            let mut pixmap = tiny_skia::Pixmap::new(*width, *height)?;
+            for (pixel, argb) in pixmap.pixels_mut().iter_mut().zip(data.iter()) {
+                let [a, r, g, b] = argb.to_be_bytes();
+                *pixel = tiny_skia::ColorU8::from_rgba(r, g, b, a).premultiply();
+            }
+            return Some(pixmap);
+        }
+        #[cfg(feature = "svg")]
+        ImageInner::AbsoluteFilePath(path) if path.ends_with(".svg") => {
+            return render_svg(&std::fs::read(path.as_str()).ok()?, svg_size)
+        }
+        #[cfg(feature = "svg")]
+        ImageInner::EmbeddedData(data) if data.starts_with(b"<svg") => {
+            return render_svg(data.as_slice(), svg_size)
+        }
+        ImageInner::AbsoluteFilePath(path) => image::open(path.as_str()).map_err(|err| {
+            eprintln!("Error loading image from {}: {}", path, err);
+        }),
+        ImageInner::EmbeddedData(data) => image::load_from_memory(data.as_slice()).map_err(|err| {
+            eprintln!("Error decoding image: {}", err);
+        }),
+    };
+    let image = image.ok()?.to_rgba8();
+    let mut pixmap = tiny_skia::Pixmap::new(image.width(), image.height())?;
+    for (pixel, rgba) in pixmap.pixels_mut().iter_mut().zip(image.pixels()) {
+        let [r, g, b, a] = rgba.0;
+        *pixel = tiny_skia::ColorU8::from_rgba(r, g, b, a).premultiply();
+    }
+    Some(pixmap)
+}
+
+#[cfg(feature = "svg")]
+fn svg_size(data: &[u8]) -> Size {
+    usvg::Tree::from_data(data, &Default::default())
+        .map(|tree| {
+            let size = tree.svg_node().size;
+            Size::new(size.width() as _, size.height() as _)
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "svg")]
+fn render_svg(data: &[u8], (width, height): (u32, u32)) -> Option<tiny_skia::Pixmap> {
+    let tree = usvg::Tree::from_data(data, &Default::default())
+        .map_err(|err| eprintln!("Error loading SVG: {}", err))
+        .ok()?;
+    let mut pixmap = tiny_skia::Pixmap::new(width.max(1), height.max(1))?;
+    resvg::render(&tree, usvg::FitTo::Size(width.max(1), height.max(1)), pixmap.as_mut())?;
+    Some(pixmap)
+}
@@ -0,0 +1,415 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+/*!
+A rendering backend which renders the components offscreen with the CPU, using [tiny-skia](https://docs.rs/tiny-skia).
+
+It does not need a display server or a GPU. It is used to take screenshots of the components
+(see [`render_window`]). Its event loop only runs the posted events, the timers and the animations. The text is rendered with the DejaVu Sans font unless
+another font is requested, so that the result does not depend on the fonts installed on the system.
+
+*NOTE*: This library is an internal crate for the [SixtyFPS project](https://sixtyfps.io).
+This crate should not be used directly by application using SixtyFPS.
+You should use the `sixtyfps` crate instead.
+
+*/
+#![doc(html_logo_url = "https://sixtyfps.io/resources/logo.drawio.svg")]
+
+use sixtyfps_corelib::component::ComponentRc;
+use sixtyfps_corelib::graphics::{FontRequest, Image, Point, Size};
+use sixtyfps_corelib::items::ItemRef;
+use sixtyfps_corelib::layout::Orientation;
+use sixtyfps_corelib::slice::Slice;
+use sixtyfps_corelib::window::{
+    ComponentWindow, DialogPresentation, MenuEntry, NativeMenuBar, PlatformWindow, Window,
+};
+use sixtyfps_corelib::Property;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Condvar, Mutex};
+
+mod fonts;
+mod images;
+mod renderer;
+
+pub use tiny_skia::Pixmap;
+
+thread_local!(static SHOWN_WINDOWS: Cell<usize> = Cell::new(0));
+
+#[derive(Default)]
+struct EventQueue {
+    events: VecDeque<Box<dyn FnOnce() + Send>>,
+    quit: bool,
+}
+
+#[derive(Default)]
+pub struct SoftwareBackend {
+    clipboard: Mutex<Option<String>>,
+    event_queue: Mutex<EventQueue>,
+    event_posted: Condvar,
+}
+
+impl sixtyfps_corelib::backend::Backend for SoftwareBackend {
+    fn create_window(&'static self) -> ComponentWindow {
+        ComponentWindow::new(Window::new(|_| Rc::new(SoftwareWindow::default())))
+    }
+
+    fn run_event_loop(&'static self, behavior: sixtyfps_corelib::backend::EventLoopQuitBehavior) {
+        use sixtyfps_corelib::timers::TimerList;
+        loop {
+            let events = std::mem::take(&mut self.event_queue.lock().unwrap().events);
+            for event in events {
+                event();
+            }
+            sixtyfps_corelib::animations::update_animations();
+            TimerList::maybe_activate_timers();
+
+            let mut queue = self.event_queue.lock().unwrap();
+            if std::mem::take(&mut queue.quit) {
+                break;
+            }
+            if matches!(
+                behavior,
+                sixtyfps_corelib::backend::EventLoopQuitBehavior::QuitOnLastWindowClosed
+            ) && SHOWN_WINDOWS.with(|count| count.get()) == 0
+            {
+                break;
+            }
+            if !queue.events.is_empty() {
+                continue;
+            }
+            // Nothing is drawn, but the animations still need to advance for the bindings using them
+            let has_active_animations = sixtyfps_corelib::animations::CURRENT_ANIMATION_DRIVER
+                .with(|driver| driver.has_active_animations());
+            let timeout = if has_active_animations {
+                Some(std::time::Duration::from_millis(16))
+            } else {
+                TimerList::next_timeout()
+                    .map(|timeout| timeout.saturating_duration_since(std::time::Instant::now()))
+            };
+            match timeout {
+                Some(timeout) => drop(self.event_posted.wait_timeout(queue, timeout).unwrap()),
+                None => drop(self.event_posted.wait(queue).unwrap()),
+            }
+        }
+    }
+
+    fn quit_event_loop(&'static self) {
+        self.event_queue.lock().unwrap().quit = true;
+        self.event_posted.notify_one();
+    }
+
+    fn register_font_from_memory(
+        &'static self,
+        data: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        fonts::register_font_from_memory(data)
+    }
+
+    fn register_font_from_path(
+        &'static self,
+        path: &std::path::Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        fonts::register_font_from_path(path)
+    }
+
+    fn set_clipboard_text(&'static self, text: String) {
+        *self.clipboard.lock().unwrap() = Some(text);
+    }
+
+    fn clipboard_text(&'static self) -> Option<String> {
+        self.clipboard.lock().unwrap().clone()
+    }
+
+    fn post_event(&'static self, event: Box<dyn FnOnce() + Send>) {
+        self.event_queue.lock().unwrap().events.push_back(event);
+        self.event_posted.notify_one();
+    }
+
+    fn image_size(&'static self, image: &Image) -> Size {
+        images::image_size(image.into())
+    }
+}
+
+pub struct SoftwareWindow {
+    scale_factor: Pin<Box<Property<f32>>>,
+    default_font_properties: RefCell<FontRequest>,
+    active_popup: RefCell<Option<(ComponentRc, Point)>>,
+    active_dialog: RefCell<Option<(ComponentRc, DialogPresentation)>>,
+    shown: Cell<bool>,
+}
+
+impl Default for SoftwareWindow {
+    fn default() -> Self {
+        Self {
+            scale_factor: Box::pin(Property::new(1.)),
+            default_font_properties: Default::default(),
+            active_popup: Default::default(),
+            active_dialog: Default::default(),
+            shown: Cell::new(false),
+        }
+    }
+}
+
+impl PlatformWindow for SoftwareWindow {
+    fn show(self: Rc<Self>) {
+        if !self.shown.replace(true) {
+            SHOWN_WINDOWS.with(|count| count.set(count.get() + 1));
+        }
+    }
+
+    fn hide(self: Rc<Self>) {
+        if self.shown.replace(false) {
+            SHOWN_WINDOWS.with(|count| count.set(count.get() - 1));
+        }
+    }
+
+    fn request_redraw(&self) {}
+
+    fn scale_factor(&self) -> f32 {
+        self.scale_factor.as_ref().get()
+    }
+
+    fn set_scale_factor(&self, factor: f32) {
+        self.scale_factor.as_ref().set(factor);
+    }
+
+    fn free_graphics_resources<'a>(
+        self: Rc<Self>,
+        _items: &Slice<'a, Pin<sixtyfps_corelib::items::ItemRef<'a>>>,
+    ) {
+    }
+
+    fn show_popup(&self, popup: &ComponentRc, position: Point) {
+        self.active_popup.replace(Some((popup.clone(), position)));
+    }
+
+    fn close_popup(&self) {
+        self.active_popup.replace(None);
+    }
+
+    fn show_dialog(&self, dialog: &ComponentRc, presentation: DialogPresentation) {
+        self.active_dialog.replace(Some((dialog.clone(), presentation)));
+    }
+
+    fn close_dialog(&self) {
+        // The dialog may be closed from one of its own callbacks
+        if let Some(dialog) = self.active_dialog.take() {
+            sixtyfps_corelib::timers::Timer::single_shot(Default::default(), move || drop(dialog));
+        }
+    }
+
+    fn key_input_component(&self) -> Option<ComponentRc> {
+        match &*self.active_dialog.borrow() {
+            Some((dialog, DialogPresentation::overlay)) => Some(dialog.clone()),
+            _ => None,
+        }
+    }
+
+    fn active_popup(&self) -> Option<ComponentRc> {
+        self.active_popup.borrow().as_ref().map(|(popup, _)| popup.clone())
+    }
+
+    fn has_native_menus(&self) -> bool {
+        false
+    }
+
+    fn show_native_menu(
+        &self,
+        _entries: &[MenuEntry],
+        _position: Point,
+        _activated: Box<dyn FnOnce(usize)>,
+    ) {
+    }
+
+    fn set_native_menu_bar(&self, _menu_bar: NativeMenuBar) {}
+
+    fn request_window_properties_update(&self) {}
+
+    fn apply_window_properties(&self, window_item: Pin<&sixtyfps_corelib::items::Window>) {
+        let family = window_item.default_font_family();
+        let size = window_item.default_font_size();
+        let weight = window_item.default_font_weight();
+        self.default_font_properties.replace(FontRequest {
+            family: if family.is_empty() { None } else { Some(family) },
+            pixel_size: Some(if size > 0. { size } else { fonts::DEFAULT_FONT_SIZE }),
+            weight: Some(if weight > 0 { weight } else { fonts::DEFAULT_FONT_WEIGHT }),
+            letter_spacing: None,
+        });
+    }
+
+    fn font_metrics(
+        &self,
+        _item_graphics_cache: &sixtyfps_corelib::item_rendering::CachedRenderingData,
+        unresolved_font_request_getter: &dyn Fn() -> sixtyfps_corelib::graphics::FontRequest,
+        _reference_text: Pin<&sixtyfps_corelib::Property<sixtyfps_corelib::SharedString>>,
+    ) -> Box<dyn sixtyfps_corelib::graphics::FontMetrics> {
+        let request =
+            unresolved_font_request_getter().merge(&self.default_font_properties.borrow());
+        Box::new(fonts::SoftwareFontMetrics { font: fonts::Font::new(&request) })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Render one frame of the component of the window into a pixmap.
+///
+/// The window must have been created by this backend. If the root of the component is a
+/// `Window`, it is resized to `size`, or to its preferred size. The size is in logical pixels:
+/// the pixmap is scaled by the scale factor of the window.
+pub fn render_window(window: &ComponentWindow, size: Option<Size>) -> Option<Pixmap> {
+    let runtime_window = window.0.clone();
+    let platform_window = runtime_window.as_any().downcast_ref::<SoftwareWindow>()?;
+    let component_rc = runtime_window.component();
+    let component = ComponentRc::borrow_pin(&component_rc);
+    runtime_window.clone().update_window_properties();
+
+    let root_item = component.as_ref().get_item_ref(0);
+    let window_item = ItemRef::downcast_pin::<sixtyfps_corelib::items::Window>(root_item);
+    let size = match (window_item, size) {
+        (Some(window_item), size) => {
+            let size = size.unwrap_or_else(|| {
+                let layout_info_h = component.as_ref().layout_info(Orientation::Horizontal);
+                let layout_info_v = component.as_ref().layout_info(Orientation::Vertical);
+                let preferred = |current: f32, info: sixtyfps_corelib::layout::LayoutInfo| {
+                    let size = if current > 0. { current } else { info.preferred };
+                    size.clamp(info.min, info.max)
+                };
+                Size::new(
+                    preferred(window_item.width(), layout_info_h),
+                    preferred(window_item.height(), layout_info_v),
+                )
+            });
+            window_item.width.set(size.width);
+            window_item.height.set(size.height);
+            size
+        }
+        (None, Some(size)) => size,
+        (None, None) => root_item.as_ref().geometry().size,
+    };
+
+    let scale_factor = runtime_window.scale_factor();
+    let mut pixmap = Pixmap::new(
+        (size.width * scale_factor).ceil().max(1.) as u32,
+        (size.height * scale_factor).ceil().max(1.) as u32,
+    )?;
+    let background =
+        window_item.map_or(sixtyfps_corelib::Color::from_rgb_u8(255, 255, 255), |w| w.background());
+    pixmap.fill(tiny_skia::Color::from_rgba8(
+        background.red(),
+        background.green(),
+        background.blue(),
+        background.alpha(),
+    ));
+
+    let default_font_properties = platform_window.default_font_properties.borrow().clone();
+    runtime_window.clone().draw_tracked(|| {
+        let mut renderer =
+            renderer::SoftwareItemRenderer::new(&mut pixmap, scale_factor, default_font_properties);
+        sixtyfps_corelib::item_rendering::render_component_items(
+            &component_rc,
+            &mut renderer,
+            Point::default(),
+        );
+        if let Some((popup, position)) = &*platform_window.active_popup.borrow() {
+            sixtyfps_corelib::item_rendering::render_component_items(
+                popup,
+                &mut renderer,
+                *position,
+            );
+        }
+        // Only the dialogs shown on top of the window are part of its screenshot
+        if let Some((dialog, DialogPresentation::overlay)) =
+            &*platform_window.active_dialog.borrow()
+        {
+            let dialog_size = sixtyfps_corelib::window::resolve_window_size(dialog);
+            sixtyfps_corelib::item_rendering::render_component_items(
+                dialog,
+                &mut renderer,
+                ((size - dialog_size) / 2.).to_vector().to_point(),
+            );
+        }
+        if let Some((overlay, position)) = runtime_window.overlay() {
+            sixtyfps_corelib::item_rendering::render_component_items(
+                &overlay,
+                &mut renderer,
+                position,
+            );
+        }
+    });
+    Some(pixmap)
+}
+
+/// Initialize the software backend.
+/// Must be called before any call that would otherwise initialize the rendering backend.
+/// Calling it when the rendering backend is already initialized will have no effects
+pub fn init() {
+    sixtyfps_corelib::backend::instance_or_init(|| Box::new(SoftwareBackend::default()));
+}
+
+#[test]
+fn render_rectangle() {
+    init();
+    let code = r#"
+        Demo := Window {
+            background: #ffffff;
+            Rectangle { x: 10px; y: 10px; width: 20px; height: 30px; background: #ff0000; }
+        }
+    "#;
+    let definition = spin_on::spin_on(
+        sixtyfps_interpreter::ComponentCompiler::default()
+            .build_from_source(code.into(), Default::default()),
+    )
+    .unwrap();
+    let instance = definition.create();
+    let pixmap = render_window(&instance.window(), Some(Size::new(50., 60.))).unwrap();
+    assert_eq!((pixmap.width(), pixmap.height()), (50, 60));
+    let pixel = |x: u32, y: u32| {
+        let p = pixmap.pixel(x, y).unwrap().demultiply();
+        (p.red(), p.green(), p.blue(), p.alpha())
+    };
+    assert_eq!(pixel(5, 5), (0xff, 0xff, 0xff, 0xff));
+    assert_eq!(pixel(20, 25), (0xff, 0, 0, 0xff));
+    assert_eq!(pixel(35, 25), (0xff, 0xff, 0xff, 0xff));
+}
+
+#[test]
+fn event_loop() {
+    use sixtyfps_corelib::backend::EventLoopQuitBehavior;
+    init();
+    let definition = spin_on::spin_on(
+        sixtyfps_interpreter::ComponentCompiler::default()
+            .build_from_source("Demo := Window {}".into(), Default::default()),
+    )
+    .unwrap();
+    let instance = definition.create();
+    instance.show();
+    let backend = sixtyfps_corelib::backend::instance().unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let timer_sender = sender.clone();
+    sixtyfps_corelib::timers::Timer::single_shot(std::time::Duration::from_millis(10), move || {
+        timer_sender.send("timer").unwrap()
+    });
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        backend.post_event(Box::new(move || {
+            sender.send("event").unwrap();
+            sixtyfps_corelib::backend::instance().unwrap().quit_event_loop();
+        }))
+    });
+    backend.run_event_loop(EventLoopQuitBehavior::QuitOnLastWindowClosed);
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec!["timer", "event"]);
+    instance.hide();
+    // Without any shown window, the event loop quits right away
+    backend.run_event_loop(EventLoopQuitBehavior::QuitOnLastWindowClosed);
+}
@@ -0,0 +1,656 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+//! The item renderer drawing into a tiny-skia pixmap
+
+use crate::fonts::Font;
+use sixtyfps_corelib::graphics::{Brush, Color, FontRequest, Rect};
+use sixtyfps_corelib::item_rendering::{CachedRenderingData, ItemRenderer};
+use sixtyfps_corelib::items::{
+    self, FillRule, ImageFit, Item, TextHorizontalAlignment, TextOverflow, TextVerticalAlignment,
+    TextWrap,
+};
+use sixtyfps_corelib::ImageInner;
+use std::pin::Pin;
+use std::rc::Rc;
+
+const KAPPA90: f32 = 0.552_284_8;
+
+#[derive(Clone)]
+struct RenderState {
+    /// From the logical coordinates of the current item to the physical pixels of the pixmap
+    transform: tiny_skia::Transform,
+    /// The clip rectangle, in the logical coordinates of the current item
+    clip: Rect,
+    clip_mask: Option<Rc<tiny_skia::ClipMask>>,
+    opacity: f32,
+}
+
+pub struct SoftwareItemRenderer<'a> {
+    pixmap: &'a mut tiny_skia::Pixmap,
+    scale_factor: f32,
+    default_font_properties: FontRequest,
+    state: Vec<RenderState>,
+}
+
+impl<'a> SoftwareItemRenderer<'a> {
+    pub fn new(
+        pixmap: &'a mut tiny_skia::Pixmap,
+        scale_factor: f32,
+        default_font_properties: FontRequest,
+    ) -> Self {
+        let clip = Rect::new(
+            Default::default(),
+            [pixmap.width() as f32 / scale_factor, pixmap.height() as f32 / scale_factor].into(),
+        );
+        Self {
+            pixmap,
+            scale_factor,
+            default_font_properties,
+            state: vec![RenderState {
+                transform: tiny_skia::Transform::from_scale(scale_factor, scale_factor),
+                clip,
+                clip_mask: None,
+                opacity: 1.,
+            }],
+        }
+    }
+
+    fn state(&self) -> &RenderState {
+        self.state.last().unwrap()
+    }
+
+    fn state_mut(&mut self) -> &mut RenderState {
+        self.state.last_mut().unwrap()
+    }
+
+    /// Create the paint for the brush, with the gradient covering `bounds`
+    fn brush_to_paint(&self, brush: Brush, bounds: Rect) -> Option<tiny_skia::Paint<'static>> {
+        if brush.is_transparent() {
+            return None;
+        }
+        let mut shader = match brush {
+            Brush::SolidColor(color) => tiny_skia::Shader::SolidColor(to_skia_color(color)),
+            Brush::LinearGradient(gradient) => {
+                let (start, end) = sixtyfps_corelib::graphics::line_for_angle(gradient.angle());
+                let map = |p: sixtyfps_corelib::graphics::Point| {
+                    tiny_skia::Point::from_xy(
+                        bounds.min_x() + p.x * bounds.width(),
+                        bounds.min_y() + p.y * bounds.height(),
+                    )
+                };
+                let stops = gradient
+                    .stops()
+                    .map(|stop| {
+                        tiny_skia::GradientStop::new(stop.position, to_skia_color(stop.color))
+                    })
+                    .collect();
+                tiny_skia::LinearGradient::new(
+                    map(start),
+                    map(end),
+                    stops,
+                    tiny_skia::SpreadMode::Pad,
+                    tiny_skia::Transform::identity(),
+                )?
+            }
+            _ => return None,
+        };
+        shader.apply_opacity(self.state().opacity);
+        Some(tiny_skia::Paint { shader, anti_alias: true, ..Default::default() })
+    }
+
+    fn fill_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        paint: &tiny_skia::Paint,
+        rule: tiny_skia::FillRule,
+    ) {
+        let state = self.state.last().unwrap();
+        self.pixmap.fill_path(path, paint, rule, state.transform, state.clip_mask.as_deref());
+    }
+
+    fn stroke_path(&mut self, path: &tiny_skia::Path, paint: &tiny_skia::Paint, width: f32) {
+        let state = self.state.last().unwrap();
+        let stroke = tiny_skia::Stroke { width, ..Default::default() };
+        self.pixmap.stroke_path(path, paint, &stroke, state.transform, state.clip_mask.as_deref());
+    }
+
+    /// Fill the `target` rectangle with the pixmap, positioned with the `transform`
+    fn draw_pixmap(
+        &mut self,
+        pixmap: &tiny_skia::Pixmap,
+        target: Rect,
+        transform: tiny_skia::Transform,
+    ) {
+        let path = match rect_to_path(target) {
+            Some(path) => path,
+            None => return,
+        };
+        let paint = tiny_skia::Paint {
+            shader: tiny_skia::Pattern::new(
+                pixmap.as_ref(),
+                tiny_skia::SpreadMode::Pad,
+                tiny_skia::FilterQuality::Bilinear,
+                self.state().opacity,
+                transform,
+            ),
+            anti_alias: true,
+            ..Default::default()
+        };
+        self.fill_path(&path, &paint, tiny_skia::FillRule::Winding);
+    }
+
+    fn draw_image_impl(
+        &mut self,
+        source: &ImageInner,
+        source_clip: Option<Rect>,
+        width: f32,
+        height: f32,
+        image_fit: ImageFit,
+        colorize: Option<Brush>,
+    ) {
+        if width <= 0. || height <= 0. {
+            return;
+        }
+        let svg_size = ((width * self.scale_factor) as u32, (height * self.scale_factor) as u32);
+        let mut pixmap = match crate::images::load_image(source, svg_size) {
+            Some(pixmap) => pixmap,
+            None => return,
+        };
+        if let Some(Brush::SolidColor(color)) = colorize.filter(|c| !c.is_transparent()) {
+            colorize_pixmap(&mut pixmap, color);
+        }
+        // The SVG images are rendered in physical pixels
+        let image_scale = if crate::images::is_svg(source) { self.scale_factor } else { 1. };
+        let source_rect = source_clip.filter(|r| !r.is_empty()).unwrap_or_else(|| {
+            Rect::new(
+                Default::default(),
+                [pixmap.width() as f32 / image_scale, pixmap.height() as f32 / image_scale].into(),
+            )
+        });
+        let (source_width, source_height) = (source_rect.width(), source_rect.height());
+        let (scale_x, scale_y) = match image_fit {
+            ImageFit::fill => (width / source_width, height / source_height),
+            ImageFit::contain => {
+                let ratio = f32::min(width / source_width, height / source_height);
+                (ratio, ratio)
+            }
+            ImageFit::cover => {
+                let ratio = f32::max(width / source_width, height / source_height);
+                (ratio, ratio)
+            }
+        };
+        // Center the image in the item
+        let offset_x = (width - source_width * scale_x) / 2.;
+        let offset_y = (height - source_height * scale_y) / 2.;
+        let target = Rect::new(
+            [offset_x.max(0.), offset_y.max(0.)].into(),
+            [width.min(source_width * scale_x), height.min(source_height * scale_y)].into(),
+        );
+        let transform = tiny_skia::Transform::from_translate(offset_x, offset_y)
+            .pre_scale(scale_x / image_scale, scale_y / image_scale)
+            .pre_translate(-source_rect.min_x() * image_scale, -source_rect.min_y() * image_scale);
+        self.draw_pixmap(&pixmap, target, transform);
+    }
+
+    fn font(&self, request: FontRequest) -> Font {
+        Font::new(&request.merge(&self.default_font_properties))
+    }
+
+    /// Draw the lines of text in the rectangle of the given size, and return the position
+    /// of the top left corner of the first line, and the x position of its characters
+    #[allow(clippy::too_many_arguments)]
+    fn draw_text_lines(
+        &mut self,
+        lines: &[String],
+        font: &Font,
+        brush: Brush,
+        width: f32,
+        height: f32,
+        horizontal_alignment: TextHorizontalAlignment,
+        vertical_alignment: TextVerticalAlignment,
+    ) -> (f32, f32, Vec<f32>) {
+        let line_height = font.height();
+        let mut y = vertical_offset(vertical_alignment, height, lines.len() as f32 * line_height);
+        let top = y;
+        let mut first_line = None;
+        let mut builder = tiny_skia::PathBuilder::new();
+        for line in lines {
+            let x = horizontal_offset(horizontal_alignment, width, font.text_width(line));
+            let positions = font.append_text_path(&mut builder, line, x, y);
+            first_line.get_or_insert((x, positions));
+            y += line_height;
+        }
+        let (x, positions) = first_line.unwrap_or_default();
+        if let Some(path) = builder.finish() {
+            if let Some(paint) =
+                self.brush_to_paint(brush, Rect::new(Default::default(), [width, height].into()))
+            {
+                self.fill_path(&path, &paint, tiny_skia::FillRule::Winding);
+            }
+        }
+        (x, top, positions)
+    }
+}
+
+impl ItemRenderer for SoftwareItemRenderer<'_> {
+    fn draw_rectangle(&mut self, rect: Pin<&items::Rectangle>) {
+        let geometry = item_rect(rect);
+        if let Some(paint) = self.brush_to_paint(rect.background(), geometry) {
+            if let Some(path) = rect_to_path(geometry) {
+                self.fill_path(&path, &paint, tiny_skia::FillRule::Winding);
+            }
+        }
+    }
+
+    fn draw_border_rectangle(&mut self, rect: Pin<&items::BorderRectangle>) {
+        let mut geometry = item_rect(rect);
+        if geometry.is_empty() {
+            return;
+        }
+        // The border is drawn entirely inside of the geometry, like in CSS
+        let mut border_width = rect.border_width().min(geometry.width() / 2.).max(0.);
+        let background = self.brush_to_paint(rect.background(), geometry);
+        let border = self.brush_to_paint(rect.border_color(), geometry);
+        if let (Some(paint), Some(path)) =
+            (background, rounded_rect_to_path(geometry, rect.border_radius()))
+        {
+            self.fill_path(&path, &paint, tiny_skia::FillRule::Winding);
+        }
+        if border_width > 0. {
+            geometry = geometry.inflate(-border_width / 2., -border_width / 2.);
+            let radius = (rect.border_radius() - border_width / 2.).max(0.);
+            if let (Some(paint), Some(path)) = (border, rounded_rect_to_path(geometry, radius)) {
+                border_width = border_width.max(f32::EPSILON);
+                self.stroke_path(&path, &paint, border_width);
+            }
+        }
+    }
+
+    fn draw_image(&mut self, image: Pin<&items::ImageItem>) {
+        let source = image.source();
+        self.draw_image_impl(
+            (&source).into(),
+            None,
+            image.width(),
+            image.height(),
+            image.image_fit(),
+            None,
+        );
+    }
+
+    fn draw_clipped_image(&mut self, image: Pin<&items::ClippedImage>) {
+        let source = image.source();
+        let source_clip = Rect::new(
+            [image.source_clip_x() as f32, image.source_clip_y() as f32].into(),
+            [image.source_clip_width() as f32, image.source_clip_height() as f32].into(),
+        );
+        self.draw_image_impl(
+            (&source).into(),
+            Some(source_clip),
+            image.width(),
+            image.height(),
+            image.image_fit(),
+            Some(image.colorize()),
+        );
+    }
+
+    fn draw_text(&mut self, text: Pin<&items::Text>) {
+        let (width, height) = (text.width(), text.height());
+        if width <= 0. || height <= 0. {
+            return;
+        }
+        let font = self.font(text.unresolved_font_request());
+        let string = text.text();
+        let wrap = text.wrap() == TextWrap::word_wrap;
+        let mut lines = font.lines(&string, if wrap { Some(width) } else { None });
+        // Only keep the lines that fit
+        let max_lines = ((height / font.height()).floor() as usize).max(1);
+        let elide_last_line = lines.len() > max_lines;
+        lines.truncate(max_lines);
+        let last = lines.len() - 1;
+        let lines = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                if text.overflow() == TextOverflow::elide {
+                    let line = if i == last && elide_last_line {
+                        format!("{}…", line)
+                    } else {
+                        line.to_string()
+                    };
+                    font.elide(&line, width)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>();
+        self.draw_text_lines(
+            &lines,
+            &font,
+            text.color(),
+            width,
+            height,
+            text.horizontal_alignment(),
+            text.vertical_alignment(),
+        );
+    }
+
+    fn draw_text_input(&mut self, text_input: Pin<&items::TextInput>) {
+        let (width, height) = (text_input.width(), text_input.height());
+        if width <= 0. || height <= 0. {
+            return;
+        }
+        let font = self.font(text_input.unresolved_font_request());
+        let string = text_input.text();
+
+        if text_input.has_selection() {
+            let (anchor, cursor) = text_input.selection_anchor_and_cursor();
+            let x = |offset: usize| font.text_width(&string[..offset]);
+            let start = x(anchor.min(cursor));
+            let selection_width = x(anchor.max(cursor)) - start;
+            let origin = horizontal_offset(
+                text_input.horizontal_alignment(),
+                width,
+                font.text_width(&string),
+            );
+            let top = vertical_offset(text_input.vertical_alignment(), height, font.height());
+            let selection =
+                Rect::new([origin + start, top].into(), [selection_width, font.height()].into());
+            let background = Brush::SolidColor(text_input.selection_background_color());
+            if let (Some(paint), Some(path)) =
+                (self.brush_to_paint(background, selection), rect_to_path(selection))
+            {
+                self.fill_path(&path, &paint, tiny_skia::FillRule::Winding);
+            }
+        }
+
+        let (x, y, positions) = self.draw_text_lines(
+            &[string.to_string()],
+            &font,
+            text_input.color(),
+            width,
+            height,
+            text_input.horizontal_alignment(),
+            text_input.vertical_alignment(),
+        );
+
+        let cursor_position = text_input.cursor_position();
+        if cursor_position >= 0 && text_input.cursor_visible() {
+            let index = string
+                .char_indices()
+                .position(|(i, _)| i >= cursor_position as usize)
+                .unwrap_or(positions.len().saturating_sub(1));
+            let cursor_x = positions.get(index).copied().unwrap_or(x);
+            let cursor = Rect::new(
+                [cursor_x, y].into(),
+                [text_input.text_cursor_width(), font.height()].into(),
+            );
+            if let (Some(paint), Some(path)) =
+                (self.brush_to_paint(text_input.color(), cursor), rect_to_path(cursor))
+            {
+                self.fill_path(&path, &paint, tiny_skia::FillRule::Winding);
+            }
+        }
+    }
+
+    fn draw_path(&mut self, path: Pin<&items::Path>) {
+        if matches!(path.elements(), sixtyfps_corelib::PathData::None) {
+            return;
+        }
+        let (offset, path_events) = path.fitted_path_events();
+        let mut builder = tiny_skia::PathBuilder::new();
+        for event in path_events.iter() {
+            match event {
+                lyon_path::Event::Begin { at } => builder.move_to(at.x, at.y),
+                lyon_path::Event::Line { to, .. } => builder.line_to(to.x, to.y),
+                lyon_path::Event::Quadratic { ctrl, to, .. } => {
+                    builder.quad_to(ctrl.x, ctrl.y, to.x, to.y)
+                }
+                lyon_path::Event::Cubic { ctrl1, ctrl2, to, .. } => {
+                    builder.cubic_to(ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y)
+                }
+                lyon_path::Event::End { close, .. } => {
+                    if close {
+                        builder.close()
+                    }
+                }
+            }
+        }
+        let skia_path = match builder.finish() {
+            Some(path) => path,
+            None => return,
+        };
+        let bounds = skia_path.bounds();
+        let bounds =
+            Rect::new([bounds.x(), bounds.y()].into(), [bounds.width(), bounds.height()].into());
+        let fill = self.brush_to_paint(path.fill(), bounds);
+        let stroke = self.brush_to_paint(path.stroke(), bounds);
+
+        self.save_state();
+        self.translate(offset.x, offset.y);
+        if let Some(paint) = fill {
+            let rule = match path.fill_rule() {
+                FillRule::nonzero => tiny_skia::FillRule::Winding,
+                FillRule::evenodd => tiny_skia::FillRule::EvenOdd,
+            };
+            self.fill_path(&skia_path, &paint, rule);
+        }
+        if let Some(paint) = stroke {
+            self.stroke_path(&skia_path, &paint, path.stroke_width());
+        }
+        self.restore_state();
+    }
+
+    fn draw_box_shadow(&mut self, box_shadow: Pin<&items::BoxShadow>) {
+        let color = box_shadow.color();
+        if color.alpha() == 0 {
+            return;
+        }
+        let blur = box_shadow.blur().max(0.);
+        let shadow_rect = Rect::new(
+            [box_shadow.offset_x(), box_shadow.offset_y()].into(),
+            [box_shadow.width(), box_shadow.height()].into(),
+        );
+        // Approximate the blur with layers of decreasing opacity around the shape
+        let steps = (blur.ceil() as usize).clamp(1, 16);
+        for step in 0..steps {
+            let spread = blur * (1. - 2. * (step as f32 + 0.5) / steps as f32);
+            let rect = shadow_rect.inflate(spread, spread);
+            if rect.is_empty() {
+                continue;
+            }
+            let radius = (box_shadow.border_radius() + spread).max(0.);
+            let alpha = color.alpha() as f32 / steps as f32;
+            let layer_color = Color::from_argb_u8(
+                alpha.round().max(1.) as u8,
+                color.red(),
+                color.green(),
+                color.blue(),
+            );
+            if let (Some(paint), Some(path)) = (
+                self.brush_to_paint(Brush::SolidColor(layer_color), rect),
+                rounded_rect_to_path(rect, radius),
+            ) {
+                self.fill_path(&path, &paint, tiny_skia::FillRule::Winding);
+            }
+        }
+    }
+
+    fn combine_clip(&mut self, rect: Rect, radius: f32, border_width: f32) {
+        let (width, height) = (self.pixmap.width(), self.pixmap.height());
+        let state = self.state_mut();
+        state.clip = state.clip.intersection(&rect).unwrap_or_default();
+        // Clip to the inside of the border
+        let rect = rect.inflate(-border_width, -border_width);
+        let radius = (radius - border_width * KAPPA90).max(0.);
+        let path = match rounded_rect_to_path(rect, radius)
+            .and_then(|path| path.transform(state.transform))
+        {
+            Some(path) => path,
+            None => {
+                // Nothing is visible
+                let mut mask = tiny_skia::ClipMask::new();
+                let empty = tiny_skia::PathBuilder::from_rect(
+                    tiny_skia::Rect::from_xywh(-2., -2., 1., 1.).unwrap(),
+                );
+                mask.set_path(width, height, &empty, tiny_skia::FillRule::Winding, false);
+                state.clip_mask = Some(Rc::new(mask));
+                return;
+            }
+        };
+        match &mut state.clip_mask {
+            Some(mask) => {
+                Rc::make_mut(mask).intersect_path(&path, tiny_skia::FillRule::Winding, true);
+            }
+            None => {
+                let mut mask = tiny_skia::ClipMask::new();
+                mask.set_path(width, height, &path, tiny_skia::FillRule::Winding, true);
+                state.clip_mask = Some(Rc::new(mask));
+            }
+        }
+    }
+
+    fn get_current_clip(&self) -> Rect {
+        self.state().clip
+    }
+
+    fn translate(&mut self, x: f32, y: f32) {
+        let state = self.state_mut();
+        state.transform = state.transform.pre_translate(x, y);
+        state.clip = state.clip.translate((-x, -y).into());
+    }
+
+    fn rotate(&mut self, angle_in_degrees: f32) {
+        let state = self.state_mut();
+        state.transform =
+            state.transform.pre_concat(tiny_skia::Transform::from_rotate(angle_in_degrees));
+        // Compute the bounding box of the clip rectangle in the rotated coordinates
+        let (sin, cos) = (-angle_in_degrees.to_radians()).sin_cos();
+        let clip = state.clip;
+        let corners = [
+            clip.origin,
+            clip.origin + euclid::vec2(clip.width(), 0.),
+            clip.origin + euclid::vec2(0., clip.height()),
+            clip.origin + clip.size,
+        ]
+        .iter()
+        .map(|p| {
+            sixtyfps_corelib::graphics::Point::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos)
+        })
+        .collect::<Vec<_>>();
+        state.clip = Rect::from_points(corners);
+    }
+
+    fn apply_opacity(&mut self, opacity: f32) {
+        // The opacity is applied to each shape, instead of the group of shapes
+        self.state_mut().opacity *= opacity;
+    }
+
+    fn save_state(&mut self) {
+        self.state.push(self.state().clone());
+    }
+
+    fn restore_state(&mut self) {
+        self.state.pop();
+    }
+
+    fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    fn draw_cached_pixmap(
+        &mut self,
+        _item_cache: &CachedRenderingData,
+        update_fn: &dyn Fn(&mut dyn FnMut(u32, u32, &[u8])),
+    ) {
+        let scale_factor = self.scale_factor;
+        update_fn(&mut |width, height, data| {
+            // The data is premultiplied RGBA, in physical pixels
+            let pixmap = match tiny_skia::PixmapRef::from_bytes(data, width, height) {
+                Some(pixmap) => pixmap.to_owned(),
+                None => return,
+            };
+            let target = Rect::new(
+                Default::default(),
+                [width as f32 / scale_factor, height as f32 / scale_factor].into(),
+            );
+            let transform = tiny_skia::Transform::from_scale(1. / scale_factor, 1. / scale_factor);
+            self.draw_pixmap(&pixmap, target, transform);
+        });
+    }
+
+    fn as_any(&mut self) -> &mut dyn core::any::Any {
+        self.pixmap
+    }
+}
+
+fn to_skia_color(color: Color) -> tiny_skia::Color {
+    tiny_skia::Color::from_rgba8(color.red(), color.green(), color.blue(), color.alpha())
+}
+
+fn item_rect<T: Item>(item: Pin<&T>) -> Rect {
+    Rect::new(Default::default(), item.as_ref().geometry().size)
+}
+
+fn rect_to_path(rect: Rect) -> Option<tiny_skia::Path> {
+    Some(tiny_skia::PathBuilder::from_rect(tiny_skia::Rect::from_xywh(
+        rect.min_x(),
+        rect.min_y(),
+        rect.width(),
+        rect.height(),
+    )?))
+}
+
+fn rounded_rect_to_path(rect: Rect, radius: f32) -> Option<tiny_skia::Path> {
+    let radius = radius.min(rect.width() / 2.).min(rect.height() / 2.);
+    if radius <= 0. {
+        return rect_to_path(rect);
+    }
+    let (x, y, w, h) = (rect.min_x(), rect.min_y(), rect.width(), rect.height());
+    let k = radius * (1. - KAPPA90);
+    let mut builder = tiny_skia::PathBuilder::new();
+    builder.move_to(x + radius, y);
+    builder.line_to(x + w - radius, y);
+    builder.cubic_to(x + w - k, y, x + w, y + k, x + w, y + radius);
+    builder.line_to(x + w, y + h - radius);
+    builder.cubic_to(x + w, y + h - k, x + w - k, y + h, x + w - radius, y + h);
+    builder.line_to(x + radius, y + h);
+    builder.cubic_to(x + k, y + h, x, y + h - k, x, y + h - radius);
+    builder.line_to(x, y + radius);
+    builder.cubic_to(x, y + k, x + k, y, x + radius, y);
+    builder.close();
+    builder.finish()
+}
+
+fn horizontal_offset(alignment: TextHorizontalAlignment, width: f32, text_width: f32) -> f32 {
+    match alignment {
+        TextHorizontalAlignment::left => 0.,
+        TextHorizontalAlignment::center => (width - text_width) / 2.,
+        TextHorizontalAlignment::right => width - text_width,
+    }
+}
+
+fn vertical_offset(alignment: TextVerticalAlignment, height: f32, text_height: f32) -> f32 {
+    match alignment {
+        TextVerticalAlignment::top => 0.,
+        TextVerticalAlignment::center => (height - text_height) / 2.,
+        TextVerticalAlignment::bottom => height - text_height,
+    }
+}
+
+/// Replace the color of each pixel by `color`, keeping the alpha of the pixel
+fn colorize_pixmap(pixmap: &mut tiny_skia::Pixmap, color: Color) {
+    for pixel in pixmap.pixels_mut() {
+        let alpha = pixel.alpha() as u32 * color.alpha() as u32 / 255;
+        *pixel =
+            tiny_skia::ColorU8::from_rgba(color.red(), color.green(), color.blue(), alpha as u8)
+                .premultiply();
+    }
+}
@@ -20,7 +20,9 @@
 use sixtyfps_corelib::component::ComponentRc;
 use sixtyfps_corelib::graphics::{FontMetrics, Image, Size};
 use sixtyfps_corelib::slice::Slice;
-use sixtyfps_corelib::window::{ComponentWindow, PlatformWindow, Window};
+use sixtyfps_corelib::window::{
+    ComponentWindow, DialogPresentation, MenuEntry, NativeMenuBar, PlatformWindow, Window,
+};
 use sixtyfps_corelib::{ImageInner, Property};
 use std::path::Path;
 use std::pin::Pin;
@@ -94,11 +96,12 @@ fn image_size(&'static self, image: &Image) -> Size {
 
 pub struct TestingWindow {
     scale_factor: Pin<Box<Property<f32>>>,
+    active_dialog: std::cell::RefCell<Option<ComponentRc>>,
 }
 
 impl Default for TestingWindow {
     fn default() -> Self {
-        Self { scale_factor: Box::pin(Property::new(1.)) }
+        Self { scale_factor: Box::pin(Property::new(1.)), active_dialog: Default::default() }
     }
 }
 impl PlatformWindow for TestingWindow {
@@ -130,6 +133,39 @@ fn show_popup(&self, _popup: &ComponentRc, _position: sixtyfps_corelib::graphics
 
     fn close_popup(&self) {}
 
+    fn show_dialog(&self, dialog: &ComponentRc, _presentation: DialogPresentation) {
+        self.active_dialog.replace(Some(dialog.clone()));
+    }
+
+    fn close_dialog(&self) {
+        // The dialog may be closed from one of its own callbacks
+        if let Some(dialog) = self.active_dialog.take() {
+            sixtyfps_corelib::timers::Timer::single_shot(Default::default(), move || drop(dialog));
+        }
+    }
+
+    fn key_input_component(&self) -> Option<ComponentRc> {
+        self.active_dialog.borrow().clone()
+    }
+
+    fn active_popup(&self) -> Option<ComponentRc> {
+        None
+    }
+
+    fn has_native_menus(&self) -> bool {
+        false
+    }
+
+    fn show_native_menu(
+        &self,
+        _entries: &[MenuEntry],
+        _position: sixtyfps_corelib::graphics::Point,
+        _activated: Box<dyn FnOnce(usize)>,
+    ) {
+    }
+
+    fn set_native_menu_bar(&self, _menu_bar: NativeMenuBar) {}
+
     fn request_window_properties_update(&self) {}
 
     fn apply_window_properties(&self, _window_item: Pin<&sixtyfps_corelib::items::Window>) {
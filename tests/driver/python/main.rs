@@ -0,0 +1,17 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+#[cfg(test)]
+mod python;
+
+include!(env!("TEST_FUNCTIONS"));
+
+fn main() {
+    println!("Nothing to see here, please run me through cargo test :)");
+}
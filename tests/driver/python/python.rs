@@ -0,0 +1,61 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+use std::error::Error;
+use std::{fs::File, io::Write};
+
+pub fn test(testcase: &test_driver_lib::TestCase) -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+
+    // Python only imports the extension module from a file named after the module
+    let module_file_name = if cfg!(target_os = "windows") { "sixtyfps.pyd" } else { "sixtyfps.so" };
+    std::fs::copy(env!("SIXTYFPS_PYTHON_NATIVE_LIB"), dir.path().join(module_file_name))
+        .map_err(|err| format!("Could not copy {}: {}", env!("SIXTYFPS_PYTHON_NATIVE_LIB"), err))?;
+
+    let mut main_py = File::create(dir.path().join("main.py"))?;
+    write!(
+        main_py,
+        r#"
+import sixtyfps as sixtyfpslib
+sixtyfpslib.private_api.init_testing_backend()
+sixtyfps = sixtyfpslib.load_file(r"{path}")
+"#,
+        path = testcase.absolute_path.to_string_lossy()
+    )?;
+    let source = std::fs::read_to_string(&testcase.absolute_path)?;
+    let include_paths = test_driver_lib::extract_include_paths(&source);
+    for x in test_driver_lib::extract_test_functions(&source).filter(|x| x.language_id == "python")
+    {
+        // Each test function runs in its own scope, like the blocks of the other drivers
+        write!(
+            main_py,
+            "def _test():\n    {}\n_test()\n",
+            x.source.trim_end().replace("\n", "\n    ")
+        )?;
+    }
+
+    let python = if cfg!(target_os = "windows") { "python" } else { "python3" };
+    let output = std::process::Command::new(python)
+        .arg(dir.path().join("main.py"))
+        .current_dir(dir.path())
+        .env("PYTHONPATH", dir.path())
+        .env("SIXTYFPS_INCLUDE_PATH", std::env::join_paths(include_paths).unwrap())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .output()
+        .map_err(|err| format!("Could not launch {}: {}", python, err))?;
+
+    if !output.status.success() {
+        print!("{}", String::from_utf8_lossy(output.stdout.as_ref()));
+        print!("{}", String::from_utf8_lossy(output.stderr.as_ref()));
+        return Err(String::from_utf8_lossy(output.stderr.as_ref()).into_owned().into());
+    }
+
+    Ok(())
+}
@@ -7,7 +7,9 @@
     This file is also available under commercial licensing terms.
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
-use sixtyfps_compilerlib::diagnostics::BuildDiagnostics;
+use sixtyfps_compilerlib::diagnostics::{
+    lint_levels, BuildDiagnostics, Diagnostic, DiagnosticLevel, Lint, SourceLocation,
+};
 use sixtyfps_compilerlib::*;
 use std::io::Write;
 use structopt::StructOpt;
@@ -22,6 +24,34 @@ struct Cli {
     #[structopt(short = "I", name = "include path", number_of_values = 1)]
     include_paths: Vec<std::path::PathBuf>,
 
+    /// Library path for `@library/file.60` imports, as `library=path`
+    #[structopt(
+        short = "L",
+        name = "library path",
+        number_of_values = 1,
+        parse(try_from_str = sixtyfps_compilerlib::parse_library_path)
+    )]
+    library_paths: Vec<(String, std::path::PathBuf)>,
+
+    /// Report a lint as a warning: unused-property, unused-callback, unused-id,
+    /// constant-state-condition, overridden-binding, invisible-element, or 'all'
+    #[structopt(
+        short = "W",
+        name = "lint",
+        number_of_values = 1,
+        parse(try_from_str = Lint::parse_list)
+    )]
+    warn_lints: Vec<Vec<Lint>>,
+
+    /// Report a lint as an error
+    #[structopt(
+        short = "D",
+        name = "denied lint",
+        number_of_values = 1,
+        parse(try_from_str = Lint::parse_list)
+    )]
+    deny_lints: Vec<Vec<Lint>>,
+
     /// Path to .60 file
     #[structopt(name = "file", parse(from_os_str))]
     path: std::path::PathBuf,
@@ -38,6 +68,27 @@ struct Cli {
     /// Sets the output file ('-' for stdout)
     #[structopt(name = "file to generate", short = "o", default_value = "-")]
     output: std::path::PathBuf,
+
+    /// Format of the diagnostics printed on stderr: 'human', or 'json' for one JSON object per line
+    #[structopt(long = "message-format", default_value = "human")]
+    message_format: MessageFormat,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum MessageFormat {
+    Human,
+    Json,
+}
+
+impl std::str::FromStr for MessageFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Unknown message format {}", s)),
+        }
+    }
 }
 
 fn main() -> std::io::Result<()> {
@@ -47,15 +98,22 @@ fn main() -> std::io::Result<()> {
     let syntax_node = parser::parse_file(&args.path, &mut diag);
     //println!("{:#?}", syntax_node);
     if diag.has_error() {
-        diag.print();
+        print_diagnostics(diag, args.message_format);
         std::process::exit(-1);
     }
     let mut compiler_config = CompilerConfiguration::new(args.format);
     compiler_config.include_paths = args.include_paths;
+    compiler_config.library_paths = args.library_paths.into_iter().collect();
+    compiler_config.lints =
+        lint_levels(args.warn_lints.into_iter().flatten(), args.deny_lints.into_iter().flatten());
     let syntax_node = syntax_node.expect("diags contained no compilation errors");
     let (doc, diag) = spin_on::spin_on(compile_syntax_node(syntax_node, diag, compiler_config));
 
-    let mut diag = diag.check_and_exit_on_error();
+    if diag.has_error() {
+        print_diagnostics(diag, args.message_format);
+        std::process::exit(-1);
+    }
+    let mut diag = diag;
 
     if args.output == std::path::Path::new("-") {
         generator::generate(args.format, &mut std::io::stdout(), &doc, &mut diag)?;
@@ -82,6 +140,67 @@ fn main() -> std::io::Result<()> {
 
         writeln!(f, "")?;
     }
-    diag.print_warnings_and_exit_on_error();
+    let has_error = diag.has_error();
+    print_diagnostics(diag, args.message_format);
+    if has_error {
+        std::process::exit(-1);
+    }
     Ok(())
 }
+
+fn print_diagnostics(diag: BuildDiagnostics, message_format: MessageFormat) {
+    match message_format {
+        MessageFormat::Human => diag.print(),
+        MessageFormat::Json => {
+            for d in diag.iter() {
+                eprintln!("{}", diagnostic_to_json(d));
+            }
+        }
+    }
+}
+
+/// The location as a JSON object with the file and the range, where lines and columns start at 1
+fn location_to_json(location: &SourceLocation) -> serde_json::Value {
+    let range =
+        location.line_column_range().map(|((start_line, start_column), (end_line, end_column))| {
+            serde_json::json!({
+                "start": { "line": start_line, "column": start_column },
+                "end": { "line": end_line, "column": end_column },
+            })
+        });
+    serde_json::json!({
+        "file": location.source_file.as_ref().map(|sf| sf.path().to_string_lossy()),
+        "range": range,
+    })
+}
+
+fn diagnostic_to_json(diagnostic: &Diagnostic) -> serde_json::Value {
+    let mut result = location_to_json(diagnostic.source_location());
+    result["severity"] = match diagnostic.level() {
+        DiagnosticLevel::Error => "error",
+        DiagnosticLevel::Warning => "warning",
+    }
+    .into();
+    result["code"] = diagnostic.code().as_str().into();
+    result["message"] = diagnostic.message().into();
+    result["notes"] = diagnostic
+        .notes()
+        .iter()
+        .map(|note| {
+            let mut json = location_to_json(&note.span);
+            json["message"] = note.message.as_str().into();
+            json
+        })
+        .collect();
+    result["suggestions"] = diagnostic
+        .suggestions()
+        .iter()
+        .map(|suggestion| {
+            let mut json = location_to_json(&suggestion.span);
+            json["message"] = suggestion.message.as_str().into();
+            json["replacement"] = suggestion.replacement.as_str().into();
+            json
+        })
+        .collect();
+    result
+}
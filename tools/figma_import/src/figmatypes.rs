@@ -24,9 +24,11 @@ pub struct File {
     pub thumbnailURL: Option<String>,
     pub version: String,
     pub document: Node,
+    #[serde(default)]
     pub components: HashMap<String, Component>,
     //schemaVersion: 0,
-    styles: HashMap<String, Style>,
+    #[serde(default)]
+    pub styles: HashMap<String, Style>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -52,6 +54,12 @@ pub struct NodeCommon {
     pub visible: bool,
     #[serde(default)]
     pub children: Vec<Node>,
+    /// In an auto-layout frame, "STRETCH" if the node fills the frame in the counter axis
+    #[serde(default)]
+    pub layoutAlign: String,
+    /// In an auto-layout frame, 1 if the node fills the frame in the primary axis
+    #[serde(default)]
+    pub layoutGrow: f32,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -200,7 +208,9 @@ pub struct Frame {
     pub node: NodeCommon,
     #[serde(default)]
     pub locked: bool,
+    #[serde(default)]
     pub background: Vec<Paint>,
+    #[serde(default)]
     pub backgroundColor: Color,
     #[serde(default)]
     pub exportSettings: Vec<ExportSetting>,
@@ -215,13 +225,30 @@ pub struct Frame {
     pub opacity: f32,
     #[serde(deserialize_with = "deserialize_or_default")]
     pub absoluteBoundingBox: Rectangle,
-    #[serde(deserialize_with = "deserialize_or_default")]
+    #[serde(default, deserialize_with = "deserialize_or_default")]
     pub size: Option<Vector>,
-    #[serde(deserialize_with = "deserialize_or_default")]
+    #[serde(default, deserialize_with = "deserialize_or_default")]
     pub relativeTransform: Option<Transform>,
+    #[serde(default)]
     pub clipsContent: bool,
     #[serde(default)]
     pub layoutGrids: Vec<LayoutGrid>,
+    /// "NONE", "HORIZONTAL" or "VERTICAL" for auto-layout frames
+    #[serde(default)]
+    pub layoutMode: Option<String>,
+    #[serde(default)]
+    pub primaryAxisAlignItems: Option<String>,
+    #[serde(default)]
+    pub itemSpacing: f32,
+    pub paddingLeft: Option<f32>,
+    pub paddingRight: Option<f32>,
+    pub paddingTop: Option<f32>,
+    pub paddingBottom: Option<f32>,
+    /// Older version of the padding, used when the padding of each side is not specified
+    #[serde(default)]
+    pub horizontalPadding: f32,
+    #[serde(default)]
+    pub verticalPadding: f32,
     #[serde(default)]
     pub effects: Vec<Effect>,
     #[serde(default)]
@@ -333,6 +360,7 @@ pub enum Node {
         relativeTransform: Option<Transform>,
     },
     COMPONENT(Frame),
+    COMPONENT_SET(Frame),
     INSTANCE {
         #[serde(flatten)]
         frame: Frame,
@@ -357,6 +385,7 @@ pub fn common(&self) -> &NodeCommon {
             Node::TEXT { vector: VectorNode { node, .. }, .. } => node,
             Node::SLICE { node, .. } => node,
             Node::COMPONENT(Frame { node, .. }) => node,
+            Node::COMPONENT_SET(Frame { node, .. }) => node,
             Node::INSTANCE { frame: Frame { node, .. }, .. } => node,
         }
     }
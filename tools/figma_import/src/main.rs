@@ -13,6 +13,7 @@
 use futures::prelude::*;
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::path::PathBuf;
 use structopt::StructOpt;
 use tokio::io::AsyncWriteExt;
 
@@ -21,18 +22,31 @@
 struct Opt {
     /// Figma asscess token
     #[structopt(short = "t", long = "token")]
-    token: String,
+    token: Option<String>,
     /// If present, load the specific node id
     #[structopt(short = "n", long = "node")]
     node_id: Option<String>,
     /// If present, load the specific child node at the specified index
     #[structopt(long = "child")]
     child_index: Option<usize>,
-    /// If set, don't connect to the network, but use the `figma_output/cache.json`
+    /// If set, don't connect to the network, but use the `cache.json` in the output directory
     #[structopt(long)]
     read_from_cache: bool,
+    /// Don't connect to the network, but load the document from a JSON file as returned by the
+    /// figma REST API for the file
+    #[structopt(
+        short = "i",
+        long = "input",
+        parse(from_os_str),
+        conflicts_with = "read-from-cache"
+    )]
+    input: Option<PathBuf>,
+    /// The directory in which the .60 file and the images are written
+    #[structopt(short = "o", long = "output", parse(from_os_str), default_value = "figma_output")]
+    output: PathBuf,
     /// Figma file
-    file: String,
+    #[structopt(required_unless_one(&["input", "read-from-cache"]))]
+    file: Option<String>,
 }
 
 mod figmatypes;
@@ -56,17 +70,23 @@ fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
 }
 
 async fn load_from_network(opt: &Opt) -> Result<figmatypes::File, Box<dyn std::error::Error>> {
-    println!("Fetch document {}...", opt.file);
+    let file = opt.file.as_ref().ok_or_else(|| Error("No figma file specified".into()))?;
+    let token = opt.token.as_ref().ok_or_else(|| {
+        Error(
+            "A token is required to load from the network. Use --input to load a JSON file".into(),
+        )
+    })?;
+    println!("Fetch document {}...", file);
     let full_doc = reqwest::Client::new()
-        .get(&format!("https://api.figma.com/v1/files/{}?geometry=paths", opt.file))
-        .header("X-Figma-Token", opt.token.clone())
+        .get(&format!("https://api.figma.com/v1/files/{}?geometry=paths", file))
+        .header("X-Figma-Token", token)
         .send()
         .await?
         .bytes()
         .await?;
 
-    std::fs::create_dir_all("figma_output/images")?;
-    std::fs::write("figma_output/cache.json", &full_doc)?;
+    std::fs::create_dir_all(opt.output.join("images"))?;
+    std::fs::write(opt.output.join("cache.json"), &full_doc)?;
 
     let r: figmatypes::File = serde_json::from_slice(&full_doc)?;
 
@@ -81,8 +101,8 @@ struct ImageResultMeta {
     };
 
     let i: ImageResult = reqwest::Client::new()
-        .get(&format!("https://api.figma.com/v1/files/{}/images", opt.file))
-        .header("X-Figma-Token", &opt.token)
+        .get(&format!("https://api.figma.com/v1/files/{}/images", file))
+        .header("X-Figma-Token", token)
         .send()
         .await?
         .json()
@@ -92,7 +112,7 @@ struct ImageResultMeta {
     let mut images = stream::iter(i.meta.images)
         .map(|(k, v)| async move {
             let mut resp = reqwest::Client::new().get(&v).send().await?.bytes_stream();
-            let mut file = tokio::fs::File::create(format!("figma_output/images/{}", k)).await?;
+            let mut file = tokio::fs::File::create(opt.output.join("images").join(k)).await?;
             while let Some(bytes) = resp.next().await {
                 file.write_all(&(bytes?)).await?;
             }
@@ -106,30 +126,29 @@ struct ImageResultMeta {
     Ok(r)
 }
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let opt = Opt::from_args();
-
-    let r = if !opt.read_from_cache {
-        load_from_network(&opt).await?
-    } else {
-        let full_doc = std::fs::read("figma_output/cache.json")?;
-        serde_json::from_slice(&full_doc)?
-    };
-
+/// Convert the node of the document to the content of a .60 file.
+///
+/// The node is the one with the given id, or the child of the first canvas at the given index,
+/// or by default the prototype start node.
+fn convert(
+    r: &figmatypes::File,
+    node_id: Option<&str>,
+    child_index: Option<usize>,
+) -> Result<String, Box<dyn std::error::Error>> {
     let mut nodeHash = HashMap::new();
     fill_hash(&mut nodeHash, &r.document);
-    let doc = rendered::Document { nodeHash };
+    let doc = rendered::Document { nodeHash, styles: &r.styles };
 
     if let figmatypes::Node::DOCUMENT(document) = &r.document {
-        if let figmatypes::Node::CANVAS { node, prototypeStartNodeID, backgroundColor, .. } =
-            &document.children[0]
+        if let Some(figmatypes::Node::CANVAS {
+            node, prototypeStartNodeID, backgroundColor, ..
+        }) = document.children.first()
         {
-            let render_node = if let Some(node_id) = &opt.node_id {
+            let render_node = if let Some(node_id) = node_id {
                 doc.nodeHash
-                    .get(node_id.as_str())
+                    .get(node_id)
                     .ok_or_else(|| Error(format!("Could not find node id {}", node_id)))?
-            } else if let Some(child_index) = opt.child_index {
+            } else if let Some(child_index) = child_index {
                 node.children
                     .get(child_index)
                     .ok_or_else(|| Error(format!("The index {} does not exist", child_index)))?
@@ -143,11 +162,40 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     )
                     .ok_or_else(|| Error("Start node not found".into()))?
             };
-            let result = rendered::render(node.name.as_str(), render_node, *backgroundColor, &doc)?;
-
-            std::fs::write("figma_output/main.60", &result)?;
+            return rendered::render(node.name.as_str(), render_node, *backgroundColor, &doc);
         }
     }
+    Err(Error("The document has no canvas".into()).into())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let opt = Opt::from_args();
+
+    let r: figmatypes::File = if let Some(input) = &opt.input {
+        let full_doc = std::fs::read(input)?;
+        serde_json::from_slice(&full_doc)?
+    } else if opt.read_from_cache {
+        let full_doc = std::fs::read(opt.output.join("cache.json"))?;
+        serde_json::from_slice(&full_doc)?
+    } else {
+        load_from_network(&opt).await?
+    };
+
+    let result = convert(&r, opt.node_id.as_deref(), opt.child_index)?;
+    std::fs::create_dir_all(&opt.output)?;
+    std::fs::write(opt.output.join("main.60"), &result)?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn convert_auto_layout() {
+        let file: super::figmatypes::File =
+            serde_json::from_str(include_str!("../tests/auto_layout.json")).unwrap();
+        let result = super::convert(&file, None, None).unwrap();
+        assert_eq!(result, include_str!("../tests/auto_layout.60"));
+    }
+}
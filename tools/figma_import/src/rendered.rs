@@ -9,12 +9,13 @@
 LICENSE END */
 
 use crate::figmatypes::{self, *};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::fmt::Write;
 
 pub struct Document<'doc> {
     pub nodeHash: HashMap<&'doc str, &'doc figmatypes::Node>,
+    pub styles: &'doc HashMap<String, figmatypes::Style>,
     //pub images: HashMap<String, Vec<u8>>,
 }
 
@@ -42,11 +43,37 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LayoutDirection {
+    Horizontal,
+    Vertical,
+}
+
+/// A .60 component generated for a figma component
+#[derive(Clone)]
+struct ComponentDefinition {
+    name: String,
+    /// The id of the text nodes, and the name of the property exposing their text
+    text_properties: Vec<(String, String)>,
+}
+
 #[derive(Default)]
 struct Ctx {
     out: String,
     indent: Indent,
     offset: Vector,
+    /// Set when the next element is a direct child of an auto-layout frame
+    parent_layout: Option<LayoutDirection>,
+    /// Set when the next element is the root of a component definition
+    component_header: Option<ComponentDefinition>,
+    /// The generated components, by node id of the figma component
+    components: HashMap<String, ComponentDefinition>,
+    /// The prefix of the properties of the `TextStyles` global, by text style id
+    text_styles: HashMap<String, String>,
+}
+
+fn element_id(node_id: &str) -> String {
+    format!("id_{}", node_id.replace(":", "-").replace(";", "_"))
 }
 
 impl Ctx {
@@ -56,19 +83,38 @@ fn begin_element(
         node: &NodeCommon,
         absoluteBoundingBox: Option<&Rectangle>,
     ) -> std::fmt::Result {
-        writeln!(
-            self,
-            "id_{} := {} {{ /* {} */",
-            node.id.replace(":", "-").replace(";", "_"),
-            element,
-            node.name
-        )?;
-        self.indent += 1;
+        let parent_layout = self.parent_layout.take();
+        let component_header = self.component_header.take();
+        if let Some(header) = &component_header {
+            writeln!(self, "{} := {} {{ /* {} */", header.name, element, node.name)?;
+            self.indent += 1;
+            for (id, property) in &header.text_properties {
+                writeln!(self, "property <string> {} <=> {}.text;", property, element_id(id))?;
+            }
+        } else {
+            writeln!(self, "{} := {} {{ /* {} */", element_id(&node.id), element, node.name)?;
+            self.indent += 1;
+        }
         if let Some(bb) = absoluteBoundingBox {
-            writeln!(self, "width: {}px;", bb.width)?;
-            writeln!(self, "height: {}px;", bb.height)?;
-            writeln!(self, "x: {}px;", bb.x - self.offset.x)?;
-            writeln!(self, "y: {}px;", bb.y - self.offset.y)?;
+            // Within a layout, the size is only set if the element does not fill the layout,
+            // and the position is given by the layout
+            let grow = node.layoutGrow > 0.;
+            let stretch = node.layoutAlign == "STRETCH";
+            let (fixed_width, fixed_height) = match parent_layout {
+                None => (true, true),
+                Some(LayoutDirection::Horizontal) => (!grow, !stretch),
+                Some(LayoutDirection::Vertical) => (!stretch, !grow),
+            };
+            if fixed_width {
+                writeln!(self, "width: {}px;", bb.width)?;
+            }
+            if fixed_height {
+                writeln!(self, "height: {}px;", bb.height)?;
+            }
+            if parent_layout.is_none() && component_header.is_none() {
+                writeln!(self, "x: {}px;", bb.x - self.offset.x)?;
+                writeln!(self, "y: {}px;", bb.y - self.offset.y)?;
+            }
         }
         Ok(())
     }
@@ -122,11 +168,46 @@ pub fn render(
         Node::FRAME(f) => f,
         Node::GROUP(f) => f,
         Node::COMPONENT(f) => f,
-        //         Node::INSTANCE { frame } => frame,
+        Node::COMPONENT_SET(f) => f,
+        Node::INSTANCE { frame, .. } => frame,
         _ => return Err(super::Error("Rendering not a frame".into()).into()),
     };
 
     let mut ctx = Ctx::default();
+
+    let mut components = vec![];
+    collect_components(node, doc, &mut components, &mut HashSet::new());
+    let mut used_names: HashSet<String> =
+        RESERVED_NAMES.iter().map(|name| name.to_string()).collect();
+    for component in &components {
+        let mut text_nodes = vec![];
+        collect_text_nodes(component, &mut text_nodes);
+        let mut used_properties = HashSet::new();
+        let text_properties = text_nodes
+            .iter()
+            .map(|text| {
+                let name = format!("{}-text", kebab_case_name(&text.common().name));
+                (text.common().id.clone(), unique_name(name, &mut used_properties))
+            })
+            .collect();
+        let name = unique_name(camel_case_name(&component.common().name), &mut used_names);
+        ctx.components
+            .insert(component.common().id.clone(), ComponentDefinition { name, text_properties });
+    }
+
+    render_text_styles(std::iter::once(node).chain(components.iter().copied()), &mut ctx, doc)?;
+
+    for component in &components {
+        let frame = match component {
+            Node::COMPONENT(frame) => frame,
+            _ => unreachable!(),
+        };
+        ctx.component_header = ctx.components.get(&frame.node.id).cloned();
+        ctx.offset = frame.absoluteBoundingBox.origin();
+        render_node_content(component, &mut ctx, doc)?;
+        writeln!(ctx)?;
+    }
+
     writeln!(ctx, "App := Window {{")?;
     ctx.indent += 1;
     writeln!(ctx, "background: {};", background)?;
@@ -139,6 +220,173 @@ pub fn render(
     Ok(ctx.out)
 }
 
+/// Names that can't be used for the generated components
+const RESERVED_NAMES: &[&str] = &[
+    "App",
+    "TextStyles",
+    "Window",
+    "Rectangle",
+    "Text",
+    "Image",
+    "Path",
+    "Clip",
+    "HorizontalLayout",
+    "VerticalLayout",
+];
+
+/// Find the components used in the tree of `node`, and the components they use.
+/// A component comes after the components it uses.
+fn collect_components<'a>(
+    node: &'a Node,
+    doc: &Document<'a>,
+    components: &mut Vec<&'a Node>,
+    seen: &mut HashSet<&'a str>,
+) {
+    let component = match node {
+        Node::INSTANCE { componentId, .. } => doc.nodeHash.get(componentId.as_str()).copied(),
+        Node::COMPONENT(_) => Some(node),
+        _ => None,
+    };
+    if let Some(component @ Node::COMPONENT(_)) = component {
+        if seen.insert(&component.common().id) {
+            for child in component.common().children.iter() {
+                collect_components(child, doc, components, seen);
+            }
+            components.push(component);
+        }
+        // The children of an instance are the ones of the component
+        return;
+    }
+    for child in node.common().children.iter() {
+        collect_components(child, doc, components, seen);
+    }
+}
+
+/// The text nodes of a component whose text is exposed as a property.
+/// The text nodes of the nested instances are not exposed.
+fn collect_text_nodes<'a>(node: &'a Node, result: &mut Vec<&'a Node>) {
+    for child in node.common().children.iter() {
+        match child {
+            Node::TEXT { .. } => result.push(child),
+            Node::INSTANCE { .. } => {}
+            _ => collect_text_nodes(child, result),
+        }
+    }
+}
+
+/// Find the texts of an instance that differ from the ones of its component, by id of the
+/// text node in the component. The tree of an instance has the same structure as its component.
+fn text_overrides<'a>(
+    instance: &'a Node,
+    component: &'a Node,
+    result: &mut HashMap<&'a str, &'a str>,
+) {
+    for (i, c) in instance.common().children.iter().zip(component.common().children.iter()) {
+        match (i, c) {
+            (
+                Node::TEXT { characters: text, .. },
+                Node::TEXT { characters: default_text, vector, .. },
+            ) => {
+                if text != default_text {
+                    result.insert(&vector.node.id, text);
+                }
+            }
+            (_, Node::INSTANCE { .. }) => {}
+            _ => text_overrides(i, c, result),
+        }
+    }
+}
+
+/// Convert a figma name like "Button / Primary" to "ButtonPrimary"
+fn camel_case_name(name: &str) -> String {
+    let mut result = String::new();
+    for word in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            result.push(first.to_ascii_uppercase());
+            result.extend(chars);
+        }
+    }
+    if !result.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        result.insert(0, 'C');
+    }
+    result
+}
+
+/// Convert a figma name like "Heading / H1" to "heading-h1"
+fn kebab_case_name(name: &str) -> String {
+    let words = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    let result = words.join("-");
+    if result.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        result
+    } else if result.is_empty() {
+        "unnamed".into()
+    } else {
+        format!("n{}", result)
+    }
+}
+
+/// Append a number to the name if it is already used
+fn unique_name(name: String, used_names: &mut HashSet<String>) -> String {
+    let mut result = name.clone();
+    let mut index = 1;
+    while !used_names.insert(result.clone()) {
+        index += 1;
+        result = format!("{}{}", name, index);
+    }
+    result
+}
+
+/// Generate the `TextStyles` global with the properties of the text styles used by the
+/// text nodes in the given trees
+fn render_text_styles<'a>(
+    roots: impl Iterator<Item = &'a Node>,
+    rc: &mut Ctx,
+    doc: &Document,
+) -> Result<(), Box<dyn std::error::Error>> {
+    fn collect<'a>(node: &'a Node, doc: &Document, result: &mut Vec<(&'a str, &'a TypeStyle)>) {
+        if let Node::TEXT { vector, style, .. } = node {
+            if let Some(id) = vector.styles.get("text") {
+                if doc.styles.get(id).map_or(false, |s| s.styleType == "TEXT")
+                    && !result.iter().any(|(x, _)| x == id)
+                {
+                    result.push((id, style));
+                }
+            }
+        }
+        for child in node.common().children.iter() {
+            collect(child, doc, result);
+        }
+    }
+    let mut text_styles = vec![];
+    for root in roots {
+        collect(root, doc, &mut text_styles);
+    }
+    if text_styles.is_empty() {
+        return Ok(());
+    }
+
+    let mut used_names = HashSet::new();
+    writeln!(rc, "global TextStyles := {{")?;
+    rc.indent += 1;
+    for (id, style) in text_styles {
+        let name = unique_name(kebab_case_name(&doc.styles[id].name), &mut used_names);
+        writeln!(rc, "property <string> {}-font-family: \"{}\";", name, style.fontFamily)?;
+        writeln!(rc, "property <length> {}-font-size: {}px;", name, style.fontSize)?;
+        writeln!(rc, "property <int> {}-font-weight: {};", name, style.fontWeight)?;
+        writeln!(rc, "property <length> {}-letter-spacing: {}px;", name, style.letterSpacing)?;
+        rc.text_styles.insert(id.to_owned(), name);
+    }
+    rc.indent -= 1;
+    writeln!(rc, "}}")?;
+    writeln!(rc)?;
+    Ok(())
+}
+
 fn render_frame(frame: &Frame, rc: &mut Ctx) -> Result<bool, Box<dyn std::error::Error>> {
     rc.begin_element("Rectangle", &frame.node, Some(&frame.absoluteBoundingBox))?;
     rc.offset = frame.absoluteBoundingBox.origin();
@@ -153,9 +401,83 @@ fn render_frame(frame: &Frame, rc: &mut Ctx) -> Result<bool, Box<dyn std::error:
         writeln!(rc, "Clip {{")?;
         rc.indent += 1;
     }
+    let direction = match frame.layoutMode.as_deref() {
+        Some("HORIZONTAL") => Some(LayoutDirection::Horizontal),
+        Some("VERTICAL") => Some(LayoutDirection::Vertical),
+        _ => None,
+    };
+    if let Some(direction) = direction {
+        render_layout(frame, direction, rc)?;
+    }
     Ok(frame.isMask)
 }
 
+/// Auto-layout frames are rendered with a layout containing the children of the frame
+fn render_layout(
+    frame: &Frame,
+    direction: LayoutDirection,
+    rc: &mut Ctx,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if direction == LayoutDirection::Horizontal {
+        writeln!(rc, "HorizontalLayout {{")?;
+    } else {
+        writeln!(rc, "VerticalLayout {{")?;
+    }
+    rc.indent += 1;
+    let padding = [
+        ("left", frame.paddingLeft.unwrap_or(frame.horizontalPadding)),
+        ("right", frame.paddingRight.unwrap_or(frame.horizontalPadding)),
+        ("top", frame.paddingTop.unwrap_or(frame.verticalPadding)),
+        ("bottom", frame.paddingBottom.unwrap_or(frame.verticalPadding)),
+    ];
+    for (side, value) in padding.iter() {
+        if *value != 0. {
+            writeln!(rc, "padding-{}: {}px;", side, value)?;
+        }
+    }
+    if frame.itemSpacing != 0. {
+        writeln!(rc, "spacing: {}px;", frame.itemSpacing)?;
+    }
+    let alignment = match frame.primaryAxisAlignItems.as_deref() {
+        Some("CENTER") => "center",
+        Some("MAX") => "end",
+        Some("SPACE_BETWEEN") => "space-between",
+        _ => "start",
+    };
+    writeln!(rc, "alignment: {};", alignment)?;
+    rc.parent_layout = Some(direction);
+    Ok(())
+}
+
+/// Render an instance of a component, setting the properties for the texts that differ from
+/// the component
+fn render_instance(
+    node: &Node,
+    definition: &ComponentDefinition,
+    rc: &mut Ctx,
+    doc: &Document,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (frame, component) = match node {
+        Node::INSTANCE { frame, componentId } => {
+            (frame, doc.nodeHash.get(componentId.as_str()).copied())
+        }
+        Node::COMPONENT(frame) => (frame, None),
+        _ => unreachable!(),
+    };
+    rc.begin_element(&definition.name, &frame.node, Some(&frame.absoluteBoundingBox))?;
+    if let Some(component) = component {
+        let mut overrides = HashMap::new();
+        text_overrides(node, component, &mut overrides);
+        for (id, property) in definition.text_properties.iter() {
+            if let Some(text) = overrides.get(id.as_str()) {
+                writeln!(rc, "{}: \"{}\";", property, text.escape_debug())?;
+            }
+        }
+    }
+    rc.end_element()?;
+    Ok(())
+}
+
 fn render_vector(
     vector: &VectorNode,
     rc: &mut Ctx,
@@ -206,12 +528,23 @@ fn render_text(
 ) -> Result<(), Box<dyn std::error::Error>> {
     rc.begin_element("Text", &vector.node, Some(&vector.absoluteBoundingBox))?;
     writeln!(rc, "text: \"{}\";", text.escape_debug())?;
-    writeln!(rc, "font-family: \"{}\";", font.fontFamily)?;
-    writeln!(rc, "font-size: {}px;", font.fontSize)?;
-    writeln!(rc, "font-weight: {};", font.fontWeight)?;
+    let text_style = vector.styles.get("text").and_then(|id| rc.text_styles.get(id)).cloned();
+    if let Some(style) = &text_style {
+        writeln!(rc, "font-family: TextStyles.{}-font-family;", style)?;
+        writeln!(rc, "font-size: TextStyles.{}-font-size;", style)?;
+        writeln!(rc, "font-weight: TextStyles.{}-font-weight;", style)?;
+    } else {
+        writeln!(rc, "font-family: \"{}\";", font.fontFamily)?;
+        writeln!(rc, "font-size: {}px;", font.fontSize)?;
+        writeln!(rc, "font-weight: {};", font.fontWeight)?;
+    }
     writeln!(rc, "horizontal-alignment: {};", font.textAlignHorizontal.to_ascii_lowercase())?;
     writeln!(rc, "vertical-alignment: {};", font.textAlignVertical.to_ascii_lowercase())?;
-    writeln!(rc, "letter-spacing: {}px;", font.letterSpacing)?;
+    if let Some(style) = &text_style {
+        writeln!(rc, "letter-spacing: TextStyles.{}-letter-spacing;", style)?;
+    } else {
+        writeln!(rc, "letter-spacing: {}px;", font.letterSpacing)?;
+    }
     for p in vector.fills.iter() {
         handle_paint(p, rc, "color")?;
     }
@@ -288,12 +621,30 @@ fn render_node(
     rc: &mut Ctx,
     doc: &Document,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let prev_ctx = (rc.indent, rc.offset);
+    let component_id = match node {
+        Node::INSTANCE { componentId, .. } => Some(componentId),
+        Node::COMPONENT(f) => Some(&f.node.id),
+        _ => None,
+    };
+    if let Some(definition) = component_id.and_then(|id| rc.components.get(id)).cloned() {
+        return render_instance(node, &definition, rc, doc);
+    }
+    render_node_content(node, rc, doc)
+}
+
+fn render_node_content(
+    node: &figmatypes::Node,
+    rc: &mut Ctx,
+    doc: &Document,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let prev_ctx = (rc.indent, rc.offset, rc.parent_layout);
     let is_mask = match node {
         Node::FRAME(f) => render_frame(f, rc)?,
         Node::GROUP(f) => render_frame(f, rc)?,
         Node::COMPONENT(f) => render_frame(f, rc)?,
-        // Node::INSTANCE { frame } => frame,
+        Node::COMPONENT_SET(f) => render_frame(f, rc)?,
+        // Instances of components that are not in this file
+        Node::INSTANCE { frame, .. } => render_frame(frame, rc)?,
         Node::VECTOR(vector) => render_vector(vector, rc, doc)?,
         Node::BOOLEAN_OPERATION { vector, .. } => render_vector(vector, rc, doc)?,
         Node::STAR(vector) => render_vector(vector, rc, doc)?,
@@ -313,9 +664,13 @@ fn render_node(
         _ => false,
     };
 
+    // The layout, if any, that was opened by this node for its children
+    let layout = rc.parent_layout;
     for x in node.common().children.iter() {
+        rc.parent_layout = layout;
         render_node(&x, rc, doc)?;
     }
+    rc.parent_layout = prev_ctx.2;
 
     if is_mask {
         return Ok(());
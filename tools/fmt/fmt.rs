@@ -8,18 +8,22 @@
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
 
-use crate::TokenWriter;
-use sixtyfps_compilerlib::parser::{syntax_nodes, NodeOrToken, SyntaxKind, SyntaxNode};
+use crate::{FormatStyle, TokenWriter};
+use sixtyfps_compilerlib::parser::{
+    syntax_nodes, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken,
+};
 
-pub(crate) fn format_document(
+/// Format the document, passing every token to the writer
+pub fn format_document(
     doc: syntax_nodes::Document,
+    style: &FormatStyle,
     writer: &mut impl TokenWriter,
 ) -> Result<(), std::io::Error> {
-    let mut state = FormatState::default();
+    let mut state = FormatState { style: style.clone(), ..Default::default() };
     format_node(&doc, writer, &mut state)
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 struct FormatState {
     /// The whitespace have been written, all further whitespace can be skipped
     skip_all_whitespace: bool,
@@ -33,6 +37,12 @@ struct FormatState {
 
     /// a comment has been written followed maybe by some spacing
     after_comment: bool,
+
+    style: FormatStyle,
+    /// The column at which the next token will be written
+    column: usize,
+    /// When set, new lines are replaced by a single space
+    single_line: bool,
 }
 
 impl FormatState {
@@ -40,18 +50,36 @@ fn new_line(&mut self) {
         if self.after_comment {
             return;
         }
+        if self.single_line {
+            if self.whitespace_to_add.is_none() {
+                self.insert_whitespace(" ");
+            }
+            return;
+        }
         self.skip_all_whitespace = true;
         if let Some(x) = &mut self.whitespace_to_add {
             x.insert(0, '\n');
             return;
         }
         let mut new_line = String::from("\n");
+        let indentation = self.style.indentation();
         for _ in 0..self.indentation_level {
-            new_line += "    ";
+            new_line += &indentation;
         }
         self.whitespace_to_add = Some(new_line);
     }
 
+    /// Update the current column after `text` was written
+    fn advance_column(&mut self, text: &str) {
+        let (text, column) = match text.rfind('\n') {
+            Some(pos) => (&text[pos + 1..], 0),
+            None => (text, self.column),
+        };
+        let tab_width = self.style.indent_width;
+        self.column =
+            column + text.chars().map(|c| if c == '\t' { tab_width } else { 1 }).sum::<usize>();
+    }
+
     fn insert_whitespace(&mut self, arg: &str) {
         if self.after_comment {
             return;
@@ -79,6 +107,12 @@ fn format_node(
         SyntaxKind::Element => {
             return format_element(node, writer, state);
         }
+        SyntaxKind::SubElement => {
+            return format_sub_element(node, writer, state);
+        }
+        SyntaxKind::PropertyAnimation => {
+            return format_property_animation(node, writer, state);
+        }
         SyntaxKind::PropertyDeclaration => {
             return format_property_declaration(node, writer, state);
         }
@@ -114,7 +148,17 @@ fn fold(
         NodeOrToken::Token(t) => {
             if t.kind() == SyntaxKind::Whitespace {
                 if state.skip_all_whitespace {
-                    writer.with_new_content(t, "")?;
+                    // Keep at most one empty line
+                    if t.text().matches('\n').count() > 1 {
+                        if let Some(ws) = &mut state.whitespace_to_add {
+                            if ws.starts_with('\n') && !ws.starts_with("\n\n") {
+                                ws.insert(0, '\n');
+                            }
+                        }
+                    }
+                    // Keep the new line at the end of the file
+                    let keep_new_line = t.next_token().is_none() && t.text().contains('\n');
+                    writer.with_new_content(t, if keep_new_line { "\n" } else { "" })?;
                     return Ok(());
                 }
             } else {
@@ -122,11 +166,14 @@ fn fold(
                 state.skip_all_whitespace = false;
                 if let Some(x) = state.whitespace_to_add.take() {
                     state.insertion_count += 1;
+                    state.advance_column(&x);
+                    state.advance_column(t.text());
                     writer.insert_before(t, x.as_ref())?;
                     return Ok(());
                 }
             }
             state.insertion_count += 1;
+            state.advance_column(t.text());
             writer.no_change(t)
         }
     }
@@ -188,6 +235,25 @@ fn format_component(
     Ok(())
 }
 
+fn format_sub_element(
+    node: &SyntaxNode,
+    writer: &mut impl TokenWriter,
+    state: &mut FormatState,
+) -> Result<(), std::io::Error> {
+    let mut sub = node.children_with_tokens();
+    let _ok = if node.child_token(SyntaxKind::Identifier).is_some() {
+        whitespace_to(&mut sub, SyntaxKind::Identifier, writer, state, "")?
+            && whitespace_to(&mut sub, SyntaxKind::ColonEqual, writer, state, " ")?
+            && whitespace_to(&mut sub, SyntaxKind::Element, writer, state, " ")?
+    } else {
+        // Without new line, this is the element following the colon of a `for` or `if`
+        let ws = if state.whitespace_to_add.is_some() { "" } else { " " };
+        whitespace_to(&mut sub, SyntaxKind::Element, writer, state, ws)?
+    };
+    finish_node(sub, writer, state)?;
+    Ok(())
+}
+
 fn format_element(
     node: &SyntaxNode,
     writer: &mut impl TokenWriter,
@@ -195,18 +261,69 @@ fn format_element(
 ) -> Result<(), std::io::Error> {
     let mut sub = node.children_with_tokens();
     if !(whitespace_to(&mut sub, SyntaxKind::QualifiedName, writer, state, "")?
-        // note: QualifiedName is already adding one space
+        // note: the whitespace at the end of the QualifiedName is kept
         && whitespace_to(&mut sub, SyntaxKind::LBrace, writer, state, "")?)
     {
         finish_node(sub, writer, state)?;
         return Ok(());
     }
 
+    format_braced_body(node, sub.collect(), writer, state)
+}
+
+fn format_property_animation(
+    node: &SyntaxNode,
+    writer: &mut impl TokenWriter,
+    state: &mut FormatState,
+) -> Result<(), std::io::Error> {
+    let mut sub = node.children_with_tokens();
+    for n in &mut sub {
+        let is_lbrace = n.kind() == SyntaxKind::LBrace;
+        fold(n, writer, state)?;
+        if is_lbrace {
+            return format_braced_body(node, sub.collect(), writer, state);
+        }
+    }
+    Ok(())
+}
+
+/// Format the content of an element or animation after the opening brace. It is kept on a
+/// single line if it was on a single line and fits within the maximum line width.
+fn format_braced_body(
+    node: &SyntaxNode,
+    body: Vec<NodeOrToken>,
+    writer: &mut impl TokenWriter,
+    state: &mut FormatState,
+) -> Result<(), std::io::Error> {
+    if !state.single_line && fits_on_one_line(node) {
+        // Try to keep the element on a single line, and only commit to that if the result
+        // is not too long
+        let mut trial_state = state.clone();
+        trial_state.single_line = true;
+        let mut recorder = RecordingWriter::default();
+        format_element_body(&body, &mut recorder, &mut trial_state)?;
+        if !recorder.has_new_line && trial_state.column <= state.style.max_line_width {
+            recorder.replay(writer)?;
+            *state = trial_state;
+            state.single_line = false;
+            state.whitespace_to_add = None;
+            state.new_line();
+            return Ok(());
+        }
+    }
+    format_element_body(&body, writer, state)
+}
+
+fn format_element_body(
+    body: &[NodeOrToken],
+    writer: &mut impl TokenWriter,
+    state: &mut FormatState,
+) -> Result<(), std::io::Error> {
     state.indentation_level += 1;
     state.new_line();
     let ins_ctn = state.insertion_count;
 
-    for n in sub {
+    for n in body.iter().cloned() {
         if n.kind() == SyntaxKind::RBrace {
             state.indentation_level -= 1;
             state.whitespace_to_add = None;
@@ -224,6 +341,60 @@ fn format_element(
     Ok(())
 }
 
+/// Elements that were written on a single line without comments may stay on a single line
+fn fits_on_one_line(node: &SyntaxNode) -> bool {
+    !node.text().contains_char('\n')
+        && !node.descendants_with_tokens().any(|n| n.kind() == SyntaxKind::Comment)
+}
+
+/// Buffers the changes so they can be discarded if the formatting attempt is not kept
+#[derive(Default)]
+struct RecordingWriter {
+    changes: Vec<(SyntaxToken, RecordedChange)>,
+    has_new_line: bool,
+}
+
+enum RecordedChange {
+    NoChange,
+    NewContent(String),
+    InsertBefore(String),
+}
+
+impl RecordingWriter {
+    fn replay(self, writer: &mut impl TokenWriter) -> std::io::Result<()> {
+        for (token, change) in self.changes {
+            match change {
+                RecordedChange::NoChange => writer.no_change(token)?,
+                RecordedChange::NewContent(contents) => {
+                    writer.with_new_content(token, &contents)?
+                }
+                RecordedChange::InsertBefore(contents) => writer.insert_before(token, &contents)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TokenWriter for RecordingWriter {
+    fn no_change(&mut self, token: SyntaxToken) -> std::io::Result<()> {
+        self.has_new_line |= token.text().contains('\n');
+        self.changes.push((token, RecordedChange::NoChange));
+        Ok(())
+    }
+
+    fn with_new_content(&mut self, token: SyntaxToken, contents: &str) -> std::io::Result<()> {
+        self.has_new_line |= contents.contains('\n');
+        self.changes.push((token, RecordedChange::NewContent(contents.into())));
+        Ok(())
+    }
+
+    fn insert_before(&mut self, token: SyntaxToken, contents: &str) -> std::io::Result<()> {
+        self.has_new_line |= contents.contains('\n') || token.text().contains('\n');
+        self.changes.push((token, RecordedChange::InsertBefore(contents.into())));
+        Ok(())
+    }
+}
+
 fn format_property_declaration(
     node: &SyntaxNode,
     writer: &mut impl TokenWriter,
@@ -328,8 +499,12 @@ fn format_qualified_name(
     writer: &mut impl TokenWriter,
     state: &mut FormatState,
 ) -> Result<(), std::io::Error> {
-    for n in node.children_with_tokens() {
-        state.skip_all_whitespace = true;
+    let mut sub = node.children_with_tokens().peekable();
+    while let Some(n) = sub.next() {
+        // The whitespace at the end of the node separates the name from what follows
+        if n.kind() != SyntaxKind::Whitespace || sub.peek().is_some() {
+            state.skip_all_whitespace = true;
+        }
         fold(n, writer, state)?;
     }
     /*if !node
@@ -352,3 +527,48 @@ fn format_qualified_name(
     }*/
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileWriter;
+    use sixtyfps_compilerlib::diagnostics::BuildDiagnostics;
+
+    fn format(source: &str, style: &FormatStyle) -> String {
+        let mut diag = BuildDiagnostics::default();
+        let node = sixtyfps_compilerlib::parser::parse(source.into(), None, &mut diag);
+        assert!(!diag.has_error());
+        let doc = syntax_nodes::Document::new(node).unwrap();
+        let mut file = Vec::new();
+        format_document(doc, style, &mut FileWriter { file: &mut file }).unwrap();
+        String::from_utf8(file).unwrap()
+    }
+
+    #[test]
+    fn indentation() {
+        let source = "Foo := Rectangle {\nproperty<int> xx;\n  Text {\ntext: \"hello\";\n}\n}\n";
+        assert_eq!(
+            format(source, &FormatStyle::default()),
+            "Foo := Rectangle {\n    property <int> xx;\n    Text {\n        text: \"hello\";\n    }\n}\n"
+        );
+        let style = FormatStyle { use_tabs: true, ..Default::default() };
+        assert_eq!(
+            format(source, &style),
+            "Foo := Rectangle {\n\tproperty <int> xx;\n\tText {\n\t\ttext: \"hello\";\n\t}\n}\n"
+        );
+    }
+
+    #[test]
+    fn single_line_elements() {
+        let source = "Foo := Rectangle {\n    Text {   text: \"hello\";  color: red;   }\n}\n";
+        assert_eq!(
+            format(source, &FormatStyle::default()),
+            "Foo := Rectangle {\n    Text { text: \"hello\"; color: red; }\n}\n"
+        );
+        let style = FormatStyle { max_line_width: 20, ..Default::default() };
+        assert_eq!(
+            format(source, &style),
+            "Foo := Rectangle {\n    Text {\n        text: \"hello\";\n        color: red;\n    }\n}\n"
+        );
+    }
+}
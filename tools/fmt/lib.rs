@@ -0,0 +1,82 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+/*!
+    Work in progress for a formatter of .60 files.
+
+    This library is used by the `sixtyfps-fmt` binary and by the LSP server.
+
+    The formatter goes over all the tokens of a document and passes each of them to a
+    [`TokenWriter`], either with no changes or with a new content. The [`FileWriter`] writes
+    the formatted file, while the LSP uses its own writer to compute the text edits.
+*/
+
+use sixtyfps_compilerlib::parser::SyntaxToken;
+use std::io::Write;
+
+mod fmt;
+
+pub use fmt::format_document;
+
+/// Configures the formatting
+#[derive(Clone, Debug)]
+pub struct FormatStyle {
+    /// The width of one level of indentation
+    pub indent_width: usize,
+    /// Indent with tabs instead of spaces
+    pub use_tabs: bool,
+    /// Elements written on a single line are kept on a single line as long as the line
+    /// does not exceed that width
+    pub max_line_width: usize,
+}
+
+impl Default for FormatStyle {
+    fn default() -> Self {
+        Self { indent_width: 4, use_tabs: false, max_line_width: 100 }
+    }
+}
+
+impl FormatStyle {
+    /// The string to insert for one level of indentation
+    fn indentation(&self) -> String {
+        if self.use_tabs {
+            "\t".into()
+        } else {
+            " ".repeat(self.indent_width)
+        }
+    }
+}
+
+/// The idea is that each token need to go through this, either with no changes,
+/// or with a new content.
+pub trait TokenWriter {
+    fn no_change(&mut self, token: SyntaxToken) -> std::io::Result<()>;
+    fn with_new_content(&mut self, token: SyntaxToken, contents: &str) -> std::io::Result<()>;
+    fn insert_before(&mut self, token: SyntaxToken, contents: &str) -> std::io::Result<()>;
+}
+
+/// Just write the token stream to a file
+pub struct FileWriter<'a, W> {
+    pub file: &'a mut W,
+}
+
+impl<'a, W: Write> TokenWriter for FileWriter<'a, W> {
+    fn no_change(&mut self, token: SyntaxToken) -> std::io::Result<()> {
+        self.file.write_all(token.text().as_bytes())
+    }
+
+    fn with_new_content(&mut self, _token: SyntaxToken, contents: &str) -> std::io::Result<()> {
+        self.file.write_all(contents.as_bytes())
+    }
+
+    fn insert_before(&mut self, token: SyntaxToken, contents: &str) -> std::io::Result<()> {
+        self.file.write_all(contents.as_bytes())?;
+        self.file.write_all(token.text().as_bytes())
+    }
+}
@@ -17,17 +17,15 @@
     Some code in this main.rs file is duplicated with the syntax_updater, i guess it could
     be refactored in a separate utility crate or module or something.
 
-    The [`TokenWriter`] trait is meant to be able to support the LSP later as the
-    LSP wants just the edits , not the full file
+    The formatting itself is done in the library, which is shared with the LSP.
 */
 
 use sixtyfps_compilerlib::diagnostics::BuildDiagnostics;
-use sixtyfps_compilerlib::parser::{syntax_nodes, SyntaxNode, SyntaxToken};
+use sixtyfps_compilerlib::parser::{syntax_nodes, SyntaxNode};
+use sixtyfps_fmt::{FileWriter, FormatStyle};
 use std::io::Write;
 use structopt::StructOpt;
 
-mod fmt;
-
 #[derive(StructOpt)]
 struct Cli {
     #[structopt(name = "path to .60 file(s)", parse(from_os_str))]
@@ -36,26 +34,47 @@ struct Cli {
     /// modify the file inline instead of printing to stdout
     #[structopt(short, long)]
     inline: bool,
+
+    /// number of spaces used for one level of indentation
+    #[structopt(long, default_value = "4")]
+    indent_width: usize,
+
+    /// indent with tabs instead of spaces
+    #[structopt(long)]
+    use_tabs: bool,
+
+    /// elements written on a single line are kept on a single line if it does not exceed that width
+    #[structopt(long, default_value = "100")]
+    max_line_width: usize,
 }
 
 fn main() -> std::io::Result<()> {
     let args = Cli::from_args();
+    let style = FormatStyle {
+        indent_width: args.indent_width,
+        use_tabs: args.use_tabs,
+        max_line_width: args.max_line_width,
+    };
 
     for path in args.paths {
         let source = std::fs::read_to_string(&path)?;
 
         if args.inline {
             let file = std::fs::File::create(&path)?;
-            process_file(source, path, file)?
+            process_file(source, path, file, &style)?
         } else {
-            process_file(source, path, std::io::stdout())?
+            process_file(source, path, std::io::stdout(), &style)?
         }
     }
     Ok(())
 }
 
 /// FIXME! this is duplicated with the updater
-fn process_rust_file(source: String, mut file: impl Write) -> std::io::Result<()> {
+fn process_rust_file(
+    source: String,
+    mut file: impl Write,
+    style: &FormatStyle,
+) -> std::io::Result<()> {
     let mut source_slice = &source[..];
     let sixtyfps_macro = format!("{}!", "sixtyfps"); // in a variable so it does not appear as is
     'l: while let Some(idx) = source_slice.find(&sixtyfps_macro) {
@@ -100,7 +119,7 @@ fn process_rust_file(source: String, mut file: impl Write) -> std::io::Result<()
         let mut diag = BuildDiagnostics::default();
         let syntax_node = sixtyfps_compilerlib::parser::parse(code.to_owned(), None, &mut diag);
         let len = syntax_node.text_range().end().into();
-        visit_node(syntax_node, &mut file, &mut State::default())?;
+        visit_node(syntax_node, &mut file, style)?;
         if diag.has_error() {
             file.write_all(&code.as_bytes()[len..])?;
             diag.print();
@@ -110,7 +129,11 @@ fn process_rust_file(source: String, mut file: impl Write) -> std::io::Result<()
 }
 
 /// FIXME! this is duplicated with the updater
-fn process_markdown_file(source: String, mut file: impl Write) -> std::io::Result<()> {
+fn process_markdown_file(
+    source: String,
+    mut file: impl Write,
+    style: &FormatStyle,
+) -> std::io::Result<()> {
     let mut source_slice = &source[..];
     const CODE_FENCE_START: &'static str = "```60\n";
     const CODE_FENCE_END: &'static str = "```\n";
@@ -130,7 +153,7 @@ fn process_markdown_file(source: String, mut file: impl Write) -> std::io::Resul
         let mut diag = BuildDiagnostics::default();
         let syntax_node = sixtyfps_compilerlib::parser::parse(code.to_owned(), None, &mut diag);
         let len = syntax_node.text_range().end().into();
-        visit_node(syntax_node, &mut file, &mut State::default())?;
+        visit_node(syntax_node, &mut file, style)?;
         if diag.has_error() {
             file.write_all(&code.as_bytes()[len..])?;
             diag.print();
@@ -143,17 +166,18 @@ fn process_file(
     source: String,
     path: std::path::PathBuf,
     mut file: impl Write,
+    style: &FormatStyle,
 ) -> std::io::Result<()> {
     match path.extension() {
-        Some(ext) if ext == "rs" => return process_rust_file(source, file),
-        Some(ext) if ext == "md" => return process_markdown_file(source, file),
+        Some(ext) if ext == "rs" => return process_rust_file(source, file, style),
+        Some(ext) if ext == "md" => return process_markdown_file(source, file, style),
         _ => {}
     }
 
     let mut diag = BuildDiagnostics::default();
     let syntax_node = sixtyfps_compilerlib::parser::parse(source.clone(), Some(&path), &mut diag);
     let len = syntax_node.node.text_range().end().into();
-    visit_node(syntax_node, &mut file, &mut State::default())?;
+    visit_node(syntax_node, &mut file, style)?;
     if diag.has_error() {
         file.write_all(&source.as_bytes()[len..])?;
         diag.print();
@@ -161,41 +185,11 @@ fn process_file(
     Ok(())
 }
 
-type State = ();
-
-fn visit_node(node: SyntaxNode, file: &mut impl Write, _state: &mut State) -> std::io::Result<()> {
+fn visit_node(node: SyntaxNode, file: &mut impl Write, style: &FormatStyle) -> std::io::Result<()> {
     if let Some(doc) = syntax_nodes::Document::new(node) {
         let mut writer = FileWriter { file };
-        fmt::format_document(doc, &mut writer)
+        sixtyfps_fmt::format_document(doc, style, &mut writer)
     } else {
         Err(std::io::Error::new(std::io::ErrorKind::Other, "Not a Document"))
     }
 }
-
-/// The idea is that each token need to go through this, either with no changes,
-/// or with a new content.
-trait TokenWriter {
-    fn no_change(&mut self, token: SyntaxToken) -> std::io::Result<()>;
-    fn with_new_content(&mut self, token: SyntaxToken, contents: &str) -> std::io::Result<()>;
-    fn insert_before(&mut self, token: SyntaxToken, contents: &str) -> std::io::Result<()>;
-}
-
-/// Just write the token stream to a file
-struct FileWriter<'a, W> {
-    file: &'a mut W,
-}
-
-impl<'a, W: Write> TokenWriter for FileWriter<'a, W> {
-    fn no_change(&mut self, token: SyntaxToken) -> std::io::Result<()> {
-        self.file.write_all(token.text().as_bytes())
-    }
-
-    fn with_new_content(&mut self, _token: SyntaxToken, contents: &str) -> std::io::Result<()> {
-        self.file.write_all(contents.as_bytes())
-    }
-
-    fn insert_before(&mut self, token: SyntaxToken, contents: &str) -> std::io::Result<()> {
-        self.file.write_all(contents.as_bytes())?;
-        self.file.write_all(token.text().as_bytes())
-    }
-}
@@ -379,3 +379,38 @@ fn complete_path_in_string(base: &Path, text: &str, offset: u32) -> Option<Vec<C
         .collect(),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn member_completion() {
+        let source = r#"
+Foo := Rectangle {
+    property <int> value: 42;
+    property <[int]> model: [1, 2];
+    property <string> a: value.to;
+    property <int> b: model.le;
+}
+"#;
+        let (document_cache, doc_node, _) = crate::loaded_document(source);
+        let complete = |text: &str| {
+            let offset = (source.find(text).unwrap() + text.len() - 1) as u32;
+            let token = SyntaxToken {
+                token: doc_node.token_at_offset(offset.into()).right_biased().unwrap(),
+                source_file: doc_node.source_file.clone(),
+            };
+            completion_at(&document_cache, token, offset, None)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.label)
+                .collect::<Vec<_>>()
+        };
+
+        let number_members = complete("value.to");
+        assert!(number_members.iter().any(|l| l == "to_fixed"), "{:?}", number_members);
+        assert!(number_members.iter().any(|l| l == "to_byte_size"), "{:?}", number_members);
+        assert_eq!(complete("model.le"), vec!["length".to_string()]);
+    }
+}
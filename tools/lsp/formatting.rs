@@ -0,0 +1,190 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+
+use super::{byte_offset_to_utf16_position, utf16_position_to_byte_offset, DocumentCache};
+use lsp_types::{FormattingOptions, FormattingProperty, Range, TextEdit, Url};
+use sixtyfps_compilerlib::diagnostics::BuildDiagnostics;
+use sixtyfps_compilerlib::parser::{syntax_nodes, SyntaxToken};
+use sixtyfps_fmt::{FormatStyle, TokenWriter};
+use std::path::Path;
+
+/// Compute the edits to format the document.
+///
+/// If `range` is set, only the edits intersecting that range are returned.
+/// Returns None if the document can't be formatted, for example because it has syntax errors.
+pub fn format_document(
+    document_cache: &mut DocumentCache,
+    uri: &Url,
+    options: &FormattingOptions,
+    range: Option<Range>,
+) -> Option<Vec<TextEdit>> {
+    let path = uri.to_file_path().ok()?;
+    let source = document_cache.documents.get_document(&path)?.node.as_ref()?.text().to_string();
+    format_source(&source, &path, options, range)
+}
+
+/// Compute the edits to format `source`. The positions of the range and of the edits count
+/// the characters in UTF-16 code units, as in the LSP protocol.
+fn format_source(
+    source: &str,
+    path: &Path,
+    options: &FormattingOptions,
+    range: Option<Range>,
+) -> Option<Vec<TextEdit>> {
+    let mut diag = BuildDiagnostics::default();
+    let node = sixtyfps_compilerlib::parser::parse(source.into(), Some(path), &mut diag);
+    if diag.has_error() {
+        return None;
+    }
+    let doc = syntax_nodes::Document::new(node)?;
+
+    let mut writer = EditWriter::default();
+    sixtyfps_fmt::format_document(doc, &style_from_options(options), &mut writer).ok()?;
+
+    let range = range.map(|range| {
+        (
+            utf16_position_to_byte_offset(source, range.start),
+            utf16_position_to_byte_offset(source, range.end),
+        )
+    });
+
+    let mut result = vec![];
+    for (edit_range, new_text) in writer.edits {
+        if source[edit_range.clone()] == new_text {
+            continue;
+        }
+        if let Some((start, end)) = range {
+            if edit_range.end < start || edit_range.start > end {
+                continue;
+            }
+        }
+        result.push(TextEdit {
+            range: Range::new(
+                byte_offset_to_utf16_position(source, edit_range.start),
+                byte_offset_to_utf16_position(source, edit_range.end),
+            ),
+            new_text,
+        });
+    }
+    Some(result)
+}
+
+/// The formatting style from the editor options. The maximum line width can be
+/// passed with the `maxLineWidth` property.
+fn style_from_options(options: &FormattingOptions) -> FormatStyle {
+    let mut style = FormatStyle {
+        indent_width: options.tab_size as usize,
+        use_tabs: !options.insert_spaces,
+        ..Default::default()
+    };
+    if let Some(FormattingProperty::Number(width)) = options.properties.get("maxLineWidth") {
+        style.max_line_width = (*width).max(0) as usize;
+    }
+    style
+}
+
+/// Collects the changes done by the formatter as a list of replacements of byte ranges
+/// of the source.
+#[derive(Default)]
+struct EditWriter {
+    edits: Vec<(std::ops::Range<usize>, String)>,
+}
+
+impl EditWriter {
+    fn add_edit(&mut self, range: std::ops::Range<usize>, text: &str) {
+        // Merge with the previous edit if they are adjacent, so that replacing some whitespace
+        // results in a single edit.
+        if let Some((last_range, last_text)) = self.edits.last_mut() {
+            if last_range.end == range.start {
+                last_range.end = range.end;
+                *last_text += text;
+                return;
+            }
+        }
+        self.edits.push((range, text.into()));
+    }
+}
+
+impl TokenWriter for EditWriter {
+    fn no_change(&mut self, _token: SyntaxToken) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn with_new_content(&mut self, token: SyntaxToken, contents: &str) -> std::io::Result<()> {
+        let range = token.text_range();
+        self.add_edit(range.start().into()..range.end().into(), contents);
+        Ok(())
+    }
+
+    fn insert_before(&mut self, token: SyntaxToken, contents: &str) -> std::io::Result<()> {
+        let offset = token.text_range().start().into();
+        self.add_edit(offset..offset, contents);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::Position;
+
+    fn format(source: &str, range: Option<Range>) -> Vec<TextEdit> {
+        let options = FormattingOptions { tab_size: 4, insert_spaces: true, ..Default::default() };
+        format_source(source, Path::new("test.60"), &options, range).unwrap()
+    }
+
+    /// Apply the edits the way an editor would, counting the characters in UTF-16 code units
+    fn apply_edits(source: &str, edits: &[TextEdit]) -> String {
+        let mut result = source.to_owned();
+        for edit in edits.iter().rev() {
+            let start = utf16_position_to_byte_offset(source, edit.range.start);
+            let end = utf16_position_to_byte_offset(source, edit.range.end);
+            result.replace_range(start..end, &edit.new_text);
+        }
+        result
+    }
+
+    #[test]
+    fn non_ascii_positions() {
+        let source = "Foo := Rectangle {\n    Text { text: \"héllo 😀\";  color: red; }\n}\n";
+        let edits = format(source, None);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range, Range::new(Position::new(1, 28), Position::new(1, 30)));
+        assert_eq!(edits[0].new_text, " ");
+        assert_eq!(
+            apply_edits(source, &edits),
+            "Foo := Rectangle {\n    Text { text: \"héllo 😀\"; color: red; }\n}\n"
+        );
+    }
+
+    #[test]
+    fn non_ascii_indentation() {
+        let source =
+            "Foo := Rectangle {\nTtext := Text {\ntext: \"日本語\";\n}\n  Text { text: \"ü\"; }\n}\n";
+        let edits = format(source, None);
+        assert_eq!(
+            apply_edits(source, &edits),
+            "Foo := Rectangle {\n    Ttext := Text {\n        text: \"日本語\";\n    }\n    Text { text: \"ü\"; }\n}\n"
+        );
+    }
+
+    #[test]
+    fn non_ascii_range() {
+        let source = "Foo := Rectangle {\n  Text { text: \"ä\";  }\n  Text { text: \"ö\";  }\n}\n";
+        // The range ends at the double space of the second line, after the non-ASCII character.
+        // Counted in bytes instead of UTF-16 code units, it would end before it.
+        let range = Range::new(Position::new(1, 0), Position::new(1, 20));
+        let edits = format(source, Some(range));
+        assert_eq!(
+            apply_edits(source, &edits),
+            "Foo := Rectangle {\n    Text { text: \"ä\"; }\n  Text { text: \"ö\";  }\n}\n"
+        );
+    }
+}
@@ -8,7 +8,7 @@
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use super::DocumentCache;
 use lsp_types::{GotoDefinitionResponse, LocationLink, Range, Url};
@@ -16,12 +16,93 @@
 use sixtyfps_compilerlib::expression_tree::Expression;
 use sixtyfps_compilerlib::langtype::Type;
 use sixtyfps_compilerlib::lookup::LookupObject;
+use sixtyfps_compilerlib::namedreference::NamedReference;
+use sixtyfps_compilerlib::object_tree::ElementRc;
 use sixtyfps_compilerlib::parser::{syntax_nodes, SyntaxKind, SyntaxNode, SyntaxToken};
 
+/// What a token in a document refers to
+pub enum TokenInfo {
+    /// A component, a struct, or a builtin element
+    Type(Type),
+    /// An element, usually referenced by its id
+    ElementRc(ElementRc),
+    /// A property or a callback of an element
+    NamedReference(NamedReference),
+    /// Any other expression (builtin functions, enum values, ...)
+    Expression(Expression),
+    /// The file of an import statement
+    FileImport(PathBuf),
+}
+
+impl TokenInfo {
+    /// Return the node in which the item referred to by this token is declared,
+    /// or None if it is not declared in a .60 file (builtin items)
+    pub fn declaration_node(&self, document_cache: &DocumentCache) -> Option<SyntaxNode> {
+        match self {
+            TokenInfo::Type(Type::Component(c)) => {
+                let root_node = c.root_element.borrow().node.clone()?;
+                root_node.parent().filter(|p| p.kind() == SyntaxKind::Component)
+            }
+            TokenInfo::Type(Type::Struct { node: Some(node), .. }) => node.parent(),
+            TokenInfo::Type(Type::Enumeration(e)) => e.node.clone().map(Into::into),
+            TokenInfo::Type(_) => None,
+            TokenInfo::ElementRc(e) => {
+                let node = e.borrow().node.clone()?;
+                node.parent()
+                    .filter(|p| p.kind() == SyntaxKind::SubElement)
+                    .or_else(|| Some((*node).clone()))
+            }
+            TokenInfo::NamedReference(nr) => property_declaration(nr),
+            TokenInfo::Expression(_) => None,
+            TokenInfo::FileImport(path) => {
+                document_cache.documents.get_document(path)?.node.clone().map(Into::into)
+            }
+        }
+    }
+}
+
+/// Return the node of the `property` or `callback` declaration of this reference.
+fn property_declaration(nr: &NamedReference) -> Option<SyntaxNode> {
+    let mut el = nr.element();
+    loop {
+        if let Some(x) = el.borrow().property_declarations.get(nr.name()) {
+            return x.node.as_ref().map(|n| (**n).clone());
+        }
+        let base = el.borrow().base_type.clone();
+        if let Type::Component(c) = base {
+            el = c.root_element.clone();
+        } else {
+            return None;
+        }
+    }
+}
+
+/// Return the identifier token that declares the name of a node returned by
+/// [`TokenInfo::declaration_node`]
+pub fn declared_identifier(node: &SyntaxNode) -> Option<SyntaxToken> {
+    match node.kind() {
+        SyntaxKind::SubElement => node.child_token(SyntaxKind::Identifier),
+        SyntaxKind::Component
+        | SyntaxKind::PropertyDeclaration
+        | SyntaxKind::CallbackDeclaration
+        | SyntaxKind::StructDeclaration
+        | SyntaxKind::EnumDeclaration => {
+            node.child_node(SyntaxKind::DeclaredIdentifier)?.child_token(SyntaxKind::Identifier)
+        }
+        _ => None,
+    }
+}
+
 pub fn goto_definition(
     document_cache: &mut DocumentCache,
     token: SyntaxToken,
 ) -> Option<GotoDefinitionResponse> {
+    let node = token_info(document_cache, token)?.declaration_node(document_cache)?;
+    goto_node(document_cache, &node)
+}
+
+/// Find out what the token refers to
+pub fn token_info(document_cache: &DocumentCache, token: SyntaxToken) -> Option<TokenInfo> {
     let mut node = token.parent();
     loop {
         if let Some(n) = syntax_nodes::QualifiedName::new(node.clone()) {
@@ -31,13 +112,8 @@ pub fn goto_definition(
                     let qual = sixtyfps_compilerlib::object_tree::QualifiedTypeName::from_node(n);
                     let doc = document_cache.documents.get_document(node.source_file.path())?;
                     match doc.local_registry.lookup_qualified(&qual.members) {
-                        sixtyfps_compilerlib::langtype::Type::Component(c) => {
-                            goto_node(document_cache, &*c.root_element.borrow().node.as_ref()?)
-                        }
-                        sixtyfps_compilerlib::langtype::Type::Struct {
-                            node: Some(node), ..
-                        } => goto_node(document_cache, node.parent().as_ref()?),
-                        _ => None,
+                        Type::Invalid => None,
+                        ty => Some(TokenInfo::Type(ty)),
                     }
                 }
                 SyntaxKind::Expression => {
@@ -62,27 +138,48 @@ pub fn goto_definition(
                         }
                         Some(expr_it)
                     })?;
-                    let gn = match expr? {
-                        Expression::ElementReference(e) => {
-                            e.upgrade()?.borrow().node.clone()?.into()
-                        }
+                    Some(match expr? {
+                        Expression::ElementReference(e) => TokenInfo::ElementRc(e.upgrade()?),
                         Expression::CallbackReference(nr) | Expression::PropertyReference(nr) => {
-                            let mut el = nr.element();
-                            loop {
-                                if let Some(x) = el.borrow().property_declarations.get(nr.name()) {
-                                    break (**x.node.as_ref()?).clone();
-                                }
-                                let base = el.borrow().base_type.clone();
-                                if let Type::Component(c) = base {
-                                    el = c.root_element.clone();
-                                } else {
-                                    return None;
-                                }
+                            TokenInfo::NamedReference(nr)
+                        }
+                        e => TokenInfo::Expression(e),
+                    })
+                }
+                SyntaxKind::StatePropertyChange | SyntaxKind::PropertyAnimation => {
+                    if token.kind() != SyntaxKind::Identifier {
+                        return None;
+                    }
+                    let mut element_node = parent.parent()?;
+                    while element_node.kind() != SyntaxKind::Element {
+                        element_node = element_node.parent()?;
+                    }
+                    let element = crate::util::find_element_rc(
+                        document_cache,
+                        &syntax_nodes::Element::new(element_node)?,
+                    )?;
+                    let mut it = n
+                        .children_with_tokens()
+                        .filter_map(|t| t.into_token())
+                        .filter(|t| t.kind() == SyntaxKind::Identifier);
+                    let first = it.next()?;
+                    let second = it.next();
+                    let (element, prop_tok) = match second {
+                        None => (element, first),
+                        Some(second) => {
+                            let id =
+                                sixtyfps_compilerlib::parser::normalize_identifier(first.text());
+                            let element = find_element_by_id(&element, &id)?;
+                            if first.token == token.token {
+                                return Some(TokenInfo::ElementRc(element));
                             }
+                            (element, second)
                         }
-                        _ => return None,
                     };
-                    goto_node(document_cache, &gn)
+                    if prop_tok.token != token.token {
+                        return None;
+                    }
+                    named_reference(&element, prop_tok.text())
                 }
                 _ => None,
             };
@@ -90,10 +187,8 @@ pub fn goto_definition(
             let doc = document_cache.documents.get_document(node.source_file.path())?;
             let imp_name = sixtyfps_compilerlib::typeloader::ImportedName::from_node(n);
             return match doc.local_registry.lookup(&imp_name.internal_name) {
-                sixtyfps_compilerlib::langtype::Type::Component(c) => {
-                    goto_node(document_cache, &*c.root_element.borrow().node.as_ref()?)
-                }
-                _ => None,
+                Type::Invalid => None,
+                ty => Some(TokenInfo::Type(ty)),
             };
         } else if let Some(n) = syntax_nodes::ImportSpecifier::new(node.clone()) {
             let import_file = node
@@ -103,9 +198,7 @@ pub fn goto_definition(
                 .unwrap_or(Path::new("/"))
                 .join(n.child_text(SyntaxKind::StringLiteral)?.trim_matches('\"'));
             let import_file = dunce::canonicalize(&import_file).unwrap_or(import_file);
-            let doc = document_cache.documents.get_document(&import_file)?;
-            let doc_node = doc.node.clone()?;
-            return goto_node(document_cache, &*doc_node);
+            return Some(TokenInfo::FileImport(import_file));
         } else if let Some(_) = syntax_nodes::BindingExpression::new(node.clone()) {
             // don't fallback to the Binding
             return None;
@@ -113,79 +206,102 @@ pub fn goto_definition(
             if token.kind() != SyntaxKind::Identifier {
                 return None;
             }
-            let prop_name = token.text();
             let element = syntax_nodes::Element::new(n.parent()?)?;
-            if let Some(p) = element.PropertyDeclaration().find_map(|p| {
-                (sixtyfps_compilerlib::parser::identifier_text(&p.DeclaredIdentifier())?
-                    == prop_name)
-                    .then(|| p)
-            }) {
-                return goto_node(document_cache, &p);
-            }
-            let n = find_property_declaration_in_base(&document_cache, element, prop_name)?;
-            return goto_node(document_cache, &n);
+            let element = crate::util::find_element_rc(document_cache, &element)?;
+            return named_reference(&element, token.text());
         } else if let Some(n) = syntax_nodes::TwoWayBinding::new(node.clone()) {
             if token.kind() != SyntaxKind::Identifier {
                 return None;
             }
-            let prop_name = token.text();
-            if prop_name != n.child_text(SyntaxKind::Identifier)? {
+            if token.text() != n.child_text(SyntaxKind::Identifier)? {
                 return None;
             }
             let element = syntax_nodes::Element::new(n.parent()?)?;
-            if let Some(p) = element.PropertyDeclaration().find_map(|p| {
-                (sixtyfps_compilerlib::parser::identifier_text(&p.DeclaredIdentifier())?
-                    == prop_name)
-                    .then(|| p)
-            }) {
-                return goto_node(document_cache, &p);
-            }
-            let n = find_property_declaration_in_base(&document_cache, element, prop_name)?;
-            return goto_node(document_cache, &n);
+            let element = crate::util::find_element_rc(document_cache, &element)?;
+            return named_reference(&element, token.text());
         } else if let Some(n) = syntax_nodes::CallbackConnection::new(node.clone()) {
             if token.kind() != SyntaxKind::Identifier {
                 return None;
             }
-            let prop_name = token.text();
-            if prop_name != n.child_text(SyntaxKind::Identifier)? {
+            if token.text() != n.child_text(SyntaxKind::Identifier)? {
                 return None;
             }
             let element = syntax_nodes::Element::new(n.parent()?)?;
-            if let Some(p) = element.CallbackDeclaration().find_map(|p| {
-                (sixtyfps_compilerlib::parser::identifier_text(&p.DeclaredIdentifier())?
-                    == prop_name)
-                    .then(|| p)
-            }) {
-                return goto_node(document_cache, &p);
+            let element = crate::util::find_element_rc(document_cache, &element)?;
+            return named_reference(&element, token.text());
+        } else if let Some(n) = syntax_nodes::DeclaredIdentifier::new(node.clone()) {
+            if token.kind() != SyntaxKind::Identifier {
+                return None;
+            }
+            let parent = n.parent()?;
+            return match parent.kind() {
+                SyntaxKind::PropertyDeclaration | SyntaxKind::CallbackDeclaration => {
+                    let element = syntax_nodes::Element::new(parent.parent()?)?;
+                    let element = crate::util::find_element_rc(document_cache, &element)?;
+                    named_reference(&element, token.text())
+                }
+                SyntaxKind::Component
+                | SyntaxKind::StructDeclaration
+                | SyntaxKind::EnumDeclaration => {
+                    let doc = document_cache.documents.get_document(node.source_file.path())?;
+                    let name = sixtyfps_compilerlib::parser::normalize_identifier(token.text());
+                    match doc.local_registry.lookup(&name) {
+                        Type::Invalid => None,
+                        ty => Some(TokenInfo::Type(ty)),
+                    }
+                }
+                _ => None,
+            };
+        } else if let Some(n) = syntax_nodes::SubElement::new(node.clone()) {
+            // Only the id, not the identifiers within the element
+            if n.child_token(SyntaxKind::Identifier)?.token != token.token {
+                return None;
+            }
+            let element = crate::util::find_element_rc(document_cache, &n.Element())?;
+            return Some(TokenInfo::ElementRc(element));
+        } else if matches!(node.kind(), SyntaxKind::ExportIdentifier | SyntaxKind::ExportName) {
+            if token.kind() != SyntaxKind::Identifier {
+                return None;
             }
-            let n = find_property_declaration_in_base(&document_cache, element, prop_name)?;
-            return goto_node(document_cache, &n);
+            let doc = document_cache.documents.get_document(node.source_file.path())?;
+            let export_identifier = if node.kind() == SyntaxKind::ExportName {
+                node.parent()?.child_node(SyntaxKind::ExportIdentifier)?
+            } else {
+                node.clone()
+            };
+            let name = sixtyfps_compilerlib::parser::normalize_identifier(
+                export_identifier.text().to_string().trim(),
+            );
+            return match doc.local_registry.lookup(&name) {
+                Type::Invalid => None,
+                ty => Some(TokenInfo::Type(ty)),
+            };
         }
         node = node.parent()?;
     }
 }
 
-/// Try to lookup the property `prop_name` in the base of the given Element
-fn find_property_declaration_in_base(
-    document_cache: &DocumentCache,
-    element: syntax_nodes::Element,
-    prop_name: &str,
-) -> Option<SyntaxNode> {
-    let global_tr = document_cache.documents.global_type_registry.borrow();
-    let tr = element
-        .source_file()
-        .and_then(|sf| document_cache.documents.get_document(sf.path()))
-        .map(|doc| &doc.local_registry)
-        .unwrap_or(&global_tr);
+/// Return a TokenInfo for the property `prop_name` of the given element
+fn named_reference(element: &ElementRc, prop_name: &str) -> Option<TokenInfo> {
+    let prop_name = sixtyfps_compilerlib::parser::normalize_identifier(prop_name);
+    let resolved_name = element.borrow().lookup_property(&prop_name).resolved_name.to_string();
+    if element.borrow().lookup_property(&resolved_name).property_type == Type::Invalid {
+        return None;
+    }
+    Some(TokenInfo::NamedReference(NamedReference::new(element, &resolved_name)))
+}
 
-    let mut element_type = crate::util::lookup_current_element_type((*element).clone(), tr)?;
-    while let Type::Component(com) = element_type {
-        if let Some(p) = com.root_element.borrow().property_declarations.get(prop_name) {
-            return p.node.as_ref().map(|x| (**x).clone());
-        }
-        element_type = com.root_element.borrow().base_type.clone();
+/// Find an element with the given id within the element, or its non-repeated children
+fn find_element_by_id(element: &ElementRc, id: &str) -> Option<ElementRc> {
+    if element.borrow().id == id {
+        return Some(element.clone());
     }
-    None
+    element
+        .borrow()
+        .children
+        .iter()
+        .filter(|c| c.borrow().repeated.is_none())
+        .find_map(|c| find_element_by_id(c, id))
 }
 
 fn goto_node(
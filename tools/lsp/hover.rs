@@ -0,0 +1,138 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+
+use super::goto::TokenInfo;
+use super::DocumentCache;
+use lsp_types::{Hover, HoverContents, MarkedString, Range, Url};
+use sixtyfps_compilerlib::expression_tree::{BuiltinMacroFunction, Expression};
+use sixtyfps_compilerlib::langtype::Type;
+use sixtyfps_compilerlib::namedreference::NamedReference;
+use sixtyfps_compilerlib::parser::SyntaxToken;
+
+pub fn hover_info(document_cache: &mut DocumentCache, token: SyntaxToken) -> Option<Hover> {
+    let info = crate::goto::token_info(document_cache, token.clone())?;
+    let mut contents = vec![];
+    match &info {
+        TokenInfo::Type(ty) => {
+            contents.push(code(describe_type(ty)));
+            if matches!(ty, Type::Builtin(_)) {
+                contents.push(MarkedString::String("builtin element".into()));
+            }
+        }
+        TokenInfo::ElementRc(e) => {
+            let e = e.borrow();
+            contents.push(code(if e.id.is_empty() {
+                e.base_type.to_string()
+            } else {
+                format!("{} := {}", e.id, e.base_type)
+            }));
+        }
+        TokenInfo::NamedReference(nr) => {
+            contents.push(code(describe_property(nr)));
+            let element = nr.element();
+            let element = element.borrow();
+            if !element.id.is_empty() {
+                contents.push(MarkedString::String(format!(
+                    "in `{} := {}`",
+                    element.id, element.base_type
+                )));
+            }
+        }
+        TokenInfo::Expression(expr) => {
+            contents.push(code(describe_expression(expr, token.text())));
+        }
+        TokenInfo::FileImport(path) => {
+            contents.push(MarkedString::String(path.display().to_string()));
+        }
+    }
+
+    let uri = Url::from_file_path(token.source_file.path()).ok()?;
+    let range = token.text_range();
+    let range = Range::new(
+        document_cache.byte_offset_to_position(range.start().into(), &uri)?,
+        document_cache.byte_offset_to_position(range.end().into(), &uri)?,
+    );
+    Some(Hover { contents: HoverContents::Array(contents), range: Some(range) })
+}
+
+fn code(text: String) -> MarkedString {
+    MarkedString::from_language_code("60".into(), text)
+}
+
+fn describe_type(ty: &Type) -> String {
+    match ty {
+        Type::Component(c) => {
+            if c.is_global() {
+                format!("global {}", c.id)
+            } else {
+                format!("{} := {}", c.id, c.root_element.borrow().base_type)
+            }
+        }
+        Type::Struct { fields, name, .. } => {
+            let fields =
+                fields.iter().map(|(k, v)| format!("    {}: {},\n", k, v)).collect::<String>();
+            match name {
+                Some(name) => format!("struct {} := {{\n{}}}", name, fields),
+                None => format!("{{\n{}}}", fields),
+            }
+        }
+        Type::Enumeration(e) => format!("enum {} {{ {} }}", e.name, e.values.join(", ")),
+        ty => ty.to_string(),
+    }
+}
+
+fn describe_property(nr: &NamedReference) -> String {
+    match nr.ty() {
+        Type::Callback { args, return_type } => {
+            let args = args.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(", ");
+            match return_type {
+                Some(rt) => format!("callback {}({}) -> {}", nr.name(), args, rt),
+                None => format!("callback {}({})", nr.name(), args),
+            }
+        }
+        Type::InferredCallback => format!("callback {}", nr.name()),
+        ty => format!("property <{}> {}", ty, nr.name()),
+    }
+}
+
+fn describe_expression(expr: &Expression, name: &str) -> String {
+    match expr {
+        Expression::BuiltinFunctionReference(..) | Expression::MemberFunction { .. } => {
+            if let Type::Function { return_type, args } = expr.ty() {
+                let args = args.iter().map(|a| a.to_string()).collect::<Vec<_>>().join(", ");
+                format!("function {}({}) -> {}", name, args, return_type)
+            } else {
+                format!("function {}", name)
+            }
+        }
+        Expression::BuiltinMacroReference(m, _) => match m {
+            BuiltinMacroFunction::Min | BuiltinMacroFunction::Max => {
+                format!("function {}(value, ...)", name)
+            }
+            BuiltinMacroFunction::CubicBezier => {
+                format!("function {}(float, float, float, float) -> easing", name)
+            }
+            BuiltinMacroFunction::Rgb => {
+                format!("function {}(int, int, int, [float]) -> color", name)
+            }
+            BuiltinMacroFunction::Hsv | BuiltinMacroFunction::Hsl => {
+                format!("function {}(float, float, float, [float]) -> color", name)
+            }
+            BuiltinMacroFunction::Debug => format!("function {}(...)", name),
+        },
+        Expression::EnumerationValue(ev) if ev.value == usize::MAX => {
+            describe_type(&Type::Enumeration(ev.enumeration.clone()))
+        }
+        Expression::EnumerationValue(ev) => {
+            format!("{}.{}", ev.enumeration.name, ev.enumeration.values[ev.value])
+        }
+        expr => format!("{}: {}", name, expr.ty()),
+    }
+}
@@ -0,0 +1,123 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+
+use super::lsp_ext::{InlayHint, InlayHintKind, InlayHintParams};
+use super::DocumentCache;
+use sixtyfps_compilerlib::expression_tree::{Expression, Unit};
+use sixtyfps_compilerlib::langtype::Type;
+use sixtyfps_compilerlib::parser::{
+    normalize_identifier, syntax_nodes, SyntaxKind, SyntaxNode, SyntaxToken,
+};
+
+/// Hints for the type of the properties declared as two way binding without type, and
+/// for the unit of the number literals
+pub fn inlay_hints(
+    document_cache: &mut DocumentCache,
+    params: &InlayHintParams,
+) -> Option<Vec<InlayHint>> {
+    let uri = &params.text_document.uri;
+    let doc = document_cache.documents.get_document(&uri.to_file_path().ok()?)?;
+    let doc_node = doc.node.clone()?;
+    let start = document_cache.position_to_byte_offset(params.range.start, uri)?;
+    // The end of the range may be past the end of the document
+    let end = document_cache.position_to_byte_offset(params.range.end, uri).unwrap_or(u32::MAX);
+
+    let mut hints = vec![];
+    let mut next = doc_node.node.first_token();
+    while let Some(t) = next {
+        next = t.next_token();
+        let range = t.text_range();
+        if u32::from(range.end()) < start || u32::from(range.start()) > end {
+            continue;
+        }
+        let t = SyntaxToken { token: t, source_file: doc_node.source_file.clone() };
+        let hint = match t.kind() {
+            SyntaxKind::Identifier => {
+                alias_type_hint(document_cache, &t).map(|label| (range.start(), label, true))
+            }
+            SyntaxKind::NumberLiteral => {
+                unit_hint(document_cache, &t).map(|label| (range.end(), label, false))
+            }
+            _ => None,
+        };
+        if let Some((offset, label, padding_right)) = hint {
+            hints.push(InlayHint {
+                position: document_cache.byte_offset_to_position(offset.into(), uri)?,
+                label,
+                kind: Some(InlayHintKind::TYPE),
+                padding_left: None,
+                padding_right: Some(padding_right),
+            });
+        }
+    }
+    Some(hints)
+}
+
+/// For `property foo <=> bar.baz;`, return the inferred type as `<type>`
+fn alias_type_hint(document_cache: &DocumentCache, token: &SyntaxToken) -> Option<String> {
+    let declaration = syntax_nodes::PropertyDeclaration::new(
+        syntax_nodes::DeclaredIdentifier::new(token.parent())?.parent()?,
+    )?;
+    if declaration.Type().is_some() || declaration.TwoWayBinding().is_none() {
+        return None;
+    }
+    let element = syntax_nodes::Element::new(declaration.parent()?)?;
+    let element = crate::util::find_element_rc(document_cache, &element)?;
+    let ty = element.borrow().lookup_property(&normalize_identifier(token.text())).property_type;
+    match ty {
+        Type::Invalid | Type::InferredProperty => None,
+        ty => Some(format!("<{}>", ty)),
+    }
+}
+
+/// For a literal `0` assigned to a property with a unit, return the unit of the property.
+/// For a literal with an unit that is not the default unit, return the value in the default unit.
+fn unit_hint(document_cache: &DocumentCache, token: &SyntaxToken) -> Option<String> {
+    let (value, unit) =
+        match sixtyfps_compilerlib::literals::parse_number_literal(token.text().into()).ok()? {
+            Expression::NumberLiteral(value, unit) => (value, unit),
+            _ => return None,
+        };
+    if unit == Unit::None {
+        if value != 0. {
+            return None;
+        }
+        let unit = bound_property_type(document_cache, token)?.default_unit()?;
+        Some(unit.to_string())
+    } else {
+        let default_unit = unit.ty().default_unit()?;
+        if default_unit == unit {
+            return None;
+        }
+        let value = (unit.normalize(value) * 1000.).round() / 1000.;
+        Some(format!(" = {}{}", value, default_unit))
+    }
+}
+
+/// If the token is the whole expression of a binding, return the type of the property
+fn bound_property_type(document_cache: &DocumentCache, token: &SyntaxToken) -> Option<Type> {
+    let mut node: SyntaxNode = token.parent();
+    while matches!(node.kind(), SyntaxKind::Expression | SyntaxKind::BindingExpression)
+        && node.text().to_string().trim().trim_end_matches(';').trim_end() == token.text()
+    {
+        node = node.parent()?;
+    }
+    let name = match node.kind() {
+        SyntaxKind::Binding => node.child_text(SyntaxKind::Identifier)?,
+        SyntaxKind::PropertyDeclaration => {
+            node.child_node(SyntaxKind::DeclaredIdentifier)?.child_text(SyntaxKind::Identifier)?
+        }
+        _ => return None,
+    };
+    let element = syntax_nodes::Element::new(node.parent()?)?;
+    let element = crate::util::find_element_rc(document_cache, &element)?;
+    let ty = element.borrow().lookup_property(&normalize_identifier(&name)).property_type;
+    Some(ty)
+}
@@ -9,9 +9,14 @@
 LICENSE END */
 
 mod completion;
+mod formatting;
 mod goto;
+mod hover;
+mod inlay_hints;
 mod lsp_ext;
 mod preview;
+mod references;
+mod semantic_tokens;
 mod util;
 
 use std::collections::HashMap;
@@ -20,17 +25,19 @@
 use lsp_server::{Connection, Message, Request, RequestId, Response};
 use lsp_types::notification::{DidChangeTextDocument, DidOpenTextDocument, Notification};
 use lsp_types::request::{
-    CodeActionRequest, DocumentColor, DocumentSymbolRequest, ExecuteCommand, GotoDefinition,
+    CodeActionRequest, DocumentColor, DocumentSymbolRequest, ExecuteCommand, Formatting,
+    GotoDefinition, RangeFormatting, References, Rename, SemanticTokensFullRequest,
 };
 use lsp_types::request::{ColorPresentationRequest, Completion, HoverRequest};
 use lsp_types::{
     CodeActionOrCommand, CodeActionProviderCapability, Color, ColorInformation, ColorPresentation,
     Command, CompletionOptions, DidChangeTextDocumentParams, DidOpenTextDocumentParams,
-    DocumentSymbolResponse, ExecuteCommandOptions, Hover, InitializeParams, Location, OneOf,
-    Position, PublishDiagnosticsParams, Range, ServerCapabilities, SymbolInformation,
-    TextDocumentSyncCapability, Url, WorkDoneProgressOptions,
+    DocumentSymbolResponse, ExecuteCommandOptions, HoverProviderCapability, InitializeParams,
+    Location, OneOf, Position, PublishDiagnosticsParams, Range, SemanticTokensFullOptions,
+    SemanticTokensOptions, ServerCapabilities, SymbolInformation, TextDocumentSyncCapability, Url,
+    WorkDoneProgressOptions,
 };
-use sixtyfps_compilerlib::diagnostics::BuildDiagnostics;
+use sixtyfps_compilerlib::diagnostics::{lint_levels, BuildDiagnostics, Lint};
 use sixtyfps_compilerlib::langtype::Type;
 use sixtyfps_compilerlib::parser::{syntax_nodes, SyntaxKind, SyntaxNode, SyntaxToken};
 use sixtyfps_compilerlib::typeloader::TypeLoader;
@@ -50,7 +57,35 @@ struct Cli {
     )]
     include_paths: Vec<std::path::PathBuf>,
 
-    /// The style name for the preview ('native' or 'ugly')
+    /// Add library paths for the `@library/file.60` imports, as `library=path`
+    #[structopt(
+        short = "L",
+        name = "Add library paths for the import statements",
+        number_of_values = 1,
+        parse(try_from_str = sixtyfps_compilerlib::parse_library_path)
+    )]
+    library_paths: Vec<(String, std::path::PathBuf)>,
+
+    /// Report a lint as a warning: unused-property, unused-callback, unused-id,
+    /// constant-state-condition, overridden-binding, invisible-element, or 'all'
+    #[structopt(
+        short = "W",
+        name = "lint",
+        number_of_values = 1,
+        parse(try_from_str = Lint::parse_list)
+    )]
+    warn_lints: Vec<Vec<Lint>>,
+
+    /// Report a lint as an error
+    #[structopt(
+        short = "D",
+        name = "denied lint",
+        number_of_values = 1,
+        parse(try_from_str = Lint::parse_list)
+    )]
+    deny_lints: Vec<Vec<Lint>>,
+
+    /// The style name for the preview ('native' or 'ugly'), or the path of a directory containing a custom style
     #[structopt(long, name = "style name", default_value)]
     style: String,
 
@@ -61,26 +96,16 @@ struct Cli {
 
 pub struct DocumentCache<'a> {
     documents: TypeLoader<'a>,
-    newline_offsets: HashMap<Url, Vec<u32>>,
+    /// The current content of the documents opened in the editor, and the content on disk of
+    /// the other documents whose offsets were converted to positions
+    contents: HashMap<Url, String>,
 }
 
 impl<'a> DocumentCache<'a> {
     fn new(config: &'a CompilerConfiguration) -> Self {
         let documents =
             TypeLoader::new(TypeRegister::builtin(), config, &mut BuildDiagnostics::default());
-        Self { documents, newline_offsets: Default::default() }
-    }
-
-    fn newline_offsets_from_content(content: &str) -> Vec<u32> {
-        let mut ln_offs = 0;
-        content
-            .split('\n')
-            .map(|line| {
-                let r = ln_offs;
-                ln_offs += line.len() as u32 + 1;
-                r
-            })
-            .collect()
+        Self { documents, contents: Default::default() }
     }
 
     pub fn byte_offset_to_position(
@@ -88,31 +113,89 @@ pub fn byte_offset_to_position(
         offset: u32,
         target_uri: &lsp_types::Url,
     ) -> Option<lsp_types::Position> {
-        let newline_offsets = match self.newline_offsets.entry(target_uri.clone()) {
+        let content = match self.contents.entry(target_uri.clone()) {
             std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
             std::collections::hash_map::Entry::Vacant(e) => {
-                e.insert(Self::newline_offsets_from_content(
-                    &std::fs::read_to_string(target_uri.to_file_path().ok()?).ok()?,
-                ))
+                e.insert(std::fs::read_to_string(target_uri.to_file_path().ok()?).ok()?)
             }
         };
-        let pos = newline_offsets.binary_search(&offset).map_or_else(
-            |line| {
-                if line == 0 {
-                    Position::new(0, offset)
-                } else {
-                    Position::new(
-                        line as u32 - 1,
-                        newline_offsets.get(line - 1).map_or(0, |x| offset - *x),
-                    )
+        Some(byte_offset_to_utf16_position(content, offset as usize))
+    }
+
+    pub fn position_to_byte_offset(&self, pos: Position, target_uri: &Url) -> Option<u32> {
+        Some(utf16_position_to_byte_offset(self.contents.get(target_uri)?, pos) as u32)
+    }
+
+    /// Apply the changes from a `textDocument/didChange` notification to the content
+    /// of the document and return the new content.
+    fn apply_content_changes(
+        &mut self,
+        uri: &Url,
+        changes: Vec<lsp_types::TextDocumentContentChangeEvent>,
+    ) -> String {
+        let content = self.contents.entry(uri.clone()).or_default();
+        for change in changes {
+            match change.range {
+                Some(range) => {
+                    let start = utf16_position_to_byte_offset(content, range.start);
+                    let end = utf16_position_to_byte_offset(content, range.end).max(start);
+                    content.replace_range(start..end, &change.text);
                 }
-            },
-            |line| Position::new(line as u32, 0),
-        );
-        Some(pos)
+                None => *content = change.text,
+            }
+        }
+        content.clone()
     }
 }
 
+/// Convert a position, where the character is counted in UTF-16 code units as in the
+/// LSP protocol, to a byte offset in the content.
+fn utf16_position_to_byte_offset(content: &str, pos: Position) -> usize {
+    let line_start = if pos.line == 0 {
+        0
+    } else {
+        match content.match_indices('\n').nth(pos.line as usize - 1) {
+            Some((newline, _)) => newline + 1,
+            None => return content.len(),
+        }
+    };
+    let mut character = 0;
+    for (offset, c) in content[line_start..].char_indices() {
+        if character >= pos.character as usize || c == '\n' {
+            return line_start + offset;
+        }
+        character += c.len_utf16();
+    }
+    content.len()
+}
+
+/// Convert a byte offset in the content to a position where the character is counted in
+/// UTF-16 code units as in the LSP protocol.
+fn byte_offset_to_utf16_position(content: &str, offset: usize) -> Position {
+    let offset = offset.min(content.len());
+    let line_start = content[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+    let line = content[..line_start].matches('\n').count();
+    let character = content[line_start..offset].encode_utf16().count();
+    Position::new(line as u32, character as u32)
+}
+
+/// Load the source of a document as if it was opened in the editor, and return the cache with
+/// the node of that document and the diagnostics of loading it.
+#[cfg(test)]
+fn loaded_document(source: &str) -> (DocumentCache<'static>, SyntaxNode, BuildDiagnostics) {
+    let config = Box::leak(Box::new(CompilerConfiguration::new(
+        sixtyfps_compilerlib::generator::OutputFormat::Interpreter,
+    )));
+    let mut document_cache = DocumentCache::new(config);
+    let path = std::env::temp_dir().join("sixtyfps_lsp_test.60");
+    let mut diag = BuildDiagnostics::default();
+    spin_on::spin_on(document_cache.documents.load_file(&path, &path, source.into(), &mut diag));
+    let uri = Url::from_file_path(&path).unwrap();
+    document_cache.contents.insert(uri, source.into());
+    let doc_node = document_cache.documents.get_document(&path).unwrap().node.clone().unwrap();
+    (document_cache, doc_node.into(), diag)
+}
+
 fn main() {
     let args: Cli = Cli::from_args();
     if !args.backend.is_empty() {
@@ -150,8 +233,13 @@ fn run_lsp_server() -> Result<(), Error> {
             work_done_progress_options: WorkDoneProgressOptions::default(),
         }),
         definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        references_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        document_range_formatting_provider: Some(OneOf::Left(true)),
         text_document_sync: Some(TextDocumentSyncCapability::Kind(
-            lsp_types::TextDocumentSyncKind::Full,
+            lsp_types::TextDocumentSyncKind::Incremental,
         )),
         code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
         execute_command_provider: Some(ExecuteCommandOptions {
@@ -160,9 +248,19 @@ fn run_lsp_server() -> Result<(), Error> {
         }),
         document_symbol_provider: Some(OneOf::Left(true)),
         color_provider: Some(true.into()),
+        semantic_tokens_provider: Some(
+            SemanticTokensOptions {
+                legend: semantic_tokens::legend(),
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            }
+            .into(),
+        ),
         ..ServerCapabilities::default()
     };
-    let server_capabilities = serde_json::to_value(&capabilities).unwrap();
+    let mut server_capabilities = serde_json::to_value(&capabilities).unwrap();
+    // Not yet in lsp_types
+    server_capabilities["inlayHintProvider"] = true.into();
     let initialization_params = connection.initialize(server_capabilities)?;
     main_loop(&connection, initialization_params)?;
     io_threads.join()?;
@@ -177,6 +275,11 @@ fn main_loop(connection: &Connection, params: serde_json::Value) -> Result<(), E
     compiler_config.style =
         Some(if cli_args.style.is_empty() { "ugly".into() } else { cli_args.style });
     compiler_config.include_paths = cli_args.include_paths;
+    compiler_config.library_paths = cli_args.library_paths.into_iter().collect();
+    compiler_config.lints = lint_levels(
+        cli_args.warn_lints.into_iter().flatten(),
+        cli_args.deny_lints.into_iter().flatten(),
+    );
 
     let mut document_cache = DocumentCache::new(&compiler_config);
     for msg in &connection.receiver {
@@ -234,18 +337,57 @@ fn handle_request(
         });
         let resp = Response::new_ok(id, result);
         connection.sender.send(Message::Response(resp))?;
-    } else if let Some((id, _params)) = cast::<HoverRequest>(&mut req) {
-        /*let result =
-            token_descr(document_cache, params.text_document_position_params).map(|x| Hover {
-                contents: lsp_types::HoverContents::Scalar(MarkedString::from_language_code(
-                    "text".into(),
-                    format!("{:?}", x.token),
-                )),
-                range: None,
-            });
-        let resp = Response::new_ok(id, result);
-        connection.sender.send(Message::Response(resp))?;*/
-        connection.sender.send(Message::Response(Response::new_ok(id, None::<Hover>)))?;
+    } else if let Some((id, params)) = cast::<HoverRequest>(&mut req) {
+        let result = token_descr(
+            document_cache,
+            params.text_document_position_params.text_document,
+            params.text_document_position_params.position,
+        )
+        .and_then(|token| hover::hover_info(document_cache, token.0));
+        connection.sender.send(Message::Response(Response::new_ok(id, result)))?;
+    } else if let Some((id, params)) = cast::<References>(&mut req) {
+        let include_declaration = params.context.include_declaration;
+        let result = token_descr(
+            document_cache,
+            params.text_document_position.text_document,
+            params.text_document_position.position,
+        )
+        .and_then(|token| {
+            references::find_references(document_cache, token.0, include_declaration)
+        });
+        connection.sender.send(Message::Response(Response::new_ok(id, result)))?;
+    } else if let Some((id, params)) = cast::<Rename>(&mut req) {
+        let new_name = params.new_name;
+        let result = token_descr(
+            document_cache,
+            params.text_document_position.text_document,
+            params.text_document_position.position,
+        )
+        .ok_or_else(|| "No identifier at this position".to_owned())
+        .and_then(|token| references::rename(document_cache, token.0, &new_name));
+        let resp = match result {
+            Ok(edit) => Response::new_ok(id, edit),
+            Err(message) => {
+                Response::new_err(id, lsp_server::ErrorCode::InvalidRequest as i32, message)
+            }
+        };
+        connection.sender.send(Message::Response(resp))?;
+    } else if let Some((id, params)) = cast::<Formatting>(&mut req) {
+        let result = formatting::format_document(
+            document_cache,
+            &params.text_document.uri,
+            &params.options,
+            None,
+        );
+        connection.sender.send(Message::Response(Response::new_ok(id, result)))?;
+    } else if let Some((id, params)) = cast::<RangeFormatting>(&mut req) {
+        let result = formatting::format_document(
+            document_cache,
+            &params.text_document.uri,
+            &params.options,
+            Some(params.range),
+        );
+        connection.sender.send(Message::Response(Response::new_ok(id, result)))?;
     } else if let Some((id, params)) = cast::<CodeActionRequest>(&mut req) {
         let result = token_descr(document_cache, params.text_document, params.range.start)
             .and_then(|token| get_code_actions(document_cache, token.0.parent()));
@@ -287,6 +429,12 @@ fn handle_request(
 
         let result = vec![ColorPresentation { label: color_literal, ..Default::default() }];
         connection.sender.send(Message::Response(Response::new_ok(id, result)))?;
+    } else if let Some((id, params)) = cast::<SemanticTokensFullRequest>(&mut req) {
+        let result = semantic_tokens::get_semantic_tokens(document_cache, &params.text_document);
+        connection.sender.send(Message::Response(Response::new_ok(id, result)))?;
+    } else if let Some((id, params)) = cast::<lsp_ext::InlayHintRequest>(&mut req) {
+        let result = inlay_hints::inlay_hints(document_cache, &params);
+        connection.sender.send(Message::Response(Response::new_ok(id, result)))?;
     } else if let Some((id, params)) = cast::<DocumentSymbolRequest>(&mut req) {
         let result = get_document_symbols(document_cache, &params.text_document);
         connection.sender.send(Message::Response(Response::new_ok(id, result)))?;
@@ -322,13 +470,10 @@ fn handle_notification(
             )?;
         }
         DidChangeTextDocument::METHOD => {
-            let mut params: DidChangeTextDocumentParams = serde_json::from_value(req.params)?;
-            reload_document(
-                connection,
-                params.content_changes.pop().unwrap().text,
-                params.text_document.uri,
-                document_cache,
-            )?;
+            let params: DidChangeTextDocumentParams = serde_json::from_value(req.params)?;
+            let content = document_cache
+                .apply_content_changes(&params.text_document.uri, params.content_changes);
+            reload_document(connection, content, params.text_document.uri, document_cache)?;
         }
         "sixtyfps/showPreview" => {
             show_preview_command(
@@ -408,17 +553,23 @@ fn reload_document(
     uri: lsp_types::Url,
     document_cache: &mut DocumentCache,
 ) -> Result<(), Error> {
-    let newline_offsets = DocumentCache::newline_offsets_from_content(&content);
-    document_cache.newline_offsets.insert(uri.clone(), newline_offsets);
+    document_cache.contents.insert(uri.clone(), content.clone());
 
     let path = uri.to_file_path().unwrap();
     let path_canon = dunce::canonicalize(&path).unwrap_or_else(|_| path.to_owned());
     preview::set_contents(&path_canon, content.clone());
     let mut diag = BuildDiagnostics::default();
-    spin_on::spin_on(document_cache.documents.load_file(&path_canon, &path, content, &mut diag));
+    // Only the documents depending on this one are re-analyzed, the other imports stay cached
+    let dependents = spin_on::spin_on(document_cache.documents.reload_file(
+        &path_canon,
+        &path,
+        content,
+        &mut diag,
+    ));
 
     // Always provide diagnostics for all files. Empty diagnostics clear any previous ones.
     let mut lsp_diags: HashMap<Url, Vec<lsp_types::Diagnostic>> = core::iter::once(&path)
+        .chain(dependents.iter())
         .chain(diag.all_loaded_files.iter())
         .map(|path| {
             let uri = Url::from_file_path(path).unwrap();
@@ -450,8 +601,7 @@ fn token_descr(
     text_document: lsp_types::TextDocumentIdentifier,
     pos: Position,
 ) -> Option<(SyntaxToken, u32)> {
-    let o = document_cache.newline_offsets.get(&text_document.uri)?.get(pos.line as usize)?
-        + pos.character as u32;
+    let o = document_cache.position_to_byte_offset(pos, &text_document.uri)?;
 
     let doc = document_cache.documents.get_document(&text_document.uri.to_file_path().ok()?)?;
     let node = doc.node.as_ref()?;
@@ -560,6 +710,7 @@ fn get_document_symbols(
 
     let inner_components = doc.inner_components.clone();
     let inner_structs = doc.inner_structs.clone();
+    let inner_enums = doc.inner_enums.clone();
     let mut make_range = |node: &SyntaxNode| {
         let r = node.text_range();
         Some(Range::new(
@@ -593,6 +744,15 @@ fn get_document_symbols(
         _ => None,
     }));
 
+    r.extend(inner_enums.iter().filter_map(|e| {
+        Some(SymbolInformation {
+            location: Location::new(uri.clone(), make_range(e.node.as_ref()?)?),
+            name: e.name.clone(),
+            kind: lsp_types::SymbolKind::Enum,
+            ..si.clone()
+        })
+    }));
+
     Some(r.into())
 
     // TODO: add the structs
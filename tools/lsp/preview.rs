@@ -212,6 +212,7 @@ async fn reload_preview(
         builder.set_style(cli_args.style)
     };
     builder.set_include_paths(cli_args.include_paths);
+    builder.set_library_paths(cli_args.library_paths.into_iter().collect());
 
     builder.set_file_loader(|path| {
         let path = path.to_owned();
@@ -0,0 +1,199 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+
+use std::collections::HashMap;
+
+use super::goto::{declared_identifier, token_info};
+use super::DocumentCache;
+use lsp_types::{Location, Range, TextEdit, Url, WorkspaceEdit};
+use sixtyfps_compilerlib::parser::{normalize_identifier, SyntaxKind, SyntaxNode, SyntaxToken};
+
+/// Return all the identifiers in the loaded documents that refer to the same item as the token.
+///
+/// The second member of the tuple is the token declaring the name of the item, if the item
+/// is declared in a .60 file
+fn find_reference_tokens(
+    document_cache: &DocumentCache,
+    token: SyntaxToken,
+) -> Option<(Vec<SyntaxToken>, Option<SyntaxToken>)> {
+    if token.kind() != SyntaxKind::Identifier {
+        return None;
+    }
+    let declaration =
+        token_info(document_cache, token.clone())?.declaration_node(document_cache)?;
+    let declared_identifier = declared_identifier(&declaration);
+
+    // The item may be known under a different name locally if it was imported with `as`
+    let mut names = vec![normalize_identifier(token.text())];
+    names.extend(declared_identifier.as_ref().map(|t| normalize_identifier(t.text())));
+
+    let mut result = vec![];
+    for doc in document_cache.documents.all_documents() {
+        let doc_node = match &doc.node {
+            Some(doc_node) => doc_node,
+            None => continue,
+        };
+        if !doc_node.source_file.path().is_absolute() {
+            // builtin files can't be edited
+            continue;
+        }
+        let mut next = doc_node.node.first_token();
+        while let Some(t) = next {
+            next = t.next_token();
+            if t.kind() != SyntaxKind::Identifier
+                || !names.contains(&normalize_identifier(t.text()))
+            {
+                continue;
+            }
+            let t = SyntaxToken { token: t, source_file: doc_node.source_file.clone() };
+            if token_info(document_cache, t.clone())
+                .and_then(|info| info.declaration_node(document_cache))
+                .map_or(false, |d| is_same_node(&d, &declaration))
+            {
+                result.push(t);
+            }
+        }
+    }
+    Some((result, declared_identifier))
+}
+
+fn is_same_node(a: &SyntaxNode, b: &SyntaxNode) -> bool {
+    a.source_file.path() == b.source_file.path() && a.text_range() == b.text_range()
+}
+
+fn is_same_token(a: &SyntaxToken, b: &SyntaxToken) -> bool {
+    a.source_file.path() == b.source_file.path() && a.text_range() == b.text_range()
+}
+
+/// Return the locations of all the references to the item referred to by the token.
+///
+/// The identifier declaring the item is only part of the result if `include_declaration` is set.
+pub fn find_references(
+    document_cache: &mut DocumentCache,
+    token: SyntaxToken,
+    include_declaration: bool,
+) -> Option<Vec<Location>> {
+    let (tokens, declared_identifier) = find_reference_tokens(document_cache, token)?;
+    Some(
+        tokens
+            .iter()
+            .filter(|t| {
+                include_declaration
+                    || declared_identifier.as_ref().map_or(true, |d| !is_same_token(t, d))
+            })
+            .filter_map(|t| token_location(document_cache, t))
+            .collect(),
+    )
+}
+
+/// Compute the edits needed to rename the item referred to by the token to `new_name`
+pub fn rename(
+    document_cache: &mut DocumentCache,
+    token: SyntaxToken,
+    new_name: &str,
+) -> Result<WorkspaceEdit, String> {
+    if new_name.is_empty()
+        || sixtyfps_compilerlib::lexer::lex_identifier(new_name, &mut Default::default())
+            != new_name.len()
+    {
+        return Err(format!("'{}' is not a valid identifier", new_name));
+    }
+    let (tokens, declared_identifier) = find_reference_tokens(document_cache, token)
+        .ok_or_else(|| "This element can't be renamed".to_owned())?;
+    let declared_identifier = declared_identifier
+        .filter(|t| t.source_file.path().is_absolute())
+        .ok_or_else(|| "Builtin items can't be renamed".to_owned())?;
+    let old_name = normalize_identifier(declared_identifier.text());
+
+    let mut changes = HashMap::<Url, Vec<TextEdit>>::new();
+    for t in tokens {
+        // Local aliases introduced by an import keep their name
+        if normalize_identifier(t.text()) != old_name {
+            continue;
+        }
+        let location = token_location(document_cache, &t).ok_or_else(|| {
+            format!("Cannot map {} to a location", t.source_file.path().display())
+        })?;
+        changes
+            .entry(location.uri)
+            .or_default()
+            .push(TextEdit { range: location.range, new_text: new_name.into() });
+    }
+    Ok(WorkspaceEdit { changes: Some(changes), ..Default::default() })
+}
+
+fn token_location(document_cache: &mut DocumentCache, token: &SyntaxToken) -> Option<Location> {
+    let uri = Url::from_file_path(token.source_file.path()).ok()?;
+    let range = token.text_range();
+    Some(Location::new(
+        uri.clone(),
+        Range::new(
+            document_cache.byte_offset_to_position(range.start().into(), &uri)?,
+            document_cache.byte_offset_to_position(range.end().into(), &uri)?,
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn include_declaration() {
+        let source = r#"
+Foo := Rectangle {
+    property <int> value: 42;
+    Text { text: value; }
+    TouchArea { clicked => { value += 1; } }
+}
+"#;
+        let (mut document_cache, doc_node, diag) = crate::loaded_document(source);
+        assert!(!diag.has_error());
+
+        let offset = source.find("value;").unwrap() as u32;
+        let token = SyntaxToken {
+            token: doc_node.token_at_offset(offset.into()).right_biased().unwrap(),
+            source_file: doc_node.source_file.clone(),
+        };
+
+        let with_declaration = find_references(&mut document_cache, token.clone(), true).unwrap();
+        assert_eq!(with_declaration.len(), 3);
+        assert_eq!(with_declaration[0].range.start, lsp_types::Position::new(2, 19));
+        let without_declaration = find_references(&mut document_cache, token, false).unwrap();
+        assert_eq!(without_declaration.len(), 2);
+        assert_eq!(without_declaration[..], with_declaration[1..]);
+    }
+
+    #[test]
+    fn non_ascii_positions() {
+        let source = r#"
+Foo := Rectangle {
+    property <int> value: 42;
+    Text { text: "é😀" + value; }
+}
+"#;
+        let (mut document_cache, doc_node, diag) = crate::loaded_document(source);
+        assert!(!diag.has_error());
+
+        let offset = source.find("value;").unwrap() as u32;
+        let token = SyntaxToken {
+            token: doc_node.token_at_offset(offset.into()).right_biased().unwrap(),
+            source_file: doc_node.source_file.clone(),
+        };
+        let references = find_references(&mut document_cache, token, false).unwrap();
+        assert_eq!(references.len(), 1);
+        // The character is counted in UTF-16 code units: 2 for the emoji, and 1 for 'é'
+        assert_eq!(references[0].range.start, lsp_types::Position::new(3, 25));
+        assert_eq!(
+            document_cache.position_to_byte_offset(references[0].range.start, &references[0].uri),
+            Some(offset)
+        );
+    }
+}
@@ -0,0 +1,149 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+
+use super::goto::{token_info, TokenInfo};
+use super::{byte_offset_to_utf16_position, DocumentCache};
+use lsp_types::{
+    SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens, SemanticTokensLegend,
+    SemanticTokensResult,
+};
+use sixtyfps_compilerlib::expression_tree::Expression;
+use sixtyfps_compilerlib::langtype::Type;
+use sixtyfps_compilerlib::parser::{SyntaxKind, SyntaxToken};
+
+/// The token types, the index in this array is the token type in the response
+const TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::TYPE,
+    SemanticTokenType::CLASS,
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::ENUM,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::EVENT,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PARAMETER,
+];
+
+/// The token modifiers, the index in this array is the bit in the modifier bitset
+const TOKEN_MODIFIERS: &[SemanticTokenModifier] =
+    &[SemanticTokenModifier::DECLARATION, SemanticTokenModifier::DEFAULT_LIBRARY];
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+pub fn get_semantic_tokens(
+    document_cache: &mut DocumentCache,
+    text_document: &lsp_types::TextDocumentIdentifier,
+) -> Option<SemanticTokensResult> {
+    let uri = &text_document.uri;
+    let doc = document_cache.documents.get_document(&uri.to_file_path().ok()?)?;
+    let doc_node = doc.node.clone()?;
+
+    let mut classified = vec![];
+    let mut next = doc_node.node.first_token();
+    while let Some(t) = next {
+        next = t.next_token();
+        if t.kind() != SyntaxKind::Identifier {
+            continue;
+        }
+        let t = SyntaxToken { token: t, source_file: doc_node.source_file.clone() };
+        if let Some(x) = classify(document_cache, t.clone()) {
+            classified.push((t, x));
+        }
+    }
+
+    // The positions and lengths are counted in UTF-16 code units
+    let source = doc_node.text().to_string();
+    let mut data = Vec::with_capacity(classified.len());
+    let (mut line, mut column) = (0, 0);
+    for (t, (token_type, token_modifiers_bitset)) in classified {
+        let pos = byte_offset_to_utf16_position(&source, t.text_range().start().into());
+        let delta_line = pos.line - line;
+        let delta_start = if delta_line == 0 { pos.character - column } else { pos.character };
+        line = pos.line;
+        column = pos.character;
+        data.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: t.text().encode_utf16().count() as u32,
+            token_type,
+            token_modifiers_bitset,
+        });
+    }
+    Some(SemanticTokensResult::Tokens(SemanticTokens { result_id: None, data }))
+}
+
+/// Return the index of the token type, and the modifier bitset
+fn classify(document_cache: &DocumentCache, token: SyntaxToken) -> Option<(u32, u32)> {
+    let is_declaration =
+        matches!(token.parent().kind(), SyntaxKind::DeclaredIdentifier | SyntaxKind::SubElement);
+    let mut default_library = false;
+    let token_type = match token_info(document_cache, token)? {
+        TokenInfo::Type(ty) => match ty {
+            Type::Component(c) if c.is_global() => SemanticTokenType::NAMESPACE,
+            Type::Component(_) => SemanticTokenType::CLASS,
+            Type::Builtin(b) => {
+                default_library = true;
+                if b.is_global {
+                    SemanticTokenType::NAMESPACE
+                } else {
+                    SemanticTokenType::CLASS
+                }
+            }
+            Type::Native(_) => {
+                default_library = true;
+                SemanticTokenType::CLASS
+            }
+            Type::Struct { .. } => SemanticTokenType::STRUCT,
+            Type::Enumeration(_) => SemanticTokenType::ENUM,
+            _ => {
+                default_library = true;
+                SemanticTokenType::TYPE
+            }
+        },
+        TokenInfo::ElementRc(e) => {
+            let e = e.borrow();
+            match e.enclosing_component.upgrade() {
+                Some(c) if c.is_global() => SemanticTokenType::NAMESPACE,
+                _ => SemanticTokenType::VARIABLE,
+            }
+        }
+        TokenInfo::NamedReference(nr) => match nr.ty() {
+            Type::Callback { .. } | Type::InferredCallback => SemanticTokenType::EVENT,
+            _ => SemanticTokenType::PROPERTY,
+        },
+        TokenInfo::Expression(expr) => match expr {
+            Expression::BuiltinFunctionReference(..)
+            | Expression::MemberFunction { .. }
+            | Expression::BuiltinMacroReference(..) => {
+                default_library = true;
+                SemanticTokenType::FUNCTION
+            }
+            Expression::EnumerationValue(ev) if ev.value == usize::MAX => SemanticTokenType::ENUM,
+            Expression::EnumerationValue(_) => SemanticTokenType::ENUM_MEMBER,
+            Expression::StructFieldAccess { .. } => SemanticTokenType::PROPERTY,
+            Expression::FunctionParameterReference { .. } => SemanticTokenType::PARAMETER,
+            Expression::RepeaterIndexReference { .. }
+            | Expression::RepeaterModelReference { .. }
+            | Expression::ReadLocalVariable { .. } => SemanticTokenType::VARIABLE,
+            _ => return None,
+        },
+        TokenInfo::FileImport(_) => return None,
+    };
+    let token_type = TOKEN_TYPES.iter().position(|t| *t == token_type)? as u32;
+    let modifiers = (is_declaration as u32) | ((default_library as u32) << 1);
+    Some((token_type, modifiers))
+}
@@ -8,10 +8,10 @@
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
 
-use sixtyfps_compilerlib::diagnostics::Spanned;
+use sixtyfps_compilerlib::diagnostics::{SourceLocation, Spanned};
 use sixtyfps_compilerlib::langtype::Type;
 use sixtyfps_compilerlib::lookup::LookupCtx;
-use sixtyfps_compilerlib::object_tree;
+use sixtyfps_compilerlib::object_tree::{self, ElementRc};
 use sixtyfps_compilerlib::parser::{syntax_nodes, SyntaxKind, SyntaxNode};
 use sixtyfps_compilerlib::typeregister::TypeRegister;
 
@@ -48,6 +48,25 @@ pub fn lookup_current_element_type(mut node: SyntaxNode, tr: &TypeRegister) -> O
     parent.lookup_type_for_child_element(&qualname.to_string(), tr).ok()
 }
 
+/// Find the ElementRc that was created from the given syntax node
+pub fn find_element_rc(
+    document_cache: &DocumentCache,
+    element: &syntax_nodes::Element,
+) -> Option<ElementRc> {
+    let doc = document_cache.documents.get_document(element.source_file.path())?;
+    doc.inner_components.iter().find_map(|c| {
+        let mut result = None;
+        object_tree::recurse_elem(&c.root_element, &(), &mut |e, _| {
+            if result.is_none()
+                && e.borrow().node.as_ref().map_or(false, |n| n.node == element.node)
+            {
+                result = Some(e.clone());
+            }
+        });
+        result
+    })
+}
+
 /// Run the function with the LoookupCtx associated with the token
 pub fn with_lookup_ctx<R>(
     document_cache: &DocumentCache,
@@ -135,9 +154,9 @@ fn lookup_expression_context(mut n: SyntaxNode) -> Option<(syntax_nodes::Element
 
 pub fn to_lsp_diag(d: &sixtyfps_compilerlib::diagnostics::Diagnostic) -> lsp_types::Diagnostic {
     lsp_types::Diagnostic::new(
-        to_range(d.line_column()),
+        to_range(d.source_location()),
         Some(to_lsp_diag_level(d.level())),
-        None,
+        Some(lsp_types::NumberOrString::String(d.code().as_str().into())),
         None,
         d.message().to_owned(),
         None,
@@ -145,11 +164,13 @@ pub fn to_lsp_diag(d: &sixtyfps_compilerlib::diagnostics::Diagnostic) -> lsp_typ
     )
 }
 
-fn to_range(span: (usize, usize)) -> lsp_types::Range {
-    let pos = lsp_types::Position::new(
-        (span.0 as u32).saturating_sub(1),
-        (span.1 as u32).saturating_sub(1),
-    );
+fn to_range(location: &SourceLocation) -> lsp_types::Range {
+    let pos = match location.source_file.as_ref().and_then(|sf| sf.source()) {
+        Some(source) if location.span.is_valid() => {
+            crate::byte_offset_to_utf16_position(source, location.span.offset)
+        }
+        _ => lsp_types::Position::default(),
+    };
     lsp_types::Range::new(pos, pos)
 }
 
@@ -161,3 +182,11 @@ fn to_lsp_diag_level(
         sixtyfps_interpreter::DiagnosticLevel::Warning => lsp_types::DiagnosticSeverity::Warning,
     }
 }
+
+#[test]
+fn non_ascii_diagnostic_position() {
+    let source = "Foo := Rectangle {\n    Text { text: \"😀\"; colr: red; }\n}\n";
+    let (_, _, diag) = crate::loaded_document(source);
+    let error = diag.into_iter().find(|d| d.message().contains("colr")).unwrap();
+    assert_eq!(to_lsp_diag(&error).range.start, lsp_types::Position::new(1, 23));
+}
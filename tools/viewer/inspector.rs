@@ -0,0 +1,265 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+/*!
+The inspector of the viewer (`--inspect`).
+
+While picking, the element under the cursor is highlighted, and a click selects it instead of
+being passed to the component. The commands to show and edit the selected element are read
+from the standard input.
+*/
+
+use crate::CURRENT_INSTANCE;
+use sixtyfps_corelib::input::MouseEvent;
+use sixtyfps_interpreter::{Brush, Color, ComponentInstance, ElementHandle, Value};
+use std::cell::RefCell;
+use std::io::BufRead;
+
+const HIGHLIGHT_SOURCE: &str = r#"
+Highlight := Rectangle {
+    border-width: 2px;
+    border-color: #e000e0;
+    background: #e000e028;
+}
+"#;
+
+const HELP: &str = "Inspector commands:
+  help                 show this help
+  show                 show the selected element and its properties
+  pick                 toggle the selection of elements with the mouse
+  unselect             clear the selection
+  set <name> <value>   set a property of the selected element, or a public property of the component
+  open                 open the selected element in the editor";
+
+#[derive(Default)]
+struct State {
+    picking: bool,
+    highlight: Option<ComponentInstance>,
+    hovered: Option<ElementHandle>,
+    selected: Option<ElementHandle>,
+    editor: Option<String>,
+}
+
+thread_local!(static STATE: RefCell<State> = Default::default());
+
+/// Start inspecting the `component`, which must be the CURRENT_INSTANCE
+pub fn start(component: &ComponentInstance, editor: Option<String>) {
+    let mut compiler = sixtyfps_interpreter::ComponentCompiler::default();
+    let highlight =
+        spin_on::spin_on(compiler.build_from_source(HIGHLIGHT_SOURCE.into(), "<inspector>".into()))
+            .expect("the highlight component must compile")
+            .create();
+    STATE.with(|state| {
+        *state.borrow_mut() =
+            State { picking: true, highlight: Some(highlight), editor, ..Default::default() }
+    });
+
+    component.window().0.set_mouse_event_filter(Some(Box::new(|event| {
+        if !STATE.with(|state| state.borrow().picking) {
+            return false;
+        }
+        let element = event.pos().and_then(|pos| {
+            CURRENT_INSTANCE.with(|current| current.borrow().as_ref()?.element_at(pos.x, pos.y))
+        });
+        match event {
+            MouseEvent::MousePressed { .. } => {
+                if let Some(element) = &element {
+                    print_element(element);
+                }
+                STATE.with(|state| state.borrow_mut().selected = element);
+            }
+            _ => STATE.with(|state| state.borrow_mut().hovered = element),
+        }
+        update_highlight();
+        true
+    })));
+
+    std::thread::spawn(|| {
+        for line in std::io::stdin().lock().lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            crate::run_in_ui_thread(Box::pin(async move { execute(line.trim()) }));
+        }
+    });
+    eprintln!("{}", HELP);
+}
+
+/// Clear the selection, for example because the component was reloaded
+pub fn unselect() {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.hovered = None;
+        state.selected = None;
+    });
+    update_highlight();
+}
+
+/// Draw the highlight over the hovered element, or over the selected element
+fn update_highlight() {
+    let window = match CURRENT_INSTANCE.with(|current| Some(current.borrow().as_ref()?.window())) {
+        Some(window) => window,
+        None => return,
+    };
+    STATE.with(|state| {
+        let state = state.borrow();
+        let overlay =
+            state.hovered.as_ref().or(state.selected.as_ref()).zip(state.highlight.as_ref());
+        match overlay {
+            Some((element, highlight)) => {
+                let geometry = element.geometry();
+                highlight.set_property("width", Value::Number(geometry.width() as _)).unwrap();
+                highlight.set_property("height", Value::Number(geometry.height() as _)).unwrap();
+                window.0.set_overlay(Some((highlight.component_rc(), geometry.origin)));
+            }
+            None => window.0.set_overlay(None),
+        }
+    });
+}
+
+fn execute(command: &str) {
+    let mut split = command.splitn(3, ' ');
+    let selected = STATE.with(|state| state.borrow().selected.clone());
+    match (split.next().unwrap_or_default(), split.next(), split.next()) {
+        ("", _, _) => {}
+        ("help", _, _) => eprintln!("{}", HELP),
+        ("show", _, _) => match &selected {
+            Some(element) => print_element(element),
+            None => eprintln!("No element selected"),
+        },
+        ("pick", _, _) => {
+            let picking = STATE.with(|state| {
+                let mut state = state.borrow_mut();
+                state.picking = !state.picking;
+                state.hovered = None;
+                state.picking
+            });
+            update_highlight();
+            eprintln!("Picking {}", if picking { "enabled" } else { "disabled" });
+        }
+        ("unselect", _, _) => unselect(),
+        ("set", Some(name), Some(value)) => set_property(selected.as_ref(), name, value.trim()),
+        ("open", _, _) => match selected.as_ref().and_then(|e| e.source_location()) {
+            Some(location) => open_in_editor(location),
+            None => eprintln!("No element with a source location selected"),
+        },
+        _ => eprintln!("Unknown command '{}'. Type 'help' for the list of commands", command),
+    }
+}
+
+fn print_element(element: &ElementHandle) {
+    let id = element.id();
+    let geometry = element.geometry();
+    println!("{} ({})", if id.is_empty() { "<no id>" } else { &id }, element.type_name());
+    if let Some((path, line, column)) = element.source_location() {
+        println!("  location: {}:{}:{}", path.display(), line, column);
+    }
+    println!(
+        "  geometry: x={} y={} width={} height={}",
+        geometry.origin.x,
+        geometry.origin.y,
+        geometry.width(),
+        geometry.height()
+    );
+    for (name, value) in element.properties() {
+        println!("  {}: {}", name, format_value(&value));
+    }
+}
+
+fn set_property(element: Option<&ElementHandle>, name: &str, text: &str) {
+    let result = match element.and_then(|e| Some((e, property_value(e, name)?))) {
+        Some((element, current)) => match parse_value(&current, text) {
+            Some(value) => element.set_property(name, value),
+            None => {
+                return eprintln!(
+                    "Invalid value '{}', the current value is {}",
+                    text,
+                    format_value(&current)
+                )
+            }
+        },
+        None => CURRENT_INSTANCE.with(|current| {
+            let current = current.borrow();
+            let instance = match current.as_ref() {
+                Some(instance) => instance,
+                None => return Ok(()),
+            };
+            match instance.get_property(name).ok().and_then(|v| parse_value(&v, text)) {
+                Some(value) => instance.set_property(name, value),
+                None => Err(sixtyfps_interpreter::SetPropertyError::NoSuchProperty),
+            }
+        }),
+    };
+    match result {
+        Ok(()) => update_highlight(),
+        Err(err) => eprintln!("Cannot set the property '{}': {}", name, err),
+    }
+}
+
+fn property_value(element: &ElementHandle, name: &str) -> Option<Value> {
+    element.properties().into_iter().find(|(n, _)| n == &name.replace('_', "-")).map(|(_, v)| v)
+}
+
+/// Parse `text` as a value of the same type as `current`
+fn parse_value(current: &Value, text: &str) -> Option<Value> {
+    match current {
+        Value::Number(_) => text.trim_end_matches("px").parse::<f64>().ok().map(Value::Number),
+        Value::Bool(_) => text.parse::<bool>().ok().map(Value::Bool),
+        Value::String(_) => {
+            let text = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')).unwrap_or(text);
+            Some(Value::String(text.into()))
+        }
+        Value::Brush(_) => parse_color(text).map(|c| Value::Brush(Brush::SolidColor(c))),
+        _ => None,
+    }
+}
+
+/// Parse a color as `#rrggbb` or `#rrggbbaa`
+fn parse_color(text: &str) -> Option<Color> {
+    let hex = text.strip_prefix('#')?;
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+        6 => Some(Color::from_argb_encoded(0xff000000 | value)),
+        8 => Some(Color::from_argb_encoded(value.rotate_right(8))),
+        _ => None,
+    }
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("{:?}", s.as_str()),
+        Value::Bool(b) => b.to_string(),
+        Value::Brush(Brush::SolidColor(c)) => {
+            format!("#{:02x}{:02x}{:02x}{:02x}", c.red(), c.green(), c.blue(), c.alpha())
+        }
+        value => format!("{:?}", value),
+    }
+}
+
+fn open_in_editor((path, line, column): (std::path::PathBuf, usize, usize)) {
+    let editor = STATE.with(|state| state.borrow().editor.clone());
+    let editor = match editor {
+        Some(editor) => editor,
+        None => return println!("{}:{}:{}", path.display(), line, column),
+    };
+    let mut args = editor.split_whitespace().map(|arg| {
+        arg.replace("{file}", &path.to_string_lossy())
+            .replace("{line}", &line.to_string())
+            .replace("{column}", &column.to_string())
+    });
+    let program = match args.next() {
+        Some(program) => program,
+        None => return,
+    };
+    if let Err(err) = std::process::Command::new(&program).args(args).spawn() {
+        eprintln!("Cannot run the editor '{}': {}", program, err);
+    }
+}
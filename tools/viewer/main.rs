@@ -8,6 +8,9 @@
     Please contact info@sixtyfps.io for more information.
 LICENSE END */
 
+mod inspector;
+mod screenshot;
+
 use sixtyfps_interpreter::ComponentInstance;
 use std::future::Future;
 use std::pin::Pin;
@@ -24,10 +27,19 @@ struct Cli {
     #[structopt(short = "I", name = "include path for other .60 files", number_of_values = 1)]
     include_paths: Vec<std::path::PathBuf>,
 
+    /// Library path for `@library/file.60` imports, as `library=path`
+    #[structopt(
+        short = "L",
+        name = "library path",
+        number_of_values = 1,
+        parse(try_from_str = sixtyfps_compilerlib::parse_library_path)
+    )]
+    library_paths: Vec<(String, std::path::PathBuf)>,
+
     #[structopt(name = "path to .60 file", parse(from_os_str))]
     path: std::path::PathBuf,
 
-    /// The style name ('native', or 'ulgy')
+    /// The style name ('native', or 'ulgy'), or the path of a directory containing a custom style
     #[structopt(long, name = "style name", default_value)]
     style: String,
 
@@ -38,21 +50,83 @@ struct Cli {
     /// Automatically watch the file system, and reload when it changes
     #[structopt(long)]
     auto_reload: bool,
+
+    /// Print each evaluation of a binding, with the property whose change caused it
+    #[cfg(feature = "debug-bindings")]
+    #[structopt(long)]
+    trace_bindings: bool,
+
+    /// Print the number of evaluations of each binding after each frame
+    #[cfg(feature = "debug-bindings")]
+    #[structopt(long)]
+    binding_stats: bool,
+
+    /// Print the value, the dependencies and the dependents of this public property after each frame
+    #[cfg(feature = "debug-bindings")]
+    #[structopt(long, name = "property name", number_of_values = 1)]
+    debug_property: Vec<String>,
+
+    /// Highlight the element under the cursor and select it with a click, to show and edit
+    /// its properties with commands read from the standard input
+    #[structopt(long)]
+    inspect: bool,
+
+    /// The command used by the `open` command of the inspector to open the selected element in an
+    /// editor, where `{file}`, `{line}` and `{column}` are replaced by its location
+    #[structopt(long, name = "editor command")]
+    editor: Option<String>,
+
+    /// Set the public properties from the fields of the JSON object in this file
+    #[structopt(long, name = "data.json", parse(from_os_str))]
+    load_data: Option<std::path::PathBuf>,
+
+    /// Render the component offscreen with the software renderer and save it to this PNG file,
+    /// instead of showing a window
+    #[structopt(long, name = "output.png", parse(from_os_str))]
+    screenshot: Option<std::path::PathBuf>,
+
+    /// The size of the screenshot in logical pixels, as `WIDTHxHEIGHT`. Defaults to the
+    /// preferred size of the component
+    #[structopt(
+        long,
+        name = "size",
+        requires = "output.png",
+        parse(try_from_str = screenshot::parse_size)
+    )]
+    size: Option<sixtyfps_corelib::graphics::Size>,
+
+    /// Set a public property before taking the screenshot, as `name=value`. The value is
+    /// parsed as JSON, so it can be a struct or an array
+    #[structopt(
+        long = "set",
+        name = "name=value",
+        number_of_values = 1,
+        requires = "output.png",
+        parse(try_from_str = screenshot::parse_property_assignment)
+    )]
+    set_properties: Vec<(String, String)>,
 }
 
-thread_local! {static CURRENT_INSTANCE: std::cell::RefCell<Option<ComponentInstance>> = Default::default();}
+thread_local! {pub(crate) static CURRENT_INSTANCE: std::cell::RefCell<Option<ComponentInstance>> = Default::default();}
 
 fn main() -> Result<()> {
     let args = Cli::from_args();
 
-    if !args.backend.is_empty() {
+    if args.screenshot.is_some() {
+        sixtyfps_rendering_backend_software::init();
+    } else if cfg!(not(feature = "backend-default")) {
+        eprintln!("This viewer is built without the backend-default feature, it can only take screenshots");
+        std::process::exit(-1);
+    } else if !args.backend.is_empty() {
         std::env::set_var("SIXTYFPS_BACKEND", &args.backend);
     }
 
     let fswatcher = if args.auto_reload { Some(start_fswatch_thread(args.clone())?) } else { None };
     let mut compiler = init_compiler(&args, fswatcher);
+    #[cfg(feature = "debug-bindings")]
+    init_binding_debugger(&args);
 
-    let c = spin_on::spin_on(compiler.build_from_path(args.path));
+    let c = spin_on::spin_on(compiler.build_from_path(&args.path));
     sixtyfps_interpreter::print_diagnostics(&compiler.diagnostics());
 
     let c = match c {
@@ -61,33 +135,76 @@ fn main() -> Result<()> {
     };
 
     let component = c.create();
+    if let Err(err) = load_data(&component, &args) {
+        eprintln!("{}", err);
+        std::process::exit(-1);
+    }
+
+    if let Some(path) = &args.screenshot {
+        if let Err(err) =
+            screenshot::take_screenshot(&component, &args.set_properties, args.size, path)
+        {
+            eprintln!("{}", err);
+            std::process::exit(-1);
+        }
+        return Ok(());
+    }
 
-    if args.auto_reload {
+    #[cfg(feature = "debug-bindings")]
+    let has_debug_property = !args.debug_property.is_empty();
+    #[cfg(not(feature = "debug-bindings"))]
+    let has_debug_property = false;
+    if args.auto_reload || args.inspect || has_debug_property {
         CURRENT_INSTANCE.with(|current| current.replace(Some(component.clone_strong())));
     }
+    if args.inspect {
+        inspector::start(&component, args.editor.clone());
+    }
 
     component.run();
     Ok(())
 }
 
+/// Set the properties from the file given with `--load-data`
+fn load_data(component: &ComponentInstance, args: &Cli) -> Result<()> {
+    let path = match &args.load_data {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let file = std::fs::File::open(path)
+        .map_err(|err| format!("Cannot open {}: {}", path.display(), err))?;
+    let json: serde_json::Value = serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|err| format!("Cannot parse {}: {}", path.display(), err))?;
+    component
+        .set_properties_from_json(&json)
+        .map_err(|err| format!("Cannot load the data from {}: {}", path.display(), err))?;
+    Ok(())
+}
+
 fn init_compiler(
     args: &Cli,
     fswatcher: Option<Arc<Mutex<notify::RecommendedWatcher>>>,
 ) -> sixtyfps_interpreter::ComponentCompiler {
     let mut compiler = sixtyfps_interpreter::ComponentCompiler::default();
     compiler.set_include_paths(args.include_paths.clone());
+    compiler.set_library_paths(args.library_paths.iter().cloned().collect());
     if !args.style.is_empty() {
         compiler.set_style(args.style.clone());
+    } else if args.screenshot.is_some() {
+        // The native style needs Qt, which cannot render with the software renderer
+        compiler.set_style("ugly".into());
     }
     if let Some(watcher) = fswatcher {
-        notify::Watcher::watch(
-            &mut *watcher.lock().unwrap(),
-            &args.path,
-            notify::RecursiveMode::NonRecursive,
-        )
-        .unwrap_or_else(|err| {
-            eprintln!("Warning: error while watching {}: {:?}", args.path.display(), err)
-        });
+        for path in std::iter::once(&args.path).chain(args.load_data.iter()) {
+            notify::Watcher::watch(
+                &mut *watcher.lock().unwrap(),
+                path,
+                notify::RecursiveMode::NonRecursive,
+            )
+            .unwrap_or_else(|err| {
+                eprintln!("Warning: error while watching {}: {:?}", path.display(), err)
+            });
+        }
         compiler.set_file_loader(move |path| {
             notify::Watcher::watch(
                 &mut *watcher.lock().unwrap(),
@@ -103,6 +220,37 @@ fn init_compiler(
     compiler
 }
 
+#[cfg(feature = "debug-bindings")]
+fn init_binding_debugger(args: &Cli) {
+    use sixtyfps_corelib::properties::debug;
+    if args.trace_bindings {
+        debug::set_trace_handler(Some(Box::new(|message| eprintln!("{}", message))));
+    }
+    if !args.binding_stats && args.debug_property.is_empty() {
+        return;
+    }
+    let binding_stats = args.binding_stats;
+    let debug_property = args.debug_property.clone();
+    debug::set_frame_statistics_handler(Some(Box::new(move |counts| {
+        if binding_stats && !counts.is_empty() {
+            eprintln!("Binding evaluations during the frame:");
+            for (name, count) in counts {
+                eprintln!("  {}: {}", name, count);
+            }
+        }
+        CURRENT_INSTANCE.with(|current| {
+            if let Some(instance) = &*current.borrow() {
+                for name in &debug_property {
+                    match instance.debug_property(name) {
+                        Ok(info) => eprint!("{}", info),
+                        Err(err) => eprintln!("{}: {}", name, err),
+                    }
+                }
+            }
+        });
+    })));
+}
+
 const PENDING_EVENTS: AtomicU32 = AtomicU32::new(0);
 
 fn start_fswatch_thread(args: Cli) -> Result<Arc<Mutex<notify::RecommendedWatcher>>> {
@@ -133,9 +281,19 @@ async fn reload(args: Cli, fswatcher: Arc<Mutex<notify::RecommendedWatcher>>) {
             let mut current = current.borrow_mut();
             if let Some(handle) = current.take() {
                 let window = handle.window();
-                current.replace(c.create_with_existing_window(window));
+                let handle = c.create_with_existing_window(window);
+                if let Err(err) = load_data(&handle, &args) {
+                    eprintln!("{}", err);
+                }
+                current.replace(handle);
+                if args.inspect {
+                    inspector::unselect();
+                }
             } else {
                 let handle = c.create();
+                if let Err(err) = load_data(&handle, &args) {
+                    eprintln!("{}", err);
+                }
                 handle.show();
                 current.replace(handle);
             }
@@ -158,7 +316,11 @@ unsafe impl Sync for FutureRunner {}
 
 impl Wake for FutureRunner {
     fn wake(self: Arc<Self>) {
-        sixtyfps_rendering_backend_default::backend().post_event(Box::new(move || {
+        #[cfg(feature = "backend-default")]
+        let backend = sixtyfps_rendering_backend_default::backend();
+        #[cfg(not(feature = "backend-default"))]
+        let backend = sixtyfps_corelib::backend::instance().unwrap();
+        backend.post_event(Box::new(move || {
             let waker = self.clone().into();
             let mut cx = std::task::Context::from_waker(&waker);
             let mut fut_opt = self.fut.lock().unwrap();
@@ -172,6 +334,6 @@ fn wake(self: Arc<Self>) {
     }
 }
 
-fn run_in_ui_thread(fut: Pin<Box<dyn Future<Output = ()>>>) {
+pub(crate) fn run_in_ui_thread(fut: Pin<Box<dyn Future<Output = ()>>>) {
     Arc::new(FutureRunner { fut: Mutex::new(Some(fut)) }).wake()
 }
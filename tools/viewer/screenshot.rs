@@ -0,0 +1,71 @@
+/* LICENSE BEGIN
+    This file is part of the SixtyFPS Project -- https://sixtyfps.io
+    Copyright (c) 2021 Olivier Goffart <olivier.goffart@sixtyfps.io>
+    Copyright (c) 2021 Simon Hausmann <simon.hausmann@sixtyfps.io>
+
+    SPDX-License-Identifier: GPL-3.0-only
+    This file is also available under commercial licensing terms.
+    Please contact info@sixtyfps.io for more information.
+LICENSE END */
+/*!
+The screenshot mode of the viewer (`--screenshot`).
+
+The component is rendered offscreen with the software rendering backend, so that no display
+server or GPU is needed.
+*/
+
+use sixtyfps_corelib::graphics::Size;
+use sixtyfps_interpreter::ComponentInstance;
+
+/// Parse a `--size` argument, as `WIDTHxHEIGHT`
+pub fn parse_size(s: &str) -> Result<Size, String> {
+    let mut split = s.splitn(2, 'x');
+    match (split.next().map(str::parse::<f32>), split.next().map(str::parse::<f32>)) {
+        (Some(Ok(width)), Some(Ok(height))) if width > 0. && height > 0. => {
+            Ok(Size::new(width, height))
+        }
+        _ => Err(format!("Invalid size '{}', expected 'WIDTHxHEIGHT'", s)),
+    }
+}
+
+/// Parse a `--set` argument, as `name=value`
+pub fn parse_property_assignment(s: &str) -> Result<(String, String), String> {
+    let mut split = s.splitn(2, '=');
+    match (split.next(), split.next()) {
+        (Some(name), Some(value)) if !name.is_empty() => Ok((name.into(), value.into())),
+        _ => Err(format!("Invalid property assignment '{}', expected 'name=value'", s)),
+    }
+}
+
+/// Set the properties, render one frame of the size `size` (or of the preferred size of
+/// the component), and save it as a PNG
+pub fn take_screenshot(
+    component: &ComponentInstance,
+    properties: &[(String, String)],
+    size: Option<Size>,
+    path: &std::path::Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for (name, text) in properties {
+        set_property(component, name, text)?;
+    }
+    let pixmap = sixtyfps_rendering_backend_software::render_window(&component.window(), size)
+        .ok_or("Cannot render the component")?;
+    pixmap.save_png(path).map_err(|err| format!("Cannot write {}: {}", path.display(), err))?;
+    Ok(())
+}
+
+/// Set a property from the value of a `--set` argument. The value is parsed as JSON, so that
+/// structs and arrays can be set. If it is not valid JSON for the type of the property, it is
+/// a string.
+fn set_property(component: &ComponentInstance, name: &str, text: &str) -> Result<(), String> {
+    let result = match serde_json::from_str(text) {
+        Ok(json) => match component.set_property_from_json(name, &json) {
+            Err(sixtyfps_interpreter::JsonError::InvalidValue { .. }) if !json.is_string() => {
+                component.set_property_from_json(name, &serde_json::Value::from(text))
+            }
+            result => result,
+        },
+        Err(_) => component.set_property_from_json(name, &serde_json::Value::from(text)),
+    };
+    result.map_err(|err| format!("Cannot set the property '{}' to '{}': {}", name, text, err))
+}
@@ -79,6 +79,7 @@ fn gen_corelib(root_dir: &Path, include_dir: &Path) -> anyhow::Result<()> {
         "ClippedImage",
         "TouchArea",
         "FocusScope",
+        "Shortcut",
         "Flickable",
         "Text",
         "Path",
@@ -102,6 +103,9 @@ fn gen_corelib(root_dir: &Path, include_dir: &Path) -> anyhow::Result<()> {
         "TextWrap",
         "ImageFit",
         "FillRule",
+        "StandardButtonKind",
+        "DialogPresentation",
+        "MenuEntry",
     ]
     .iter()
     .chain(items.iter())
@@ -132,6 +136,12 @@ fn gen_corelib(root_dir: &Path, include_dir: &Path) -> anyhow::Result<()> {
         "KeyEventArg",
         "sixtyfps_color_brighter",
         "sixtyfps_color_darker",
+        "sixtyfps_color_from_hsva",
+        "sixtyfps_color_from_hsla",
+        "sixtyfps_color_with_alpha",
+        "sixtyfps_color_transparentize",
+        "sixtyfps_color_mix",
+        "sixtyfps_color_luminance",
         "sixtyfps_image_size",
     ]
     .iter()
@@ -189,7 +199,17 @@ fn gen_corelib(root_dir: &Path, include_dir: &Path) -> anyhow::Result<()> {
             "sixtyfps_image_internal.h",
         ),
         (
-            vec!["Color", "sixtyfps_color_brighter", "sixtyfps_color_darker"],
+            vec![
+                "Color",
+                "sixtyfps_color_brighter",
+                "sixtyfps_color_darker",
+                "sixtyfps_color_from_hsva",
+                "sixtyfps_color_from_hsla",
+                "sixtyfps_color_with_alpha",
+                "sixtyfps_color_transparentize",
+                "sixtyfps_color_mix",
+                "sixtyfps_color_luminance",
+            ],
             vec![],
             "sixtyfps_color_internal.h",
         ),
@@ -225,10 +245,22 @@ fn gen_corelib(root_dir: &Path, include_dir: &Path) -> anyhow::Result<()> {
             "sixtyfps_component_window_set_focus_item",
             "sixtyfps_component_window_set_component",
             "sixtyfps_component_window_show_popup",
+            "sixtyfps_component_window_show_dialog",
+            "sixtyfps_component_window_close_dialog",
+            "sixtyfps_component_window_has_native_menus",
+            "sixtyfps_component_window_show_native_menu",
+            "sixtyfps_component_window_set_native_menu_bar",
+            "sixtyfps_standard_button_rank",
             "sixtyfps_new_path_elements",
             "sixtyfps_new_path_events",
             "sixtyfps_color_brighter",
             "sixtyfps_color_darker",
+            "sixtyfps_color_from_hsva",
+            "sixtyfps_color_from_hsla",
+            "sixtyfps_color_with_alpha",
+            "sixtyfps_color_transparentize",
+            "sixtyfps_color_mix",
+            "sixtyfps_color_luminance",
             "sixtyfps_image_size",
         ]
         .iter()
@@ -292,6 +324,11 @@ fn gen_corelib(root_dir: &Path, include_dir: &Path) -> anyhow::Result<()> {
         .body
         .insert("Flickable".to_owned(), "    inline Flickable(); inline ~Flickable();".into());
     config.export.pre_body.insert("FlickableDataBox".to_owned(), "struct FlickableData;".into());
+    config
+        .export
+        .body
+        .insert("Shortcut".to_owned(), "    inline Shortcut(); inline ~Shortcut();".into());
+    config.export.pre_body.insert("ShortcutDataBox".to_owned(), "struct ShortcutData;".into());
     config.export.include.push("StandardListViewItem".into());
     cbindgen::Builder::new()
         .with_config(config)